@@ -0,0 +1,22 @@
+//! Loadable GStreamer plugin exposing the `asciirendr` element
+//!
+//! Build with `cargo build --release` in this directory and add
+//! `target/release` to `GST_PLUGIN_PATH`.
+
+use gstreamer as gst;
+
+fn plugin_init(plugin: &gst::Plugin) -> Result<(), gst::glib::BoolError> {
+    ascii_rendr::gst_element::register(Some(plugin))
+}
+
+gst::plugin_define!(
+    asciirendr,
+    env!("CARGO_PKG_DESCRIPTION"),
+    plugin_init,
+    env!("CARGO_PKG_VERSION"),
+    "MIT/X11",
+    env!("CARGO_PKG_NAME"),
+    env!("CARGO_PKG_NAME"),
+    env!("CARGO_PKG_REPOSITORY"),
+    "2025-12-01"
+);
@@ -1,16 +1,70 @@
-use ascii_rendr::{AsciiConfig, process_image, process_image_preserve_colors};
+use crate::brush::RegionBrush;
+#[cfg(feature = "midi")]
+use crate::midi::{MidiInput, MidiMapping, learnable_params};
+use crate::plugins::PluginManager;
+use crate::session::Session;
+use crate::variations::Variations;
+use crate::video::{VideoSettings, VideoSource};
+use ascii_rendr::before_after::{BeforeAfter, Transition};
+use ascii_rendr::color_vision::{ColorVision, simulate_color_vision};
+use ascii_rendr::params::PipelineStage;
+use ascii_rendr::png_metadata::save_png_with_config;
+use ascii_rendr::progress::CancelToken;
+use ascii_rendr::regions::{RegionOverride, process_image_regions};
+use ascii_rendr::watermark::{Watermark, WatermarkContent, WatermarkCorner};
+use ascii_rendr::{
+    AsciiConfig, AsciiError, BlurMode, CharSelection, Charset, CompositeMode, EdgeChars, EdgeMode,
+    FillDither, LuminanceAdjust, LuminanceMode, OutputMode, Palette, PreFilter, Preset, Processor,
+    TILE_SIZES, ThresholdMode, TileStatistic,
+};
 use eframe::egui;
 use image::RgbaImage;
-use std::time::Instant;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often unsaved changes are written to the session autosave
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Color setting the eyedropper assigns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColorTarget {
+    Ascii,
+    Background,
+}
+
+/// Conversion of a still image running on a worker thread, so large images
+/// do not freeze the UI
+struct ProcessJob {
+    cancel: CancelToken,
+    /// Last stage finished by the worker and the fraction done
+    progress: Arc<Mutex<(PipelineStage, f32)>>,
+    /// The worker's processor, handed back for the next job, and its output
+    result: mpsc::Receiver<(Processor, Result<RgbaImage, AsciiError>)>,
+    start: Instant,
+    /// Settings changed while it ran; a new conversion starts once the
+    /// processor is back, so its cached stages carry over
+    rerun: bool,
+}
 
 /// Main application state for the ASCII renderer GUI
 pub struct AsciiApp {
     /// Input image (original)
     input_image: Option<RgbaImage>,
+    /// File the input image was loaded from
+    image_path: Option<PathBuf>,
     /// Output image (ASCII art)
     output_image: Option<RgbaImage>,
+    /// Output as seen with `color_vision`, shown instead of the output when set
+    output_preview: Option<RgbaImage>,
     /// Configuration parameters
     config: AsciiConfig,
+    /// Converter keeping stage results, so changes to later stages skip the
+    /// edge passes
+    processor: Processor,
+    /// Conversion in progress, if any
+    job: Option<ProcessJob>,
 
     /// Texture handle for input image display
     input_texture: Option<egui::TextureHandle>,
@@ -24,6 +78,60 @@ pub struct AsciiApp {
 
     /// Whether to preserve original colors (vs using color picker)
     preserve_original_colors: bool,
+    /// Color vision deficiency simulated in the output preview (never saved)
+    color_vision: ColorVision,
+    /// Color the next click on the original image assigns (if any)
+    eyedropper: Option<ColorTarget>,
+    /// Whether the eyedropper averages the tile instead of one pixel
+    eyedropper_tile: bool,
+
+    /// Live video source replacing the still input image (if any)
+    video: Option<VideoSource>,
+    /// Resolution, frame-rate and mirroring controls for the video source
+    video_settings: VideoSettings,
+    /// Camera opened by `Open Camera`, as ffmpeg names it on this platform
+    camera_device: String,
+
+    #[cfg(feature = "midi")]
+    /// Controller-to-parameter bindings
+    midi_mapping: MidiMapping,
+    #[cfg(feature = "midi")]
+    /// Connected MIDI input (if any)
+    midi_input: Option<MidiInput>,
+    #[cfg(feature = "midi")]
+    /// Available MIDI input ports, refreshed on demand
+    midi_ports: Vec<String>,
+    #[cfg(feature = "midi")]
+    /// Parameter selected for MIDI learn
+    midi_learn_param: &'static str,
+    #[cfg(feature = "midi")]
+    /// Whether the next control change is bound to `midi_learn_param`
+    midi_learning: bool,
+
+    /// Post-effect plugins applied to the output
+    plugins: PluginManager,
+
+    /// Whether the watermark is stamped onto the output
+    watermark_enabled: bool,
+    /// Signature stamped after the ASCII pass and plugins
+    watermark: Watermark,
+
+    /// Painted region with its alternate settings
+    brush: RegionBrush,
+
+    /// Thumbnails of the image under perturbed settings
+    variations: Variations,
+
+    /// Session left by a previous run that crashed, awaiting the user's choice
+    pending_restore: Option<Session>,
+    /// When the session was last checked for autosave
+    last_autosave: Instant,
+    /// Contents of the last successful autosave
+    last_saved_session: String,
+    /// Set once the window is closing, to stop autosaving
+    closing: bool,
+    /// Set after an autosave failed, so a persistent error is reported once
+    autosave_failed: bool,
 
     /// Last processing time in milliseconds
     last_process_time_ms: f64,
@@ -35,13 +143,48 @@ impl Default for AsciiApp {
     fn default() -> Self {
         Self {
             input_image: None,
+            image_path: None,
             output_image: None,
+            output_preview: None,
             config: AsciiConfig::default(),
+            processor: Processor::new(&AsciiConfig::default(), true),
+            job: None,
             input_texture: None,
             output_texture: None,
             auto_process: false,
             needs_reprocess: false,
             preserve_original_colors: true,
+            color_vision: ColorVision::Normal,
+            eyedropper: None,
+            eyedropper_tile: false,
+            video: None,
+            camera_device: if cfg!(target_os = "macos") {
+                "0"
+            } else {
+                "/dev/video0"
+            }
+            .to_string(),
+            video_settings: VideoSettings::default(),
+            #[cfg(feature = "midi")]
+            midi_mapping: MidiMapping::default(),
+            #[cfg(feature = "midi")]
+            midi_input: None,
+            #[cfg(feature = "midi")]
+            midi_ports: MidiInput::port_names(),
+            #[cfg(feature = "midi")]
+            midi_learn_param: "sigma",
+            #[cfg(feature = "midi")]
+            midi_learning: false,
+            plugins: PluginManager::default(),
+            watermark_enabled: false,
+            watermark: Watermark::text(""),
+            brush: RegionBrush::default(),
+            variations: Variations::default(),
+            pending_restore: None,
+            last_autosave: Instant::now(),
+            last_saved_session: String::new(),
+            closing: false,
+            autosave_failed: false,
             last_process_time_ms: 0.0,
             error_message: None,
         }
@@ -51,32 +194,40 @@ impl Default for AsciiApp {
 impl AsciiApp {
     /// Create a new ASCII renderer application
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self::default()
+        Self {
+            pending_restore: Session::load_unfinished(),
+            ..Self::default()
+        }
     }
 
     /// Load an image from file path
     pub fn load_image(&mut self, path: &std::path::Path) {
+        self.video = None;
         match image::open(path) {
             Ok(img) => {
                 let rgba = img.to_rgba8();
                 let (width, height) = rgba.dimensions();
 
-                // Check if dimensions need adjustment (not multiples of 8)
-                let target_width = (width / 8) * 8;
-                let target_height = (height / 8) * 8;
+                // Check if dimensions need adjustment (not multiples of the tile size)
+                let (tile_w, tile_h) = self.config.tile_dimensions();
+                let target_width = (width / tile_w) * tile_w;
+                let target_height = (height / tile_h) * tile_h;
 
                 if width != target_width || height != target_height {
                     self.error_message = Some(format!(
-                        "Image will be automatically resized from {}x{} to {}x{} (nearest multiple of 8)",
-                        width, height, target_width, target_height
+                        "Image will be automatically resized from {}x{} to {}x{} (nearest multiple of {}x{})",
+                        width, height, target_width, target_height, tile_w, tile_h
                     ));
                 } else {
                     self.error_message = None;
                 }
 
                 self.input_image = Some(rgba);
+                self.image_path = Some(path.to_path_buf());
                 self.input_texture = None; // Clear old texture
                 self.output_texture = None;
+                self.variations.input_changed = true;
+                self.brush.clear();
                 self.needs_reprocess = true;
             }
             Err(e) => {
@@ -85,39 +236,742 @@ impl AsciiApp {
         }
     }
 
-    /// Save the output image to file
+    /// Start playing a video file as the input
+    pub fn load_video(&mut self, path: &std::path::Path) {
+        match VideoSource::open(path) {
+            Ok(video) => {
+                self.video = Some(video);
+                self.image_path = None;
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to open video: {}", e));
+            }
+        }
+    }
+
+    /// Open a camera as live source, mirrored like a webcam preview
+    fn load_camera(&mut self) {
+        match VideoSource::open_camera(&self.camera_device) {
+            Ok(video) => {
+                self.video = Some(video);
+                self.video_settings.mirror = true;
+                self.image_path = None;
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to open camera: {}", e));
+            }
+        }
+    }
+
+    /// Take the next video frame (if due) and process it
+    fn update_video(&mut self, ctx: &egui::Context) {
+        let Some(video) = self.video.as_mut() else {
+            return;
+        };
+
+        match video.poll(&self.video_settings) {
+            Ok(Some(frame)) => {
+                self.input_image = Some(frame);
+                self.input_texture = None;
+                self.process();
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.error_message = Some(format!("Video playback stopped: {}", e));
+                self.video = None;
+                return;
+            }
+        }
+
+        // Keep polling for new frames
+        ctx.request_repaint_after(std::time::Duration::from_millis(5));
+    }
+
+    #[cfg(feature = "midi")]
+    /// Apply pending MIDI control changes (or bind one while learning)
+    fn update_midi(&mut self) {
+        let Some(input) = self.midi_input.as_ref() else {
+            return;
+        };
+
+        for (channel, controller, value) in input.drain() {
+            if self.midi_learning {
+                self.midi_mapping
+                    .bind(channel, controller, self.midi_learn_param);
+                self.midi_learning = false;
+            } else if self
+                .midi_mapping
+                .apply(&mut self.config, channel, controller, value)
+            {
+                self.needs_reprocess = true;
+            }
+        }
+    }
+
+    /// Load a config file and the MIDI mapping saved next to it (if any), or
+    /// the config embedded in a saved output PNG
+    fn load_config(&mut self, path: &std::path::Path) {
+        let from_png = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+        let loaded = if from_png {
+            AsciiConfig::from_png_metadata(path)
+        } else {
+            AsciiConfig::from_path(path)
+        };
+        self.config = match loaded {
+            Ok(config) => config,
+            Err(e) => {
+                self.error_message = Some(e.to_string());
+                return;
+            }
+        };
+        self.needs_reprocess = true;
+
+        #[cfg(feature = "midi")]
+        {
+            let mapping_path = MidiMapping::path_for_config(path);
+            if mapping_path.exists() {
+                match MidiMapping::load(&mapping_path) {
+                    Ok(mapping) => self.midi_mapping = mapping,
+                    Err(e) => self.error_message = Some(e),
+                }
+            }
+        }
+    }
+
+    /// Save the config, plus the MIDI mapping next to it when there are bindings
+    fn save_config(&self, path: &std::path::Path) -> Result<(), String> {
+        self.config.save_to_path(path)?;
+        #[cfg(feature = "midi")]
+        if !self.midi_mapping.bindings.is_empty() {
+            self.midi_mapping
+                .save(&MidiMapping::path_for_config(path))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "midi")]
+    /// Render the MIDI connection and mapping controls
+    fn render_midi_controls(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("MIDI", |ui| {
+            match &self.midi_input {
+                Some(input) => {
+                    ui.label(format!("Connected: {}", input.port_name));
+                    if ui.button("Disconnect").clicked() {
+                        self.midi_input = None;
+                        self.midi_learning = false;
+                    }
+                }
+                None => {
+                    ui.horizontal(|ui| {
+                        if ui.button("Refresh").clicked() {
+                            self.midi_ports = MidiInput::port_names();
+                        }
+                        if self.midi_ports.is_empty() {
+                            ui.label("No MIDI inputs found");
+                        }
+                    });
+
+                    let mut connect_to = None;
+                    for port in &self.midi_ports {
+                        if ui.button(format!("Connect {}", port)).clicked() {
+                            connect_to = Some(port.clone());
+                        }
+                    }
+                    if let Some(port) = connect_to {
+                        match MidiInput::connect(&port, ui.ctx().clone()) {
+                            Ok(input) => self.midi_input = Some(input),
+                            Err(e) => self.error_message = Some(e),
+                        }
+                    }
+                }
+            }
+
+            ui.separator();
+
+            ui.add_enabled_ui(self.midi_input.is_some(), |ui| {
+                egui::ComboBox::from_label("Parameter")
+                    .selected_text(self.midi_learn_param)
+                    .show_ui(ui, |ui| {
+                        for name in learnable_params() {
+                            ui.selectable_value(&mut self.midi_learn_param, name, name);
+                        }
+                    });
+
+                if self.midi_learning {
+                    ui.label("Move a knob or fader...");
+                    if ui.button("Cancel").clicked() {
+                        self.midi_learning = false;
+                    }
+                } else if ui
+                    .button("Learn")
+                    .on_hover_text("Bind the next control you move to this parameter")
+                    .clicked()
+                {
+                    self.midi_learning = true;
+                }
+            });
+
+            let mut remove = None;
+            for (i, binding) in self.midi_mapping.bindings.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Ch {} CC {} → {}",
+                        binding.channel + 1,
+                        binding.controller,
+                        binding.param
+                    ));
+                    if ui.small_button("✕").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove {
+                self.midi_mapping.bindings.remove(i);
+            }
+        });
+    }
+
+    /// Render the post-effect plugin manager
+    fn render_plugin_controls(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        ui.collapsing("Post Effects", |ui| {
+            if ui
+                .button("Load Plugin...")
+                .on_hover_text("Load a post-processing plugin library")
+                .clicked()
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Plugins", &["so", "dylib", "dll"])
+                    .pick_file()
+            {
+                match self.plugins.load(&path) {
+                    Ok(()) => changed = true,
+                    Err(e) => self.error_message = Some(e),
+                }
+            }
+
+            if self.plugins.plugins.is_empty() {
+                ui.label("No plugins loaded");
+            }
+
+            let count = self.plugins.plugins.len();
+            let mut move_up = None;
+            let mut remove = None;
+            for (i, plugin) in self.plugins.plugins.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .checkbox(&mut plugin.enabled, plugin.name.as_str())
+                        .on_hover_text(plugin.path.display().to_string())
+                        .changed();
+                    if ui
+                        .add_enabled(i > 0, egui::Button::new("⏶").small())
+                        .on_hover_text("Run earlier")
+                        .clicked()
+                    {
+                        move_up = Some(i);
+                    }
+                    if ui
+                        .add_enabled(i + 1 < count, egui::Button::new("⏷").small())
+                        .on_hover_text("Run later")
+                        .clicked()
+                    {
+                        move_up = Some(i + 1);
+                    }
+                    if ui.small_button("✕").on_hover_text("Unload").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+
+            if let Some(i) = move_up {
+                self.plugins.plugins.swap(i - 1, i);
+                changed = true;
+            }
+            if let Some(i) = remove {
+                self.plugins.plugins.remove(i);
+                changed = true;
+            }
+        });
+
+        changed
+    }
+
+    /// Render the region brush and the settings used inside the painted region
+    fn render_brush_controls(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        ui.collapsing("Region Brush", |ui| {
+            ui.checkbox(&mut self.brush.painting, "Paint Regions")
+                .on_hover_text(
+                    "Drag on the original image to mark where the region settings apply",
+                );
+
+            ui.add_enabled_ui(self.brush.painting, |ui| {
+                ui.checkbox(&mut self.brush.erasing, "Erase");
+                ui.add(egui::Slider::new(&mut self.brush.radius, 2.0..=128.0).text("Brush Radius"));
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Clear Mask").clicked() {
+                    self.brush.clear();
+                    changed = true;
+                }
+                if ui
+                    .button("Copy Current Settings")
+                    .on_hover_text("Start the region settings from the main settings")
+                    .clicked()
+                {
+                    self.brush.config = self.config.clone();
+                    changed = true;
+                }
+            });
+
+            ui.separator();
+            ui.label("Inside the region:");
+
+            let config = &mut self.brush.config;
+            changed |= ui
+                .add(egui::Slider::new(&mut config.edge_threshold, 0..=64).text("Edge Threshold"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut config.sigma, 0.0..=5.0).text("Sigma"))
+                .changed();
+            changed |= ui.checkbox(&mut config.draw_edges, "Draw Edges").changed();
+            changed |= ui.checkbox(&mut config.draw_fill, "Draw Fill").changed();
+            changed |= ui
+                .checkbox(&mut config.invert_luminance, "Invert Luminance")
+                .changed();
+        });
+
+        changed
+    }
+
+    /// Show the brush mask over the original image and paint where dragged
+    fn paint_region(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        let Some(input) = self.input_image.as_ref() else {
+            return;
+        };
+        let (width, height) = input.dimensions();
+
+        if self.brush.painting
+            && self.eyedropper.is_none()
+            && (response.dragged() || response.clicked())
+            && let Some(pos) = response.interact_pointer_pos()
+        {
+            let relative = (pos - response.rect.min) / response.rect.size();
+            self.brush.paint(
+                relative.x * width as f32,
+                relative.y * height as f32,
+                width,
+                height,
+            );
+            self.needs_reprocess = true;
+        }
+
+        if self.brush.texture.is_none()
+            && let Some(overlay) = self.brush.overlay()
+        {
+            self.brush.texture = Some(ui.ctx().load_texture(
+                "brush mask",
+                Self::rgba_to_color_image(&overlay),
+                egui::TextureOptions::default(),
+            ));
+        }
+        if let Some(texture) = &self.brush.texture {
+            ui.painter().image(
+                texture.id(),
+                response.rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+
+    /// Assign the clicked color of the original image while the eyedropper is armed
+    fn pick_color(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        let (Some(target), Some(input)) = (self.eyedropper, self.input_image.as_ref()) else {
+            return;
+        };
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+        }
+        if !response.clicked() {
+            return;
+        }
+        let Some(pos) = response.interact_pointer_pos() else {
+            return;
+        };
+
+        let relative = (pos - response.rect.min) / response.rect.size();
+        let (width, height) = input.dimensions();
+        let x = ((relative.x * width as f32) as u32).min(width - 1);
+        let y = ((relative.y * height as f32) as u32).min(height - 1);
+        let color = sample_color(
+            input,
+            x,
+            y,
+            self.eyedropper_tile
+                .then_some(self.config.tile_dimensions()),
+        );
+        match target {
+            ColorTarget::Ascii => self.config.ascii_color = color,
+            ColorTarget::Background => self.config.bg_color = color,
+        }
+        self.eyedropper = None;
+        self.needs_reprocess = true;
+    }
+
+    /// Toggle button arming the eyedropper for `target`
+    fn eyedropper_button(&mut self, ui: &mut egui::Ui, target: ColorTarget) {
+        let armed = self.eyedropper == Some(target);
+        if ui
+            .selectable_label(armed, "Pick")
+            .on_hover_text("Click the original image to sample this color")
+            .clicked()
+        {
+            self.eyedropper = if armed { None } else { Some(target) };
+        }
+    }
+
+    /// Render the watermark settings
+    fn render_watermark_controls(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        ui.collapsing("Watermark", |ui| {
+            changed |= ui
+                .checkbox(&mut self.watermark_enabled, "Stamp Watermark")
+                .on_hover_text("Overlay a signature on the rendered output")
+                .changed();
+
+            ui.horizontal(|ui| match &mut self.watermark.content {
+                WatermarkContent::Text(text) => {
+                    ui.label("Text");
+                    changed |= ui.text_edit_singleline(text).changed();
+                }
+                WatermarkContent::Image(image) => {
+                    ui.label(format!("Image {}x{}", image.width(), image.height()));
+                    if ui.small_button("Use Text").clicked() {
+                        self.watermark.content = WatermarkContent::Text(String::new());
+                        changed = true;
+                    }
+                }
+            });
+
+            if ui.button("Load Image...").clicked()
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Images", &["png", "jpg", "jpeg", "bmp", "gif", "webp"])
+                    .pick_file()
+            {
+                match image::open(&path) {
+                    Ok(img) => {
+                        self.watermark.content = WatermarkContent::Image(img.to_rgba8());
+                        changed = true;
+                    }
+                    Err(e) => self.error_message = Some(format!("Failed to load image: {}", e)),
+                }
+            }
+
+            egui::ComboBox::from_label("Corner")
+                .selected_text(format!("{:?}", self.watermark.corner))
+                .show_ui(ui, |ui| {
+                    for corner in [
+                        WatermarkCorner::TopLeft,
+                        WatermarkCorner::TopRight,
+                        WatermarkCorner::BottomLeft,
+                        WatermarkCorner::BottomRight,
+                    ] {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.watermark.corner,
+                                corner,
+                                format!("{:?}", corner),
+                            )
+                            .changed();
+                    }
+                });
+
+            changed |= ui
+                .add(egui::Slider::new(&mut self.watermark.opacity, 0.0..=1.0).text("Opacity"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut self.watermark.scale, 1..=8).text("Text Size"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut self.watermark.margin, 0..=64).text("Margin"))
+                .changed();
+
+            ui.horizontal(|ui| {
+                let mut color = self.watermark.color.map(|c| c as f32 / 255.0);
+                if ui.color_edit_button_rgb(&mut color).changed() {
+                    self.watermark.color = color.map(|c| (c * 255.0) as u8);
+                    changed = true;
+                }
+                ui.label("Text Color");
+            });
+        });
+
+        changed
+    }
+
+    /// Current state as a restorable session
+    fn session(&self) -> Session {
+        Session {
+            image_path: self.image_path.clone(),
+            video_path: self.video.as_ref().and_then(|video| video.path.clone()),
+            preserve_original_colors: self.preserve_original_colors,
+            config: self.config.clone(),
+        }
+    }
+
+    /// Periodically write the session to disk when it has changed; the first
+    /// failure is shown and stops autosaving
+    fn autosave(&mut self) {
+        // Keep the crashed session on disk until the user has decided about it
+        if self.closing
+            || self.autosave_failed
+            || self.pending_restore.is_some()
+            || self.last_autosave.elapsed() < AUTOSAVE_INTERVAL
+        {
+            return;
+        }
+        self.last_autosave = Instant::now();
+
+        let result = self.session().to_toml().and_then(|contents| {
+            if contents != self.last_saved_session {
+                Session::write(&contents)?;
+                self.last_saved_session = contents;
+            }
+            Ok(())
+        });
+        // Stop instead of failing again every interval (e.g. a read-only
+        // state directory)
+        if let Err(e) = result {
+            self.error_message = Some(format!("Autosave stopped: {}", e));
+            self.autosave_failed = true;
+        }
+    }
+
+    /// Apply a restored session
+    fn restore_session(&mut self, session: Session) {
+        self.config = session.config;
+        self.preserve_original_colors = session.preserve_original_colors;
+        if let Some(path) = session.video_path {
+            self.load_video(&path);
+        } else if let Some(path) = session.image_path {
+            self.load_image(&path);
+        }
+        self.needs_reprocess = true;
+    }
+
+    /// Ask whether to restore the session of a run that did not exit cleanly
+    fn render_restore_prompt(&mut self, ctx: &egui::Context) {
+        let Some(session) = self.pending_restore.as_ref() else {
+            return;
+        };
+
+        let mut choice = None;
+        egui::Window::new("Restore previous session?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("ASCII Renderer did not exit cleanly last time.");
+                if let Some(path) = session.video_path.as_ref().or(session.image_path.as_ref()) {
+                    ui.label(format!("Open file: {}", path.display()));
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Discard").clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+
+        match choice {
+            Some(true) => {
+                if let Some(session) = self.pending_restore.take() {
+                    self.restore_session(session);
+                }
+            }
+            Some(false) => self.pending_restore = None,
+            None => {}
+        }
+    }
+
+    /// Save the output image to file, with the config embedded in PNGs
     pub fn save_output(&self, path: &std::path::Path) -> Result<(), String> {
+        let is_png = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
         match &self.output_image {
+            Some(img) if is_png => save_png_with_config(img, &self.config, path),
             Some(img) => img.save(path).map_err(|e| format!("Failed to save: {}", e)),
             None => Err("No output image to save".to_string()),
         }
     }
 
+    /// Save a looping before/after clip of the input and the current output
+    fn export_before_after(
+        &self,
+        path: &std::path::Path,
+        transition: Transition,
+    ) -> Result<(), String> {
+        let (Some(input), Some(output)) = (&self.input_image, &self.output_image) else {
+            return Err("No output image to export".to_string());
+        };
+        // Three seconds at 16 fps
+        BeforeAfter::from_images(input, output.clone())
+            .with_transition(transition)
+            .save_animation(path, 48, 16.0)
+    }
+
     /// Process the input image with current configuration
+    ///
+    /// Still images without brush regions are converted on a worker thread,
+    /// see [`Self::poll_process_job`]; video frames and brushed images are
+    /// converted right away.
     fn process(&mut self) {
-        if let Some(ref input) = self.input_image {
-            let start = Instant::now();
-
-            match self.config.validate() {
-                Ok(_) => {
-                    let output = if self.preserve_original_colors {
-                        process_image_preserve_colors(input, &self.config)
-                    } else {
-                        process_image(input, &self.config)
-                    };
-                    self.last_process_time_ms = start.elapsed().as_secs_f64() * 1000.0;
-                    self.output_image = Some(output);
-                    self.output_texture = None; // Clear old texture
-                    self.needs_reprocess = false;
-                    self.error_message = None;
+        let Some(ref input) = self.input_image else {
+            return;
+        };
+        let start = Instant::now();
+
+        let regions: Vec<RegionOverride> = self.brush.region().into_iter().collect();
+        if regions.is_empty() && self.video.is_none() {
+            if let Err(e) = self.config.validate() {
+                self.error_message = Some(format!("Invalid config: {}", e));
+                return;
+            }
+            self.start_process_job(input.clone(), start);
+            return;
+        }
+
+        let result = if regions.is_empty() {
+            self.config.validate().map(|()| {
+                self.processor.set_config(&self.config);
+                self.processor
+                    .set_preserve_colors(self.preserve_original_colors);
+                self.processor.process(input).render_image()
+            })
+        } else {
+            process_image_regions(input, &self.config, self.preserve_original_colors, &regions)
+        };
+        self.finish_process(result, start);
+    }
+
+    /// Converts `input` on a worker thread
+    ///
+    /// A conversion still running is cancelled, and this one is deferred
+    /// until the worker hands the processor back.
+    fn start_process_job(&mut self, input: RgbaImage, start: Instant) {
+        if let Some(job) = self.job.as_mut() {
+            job.cancel.cancel();
+            job.rerun = true;
+            self.needs_reprocess = false;
+            return;
+        }
+
+        // The worker borrows the processor and hands it back with the output
+        let mut processor = std::mem::replace(
+            &mut self.processor,
+            Processor::new(&self.config, self.preserve_original_colors),
+        );
+        processor.set_config(&self.config);
+        processor.set_preserve_colors(self.preserve_original_colors);
+
+        let cancel = CancelToken::new();
+        let progress = Arc::new(Mutex::new((PipelineStage::Luminance, 0.0)));
+        let (sender, result) = mpsc::channel();
+        let worker_cancel = cancel.clone();
+        let worker_progress = Arc::clone(&progress);
+        std::thread::spawn(move || {
+            let output = processor
+                .process_with_progress(&input, &worker_cancel, &mut |stage, fraction| {
+                    *worker_progress.lock().unwrap() = (stage, fraction);
+                })
+                .map(|art| art.render_image());
+            // The receiver is gone when a newer job replaced this one
+            let _ = sender.send((processor, output));
+        });
+
+        self.job = Some(ProcessJob {
+            cancel,
+            progress,
+            result,
+            start,
+            rerun: false,
+        });
+        self.needs_reprocess = false;
+    }
+
+    /// Picks up the output of a finished worker conversion
+    fn poll_process_job(&mut self, ctx: &egui::Context) {
+        let Some(job) = self.job.as_ref() else {
+            return;
+        };
+        match job.result.try_recv() {
+            Ok((processor, result)) => {
+                let (start, rerun) = (job.start, job.rerun);
+                self.job = None;
+                self.processor = processor;
+                match result {
+                    // The output is stale; convert again with the current settings
+                    _ if rerun => self.process(),
+                    Err(AsciiError::Cancelled) => {}
+                    result => self.finish_process(result, start),
+                }
+            }
+            Err(TryRecvError::Empty) => {
+                ctx.request_repaint_after(Duration::from_millis(16));
+            }
+            Err(TryRecvError::Disconnected) => {
+                self.job = None;
+                self.error_message = Some("Processing failed".to_string());
+            }
+        }
+    }
+
+    /// Shows the processed output, or the error that prevented it
+    fn finish_process(&mut self, result: Result<RgbaImage, AsciiError>, start: Instant) {
+        match result {
+            Ok(mut output) => {
+                if self.plugins.is_active() {
+                    self.plugins.apply(&mut output);
                 }
-                Err(e) => {
-                    self.error_message = Some(format!("Invalid config: {}", e));
+                if self.watermark_enabled {
+                    self.watermark.apply(&mut output);
                 }
+                self.last_process_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+                self.output_image = Some(output);
+                self.update_preview();
+                self.needs_reprocess = false;
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Invalid config: {}", e));
             }
         }
     }
 
+    /// Rebuild the simulated output preview and clear the old texture
+    fn update_preview(&mut self) {
+        self.output_preview = match (&self.output_image, self.color_vision) {
+            (_, ColorVision::Normal) | (None, _) => None,
+            (Some(output), vision) => {
+                let mut preview = output.clone();
+                simulate_color_vision(&mut preview, vision);
+                Some(preview)
+            }
+        };
+        self.output_texture = None;
+    }
+
     /// Render the control panel UI
     fn render_controls(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
@@ -125,6 +979,46 @@ impl AsciiApp {
         ui.heading("Controls");
         ui.separator();
 
+        // Video source settings (only while a video is playing)
+        if let Some(video) = self.video.as_mut() {
+            ui.collapsing("Video Source", |ui| {
+                ui.label(format!(
+                    "Source: {}x{} @ {:.1} fps",
+                    video.width, video.height, video.frame_rate
+                ));
+
+                egui::ComboBox::from_label("Resolution")
+                    .selected_text(format!("{:.0}%", self.video_settings.scale * 100.0))
+                    .show_ui(ui, |ui| {
+                        for scale in [1.0, 0.75, 0.5, 0.25] {
+                            ui.selectable_value(
+                                &mut self.video_settings.scale,
+                                scale,
+                                format!("{:.0}%", scale * 100.0),
+                            );
+                        }
+                    });
+
+                ui.add(egui::Slider::new(&mut self.video_settings.fps_cap, 1..=60).text("FPS Cap"))
+                    .on_hover_text("Maximum frames per second to process");
+
+                ui.checkbox(&mut self.video_settings.mirror, "Mirror")
+                    .on_hover_text("Flip frames horizontally");
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("Dropped frames: {}", video.dropped_frames()))
+                        .on_hover_text(
+                            "Source frames skipped because processing could not keep up",
+                        );
+                    if ui.small_button("Reset").clicked() {
+                        video.reset_dropped_frames();
+                    }
+                });
+            });
+
+            ui.add_space(8.0);
+        }
+
         // Blur settings
         ui.collapsing("Blur Settings", |ui| {
             changed |= ui
@@ -141,20 +1035,220 @@ impl AsciiApp {
                 .add(egui::Slider::new(&mut self.config.sigma_scale, 0.0..=5.0).text("Sigma Scale"))
                 .on_hover_text("Scale for second Gaussian in DoG")
                 .changed();
+
+            egui::ComboBox::from_label("Blur")
+                .selected_text(format!("{:?}", self.config.blur_mode))
+                .show_ui(ui, |ui| {
+                    for mode in [BlurMode::Gaussian, BlurMode::Box] {
+                        changed |= ui
+                            .selectable_value(&mut self.config.blur_mode, mode, format!("{:?}", mode))
+                            .on_hover_text(
+                                "Box approximates the Gaussians with three fast box blurs, for previews and video",
+                            )
+                            .changed();
+                    }
+                });
+
+            let mut median = self.config.median_radius.is_some();
+            if ui
+                .checkbox(&mut median, "Despeckle")
+                .on_hover_text("Median filter against salt-and-pepper noise before edge detection")
+                .changed()
+            {
+                self.config.median_radius = median.then_some(1);
+                changed = true;
+            }
+
+            if let Some(radius) = self.config.median_radius.as_mut() {
+                changed |= ui
+                    .add(egui::Slider::new(radius, 1..=3).text("Median Radius"))
+                    .on_hover_text("Window radius of the median filter in pixels")
+                    .changed();
+            }
+
+            let selected = match self.config.pre_filter {
+                PreFilter::None => "None",
+                PreFilter::Bilateral { .. } => "Bilateral",
+                PreFilter::Kuwahara { .. } => "Kuwahara",
+            };
+            egui::ComboBox::from_label("Pre-filter")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (filter, label) in [
+                        (PreFilter::None, "None"),
+                        (PreFilter::BILATERAL, "Bilateral"),
+                        (PreFilter::KUWAHARA, "Kuwahara"),
+                    ] {
+                        let current = label == selected;
+                        if ui.selectable_label(current, label).clicked() && !current {
+                            self.config.pre_filter = filter;
+                            changed = true;
+                        }
+                    }
+                });
+
+            match &mut self.config.pre_filter {
+                PreFilter::None => {}
+                PreFilter::Bilateral {
+                    sigma_space,
+                    sigma_range,
+                } => {
+                    changed |= ui
+                        .add(egui::Slider::new(sigma_space, 0.5..=8.0).text("Spatial Sigma"))
+                        .on_hover_text("Neighborhood spread of the bilateral filter in pixels")
+                        .changed();
+                    changed |= ui
+                        .add(egui::Slider::new(sigma_range, 0.01..=1.0).text("Range Sigma"))
+                        .on_hover_text(
+                            "Luminance difference still smoothed across; lower keeps more edges",
+                        )
+                        .changed();
+                }
+                PreFilter::Kuwahara { radius, sectors } => {
+                    changed |= ui
+                        .add(egui::Slider::new(radius, 1..=16).text("Kuwahara Radius"))
+                        .on_hover_text("Size of the painted patches in pixels")
+                        .changed();
+                    changed |= ui
+                        .add(egui::Slider::new(sectors, 4..=16).text("Sectors"))
+                        .on_hover_text("Wedges compared around each pixel")
+                        .changed();
+                }
+            }
         });
 
         ui.add_space(8.0);
 
         // Edge detection settings
         ui.collapsing("Edge Detection", |ui| {
+            let selected = match self.config.edge_mode {
+                EdgeMode::Dog => "DoG",
+                EdgeMode::Xdog { .. } => "XDoG",
+                EdgeMode::Sobel { .. } => "Sobel",
+            };
+            egui::ComboBox::from_label("Edge Mode")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (mode, label) in [
+                        (EdgeMode::Dog, "DoG"),
+                        (EdgeMode::XDOG, "XDoG"),
+                        (EdgeMode::SOBEL, "Sobel"),
+                    ] {
+                        let current = label == selected;
+                        if ui.selectable_label(current, label).clicked() && !current {
+                            self.config.edge_mode = mode;
+                            changed = true;
+                        }
+                    }
+                });
+
             changed |= ui
                 .add(egui::Slider::new(&mut self.config.tau, 0.0..=1.1).text("Tau"))
                 .on_hover_text("DoG threshold multiplier")
                 .changed();
 
+            match &mut self.config.edge_mode {
+                EdgeMode::Dog => {
+                    let selected = match self.config.threshold_mode {
+                        ThresholdMode::Fixed => "Fixed",
+                        ThresholdMode::Otsu => "Otsu",
+                        ThresholdMode::Percentile { .. } => "Percentile",
+                    };
+                    egui::ComboBox::from_label("Threshold Mode")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for (mode, label) in [
+                                (ThresholdMode::Fixed, "Fixed"),
+                                (ThresholdMode::Otsu, "Otsu"),
+                                (ThresholdMode::PERCENTILE, "Percentile"),
+                            ] {
+                                let current = label == selected;
+                                if ui.selectable_label(current, label).clicked() && !current {
+                                    self.config.threshold_mode = mode;
+                                    changed = true;
+                                }
+                            }
+                        });
+
+                    match &mut self.config.threshold_mode {
+                        ThresholdMode::Fixed => {
+                            changed |= ui
+                                .add(
+                                    egui::Slider::new(&mut self.config.threshold, 0.001..=0.1)
+                                        .text("Threshold"),
+                                )
+                                .on_hover_text("DoG binary threshold")
+                                .changed();
+                        }
+                        ThresholdMode::Otsu => {}
+                        ThresholdMode::Percentile { percentile } => {
+                            changed |= ui
+                                .add(egui::Slider::new(percentile, 50.0..=99.9).text("Percentile"))
+                                .on_hover_text("Share of pixels below the DoG threshold")
+                                .changed();
+                        }
+                    }
+                }
+                EdgeMode::Xdog { phi, epsilon } => {
+                    changed |= ui
+                        .add(
+                            egui::Slider::new(phi, 1.0..=100_000.0)
+                                .logarithmic(true)
+                                .text("Phi"),
+                        )
+                        .on_hover_text("Steepness of the soft falloff below epsilon")
+                        .changed();
+                    changed |= ui
+                        .add(egui::Slider::new(epsilon, 0.001..=0.1).text("Epsilon"))
+                        .on_hover_text("DoG value at which edges become fully white")
+                        .changed();
+                }
+                EdgeMode::Sobel { threshold } => {
+                    changed |= ui
+                        .add(egui::Slider::new(threshold, 0.01..=4.0).text("Gradient Threshold"))
+                        .on_hover_text("Luminance gradient a pixel needs to count as an edge")
+                        .changed();
+                }
+            }
+
+            if !matches!(self.config.edge_mode, EdgeMode::Sobel { .. }) {
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.config.edge_dilation, -3..=3)
+                            .text("Line Weight"),
+                    )
+                    .on_hover_text("Thicken (positive) or thin (negative) the DoG edge lines")
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.config.sobel_threshold, 0.0..=4.0)
+                            .logarithmic(true)
+                            .text("Sobel Threshold"),
+                    )
+                    .on_hover_text("Gradient magnitude an edge pixel needs to take part in voting")
+                    .changed();
+            }
+
+            let mut tensor = self.config.structure_tensor_sigma.is_some();
+            if ui
+                .checkbox(&mut tensor, "Smooth Directions")
+                .on_hover_text("Edge directions from a smoothed structure tensor")
+                .changed()
+            {
+                self.config.structure_tensor_sigma = tensor.then_some(1.5);
+                changed = true;
+            }
+
+            if let Some(sigma) = self.config.structure_tensor_sigma.as_mut() {
+                changed |= ui
+                    .add(egui::Slider::new(sigma, 0.5..=5.0).text("Tensor Sigma"))
+                    .on_hover_text("Neighborhood the edge direction is averaged over")
+                    .changed();
+            }
+
             changed |= ui
-                .add(egui::Slider::new(&mut self.config.threshold, 0.001..=0.1).text("Threshold"))
-                .on_hover_text("DoG binary threshold")
+                .checkbox(&mut self.config.non_max_suppression, "Thin Edges")
+                .on_hover_text("Non-maximum suppression: thin edges to one pixel before voting")
                 .changed();
 
             changed |= ui
@@ -162,12 +1256,250 @@ impl AsciiApp {
                     egui::Slider::new(&mut self.config.edge_threshold, 0..=64)
                         .text("Edge Threshold"),
                 )
-                .on_hover_text("Pixels needed in 8x8 tile for edge detection")
+                .on_hover_text(
+                    "Pixels needed in an 8x8 tile for edge detection (scaled to other tile sizes)",
+                )
+                .changed();
+
+            let mut junctions = self.config.edge_junction_margin.is_some();
+            if ui
+                .checkbox(&mut junctions, "Edge Junctions")
+                .on_hover_text("Draw + or X where perpendicular edge directions tie")
+                .changed()
+            {
+                self.config.edge_junction_margin = junctions.then_some(2);
+                changed = true;
+            }
+
+            if let Some(margin) = self.config.edge_junction_margin.as_mut() {
+                changed |= ui
+                    .add(egui::Slider::new(margin, 0..=64).text("Junction Margin"))
+                    .on_hover_text("Maximum vote gap between directions to form a junction")
+                    .changed();
+            }
+
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.config.saliency_strength, 0.0..=1.0)
+                        .text("Saliency"),
+                )
+                .on_hover_text("Give focal subjects more edges than flat backgrounds")
                 .changed();
         });
 
         ui.add_space(8.0);
 
+        // Fill settings
+        ui.collapsing("Fill", |ui| {
+            let selected = match self.config.luminance_mode {
+                LuminanceMode::Rec601 => "Rec. 601",
+                LuminanceMode::Rec709 => "Rec. 709",
+                LuminanceMode::Rec2020 => "Rec. 2020",
+                LuminanceMode::Average => "Average",
+                LuminanceMode::Max => "Max Channel",
+                LuminanceMode::Custom { .. } => "Custom",
+            };
+            egui::ComboBox::from_label("Luminance")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (mode, label) in [
+                        (LuminanceMode::Rec601, "Rec. 601"),
+                        (LuminanceMode::Rec709, "Rec. 709"),
+                        (LuminanceMode::Rec2020, "Rec. 2020"),
+                        (LuminanceMode::Average, "Average"),
+                        (LuminanceMode::Max, "Max Channel"),
+                        (
+                            LuminanceMode::Custom {
+                                weights: [0.2127, 0.7152, 0.0722],
+                            },
+                            "Custom",
+                        ),
+                    ] {
+                        let current = label == selected;
+                        if ui.selectable_label(current, label).clicked() && !current {
+                            self.config.luminance_mode = mode;
+                            changed = true;
+                        }
+                    }
+                });
+
+            if let LuminanceMode::Custom { weights } = &mut self.config.luminance_mode {
+                for (weight, label) in weights.iter_mut().zip(["Red", "Green", "Blue"]) {
+                    changed |= ui
+                        .add(
+                            egui::Slider::new(weight, 0.01..=1.0).text(format!("{} Weight", label)),
+                        )
+                        .changed();
+                }
+            }
+
+            changed |= ui
+                .checkbox(&mut self.config.linear_luminance, "Linear Luminance")
+                .on_hover_text(
+                    "Weight colors in linear light, so saturated colors and mid-tones get the brightness they appear to have",
+                )
+                .changed();
+
+            let selected = match self.config.luminance_adjust {
+                LuminanceAdjust::None => "None",
+                LuminanceAdjust::AutoContrast => "Auto Contrast",
+                LuminanceAdjust::Equalize => "Equalize",
+                LuminanceAdjust::Clahe { .. } => "CLAHE",
+            };
+            egui::ComboBox::from_label("Contrast")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (adjust, label) in [
+                        (LuminanceAdjust::None, "None"),
+                        (LuminanceAdjust::AutoContrast, "Auto Contrast"),
+                        (LuminanceAdjust::Equalize, "Equalize"),
+                        (LuminanceAdjust::CLAHE, "CLAHE"),
+                    ] {
+                        let current = label == selected;
+                        if ui.selectable_label(current, label).clicked() && !current {
+                            self.config.luminance_adjust = adjust;
+                            changed = true;
+                        }
+                    }
+                });
+
+            if let LuminanceAdjust::Clahe { clip_limit, grid } = &mut self.config.luminance_adjust {
+                changed |= ui
+                    .add(egui::Slider::new(clip_limit, 1.0..=16.0).text("Clip Limit"))
+                    .on_hover_text("Caps how strongly CLAHE boosts contrast in flat regions")
+                    .changed();
+                changed |= ui
+                    .add(egui::Slider::new(grid, 1..=32).text("CLAHE Grid"))
+                    .on_hover_text("Regions per side equalized separately")
+                    .changed();
+            }
+
+            changed |= ui
+                .add(egui::Slider::new(&mut self.config.exposure, 0.0..=5.0).text("Exposure"))
+                .on_hover_text("Brighten (>1) or darken (<1) the fill luminance")
+                .changed();
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.config.attenuation, 0.1..=5.0).text("Attenuation"),
+                )
+                .on_hover_text("Exponent on the exposed luminance (>1 darker mid-tones)")
+                .changed();
+
+            egui::ComboBox::from_label("Tile Statistic")
+                .selected_text(format!("{:?}", self.config.tile_statistic))
+                .show_ui(ui, |ui| {
+                    for statistic in [
+                        TileStatistic::Mean,
+                        TileStatistic::Median,
+                        TileStatistic::Max,
+                        TileStatistic::Percentile(90),
+                    ] {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.config.tile_statistic,
+                                statistic,
+                                format!("{:?}", statistic),
+                            )
+                            .changed();
+                    }
+                });
+
+            if let TileStatistic::Percentile(p) = &mut self.config.tile_statistic {
+                changed |= ui
+                    .add(egui::Slider::new(p, 0..=100).text("Percentile"))
+                    .on_hover_text("Rank of the tile pixel used for fill")
+                    .changed();
+            }
+
+            let mut local_contrast = self.config.local_contrast_radius.is_some();
+            if ui
+                .checkbox(&mut local_contrast, "Local Contrast")
+                .on_hover_text(
+                    "Normalize each tile against its neighborhood before picking a character",
+                )
+                .changed()
+            {
+                self.config.local_contrast_radius = local_contrast.then_some(4);
+                changed = true;
+            }
+
+            if let Some(radius) = self.config.local_contrast_radius.as_mut() {
+                changed |= ui
+                    .add(egui::Slider::new(radius, 1..=16).text("Contrast Radius"))
+                    .on_hover_text("Neighborhood radius in tiles")
+                    .changed();
+            }
+
+            let selected = match self.config.charset {
+                Charset::Custom { .. } => "Custom".to_string(),
+                ref charset => format!("{:?}", charset),
+            };
+            egui::ComboBox::from_label("Charset")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for charset in [Charset::Ascii, Charset::Blocks] {
+                        let ramp: String = charset.ramp().iter().collect();
+                        let label = format!("{:?}", charset);
+                        changed |= ui
+                            .selectable_value(&mut self.config.charset, charset, label)
+                            .on_hover_text(ramp)
+                            .changed();
+                    }
+                });
+
+            egui::ComboBox::from_label("Fill Selection")
+                .selected_text(format!("{:?}", self.config.char_selection))
+                .show_ui(ui, |ui| {
+                    for mode in [CharSelection::Luminance, CharSelection::Structure] {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.config.char_selection,
+                                mode,
+                                format!("{:?}", mode),
+                            )
+                            .changed();
+                    }
+                });
+
+            let mut texture = self.config.texture_threshold.is_some();
+            if ui
+                .checkbox(&mut texture, "Texture-Aware Fill")
+                .on_hover_text("Use a denser character on tiles with high luminance variance")
+                .changed()
+            {
+                self.config.texture_threshold = texture.then_some(32);
+                changed = true;
+            }
+
+            if let Some(threshold) = self.config.texture_threshold.as_mut() {
+                changed |= ui
+                    .add(egui::Slider::new(threshold, 1..=128).text("Texture Threshold"))
+                    .on_hover_text("Luminance standard deviation (0-255) that counts as textured")
+                    .changed();
+            }
+
+            changed |= ui
+                .add(egui::Slider::new(&mut self.config.fill_gamma, 0.1..=5.0).text("Fill Gamma"))
+                .on_hover_text("Bias the luminance-to-character mapping (>1 sparser, <1 denser)")
+                .changed();
+
+            egui::ComboBox::from_label("Dither")
+                .selected_text(format!("{:?}", self.config.fill_dither))
+                .show_ui(ui, |ui| {
+                    for mode in [FillDither::Off, FillDither::Ordered, FillDither::Noise] {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.config.fill_dither,
+                                mode,
+                                format!("{:?}", mode),
+                            )
+                            .changed();
+                    }
+                });
+        });
+
+        ui.add_space(8.0);
+
         // Rendering settings
         ui.collapsing("Rendering", |ui| {
             changed |= ui
@@ -175,15 +1507,174 @@ impl AsciiApp {
                 .on_hover_text("Render detected edges as ASCII characters")
                 .changed();
 
+            ui.add_enabled_ui(self.config.draw_edges, |ui| {
+                let selected = match self.config.edge_chars {
+                    EdgeChars::Custom { .. } => "Custom".to_string(),
+                    edge_chars => format!("{:?}", edge_chars),
+                };
+                egui::ComboBox::from_label("Edge Chars")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for edge_chars in
+                            [EdgeChars::Ascii, EdgeChars::BoxDrawing, EdgeChars::Heavy]
+                        {
+                            let chars: String = edge_chars.chars().iter().collect();
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.config.edge_chars,
+                                    edge_chars,
+                                    format!("{:?}", edge_chars),
+                                )
+                                .on_hover_text(chars)
+                                .changed();
+                        }
+                    });
+
+                changed |= ui
+                    .checkbox(&mut self.config.positional_edges, "Positional Edges")
+                    .on_hover_text("Shift edge glyphs onto where the edge crosses the tile")
+                    .changed();
+            });
+
             changed |= ui
                 .checkbox(&mut self.config.draw_fill, "Draw Fill")
                 .on_hover_text("Fill areas with luminance-based ASCII characters")
                 .changed();
 
+            egui::ComboBox::from_label("Composite")
+                .selected_text(format!("{:?}", self.config.composite_mode))
+                .show_ui(ui, |ui| {
+                    for mode in [
+                        CompositeMode::EdgesOverFill,
+                        CompositeMode::FillWhereNoEdge,
+                        CompositeMode::Blend,
+                    ] {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.config.composite_mode,
+                                mode,
+                                format!("{:?}", mode),
+                            )
+                            .changed();
+                    }
+                });
+
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.config.blend_with_base, 0.0..=1.0)
+                        .text("Blend With Base"),
+                )
+                .on_hover_text("Mix the source image into the output")
+                .changed();
+
             changed |= ui
                 .checkbox(&mut self.config.invert_luminance, "Invert Luminance")
                 .on_hover_text("Invert brightness mapping")
                 .changed();
+
+            changed |= ui
+                .checkbox(&mut self.config.auto_invert, "Auto Invert")
+                .on_hover_text(
+                    "Flip the brightness mapping for mostly bright images (documents, whiteboards)",
+                )
+                .changed();
+
+            changed |= ui
+                .checkbox(&mut self.config.edge_debug, "Edge Debug Colors")
+                .on_hover_text(
+                    "Color edge tiles by direction (classic: | red, - green, / blue, \\ yellow) \
+                     and fill tiles by luminance",
+                )
+                .changed();
+
+            ui.add_enabled_ui(self.config.edge_debug, |ui| {
+                egui::ComboBox::from_label("Debug Palette")
+                    .selected_text(format!("{:?}", self.config.palette))
+                    .show_ui(ui, |ui| {
+                        for palette in [Palette::Classic, Palette::OkabeIto, Palette::TolBright] {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.config.palette,
+                                    palette,
+                                    format!("{:?}", palette),
+                                )
+                                .changed();
+                        }
+                    });
+            });
+
+            let mut rgb_split = self.config.rgb_split.is_some();
+            if ui
+                .checkbox(&mut rgb_split, "RGB Split")
+                .on_hover_text(
+                    "Convert the red, green and blue channels separately and overlay them",
+                )
+                .changed()
+            {
+                self.config.rgb_split = rgb_split.then_some(3);
+                changed = true;
+            }
+
+            if let Some(offset) = self.config.rgb_split.as_mut() {
+                changed |= ui
+                    .add(egui::Slider::new(offset, 0..=16).text("Split Offset"))
+                    .on_hover_text("Pixels the red and blue layers are shifted left and right")
+                    .changed();
+            }
+
+            egui::ComboBox::from_label("Output")
+                .selected_text(format!("{:?}", self.config.output_mode))
+                .show_ui(ui, |ui| {
+                    for mode in [OutputMode::Ascii, OutputMode::Braille] {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.config.output_mode,
+                                mode,
+                                format!("{:?}", mode),
+                            )
+                            .on_hover_text(match mode {
+                                OutputMode::Ascii => "One ASCII glyph per tile",
+                                OutputMode::Braille => "One braille character per 2x4 pixels",
+                            })
+                            .changed();
+                    }
+                });
+
+            ui.add_enabled_ui(self.config.output_mode == OutputMode::Ascii, |ui| {
+                egui::ComboBox::from_label("Tile Width")
+                    .selected_text(self.config.tile_size.to_string())
+                    .show_ui(ui, |ui| {
+                        for size in TILE_SIZES {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.config.tile_size,
+                                    size,
+                                    size.to_string(),
+                                )
+                                .changed();
+                        }
+                    });
+                egui::ComboBox::from_label("Tile Height")
+                    .selected_text(
+                        self.config
+                            .tile_height
+                            .map_or("Square".to_string(), |h| h.to_string()),
+                    )
+                    .show_ui(ui, |ui| {
+                        changed |= ui
+                            .selectable_value(&mut self.config.tile_height, None, "Square")
+                            .changed();
+                        for size in TILE_SIZES {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.config.tile_height,
+                                    Some(size),
+                                    size.to_string(),
+                                )
+                                .changed();
+                        }
+                    });
+            });
         });
 
         ui.add_space(8.0);
@@ -216,6 +1707,7 @@ impl AsciiApp {
                     changed = true;
                 }
                 ui.label("ASCII Color");
+                self.eyedropper_button(ui, ColorTarget::Ascii);
 
                 ui.add_space(4.0);
 
@@ -233,9 +1725,76 @@ impl AsciiApp {
                     changed = true;
                 }
                 ui.label("Background Color");
+                self.eyedropper_button(ui, ColorTarget::Background);
+
+                ui.checkbox(&mut self.eyedropper_tile, "Pick Tile Average")
+                    .on_hover_text("Sample the mean color of the tile instead of one pixel");
             });
+
+            ui.add_space(4.0);
+
+            let vision = self.color_vision;
+            egui::ComboBox::from_label("Simulate Vision")
+                .selected_text(vision.name())
+                .show_ui(ui, |ui| {
+                    for mode in ColorVision::ALL {
+                        ui.selectable_value(&mut self.color_vision, mode, mode.name());
+                    }
+                });
+            if self.color_vision != vision {
+                // Preview only: the saved output keeps its real colors
+                self.update_preview();
+            }
         });
 
+        ui.add_space(8.0);
+
+        // Post-effect plugins
+        changed |= self.render_plugin_controls(ui);
+
+        ui.add_space(8.0);
+
+        // Watermark
+        changed |= self.render_watermark_controls(ui);
+
+        ui.add_space(8.0);
+
+        // Region brush
+        changed |= self.render_brush_controls(ui);
+
+        ui.add_space(8.0);
+
+        // MIDI controller mapping
+        #[cfg(feature = "midi")]
+        {
+            self.render_midi_controls(ui);
+            ui.add_space(8.0);
+        }
+
+        // Exploration
+        ui.horizontal(|ui| {
+            if ui
+                .button("I'm Feeling Lucky")
+                .on_hover_text("Replace all settings with a random valid configuration")
+                .clicked()
+            {
+                self.config = AsciiConfig::random(Self::exploration_seed());
+                changed = true;
+            }
+
+            if ui
+                .button("Mutate")
+                .on_hover_text("Nudge the current settings slightly")
+                .clicked()
+            {
+                self.config = self.config.mutate(Self::exploration_seed(), 0.1);
+                changed = true;
+            }
+        });
+
+        ui.checkbox(&mut self.variations.open, "Show Variations")
+            .on_hover_text("Preview the image under nearby settings; click one to adopt it");
+
         ui.add_space(16.0);
         ui.separator();
 
@@ -250,6 +1809,21 @@ impl AsciiApp {
             }
         });
 
+        // Progress of a conversion running in the background
+        if let Some(job) = self.job.as_ref() {
+            let (_, fraction) = *job.progress.lock().unwrap();
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::ProgressBar::new(fraction)
+                        .desired_width(160.0)
+                        .show_percentage(),
+                );
+                if ui.button("Cancel").clicked() {
+                    job.cancel.cancel();
+                }
+            });
+        }
+
         // Show processing time
         if self.last_process_time_ms > 0.0 {
             ui.label(format!("Last process: {:.1} ms", self.last_process_time_ms));
@@ -258,6 +1832,77 @@ impl AsciiApp {
         changed
     }
 
+    /// Render the variations grid; clicking a thumbnail adopts its settings
+    fn render_variations(&mut self, ui: &mut egui::Ui) {
+        let Some(input) = self.input_image.as_ref() else {
+            ui.label("Load an image to see variations");
+            return;
+        };
+
+        let current = self
+            .variations
+            .is_current(&self.config, self.preserve_original_colors);
+        ui.horizontal(|ui| {
+            ui.heading("Variations");
+            if ui
+                .add_enabled(!current, egui::Button::new("Regenerate"))
+                .on_hover_text("Render variations around the current settings")
+                .clicked()
+            {
+                self.variations
+                    .generate(input, &self.config, self.preserve_original_colors);
+            }
+            if !current && !self.variations.items.is_empty() {
+                ui.label("Settings changed since these were rendered");
+            }
+        });
+        if self.variations.items.is_empty() {
+            self.variations
+                .generate(input, &self.config, self.preserve_original_colors);
+        }
+
+        let mut picked = None;
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                for (i, variation) in self.variations.items.iter_mut().enumerate() {
+                    let texture = variation.texture.get_or_insert_with(|| {
+                        ui.ctx().load_texture(
+                            format!("variation {}", i),
+                            Self::rgba_to_color_image(&variation.image),
+                            egui::TextureOptions::default(),
+                        )
+                    });
+                    ui.vertical(|ui| {
+                        if ui
+                            .add(egui::Button::image((texture.id(), texture.size_vec2())))
+                            .on_hover_text("Use these settings")
+                            .clicked()
+                        {
+                            picked = Some(variation.config.clone());
+                        }
+                        ui.label(&variation.label);
+                    });
+                }
+            });
+        });
+
+        if let Some(config) = picked {
+            self.config = config;
+            self.needs_reprocess = true;
+            // Explore further from the adopted settings
+            self.variations
+                .generate(input, &self.config, self.preserve_original_colors);
+        }
+    }
+
+    /// Seed for random exploration, derived from the current time
+    fn exploration_seed() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)
+    }
+
     /// Convert RgbaImage to egui ColorImage
     fn rgba_to_color_image(img: &RgbaImage) -> egui::ColorImage {
         let (width, height) = img.dimensions();
@@ -274,12 +1919,15 @@ impl AsciiApp {
     }
 
     /// Display an image in the UI (standalone helper function)
+    ///
+    /// Returns the response of the image widget, which senses `sense`.
     fn display_image(
         ui: &mut egui::Ui,
         image: Option<&RgbaImage>,
         texture: &mut Option<egui::TextureHandle>,
         label: &str,
-    ) {
+        sense: egui::Sense,
+    ) -> Option<egui::Response> {
         ui.vertical(|ui| {
             ui.heading(label);
 
@@ -295,30 +1943,46 @@ impl AsciiApp {
                 }
 
                 // Display the texture
-                if let Some(tex) = texture {
+                texture.as_ref().map(|tex| {
                     let size = tex.size_vec2();
                     let max_size = ui.available_size();
                     // Allow upscaling for small images, but limit to reasonable max scale
                     let scale = ((max_size.x / size.x).min(max_size.y / size.y)).min(4.0);
                     let display_size = size * scale;
 
-                    ui.image((tex.id(), display_size));
+                    let response = ui.add(egui::Image::new((tex.id(), display_size)).sense(sense));
                     ui.label(format!(
                         "{}x{} (scale: {:.1}x)",
                         img.width(),
                         img.height(),
                         scale
                     ));
-                }
+                    response
+                })
             } else {
                 ui.label("No image loaded");
+                None
             }
-        });
+        })
+        .inner
     }
 }
 
 impl eframe::App for AsciiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        #[cfg(feature = "midi")]
+        self.update_midi();
+        self.update_video(ctx);
+        self.poll_process_job(ctx);
+
+        // A clean exit leaves nothing to restore
+        if ctx.input(|i| i.viewport().close_requested()) {
+            self.closing = true;
+            Session::clear();
+        }
+        self.autosave();
+        self.render_restore_prompt(ctx);
+
         // Top menu bar
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
@@ -333,6 +1997,32 @@ impl eframe::App for AsciiApp {
                         ui.close();
                     }
 
+                    if ui.button("Open Video...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Videos", &["mp4", "mov", "mkv", "webm", "avi", "gif"])
+                            .pick_file()
+                        {
+                            self.load_video(&path);
+                        }
+                        ui.close();
+                    }
+
+                    ui.menu_button("Open Camera", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Device:");
+                            ui.text_edit_singleline(&mut self.camera_device);
+                        });
+                        if ui.button("Open").clicked() {
+                            self.load_camera();
+                            ui.close();
+                        }
+                    });
+
+                    if self.video.is_some() && ui.button("Close Video").clicked() {
+                        self.video = None;
+                        ui.close();
+                    }
+
                     if ui.button("Save Output...").clicked() {
                         if let Some(path) = rfd::FileDialog::new()
                             .add_filter("PNG", &["png"])
@@ -344,6 +2034,49 @@ impl eframe::App for AsciiApp {
                         ui.close();
                     }
 
+                    ui.menu_button("Export Before/After", |ui| {
+                        for transition in Transition::ALL {
+                            if ui.button(transition.name()).clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .add_filter("GIF", &["gif"])
+                                    .add_filter("WebP", &["webp"])
+                                    .add_filter("APNG", &["png"])
+                                    .add_filter("MP4", &["mp4"])
+                                    .save_file()
+                                    && let Err(e) = self.export_before_after(&path, transition)
+                                {
+                                    self.error_message = Some(e);
+                                }
+                                ui.close();
+                            }
+                        }
+                    });
+
+                    ui.separator();
+
+                    if ui.button("Load Config...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Config", &["toml", "json"])
+                            .add_filter("Saved output", &["png"])
+                            .pick_file()
+                        {
+                            self.load_config(&path);
+                        }
+                        ui.close();
+                    }
+
+                    if ui.button("Save Config...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("TOML", &["toml"])
+                            .add_filter("JSON", &["json"])
+                            .save_file()
+                            && let Err(e) = self.save_config(&path)
+                        {
+                            self.error_message = Some(e);
+                        }
+                        ui.close();
+                    }
+
                     ui.separator();
 
                     if ui.button("Quit").clicked() {
@@ -351,6 +2084,24 @@ impl eframe::App for AsciiApp {
                     }
                 });
 
+                ui.menu_button("Presets", |ui| {
+                    for preset in Preset::ALL {
+                        if ui.button(preset.name()).clicked() {
+                            self.config = AsciiConfig::preset(preset);
+                            self.needs_reprocess = true;
+                            ui.close();
+                        }
+                    }
+
+                    ui.separator();
+
+                    if ui.button("Reset to Defaults").clicked() {
+                        self.config = AsciiConfig::default();
+                        self.needs_reprocess = true;
+                        ui.close();
+                    }
+                });
+
                 ui.menu_button("Help", |ui| {
                     if ui.button("About").clicked() {
                         self.error_message = Some(
@@ -376,6 +2127,13 @@ impl eframe::App for AsciiApp {
                 });
             });
 
+        // Bottom panel: Variations grid
+        if self.variations.open {
+            egui::TopBottomPanel::bottom("variations_panel")
+                .resizable(true)
+                .show(ctx, |ui| self.render_variations(ui));
+        }
+
         // Central panel: Image display
         egui::CentralPanel::default().show(ctx, |ui| {
             // Show error message if any
@@ -401,12 +2159,17 @@ impl eframe::App for AsciiApp {
                     egui::vec2(half_width, ui.available_height()),
                     egui::Layout::top_down(egui::Align::Center),
                     |ui| {
-                        Self::display_image(
+                        let response = Self::display_image(
                             ui,
                             self.input_image.as_ref(),
                             &mut self.input_texture,
                             "Original",
+                            egui::Sense::click_and_drag(),
                         );
+                        if let Some(response) = response {
+                            self.pick_color(ui, &response);
+                            self.paint_region(ui, &response);
+                        }
                     },
                 );
 
@@ -418,9 +2181,10 @@ impl eframe::App for AsciiApp {
                     |ui| {
                         Self::display_image(
                             ui,
-                            self.output_image.as_ref(),
+                            self.output_preview.as_ref().or(self.output_image.as_ref()),
                             &mut self.output_texture,
                             "ASCII Output",
+                            egui::Sense::hover(),
                         );
                     },
                 );
@@ -428,3 +2192,29 @@ impl eframe::App for AsciiApp {
         });
     }
 }
+
+/// Color of input pixel (`x`, `y`), or with a `tile_size` (width, height) the
+/// mean of the tile containing it
+fn sample_color(input: &RgbaImage, x: u32, y: u32, tile_size: Option<(u32, u32)>) -> [u8; 3] {
+    let Some((tile_w, tile_h)) = tile_size else {
+        let p = input.get_pixel(x, y);
+        return [p[0], p[1], p[2]];
+    };
+
+    let (x0, y0) = (x / tile_w * tile_w, y / tile_h * tile_h);
+    let (x1, y1) = (
+        (x0 + tile_w).min(input.width()),
+        (y0 + tile_h).min(input.height()),
+    );
+    let mut sum = [0u32; 3];
+    for py in y0..y1 {
+        for px in x0..x1 {
+            let p = input.get_pixel(px, py);
+            for (total, &value) in sum.iter_mut().zip(&p.0) {
+                *total += value as u32;
+            }
+        }
+    }
+    let count = (x1 - x0) * (y1 - y0);
+    sum.map(|total| ((total + count / 2) / count) as u8)
+}
@@ -0,0 +1,110 @@
+use ascii_rendr::AsciiConfig;
+use ascii_rendr::params::ParamValue;
+use ascii_rendr::regions::RegionOverride;
+use eframe::egui;
+use image::{GrayImage, Luma, RgbaImage};
+
+/// Painted mask marking where alternate settings apply
+///
+/// The mask has the size of the input image; 255 marks painted pixels. A tile
+/// of the output uses the override settings when its center is painted.
+pub struct RegionBrush {
+    /// Whether dragging on the original image paints the mask
+    pub painting: bool,
+    /// Whether painting clears the mask instead
+    pub erasing: bool,
+    /// Brush radius in input pixels
+    pub radius: f32,
+    /// Settings used inside the painted region
+    pub config: AsciiConfig,
+    /// Painted pixels, sized like the input image
+    pub mask: Option<GrayImage>,
+    /// Mask overlay shown on the original image, rebuilt after painting
+    pub texture: Option<egui::TextureHandle>,
+}
+
+impl Default for RegionBrush {
+    fn default() -> Self {
+        Self {
+            painting: false,
+            erasing: false,
+            radius: 24.0,
+            config: AsciiConfig {
+                draw_edges: false,
+                ..AsciiConfig::default()
+            },
+            mask: None,
+            texture: None,
+        }
+    }
+}
+
+impl RegionBrush {
+    /// Whether any pixel is painted
+    pub fn is_active(&self) -> bool {
+        self.mask
+            .as_ref()
+            .is_some_and(|mask| mask.pixels().any(|p| p[0] > 0))
+    }
+
+    /// Clears the mask
+    pub fn clear(&mut self) {
+        self.mask = None;
+        self.texture = None;
+    }
+
+    /// Paints (or erases) a disc at input pixel (`x`, `y`)
+    ///
+    /// The mask is recreated when the input size changed.
+    pub fn paint(&mut self, x: f32, y: f32, width: u32, height: u32) {
+        let mask = match &mut self.mask {
+            Some(mask) if mask.dimensions() == (width, height) => mask,
+            slot => slot.insert(GrayImage::new(width, height)),
+        };
+
+        let value = if self.erasing { 0 } else { 255 };
+        let radius = self.radius.max(1.0);
+        let x0 = (x - radius).floor().max(0.0) as u32;
+        let y0 = (y - radius).floor().max(0.0) as u32;
+        let x1 = ((x + radius).ceil().max(0.0) as u32).min(width);
+        let y1 = ((y + radius).ceil().max(0.0) as u32).min(height);
+        for py in y0..y1 {
+            for px in x0..x1 {
+                let (dx, dy) = (px as f32 + 0.5 - x, py as f32 + 0.5 - y);
+                if dx * dx + dy * dy <= radius * radius {
+                    mask.put_pixel(px, py, Luma([value]));
+                }
+            }
+        }
+        self.texture = None;
+    }
+
+    /// Translucent overlay of the mask for display over the original image
+    pub fn overlay(&self) -> Option<RgbaImage> {
+        let mask = self.mask.as_ref()?;
+        Some(RgbaImage::from_fn(mask.width(), mask.height(), |x, y| {
+            let alpha = mask.get_pixel(x, y)[0] / 3;
+            image::Rgba([255, 64, 64, alpha])
+        }))
+    }
+
+    /// Override applying the brush settings inside the painted region
+    pub fn region(&self) -> Option<RegionOverride> {
+        let mask = self.mask.clone().filter(|_| self.is_active())?;
+        let config = &self.config;
+        Some(
+            RegionOverride::mask(mask)
+                .set(
+                    "edge_threshold",
+                    ParamValue::Int(config.edge_threshold as i64),
+                )
+                .set("sigma", ParamValue::Float(config.sigma as f64))
+                .set("draw_edges", ParamValue::Bool(config.draw_edges))
+                .set("draw_fill", ParamValue::Bool(config.draw_fill))
+                .set(
+                    "invert_luminance",
+                    ParamValue::Bool(config.invert_luminance),
+                ),
+        )
+    }
+}
@@ -1,4 +1,11 @@
 mod app;
+mod brush;
+#[cfg(feature = "midi")]
+mod midi;
+mod plugins;
+mod session;
+mod variations;
+mod video;
 
 use app::AsciiApp;
 use eframe::egui;
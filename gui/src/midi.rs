@@ -0,0 +1,148 @@
+use ascii_rendr::AsciiConfig;
+use ascii_rendr::params::{PARAMS, ParamKind, param_info};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+/// A MIDI control change bound to a config parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MidiBinding {
+    /// MIDI channel (0-15)
+    pub channel: u8,
+    /// Controller number (0-127)
+    pub controller: u8,
+    /// Config field name, as in `params::PARAMS`
+    pub param: String,
+}
+
+/// Set of controller bindings, saved next to config files
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MidiMapping {
+    #[serde(default)]
+    pub bindings: Vec<MidiBinding>,
+}
+
+impl MidiMapping {
+    /// Mapping file stored next to a config file (`look.toml` -> `look.midi.toml`)
+    pub fn path_for_config(config_path: &Path) -> PathBuf {
+        let stem = config_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        config_path.with_file_name(format!("{}.midi.toml", stem))
+    }
+
+    /// Loads a mapping from a TOML file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("{}: failed to read MIDI mapping: {}", path.display(), e))?;
+        toml::from_str(&contents)
+            .map_err(|e| format!("{}: invalid MIDI mapping: {}", path.display(), e))
+    }
+
+    /// Saves the mapping as TOML
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| format!("failed to serialize MIDI mapping: {}", e))?;
+        std::fs::write(path, contents)
+            .map_err(|e| format!("{}: failed to write MIDI mapping: {}", path.display(), e))
+    }
+
+    /// Binds a controller to a parameter, replacing any previous binding of that controller
+    pub fn bind(&mut self, channel: u8, controller: u8, param: &str) {
+        self.bindings
+            .retain(|b| !(b.channel == channel && b.controller == controller));
+        self.bindings.push(MidiBinding {
+            channel,
+            controller,
+            param: param.to_string(),
+        });
+    }
+
+    /// Applies a control change to `config`, returning true if a bound parameter changed
+    pub fn apply(&self, config: &mut AsciiConfig, channel: u8, controller: u8, value: u8) -> bool {
+        let mut changed = false;
+        for binding in &self.bindings {
+            if binding.channel != channel || binding.controller != controller {
+                continue;
+            }
+            // Spread the 7-bit controller value over the parameter's range
+            if let Some(info) = param_info(&binding.param)
+                && let Some(param) = info.value_at(value.min(127) as f64 / 127.0)
+            {
+                changed |= config.set_param(info.name, param).is_ok();
+            }
+        }
+        changed
+    }
+}
+
+/// An open MIDI input port delivering control changes
+pub struct MidiInput {
+    pub port_name: String,
+    receiver: Receiver<(u8, u8, u8)>,
+    _connection: midir::MidiInputConnection<()>,
+}
+
+impl MidiInput {
+    /// Names of the available MIDI input ports
+    pub fn port_names() -> Vec<String> {
+        let Ok(midi_in) = midir::MidiInput::new("ascii-rendr") else {
+            return Vec::new();
+        };
+        midi_in
+            .ports()
+            .iter()
+            .filter_map(|port| midi_in.port_name(port).ok())
+            .collect()
+    }
+
+    /// Connects to the named port; `ctx` is repainted whenever a message arrives
+    pub fn connect(port_name: &str, ctx: egui::Context) -> Result<Self, String> {
+        let midi_in =
+            midir::MidiInput::new("ascii-rendr").map_err(|e| format!("MIDI unavailable: {}", e))?;
+        let port = midi_in
+            .ports()
+            .into_iter()
+            .find(|port| midi_in.port_name(port).ok().as_deref() == Some(port_name))
+            .ok_or_else(|| format!("MIDI port '{}' not found", port_name))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let connection = midi_in
+            .connect(
+                &port,
+                "ascii-rendr-input",
+                move |_timestamp, message, _| {
+                    // Control change: 0xBn controller value
+                    if let &[status, controller, value, ..] = message
+                        && status & 0xf0 == 0xb0
+                    {
+                        let _ = sender.send((status & 0x0f, controller, value));
+                        ctx.request_repaint();
+                    }
+                },
+                (),
+            )
+            .map_err(|e| format!("failed to connect to '{}': {}", port_name, e))?;
+
+        Ok(Self {
+            port_name: port_name.to_string(),
+            receiver,
+            _connection: connection,
+        })
+    }
+
+    /// Control changes received since the last call, as (channel, controller, value)
+    pub fn drain(&self) -> Vec<(u8, u8, u8)> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+/// Names of the parameters a single controller can drive
+pub fn learnable_params() -> impl Iterator<Item = &'static str> {
+    PARAMS
+        .iter()
+        .filter(|p| !matches!(p.kind, ParamKind::Color))
+        .map(|p| p.name)
+}
@@ -0,0 +1,86 @@
+use ascii_rendr::plugin::{PLUGIN_ABI_VERSION, PLUGIN_ENTRY_SYMBOL, PostEffectEntry};
+use image::RgbaImage;
+use libloading::Library;
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+
+/// A post-effect plugin loaded from a dynamic library
+pub struct LoadedPlugin {
+    /// Display name reported by the plugin
+    pub name: String,
+    /// Library the plugin was loaded from
+    pub path: PathBuf,
+    /// Whether the effect runs on the output
+    pub enabled: bool,
+    process: unsafe extern "C" fn(*mut u8, u32, u32),
+    // Keeps `process` valid; must outlive it
+    _library: Library,
+}
+
+/// Ordered chain of post-effect plugins applied to the rendered output
+#[derive(Default)]
+pub struct PluginManager {
+    pub plugins: Vec<LoadedPlugin>,
+}
+
+impl PluginManager {
+    /// Loads a plugin library and appends it to the chain (enabled)
+    pub fn load(&mut self, path: &Path) -> Result<(), String> {
+        // SAFETY: loading runs the library's initializers; plugins are trusted code
+        let library = unsafe { Library::new(path) }
+            .map_err(|e| format!("{}: failed to load plugin: {}", path.display(), e))?;
+
+        // SAFETY: the entry point signature is defined by `ascii_rendr::plugin`
+        let descriptor = unsafe {
+            let entry = library
+                .get::<PostEffectEntry>(PLUGIN_ENTRY_SYMBOL)
+                .map_err(|_| format!("{}: not an ascii-rendr plugin", path.display()))?;
+            entry().as_ref()
+        }
+        .ok_or_else(|| format!("{}: plugin returned no descriptor", path.display()))?;
+
+        if descriptor.abi_version != PLUGIN_ABI_VERSION {
+            return Err(format!(
+                "{}: plugin ABI version {} is not supported (expected {})",
+                path.display(),
+                descriptor.abi_version,
+                PLUGIN_ABI_VERSION
+            ));
+        }
+
+        let name = if descriptor.name.is_null() {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        } else {
+            // SAFETY: the ABI requires a NUL-terminated static string
+            unsafe { CStr::from_ptr(descriptor.name) }
+                .to_string_lossy()
+                .into_owned()
+        };
+        let process = descriptor.process;
+
+        self.plugins.push(LoadedPlugin {
+            name,
+            path: path.to_path_buf(),
+            enabled: true,
+            process,
+            _library: library,
+        });
+        Ok(())
+    }
+
+    /// Whether any plugin will modify the output
+    pub fn is_active(&self) -> bool {
+        self.plugins.iter().any(|p| p.enabled)
+    }
+
+    /// Runs all enabled plugins on `image`, in order
+    pub fn apply(&self, image: &mut RgbaImage) {
+        let (width, height) = image.dimensions();
+        for plugin in self.plugins.iter().filter(|p| p.enabled) {
+            // SAFETY: the buffer is exactly width * height tightly packed RGBA pixels
+            unsafe { (plugin.process)(image.as_mut_ptr(), width, height) };
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use ascii_rendr::AsciiConfig;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Snapshot of the GUI state that is worth restoring after a crash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// Image that was open (if any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_path: Option<PathBuf>,
+    /// Video that was playing (if any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video_path: Option<PathBuf>,
+    /// Whether output kept the source colors
+    pub preserve_original_colors: bool,
+    pub config: AsciiConfig,
+}
+
+impl Session {
+    /// Location of the autosave file
+    ///
+    /// `$XDG_STATE_HOME/ascii-rendr`, `%APPDATA%\ascii-rendr` or
+    /// `~/.local/state/ascii-rendr`.
+    fn path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state"))
+            })?;
+        Some(base.join("ascii-rendr").join("session.toml"))
+    }
+
+    /// Session left behind by a run that did not exit cleanly
+    pub fn load_unfinished() -> Option<Self> {
+        let contents = std::fs::read_to_string(Self::path()?).ok()?;
+        toml::from_str(&contents).ok()
+    }
+
+    /// Serializes the session for [`Session::write`]
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| format!("failed to serialize session: {}", e))
+    }
+
+    /// Writes serialized session data, replacing the previous autosave atomically
+    pub fn write(contents: &str) -> Result<(), String> {
+        let path = Self::path().ok_or("no directory for session autosave")?;
+        let dir = path.parent().expect("session path has a parent");
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("{}: failed to create directory: {}", dir.display(), e))?;
+
+        // Write then rename, so a crash mid-write never leaves a truncated file
+        let tmp = path.with_extension("toml.tmp");
+        std::fs::write(&tmp, contents)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(|e| format!("{}: failed to autosave session: {}", path.display(), e))
+    }
+
+    /// Removes the autosave after a clean exit
+    pub fn clear() {
+        if let Some(path) = Self::path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
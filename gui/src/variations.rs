@@ -0,0 +1,157 @@
+use ascii_rendr::{AsciiConfig, FillDither, process_image, process_image_preserve_colors};
+use eframe::egui;
+use image::{RgbaImage, imageops};
+
+/// Widest thumbnail rendered for a variation, in pixels
+const THUMBNAIL_WIDTH: u32 = 240;
+
+/// The current image rendered under one perturbed configuration
+pub struct Variation {
+    /// Short description of what differs from the current settings
+    pub label: String,
+    /// Settings adopted when the variation is picked
+    pub config: AsciiConfig,
+    /// Rendered thumbnail
+    pub image: RgbaImage,
+    /// Texture for `image`, created when first drawn
+    pub texture: Option<egui::TextureHandle>,
+}
+
+/// Grid of automatic parameter variations around the current settings
+#[derive(Default)]
+pub struct Variations {
+    /// Whether the variations panel is shown
+    pub open: bool,
+    /// Rendered variations, in grid order
+    pub items: Vec<Variation>,
+    /// Set when a new input image is loaded
+    pub input_changed: bool,
+    /// Serialized settings the variations were derived from
+    base: String,
+    /// Color mode the variations were rendered with
+    preserve_colors: bool,
+}
+
+impl Variations {
+    /// Renders thumbnails of `input` for each perturbation of `config`
+    ///
+    /// Perturbations that produce an invalid config are left out.
+    pub fn generate(&mut self, input: &RgbaImage, config: &AsciiConfig, preserve_colors: bool) {
+        let thumbnail = thumbnail(input, config.tile_dimensions());
+        self.items = perturbations(config)
+            .into_iter()
+            .filter(|(_, config)| config.validate().is_ok())
+            .map(|(label, config)| {
+                let image = if preserve_colors {
+                    process_image_preserve_colors(&thumbnail, &config).render_image()
+                } else {
+                    process_image(&thumbnail, &config).render_image()
+                };
+                Variation {
+                    label,
+                    config,
+                    image,
+                    texture: None,
+                }
+            })
+            .collect();
+        self.base = serialize(config);
+        self.preserve_colors = preserve_colors;
+        self.input_changed = false;
+    }
+
+    /// Whether the variations were derived from the current image and settings
+    pub fn is_current(&self, config: &AsciiConfig, preserve_colors: bool) -> bool {
+        !self.input_changed
+            && self.preserve_colors == preserve_colors
+            && self.base == serialize(config)
+    }
+}
+
+fn serialize(config: &AsciiConfig) -> String {
+    toml::to_string(config).unwrap_or_default()
+}
+
+/// Downscales `input` to thumbnail width, keeping tile-aligned dimensions
+fn thumbnail(input: &RgbaImage, tile_size: (u32, u32)) -> RgbaImage {
+    let (width, height) = input.dimensions();
+    let (tile_w, tile_h) = tile_size;
+    let scale = (THUMBNAIL_WIDTH as f32 / width as f32).min(1.0);
+    let target_width = ((width as f32 * scale) as u32 / tile_w * tile_w).max(tile_w);
+    let target_height = ((height as f32 * scale) as u32 / tile_h * tile_h).max(tile_h);
+    imageops::resize(
+        input,
+        target_width,
+        target_height,
+        imageops::FilterType::Triangle,
+    )
+}
+
+/// Labeled variations of `config`: edge thresholds, blur sigmas and fill
+/// character mappings on either side of the current values
+fn perturbations(config: &AsciiConfig) -> Vec<(String, AsciiConfig)> {
+    let mut variations = Vec::new();
+    let mut push = |label: String, config: AsciiConfig| variations.push((label, config));
+
+    for edge_threshold in [
+        config.edge_threshold / 2,
+        (config.edge_threshold * 2).max(4),
+    ] {
+        let edge_threshold = edge_threshold.min(64);
+        push(
+            format!("Edge Threshold {}", edge_threshold),
+            AsciiConfig {
+                edge_threshold,
+                ..config.clone()
+            },
+        );
+    }
+
+    for sigma in [config.sigma * 0.6, (config.sigma * 1.5).max(0.5)] {
+        let sigma = sigma.min(5.0);
+        push(
+            format!("Sigma {:.2}", sigma),
+            AsciiConfig {
+                sigma,
+                ..config.clone()
+            },
+        );
+    }
+
+    for fill_gamma in [config.fill_gamma * 0.6, config.fill_gamma * 1.6] {
+        let fill_gamma = fill_gamma.clamp(0.1, 5.0);
+        push(
+            format!("Fill Gamma {:.2}", fill_gamma),
+            AsciiConfig {
+                fill_gamma,
+                ..config.clone()
+            },
+        );
+    }
+
+    let fill_dither = match config.fill_dither {
+        FillDither::Off => FillDither::Ordered,
+        _ => FillDither::Off,
+    };
+    push(
+        format!("{:?} Dither", fill_dither),
+        AsciiConfig {
+            fill_dither,
+            ..config.clone()
+        },
+    );
+
+    push(
+        if config.invert_luminance {
+            "Normal Fill".to_string()
+        } else {
+            "Inverted Fill".to_string()
+        },
+        AsciiConfig {
+            invert_luminance: !config.invert_luminance,
+            ..config.clone()
+        },
+    );
+
+    variations
+}
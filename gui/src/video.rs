@@ -0,0 +1,138 @@
+use ascii_rendr::video_io::{LiveCapture, VideoReader};
+use image::{RgbaImage, imageops};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Tuning for live sources, so processing can keep up on slower CPUs
+pub struct VideoSettings {
+    /// Input resolution relative to the source (1.0 = full size)
+    pub scale: f32,
+    /// Maximum frames per second taken from the source
+    pub fps_cap: u32,
+    /// Flip frames horizontally (mirror view)
+    pub mirror: bool,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            fps_cap: 30,
+            mirror: false,
+        }
+    }
+}
+
+/// A video file decoded in the background at its native frame rate, or a
+/// camera read at its own pace
+///
+/// File playback loops. Frames the UI does not pick up before the next one
+/// arrives are counted as dropped.
+pub struct VideoSource {
+    /// File being played, None for a camera
+    pub path: Option<PathBuf>,
+    /// Source width in pixels
+    pub width: u32,
+    /// Source height in pixels
+    pub height: u32,
+    /// Source frames per second
+    pub frame_rate: f64,
+
+    capture: LiveCapture,
+    /// Dropped frames already counted when the counter was last reset
+    dropped_before: u64,
+    last_taken: Option<Instant>,
+}
+
+impl VideoSource {
+    /// Opens a video file and starts decoding it
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut reader = VideoReader::open(path)?;
+        let info = reader.info();
+        let frame_interval = Duration::from_secs_f64(1.0 / info.frame_rate.max(1.0));
+        let looped = path.to_path_buf();
+        let mut next_due = Instant::now();
+
+        // Pace to the source frame rate, reopening the file at the end
+        let capture = LiveCapture::from_fn(info, move || {
+            if let Some(wait) = next_due.checked_duration_since(Instant::now()) {
+                thread::sleep(wait);
+            } else {
+                next_due = Instant::now();
+            }
+            next_due += frame_interval;
+
+            loop {
+                match reader.next_frame()? {
+                    Some(frame) => return Ok(Some(frame)),
+                    None => reader = VideoReader::open(&looped)?,
+                }
+            }
+        });
+        Ok(Self::new(capture, Some(path.to_path_buf())))
+    }
+
+    /// Opens a camera (see `VideoReader::open_camera`) and starts reading it
+    pub fn open_camera(device: &str) -> Result<Self, String> {
+        Ok(Self::new(LiveCapture::open(device)?, None))
+    }
+
+    fn new(capture: LiveCapture, path: Option<PathBuf>) -> Self {
+        let info = capture.info();
+        Self {
+            path,
+            width: info.width,
+            height: info.height,
+            frame_rate: info.frame_rate,
+            capture,
+            dropped_before: 0,
+            last_taken: None,
+        }
+    }
+
+    /// Returns the newest frame if one is available and the frame-rate cap allows it
+    ///
+    /// The frame is scaled and mirrored according to `settings`.
+    ///
+    /// # Errors
+    /// Returns the decoder error once decoding stopped.
+    pub fn poll(&mut self, settings: &VideoSettings) -> Result<Option<RgbaImage>, String> {
+        let min_interval = Duration::from_secs_f64(1.0 / settings.fps_cap.max(1) as f64);
+        if self
+            .last_taken
+            .is_some_and(|taken| taken.elapsed() < min_interval)
+        {
+            return Ok(None);
+        }
+
+        let Some(frame) = self.capture.try_next_frame()? else {
+            return Ok(None);
+        };
+        self.last_taken = Some(Instant::now());
+
+        let frame = if settings.scale < 1.0 {
+            let width = ((frame.width() as f32 * settings.scale) as u32).max(8);
+            let height = ((frame.height() as f32 * settings.scale) as u32).max(8);
+            imageops::resize(&frame, width, height, imageops::FilterType::Triangle)
+        } else {
+            frame
+        };
+
+        Ok(Some(if settings.mirror {
+            imageops::flip_horizontal(&frame)
+        } else {
+            frame
+        }))
+    }
+
+    /// Frames skipped because processing could not keep up
+    pub fn dropped_frames(&self) -> u64 {
+        self.capture.dropped_frames() - self.dropped_before
+    }
+
+    /// Starts counting dropped frames from zero
+    pub fn reset_dropped_frames(&mut self) {
+        self.dropped_before = self.capture.dropped_frames();
+    }
+}
@@ -1,6 +1,7 @@
 /// Basic example: Convert a simple test image to ASCII art
 ///
 /// This creates a test image with some basic shapes and converts it to ASCII
+use ascii_rendr::text_rows::TextRows;
 use ascii_rendr::{AsciiConfig, process_image};
 use image::{Rgba, RgbaImage};
 
@@ -65,6 +66,7 @@ fn main() {
         draw_edges: true,
         draw_fill: true,
         invert_luminance: false,
+        ..Default::default()
     };
 
     println!("Processing with config:");
@@ -75,7 +77,7 @@ fn main() {
     println!();
 
     // Process the image
-    let output = process_image(&img, &config);
+    let output = process_image(&img, &config).render_image();
 
     // Save both images
     img.save("basic_input.png").expect("Failed to save input");
@@ -83,6 +85,13 @@ fn main() {
         .save("basic_output.png")
         .expect("Failed to save output");
 
+    // Print a text preview, one row at a time as it is converted
+    println!("Text preview:");
+    for row in TextRows::new(&img, &config) {
+        println!("{}", row);
+    }
+    println!();
+
     println!("✓ Saved input to:  basic_input.png");
     println!("✓ Saved output to: basic_output.png");
     println!("\nASCII conversion complete!");
@@ -0,0 +1,201 @@
+/// Batch example: Convert every image in a directory, incrementally
+///
+/// Usage: cargo run --release --example batch -- <INPUT_DIR> <OUTPUT_DIR> [--config FILE] [--mono] [--force]
+///
+/// Each input is hashed together with the effective config; inputs whose
+/// output exists and was produced from the same hash are skipped, so re-running
+/// after adding a few files only converts the new ones. `--force` converts
+/// everything again. Outputs are named after the whole input file name, e.g.
+/// `photo.jpg` -> `photo.jpg.png`.
+use ascii_rendr::{AsciiConfig, process_image, process_image_preserve_colors};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Hashes of converted inputs, kept in the output directory
+const MANIFEST_NAME: &str = ".ascii-rendr-batch";
+
+const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "bmp", "webp", "gif"];
+
+struct Options {
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    config: AsciiConfig,
+    preserve_colors: bool,
+    force: bool,
+}
+
+fn main() -> ExitCode {
+    match parse_args().and_then(|options| run(&options)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("batch: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut dirs = Vec::new();
+    let mut config = AsciiConfig::default();
+    let mut preserve_colors = true;
+    let mut force = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => {
+                let path = args.next().ok_or("--config needs a file")?;
+                config = AsciiConfig::from_path(path).map_err(|e| e.to_string())?;
+            }
+            "--mono" => preserve_colors = false,
+            "--force" => force = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown option `{}`", flag)),
+            dir => dirs.push(PathBuf::from(dir)),
+        }
+    }
+
+    let [input_dir, output_dir]: [PathBuf; 2] = dirs.try_into().map_err(|_| {
+        "usage: batch <INPUT_DIR> <OUTPUT_DIR> [--config FILE] [--mono] [--force]".to_string()
+    })?;
+    config.validate().map_err(|e| e.to_string())?;
+    Ok(Options {
+        input_dir,
+        output_dir,
+        config,
+        preserve_colors,
+        force,
+    })
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    fs::create_dir_all(&options.output_dir)
+        .map_err(|e| format!("{}: {}", options.output_dir.display(), e))?;
+    let manifest_path = options.output_dir.join(MANIFEST_NAME);
+    let mut manifest = if options.force {
+        HashMap::new()
+    } else {
+        read_manifest(&manifest_path)
+    };
+
+    // Everything that changes the output besides the input itself
+    let settings = format!(
+        "{}\n{}\n{}",
+        env!("CARGO_PKG_VERSION"),
+        options.preserve_colors,
+        toml::to_string(&options.config).map_err(|e| e.to_string())?
+    );
+
+    let (mut converted, mut skipped, mut failed) = (0, 0, 0);
+    for input in list_images(&options.input_dir)? {
+        // Keep the source extension so `a.png` and `a.jpg` don't share an output
+        let name = input.file_name().unwrap_or_default().to_string_lossy();
+        let output_name = format!("{}.png", name);
+        let output = options.output_dir.join(&output_name);
+
+        let data = match fs::read(&input) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("{}: {}", input.display(), e);
+                failed += 1;
+                continue;
+            }
+        };
+        let hash = format!("{:016x}", fnv1a(&[&data, settings.as_bytes()]));
+
+        if output.exists() && manifest.get(&output_name) == Some(&hash) {
+            skipped += 1;
+            continue;
+        }
+
+        match convert(&data, &output, options) {
+            Ok(()) => {
+                println!("{} -> {}", input.display(), output.display());
+                manifest.insert(output_name, hash);
+                converted += 1;
+            }
+            Err(e) => {
+                eprintln!("{}: {}", input.display(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    write_manifest(&manifest_path, &manifest)?;
+    println!(
+        "{} converted, {} up to date, {} failed",
+        converted, skipped, failed
+    );
+    if failed > 0 {
+        return Err(format!("{} file(s) failed", failed));
+    }
+    Ok(())
+}
+
+fn convert(data: &[u8], output: &Path, options: &Options) -> Result<(), String> {
+    let input = image::load_from_memory(data)
+        .map_err(|e| format!("failed to decode: {}", e))?
+        .to_rgba8();
+    let result = if options.preserve_colors {
+        process_image_preserve_colors(&input, &options.config).render_image()
+    } else {
+        process_image(&input, &options.config).render_image()
+    };
+    result
+        .save(output)
+        .map_err(|e| format!("failed to save {}: {}", output.display(), e))
+}
+
+/// Image files directly inside `dir`, sorted by name
+fn list_images(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut images: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+                    })
+        })
+        .collect();
+    images.sort();
+    Ok(images)
+}
+
+/// Reads `<hash> <output name>` lines; a missing or unreadable manifest is empty
+fn read_manifest(path: &Path) -> HashMap<String, String> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(hash, name)| (name.to_string(), hash.to_string()))
+        .collect()
+}
+
+fn write_manifest(path: &Path, manifest: &HashMap<String, String>) -> Result<(), String> {
+    let mut lines: Vec<String> = manifest
+        .iter()
+        .map(|(name, hash)| format!("{} {}\n", hash, name))
+        .collect();
+    lines.sort();
+    fs::write(path, lines.concat()).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// 64-bit FNV-1a over several byte strings; stable across runs and toolchains
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for &byte in *part {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        // Separator so ("ab", "c") and ("a", "bc") differ
+        hash ^= 0xff;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
@@ -0,0 +1,15 @@
+//! Example post-effect plugin: inverts the colors of the rendered output
+//!
+//! Build with `cargo build --example invert_plugin` and load the resulting
+//! library (e.g. `target/debug/examples/libinvert_plugin.so`) from the GUI's
+//! `Post Effects` panel.
+
+fn invert(rgba: &mut [u8], _width: u32, _height: u32) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        for channel in &mut pixel[..3] {
+            *channel = 255 - *channel;
+        }
+    }
+}
+
+ascii_rendr::export_post_effect!("Invert", invert);
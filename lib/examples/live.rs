@@ -0,0 +1,68 @@
+/// Live example: Mirror a webcam as colored ASCII art in the terminal
+///
+/// Usage: cargo run --release --features video_io --example live -- [DEVICE] [COLUMNS]
+///
+/// DEVICE defaults to /dev/video0 on Linux and 0 on macOS (see
+/// `VideoReader::open_camera`); COLUMNS defaults to 100. Frames the
+/// conversion cannot keep up with are skipped. Stop with Ctrl-C.
+use ascii_rendr::render::ansi::AnsiOptions;
+use ascii_rendr::video_io::LiveCapture;
+use ascii_rendr::{AsciiConfig, Processor};
+use image::imageops;
+use std::io::Write;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let device = args.next().unwrap_or_else(|| {
+        if cfg!(target_os = "macos") {
+            "0"
+        } else {
+            "/dev/video0"
+        }
+        .to_string()
+    });
+    let columns: u32 = match args.next().map(|c| c.parse()) {
+        None => 100,
+        Some(Ok(columns)) if columns > 0 => columns,
+        Some(_) => {
+            eprintln!("live: COLUMNS must be a positive number");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&device, columns) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("live: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(device: &str, columns: u32) -> Result<(), String> {
+    let mut camera = LiveCapture::open(device)?;
+    let info = camera.info();
+
+    // Terminal cells are about twice as tall as wide, so use half the rows
+    let config = AsciiConfig::default();
+    let width = columns * 8;
+    let height = ((info.height as u64 * width as u64 / info.width as u64) as u32 / 16 * 8).max(8);
+    let mut processor = Processor::new(&config, true);
+
+    let mut stdout = std::io::stdout().lock();
+    // Clear the screen once, then redraw in place
+    write!(stdout, "\x1b[2J").map_err(|e| e.to_string())?;
+    loop {
+        let frame = camera.next_frame()?;
+        let frame = imageops::resize(&frame, width, height, imageops::FilterType::Triangle);
+        let frame = imageops::flip_horizontal(&frame);
+
+        let ansi = processor
+            .process(&frame)
+            .render_ansi(&AnsiOptions::default());
+        write!(stdout, "\x1b[H{}", ansi)
+            .and_then(|()| stdout.flush())
+            .map_err(|e| e.to_string())?;
+    }
+}
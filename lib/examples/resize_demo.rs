@@ -28,7 +28,7 @@ fn main() {
         }
 
         // Process the image (will auto-resize if needed)
-        let output = process_image(&img, &config);
+        let output = process_image(&img, &config).render_image();
         let (out_w, out_h) = output.dimensions();
 
         println!("  Input:  {}x{}", width, height);
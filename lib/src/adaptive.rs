@@ -0,0 +1,177 @@
+//! Adaptive quality for real-time conversion
+//!
+//! A [`Processor`](crate::Processor) given a frame budget with
+//! [`ProcessorOptions::target_ms`](crate::ProcessorOptions::target_ms) times
+//! every conversion. When frames keep taking longer than the budget it steps
+//! down one quality level at a time, and when they finish well within it
+//! steps back up, so a live webcam or stream keeps its frame rate on slower
+//! machines and regains full quality once the load drops.
+//!
+//! Each level keeps the savings of the ones before it:
+//!
+//! 1. Half the blur kernel radius
+//! 2. Box approximation of the Gaussian blurs ([`BlurMode::Box`])
+//! 3. Tiles of twice the size, a quarter of the tiles to fill
+//! 4. No edge pass: DoG, Sobel and voting are skipped and only fill
+//!    characters are drawn
+
+use crate::config::{AsciiConfig, BlurMode, TILE_SIZES};
+use std::time::Duration;
+
+/// Lowest quality level, see the [module docs](self)
+pub const MAX_LEVEL: usize = 4;
+
+/// Level from which the edge pass is skipped
+pub(crate) const SKIP_EDGES_LEVEL: usize = 4;
+
+/// Frames over budget, on average, before stepping down a level
+const DEGRADE_FRAMES: u32 = 3;
+
+/// Frames well within budget before stepping back up a level; longer than
+/// [`DEGRADE_FRAMES`] so a level that is barely too slow is not retried on
+/// every other frame
+const RESTORE_FRAMES: u32 = 30;
+
+/// Share of the budget the average frame must stay under to restore a level
+const RESTORE_HEADROOM: f64 = 0.5;
+
+/// Settings of `config` at quality `level`, from 0 (unchanged) to
+/// [`MAX_LEVEL`]
+pub fn degrade(config: &AsciiConfig, level: usize) -> AsciiConfig {
+    let mut config = config.clone();
+    if level >= 1 {
+        config.kernel_size = (config.kernel_size / 2).max(1);
+    }
+    if level >= 2 {
+        config.blur_mode = BlurMode::Box;
+    }
+    if level >= 3 {
+        config.tile_size = larger_tile(config.tile_size);
+        config.tile_height = config.tile_height.map(larger_tile);
+    }
+    if level >= SKIP_EDGES_LEVEL {
+        config.draw_edges = false;
+        config.positional_edges = false;
+    }
+    config
+}
+
+/// Next tile size up, or the largest one
+fn larger_tile(size: u32) -> u32 {
+    (size * 2).min(TILE_SIZES[TILE_SIZES.len() - 1])
+}
+
+/// Frame time tracking behind the quality level of a [`Processor`](crate::Processor)
+#[derive(Debug, Clone)]
+pub(crate) struct FrameBudget {
+    target: Duration,
+    level: usize,
+    /// Smoothed frame time at the current level, in seconds
+    average: Option<f64>,
+    /// Frames converted since the level last changed
+    frames: u32,
+}
+
+impl FrameBudget {
+    pub(crate) fn new(target: Duration) -> Self {
+        Self {
+            target,
+            level: 0,
+            average: None,
+            frames: 0,
+        }
+    }
+
+    /// Current quality level, 0 for full quality
+    pub(crate) fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Records how long a frame took, changing the level when the smoothed
+    /// frame time has stayed off budget for long enough
+    pub(crate) fn record(&mut self, elapsed: Duration) {
+        let elapsed = elapsed.as_secs_f64();
+        let average = match self.average {
+            // Exponential moving average, so one slow frame does not count much
+            Some(average) => average + (elapsed - average) * 0.25,
+            None => elapsed,
+        };
+        self.average = Some(average);
+        self.frames += 1;
+
+        let target = self.target.as_secs_f64();
+        let level = if average > target && self.frames >= DEGRADE_FRAMES {
+            (self.level + 1).min(MAX_LEVEL)
+        } else if average < target * RESTORE_HEADROOM && self.frames >= RESTORE_FRAMES {
+            self.level.saturating_sub(1)
+        } else {
+            self.level
+        };
+        if level != self.level {
+            self.level = level;
+            self.average = None;
+            self.frames = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degrade_levels() {
+        let config = AsciiConfig {
+            kernel_size: 5,
+            tile_height: Some(16),
+            positional_edges: true,
+            ..AsciiConfig::default()
+        };
+        assert_eq!(degrade(&config, 0).first_changed_stage(&config), None);
+        for level in 1..=MAX_LEVEL {
+            assert!(degrade(&config, level).validate().is_ok());
+        }
+
+        let lowest = degrade(&config, MAX_LEVEL);
+        assert_eq!(lowest.kernel_size, 2);
+        assert_eq!(lowest.blur_mode, BlurMode::Box);
+        assert_eq!(lowest.tile_dimensions(), (16, 32));
+        assert!(!lowest.draw_edges && !lowest.positional_edges);
+        assert_eq!(degrade(&lowest, 3).tile_size, 32);
+    }
+
+    #[test]
+    fn test_budget_degrades_and_restores() {
+        let mut budget = FrameBudget::new(Duration::from_millis(20));
+        let slow = Duration::from_millis(45);
+        let fast = Duration::from_millis(5);
+
+        // A single slow frame is not enough
+        budget.record(slow);
+        budget.record(fast);
+        assert_eq!(budget.level(), 0);
+
+        for _ in 0..DEGRADE_FRAMES * 2 {
+            budget.record(slow);
+        }
+        assert_eq!(budget.level(), 2);
+        for _ in 0..100 {
+            budget.record(slow);
+        }
+        assert_eq!(budget.level(), MAX_LEVEL);
+
+        // Within budget but without headroom the level stays
+        for _ in 0..100 {
+            budget.record(Duration::from_millis(15));
+        }
+        assert_eq!(budget.level(), MAX_LEVEL);
+        for _ in 0..RESTORE_FRAMES {
+            budget.record(fast);
+        }
+        assert_eq!(budget.level(), MAX_LEVEL - 1);
+        for _ in 0..RESTORE_FRAMES * MAX_LEVEL as u32 {
+            budget.record(fast);
+        }
+        assert_eq!(budget.level(), 0);
+    }
+}
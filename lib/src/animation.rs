@@ -0,0 +1,538 @@
+//! Animated GIF, WebP and PNG frame sequences
+//!
+//! [`load_frames`] decodes an animation into frames with their delays and
+//! [`save_frames`] writes frames back out, picking the format from the file
+//! extension; [`save_frames_looped`] plays them a set number of times instead
+//! of forever. Animated WebP and APNG are lossless, which suits ASCII art (few
+//! colors, large flat areas): both are usually much smaller than the same GIF
+//! without its 256 color limit, and embed on web pages like any image.
+//!
+//! Converting an animation frame by frame:
+//!
+//! ```no_run
+//! use ascii_rendr::animation::{load_frames, save_frames};
+//! use ascii_rendr::{AsciiConfig, process_image};
+//! use image::Frame;
+//!
+//! let config = AsciiConfig::default();
+//! let frames = load_frames("input.webp").unwrap().into_iter().map(|frame| {
+//!     let delay = frame.delay();
+//!     Frame::from_parts(process_image(frame.buffer(), &config).render_image(), 0, 0, delay)
+//! });
+//! save_frames("ascii.webp", frames).unwrap();
+//! ```
+
+use crate::png_metadata::{PNG_SIGNATURE, parse_chunks, write_chunk};
+use image::codecs::gif::{GifDecoder, GifEncoder, Repeat};
+use image::codecs::png::{PngDecoder, PngEncoder};
+use image::codecs::webp::{WebPDecoder, WebPEncoder};
+use image::{AnimationDecoder, Delay, ExtendedColorType, Frame, ImageEncoder, RgbaImage, imageops};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Largest canvas side an animated WebP can describe (24-bit field)
+const WEBP_MAX_DIMENSION: u32 = 1 << 24;
+
+/// How many times an animation plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Loops {
+    /// Repeat endlessly
+    #[default]
+    Forever,
+    /// Play this many times (at least once), then stop on the last frame
+    Times(u16),
+}
+
+/// Delay between frames for a playback frame rate
+pub fn frame_delay(fps: f32) -> Result<Delay, String> {
+    if !fps.is_finite() || fps <= 0.0 {
+        return Err(format!("frame rate must be positive, got {}", fps));
+    }
+    Ok(Delay::from_saturating_duration(Duration::from_secs_f64(
+        1.0 / fps as f64,
+    )))
+}
+
+/// Decodes every frame of a GIF, WebP or PNG (APNG) animation
+///
+/// Still images (including other formats) load as a single frame.
+pub fn load_frames(path: impl AsRef<Path>) -> Result<Vec<Frame>, String> {
+    let path = path.as_ref();
+    let decode_error =
+        |e: image::ImageError| format!("{}: failed to decode: {}", path.display(), e);
+    let open = || {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| format!("{}: failed to open file: {}", path.display(), e))
+    };
+
+    match extension(path).as_deref() {
+        Some("gif") => GifDecoder::new(open()?)
+            .and_then(|decoder| decoder.into_frames().collect_frames())
+            .map_err(decode_error),
+        Some("webp") => {
+            let decoder = WebPDecoder::new(open()?).map_err(decode_error)?;
+            if decoder.has_animation() {
+                decoder.into_frames().collect_frames().map_err(decode_error)
+            } else {
+                load_still(path)
+            }
+        }
+        Some("png" | "apng") => {
+            let decoder = PngDecoder::new(open()?).map_err(decode_error)?;
+            if decoder.is_apng().map_err(decode_error)? {
+                decoder
+                    .apng()
+                    .and_then(|decoder| decoder.into_frames().collect_frames())
+                    .map_err(decode_error)
+            } else {
+                load_still(path)
+            }
+        }
+        _ => load_still(path),
+    }
+}
+
+/// Whether [`save_frames`] can write an animation to `path`, by extension
+pub fn is_animation_path(path: impl AsRef<Path>) -> bool {
+    matches!(
+        extension(path.as_ref()).as_deref(),
+        Some("gif" | "webp" | "png" | "apng")
+    )
+}
+
+/// Writes frames as an endlessly repeating GIF, WebP or PNG (APNG)
+/// animation, by extension
+pub fn save_frames(
+    path: impl AsRef<Path>,
+    frames: impl IntoIterator<Item = Frame>,
+) -> Result<(), String> {
+    save_frames_looped(path, frames, Loops::Forever)
+}
+
+/// [`save_frames`] playing the animation `loops` times
+pub fn save_frames_looped(
+    path: impl AsRef<Path>,
+    frames: impl IntoIterator<Item = Frame>,
+    loops: Loops,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    if loops == Loops::Times(0) {
+        return Err(format!(
+            "{}: an animation must play at least once",
+            path.display()
+        ));
+    }
+    match extension(path).as_deref() {
+        Some("gif") => write_gif(path, frames, loops),
+        Some("webp") => write_file(path, encode_webp(frames, loops), "WebP"),
+        Some("png" | "apng") => write_file(path, encode_apng(frames, loops), "PNG"),
+        _ => Err(format!(
+            "{}: unsupported animation format, expected .gif, .webp or .png",
+            path.display()
+        )),
+    }
+}
+
+/// Writes frames as an endlessly repeating GIF
+pub fn save_gif(
+    path: impl AsRef<Path>,
+    frames: impl IntoIterator<Item = Frame>,
+) -> Result<(), String> {
+    write_gif(path.as_ref(), frames, Loops::Forever)
+}
+
+/// Writes frames as an endlessly repeating lossless animated WebP
+///
+/// Odd frame offsets are rounded down to even ones, as the format requires.
+pub fn save_webp(
+    path: impl AsRef<Path>,
+    frames: impl IntoIterator<Item = Frame>,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    write_file(path, encode_webp(frames, Loops::Forever), "WebP")
+}
+
+/// Writes frames as an endlessly repeating animated PNG (APNG)
+///
+/// Viewers without APNG support show the first frame.
+pub fn save_apng(
+    path: impl AsRef<Path>,
+    frames: impl IntoIterator<Item = Frame>,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    write_file(path, encode_apng(frames, Loops::Forever), "PNG")
+}
+
+fn write_gif(
+    path: &Path,
+    frames: impl IntoIterator<Item = Frame>,
+    loops: Loops,
+) -> Result<(), String> {
+    let file = File::create(path)
+        .map_err(|e| format!("{}: failed to create file: {}", path.display(), e))?;
+    let mut encoder = GifEncoder::new(BufWriter::new(file));
+    let write_error =
+        |e: image::ImageError| format!("{}: failed to write GIF: {}", path.display(), e);
+    // The GIF loop count is the number of repeats after the first play, and
+    // without one the animation plays once
+    match loops {
+        Loops::Forever => encoder.set_repeat(Repeat::Infinite),
+        Loops::Times(plays) if plays > 1 => encoder.set_repeat(Repeat::Finite(plays - 1)),
+        Loops::Times(_) => Ok(()),
+    }
+    .map_err(write_error)?;
+
+    for frame in frames {
+        encoder.encode_frame(frame).map_err(write_error)?;
+    }
+    Ok(())
+}
+
+/// Writes encoded animation data, prefixing errors with the path
+fn write_file(path: &Path, data: Result<Vec<u8>, String>, format: &str) -> Result<(), String> {
+    let data = data.map_err(|e| format!("{}: {}", path.display(), e))?;
+    File::create(path)
+        .and_then(|mut file| file.write_all(&data))
+        .map_err(|e| format!("{}: failed to write {}: {}", path.display(), format, e))
+}
+
+fn load_still(path: &Path) -> Result<Vec<Frame>, String> {
+    let image =
+        image::open(path).map_err(|e| format!("{}: failed to decode: {}", path.display(), e))?;
+    Ok(vec![Frame::new(image.into_rgba8())])
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+/// Loop count field of WebP and APNG, where 0 repeats forever
+fn loop_count(loops: Loops) -> u16 {
+    match loops {
+        Loops::Forever => 0,
+        Loops::Times(plays) => plays,
+    }
+}
+
+/// Builds an animated WebP file (RIFF container with VP8X, ANIM and ANMF chunks)
+fn encode_webp(frames: impl IntoIterator<Item = Frame>, loops: Loops) -> Result<Vec<u8>, String> {
+    let (mut canvas_width, mut canvas_height) = (0, 0);
+    let mut has_alpha = false;
+    let mut frame_chunks = Vec::new();
+
+    for frame in frames {
+        let (x, y) = (frame.left() / 2 * 2, frame.top() / 2 * 2);
+        let image = frame.buffer();
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return Err("frames must not be empty".to_string());
+        }
+        canvas_width = canvas_width.max(x + width);
+        canvas_height = canvas_height.max(y + height);
+        if canvas_width > WEBP_MAX_DIMENSION || canvas_height > WEBP_MAX_DIMENSION {
+            return Err(format!(
+                "canvas {}x{} is too large for WebP",
+                canvas_width, canvas_height
+            ));
+        }
+        has_alpha |= image.pixels().any(|p| p[3] < 255);
+
+        let mut anmf = Vec::new();
+        push_u24(&mut anmf, x / 2);
+        push_u24(&mut anmf, y / 2);
+        push_u24(&mut anmf, width - 1);
+        push_u24(&mut anmf, height - 1);
+        push_u24(&mut anmf, delay_ms(frame.delay()));
+        anmf.push(0b10); // Do not blend, no disposal: each frame replaces its area
+        anmf.extend_from_slice(&lossless_chunk(image)?);
+        push_chunk(&mut frame_chunks, b"ANMF", &anmf);
+    }
+    if frame_chunks.is_empty() {
+        return Err("no frames to encode".to_string());
+    }
+
+    let mut vp8x = vec![0x02 | if has_alpha { 0x10 } else { 0 }, 0, 0, 0];
+    push_u24(&mut vp8x, canvas_width - 1);
+    push_u24(&mut vp8x, canvas_height - 1);
+    // Transparent background (BGRA), then the loop count
+    let [low, high] = loop_count(loops).to_le_bytes();
+    let anim = [0, 0, 0, 0, low, high];
+
+    let mut body = b"WEBP".to_vec();
+    push_chunk(&mut body, b"VP8X", &vp8x);
+    push_chunk(&mut body, b"ANIM", &anim);
+    body.extend_from_slice(&frame_chunks);
+
+    let mut data = b"RIFF".to_vec();
+    data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    data.extend_from_slice(&body);
+    Ok(data)
+}
+
+/// Encodes one image losslessly and returns its complete VP8L chunk
+fn lossless_chunk(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut file = Vec::new();
+    WebPEncoder::new_lossless(&mut file)
+        .encode(
+            image.as_raw(),
+            image.width(),
+            image.height(),
+            ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| format!("failed to encode WebP frame: {}", e))?;
+
+    // Walk the chunks after the 12-byte RIFF header
+    let mut pos = 12;
+    while pos + 8 <= file.len() {
+        let size = u32::from_le_bytes([file[pos + 4], file[pos + 5], file[pos + 6], file[pos + 7]])
+            as usize;
+        let end = (pos + 8 + size + size % 2).min(file.len());
+        if &file[pos..pos + 4] == b"VP8L" {
+            return Ok(file[pos..end].to_vec());
+        }
+        pos = end;
+    }
+    Err("encoder produced no VP8L chunk".to_string())
+}
+
+/// Builds an animated PNG (IHDR, acTL, then fcTL with IDAT for the first
+/// frame and fdAT for the others)
+///
+/// The canvas covers every frame at its offset; the first frame doubles as
+/// the still image older viewers show, so it is padded to the canvas.
+fn encode_apng(frames: impl IntoIterator<Item = Frame>, loops: Loops) -> Result<Vec<u8>, String> {
+    let frames: Vec<Frame> = frames.into_iter().collect();
+    if frames.is_empty() {
+        return Err("no frames to encode".to_string());
+    }
+    let (mut canvas_width, mut canvas_height) = (0, 0);
+    for frame in &frames {
+        let (width, height) = frame.buffer().dimensions();
+        if width == 0 || height == 0 {
+            return Err("frames must not be empty".to_string());
+        }
+        canvas_width = canvas_width.max(frame.left() + width);
+        canvas_height = canvas_height.max(frame.top() + height);
+    }
+
+    let mut actl = (frames.len() as u32).to_be_bytes().to_vec();
+    actl.extend_from_slice(&(loop_count(loops) as u32).to_be_bytes());
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    let mut sequence = 0u32;
+    for (index, frame) in frames.iter().enumerate() {
+        let (mut image, mut x, mut y) = (frame.buffer(), frame.left(), frame.top());
+        let padded;
+        if index == 0 && image.dimensions() != (canvas_width, canvas_height) {
+            let mut canvas = RgbaImage::new(canvas_width, canvas_height);
+            imageops::replace(&mut canvas, image, x as i64, y as i64);
+            padded = canvas;
+            (image, x, y) = (&padded, 0, 0);
+        }
+        let png = encode_png(image)?;
+        let chunks = parse_chunks(&png)?;
+        if index == 0 {
+            out.extend_from_slice(chunks[0].raw);
+            write_chunk(&mut out, b"acTL", &actl);
+        }
+
+        // Delays are stored as a fraction of a second
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let (numer, denom) = reduce_delay(numer, denom.saturating_mul(1000));
+        let mut fctl = sequence.to_be_bytes().to_vec();
+        for value in [image.width(), image.height(), x, y] {
+            fctl.extend_from_slice(&value.to_be_bytes());
+        }
+        fctl.extend_from_slice(&numer.to_be_bytes());
+        fctl.extend_from_slice(&denom.to_be_bytes());
+        // No disposal, and each frame replaces its area
+        fctl.extend_from_slice(&[0, 0]);
+        write_chunk(&mut out, b"fcTL", &fctl);
+        sequence += 1;
+
+        for chunk in chunks.iter().filter(|chunk| &chunk.kind == b"IDAT") {
+            if index == 0 {
+                out.extend_from_slice(chunk.raw);
+            } else {
+                let mut fdat = sequence.to_be_bytes().to_vec();
+                fdat.extend_from_slice(chunk.data);
+                write_chunk(&mut out, b"fdAT", &fdat);
+                sequence += 1;
+            }
+        }
+    }
+    write_chunk(&mut out, b"IEND", &[]);
+    Ok(out)
+}
+
+/// Encodes one image as an RGBA PNG
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png)
+        .write_image(
+            image.as_raw(),
+            image.width(),
+            image.height(),
+            ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| format!("failed to encode PNG frame: {}", e))?;
+    Ok(png)
+}
+
+/// A delay of `numer / denom` seconds as the 16-bit fraction APNG stores,
+/// approximated when the terms do not fit
+fn reduce_delay(numer: u32, denom: u32) -> (u16, u16) {
+    if denom == 0 {
+        return (0, 1);
+    }
+    let divisor = gcd(numer, denom);
+    let (numer, denom) = (numer / divisor, denom / divisor);
+    if numer <= u16::MAX as u32 && denom <= u16::MAX as u32 {
+        return (numer as u16, denom as u16);
+    }
+    // Fall back to milliseconds
+    let ms = (numer as f64 * 1000.0 / denom as f64).round();
+    (ms.min(u16::MAX as f64) as u16, 1000)
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], payload: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(payload);
+    if payload.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+fn push_u24(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes()[..3]);
+}
+
+fn delay_ms(delay: Delay) -> u32 {
+    let (numer, denom) = delay.numer_denom_ms();
+    if denom == 0 {
+        return 0;
+    }
+    ((numer as f64 / denom as f64).round() as u32).min(WEBP_MAX_DIMENSION - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_webp_round_trip() {
+        let delay = Delay::from_numer_denom_ms(100, 1);
+        let first = RgbaImage::from_pixel(5, 3, Rgba([255, 0, 0, 255]));
+        let mut second = RgbaImage::from_pixel(5, 3, Rgba([0, 0, 255, 255]));
+        second.put_pixel(2, 1, Rgba([0, 255, 0, 128]));
+        let frames = vec![
+            Frame::from_parts(first.clone(), 0, 0, delay),
+            Frame::from_parts(second.clone(), 0, 0, delay),
+        ];
+
+        let data = encode_webp(frames, Loops::Forever).unwrap();
+        assert_eq!(&data[0..4], b"RIFF");
+        assert_eq!(
+            data.len(),
+            8 + u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize
+        );
+
+        let decoder = WebPDecoder::new(Cursor::new(data)).unwrap();
+        assert!(decoder.has_animation());
+        let decoded = decoder.into_frames().collect_frames().unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].buffer(), &first);
+        assert_eq!(decoded[1].buffer(), &second);
+        assert_eq!(delay_ms(decoded[1].delay()), 100);
+    }
+
+    #[test]
+    fn test_apng_round_trip() {
+        let delay = Delay::from_numer_denom_ms(1000, 12);
+        let first = RgbaImage::from_pixel(6, 4, Rgba([255, 0, 0, 255]));
+        let second = RgbaImage::from_pixel(2, 2, Rgba([0, 0, 255, 128]));
+        let frames = vec![
+            Frame::from_parts(first.clone(), 0, 0, delay),
+            Frame::from_parts(second.clone(), 3, 1, delay),
+        ];
+
+        let data = encode_apng(frames, Loops::Times(3)).unwrap();
+        let chunks = parse_chunks(&data).unwrap();
+        let actl = chunks.iter().find(|chunk| &chunk.kind == b"acTL").unwrap();
+        assert_eq!(actl.data, [0, 0, 0, 2, 0, 0, 0, 3]);
+
+        let decoder = PngDecoder::new(Cursor::new(data)).unwrap();
+        assert!(decoder.is_apng().unwrap());
+        let decoded = decoder
+            .apng()
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].buffer(), &first);
+        // Frames decode composited onto the canvas
+        assert_eq!(decoded[1].buffer().get_pixel(3, 1), &Rgba([0, 0, 255, 128]));
+        assert_eq!(decoded[1].buffer().get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+        assert_eq!(decoded[1].delay().numer_denom_ms(), (250, 3));
+    }
+
+    #[test]
+    fn test_loop_counts() {
+        let frames = || {
+            vec![Frame::new(RgbaImage::from_pixel(
+                2,
+                2,
+                Rgba([9, 9, 9, 255]),
+            ))]
+        };
+        let webp = encode_webp(frames(), Loops::Times(2)).unwrap();
+        // RIFF header, VP8X chunk, then the ANIM payload ends with the count
+        assert_eq!(&webp[30..34], b"ANIM");
+        assert_eq!(&webp[42..44], &[2, 0]);
+        assert_eq!(reduce_delay(1000, 12_000), (1, 12));
+        assert_eq!(reduce_delay(1, 0), (0, 1));
+
+        let err = save_frames_looped("out.gif", frames(), Loops::Times(0)).unwrap_err();
+        assert!(err.contains("at least once"));
+        assert!(is_animation_path("out.PNG") && !is_animation_path("out.mp4"));
+    }
+
+    #[test]
+    fn test_save_frames_checks_input() {
+        assert!(encode_webp(Vec::new(), Loops::Forever).is_err());
+        assert!(encode_apng(Vec::new(), Loops::Forever).is_err());
+        let err = save_frames("out.mp4", Vec::new()).unwrap_err();
+        assert!(err.contains("unsupported"));
+        assert!(frame_delay(0.0).is_err());
+        assert_eq!(delay_ms(frame_delay(20.0).unwrap()), 50);
+    }
+
+    #[test]
+    fn test_frame_delay_keeps_fractional_rates() {
+        let ms = |fps: f32| {
+            let (numer, denom) = frame_delay(fps).unwrap().numer_denom_ms();
+            numer as f64 / denom as f64
+        };
+        assert!((ms(29.97) - 1000.0 / 29.97).abs() < 0.01);
+        assert!((ms(23.976) - 1000.0 / 23.976).abs() < 0.01);
+        // Below one frame per second the delay is longer than a second
+        assert!((ms(0.5) - 2000.0).abs() < 0.01);
+        assert!((ms(0.25) - 4000.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,384 @@
+//! Converted images before rendering
+//!
+//! [`AsciiArt`] is what the pipeline produces: the character grid with its
+//! edge directions and tile luminances, plus the source image when its colors
+//! are preserved or it is blended into the output. Nothing is drawn until an output is requested, so one conversion
+//! can be rendered as an image, text, ANSI, HTML or SVG without running the
+//! pipeline again. [`AsciiArt::to_output`] renders the image and flattens the
+//! grid into a [`ProcessOutput`] for the exporters that take one.
+//!
+//! ```no_run
+//! use ascii_rendr::{AsciiConfig, process_image_preserve_colors};
+//!
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! let art = process_image_preserve_colors(&input, &AsciiConfig::default());
+//! art.render_image().save("photo_ascii.png").unwrap();
+//! std::fs::write("photo.txt", art.render_text()).unwrap();
+//! ```
+
+use crate::ascii::tile_representative_chars;
+use crate::braille::{BrailleLayers, compute_braille_layers};
+use crate::config::{AsciiConfig, OutputMode};
+use crate::edges::EdgeDirection;
+use crate::glyphs::GlyphAtlas;
+use crate::output::ProcessOutput;
+use crate::png_metadata::save_png_with_config;
+use crate::processor::{
+    CharLayers, blend_with_base, compute_char_layers, compute_char_layers_from_luminance,
+    luminance, tile_colors,
+};
+use crate::render::ansi::{AnsiOptions, grid_to_ansi};
+use crate::render::html::{HtmlOptions, grid_to_html};
+use crate::render::renderer::TileRenderer;
+use crate::render::svg::{SvgOptions, grid_to_svg};
+use image::{GrayImage, RgbaImage};
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Character grid of one converted image, ready to render
+pub struct AsciiArt {
+    config: AsciiConfig,
+    grid: Grid,
+    /// Working image, kept when colors are preserved or `blend_with_base` is on
+    source: Option<RgbaImage>,
+    /// Whether colors are sampled from `source`
+    preserve_colors: bool,
+}
+
+/// Layers of the configured output mode, with the composited result
+enum Grid {
+    Tiles {
+        layers: CharLayers,
+        chars: Vec<Vec<char>>,
+    },
+    Braille {
+        layers: BrailleLayers,
+        dots: Vec<bool>,
+    },
+}
+
+impl AsciiArt {
+    /// Runs pipeline steps 1-6 on a working image (dimensions multiples of the
+    /// tile size), copying a borrowed one only when it is kept as the source
+    pub(crate) fn from_image(
+        working_image: Cow<'_, RgbaImage>,
+        config: &AsciiConfig,
+        preserve_colors: bool,
+    ) -> Self {
+        let keep = |image: Cow<'_, RgbaImage>| {
+            (preserve_colors || config.blend_with_base > 0.0).then(|| image.into_owned())
+        };
+        match config.output_mode {
+            OutputMode::Ascii => {
+                let layers = compute_char_layers(&working_image, config);
+                Self::from_char_layers(layers, config, keep(working_image), preserve_colors)
+            }
+            OutputMode::Braille => {
+                let lum = luminance(&working_image, config);
+                Self::from_luminance(&lum, config, keep(working_image), preserve_colors)
+            }
+        }
+    }
+
+    /// Runs pipeline steps 2-6 on a luminance image (dimensions multiples of
+    /// the tile size), with a `source` image of the same size if there is one
+    pub(crate) fn from_luminance(
+        lum: &GrayImage,
+        config: &AsciiConfig,
+        source: Option<RgbaImage>,
+        preserve_colors: bool,
+    ) -> Self {
+        match config.output_mode {
+            OutputMode::Ascii => {
+                let layers = compute_char_layers_from_luminance(lum, config);
+                Self::from_char_layers(layers, config, source, preserve_colors)
+            }
+            OutputMode::Braille => Self::from_braille_layers(
+                compute_braille_layers(lum, config),
+                config,
+                source,
+                preserve_colors,
+            ),
+        }
+    }
+
+    /// Composites tile character layers
+    pub(crate) fn from_char_layers(
+        layers: CharLayers,
+        config: &AsciiConfig,
+        source: Option<RgbaImage>,
+        preserve_colors: bool,
+    ) -> Self {
+        let chars = layers.composite(config);
+        Self::new(
+            config,
+            Grid::Tiles { layers, chars },
+            source,
+            preserve_colors,
+        )
+    }
+
+    /// Composites braille dot layers
+    pub(crate) fn from_braille_layers(
+        layers: BrailleLayers,
+        config: &AsciiConfig,
+        source: Option<RgbaImage>,
+        preserve_colors: bool,
+    ) -> Self {
+        let dots = layers.composite(config);
+        Self::new(
+            config,
+            Grid::Braille { layers, dots },
+            source,
+            preserve_colors,
+        )
+    }
+
+    /// Keeps `source` only when something reads it
+    fn new(
+        config: &AsciiConfig,
+        grid: Grid,
+        source: Option<RgbaImage>,
+        preserve_colors: bool,
+    ) -> Self {
+        let preserve_colors = preserve_colors && source.is_some();
+        Self {
+            config: config.clone(),
+            grid,
+            source: source.filter(|_| preserve_colors || config.blend_with_base > 0.0),
+            preserve_colors,
+        }
+    }
+
+    /// Settings the image was converted with
+    pub fn config(&self) -> &AsciiConfig {
+        &self.config
+    }
+
+    /// Number of character columns
+    pub fn columns(&self) -> u32 {
+        match &self.grid {
+            Grid::Tiles { layers, .. } => layers.tile_width,
+            Grid::Braille { layers, .. } => layers.columns(),
+        }
+    }
+
+    /// Number of character rows
+    pub fn rows(&self) -> u32 {
+        match &self.grid {
+            Grid::Tiles { layers, .. } => layers.tile_height,
+            Grid::Braille { layers, .. } => layers.rows(),
+        }
+    }
+
+    /// Dimensions of the rendered image
+    pub fn dimensions(&self) -> (u32, u32) {
+        match &self.grid {
+            Grid::Tiles { layers, .. } => {
+                let (tile_w, tile_h) = self.config.tile_dimensions();
+                (layers.tile_width * tile_w, layers.tile_height * tile_h)
+            }
+            Grid::Braille { layers, .. } => (layers.width, layers.height),
+        }
+    }
+
+    /// One character per cell, row-major
+    pub fn chars(&self) -> Vec<char> {
+        match &self.grid {
+            Grid::Tiles { chars, .. } => tile_representative_chars(chars),
+            Grid::Braille { layers, dots } => layers.chars(dots),
+        }
+    }
+
+    /// Edge layer before compositing, row-major; spaces (or empty braille
+    /// cells) where there is no edge
+    pub fn edge_chars(&self) -> Vec<char> {
+        match &self.grid {
+            Grid::Tiles { layers, .. } => tile_representative_chars(&layers.edges),
+            Grid::Braille { layers, .. } => layers.chars(&layers.edges),
+        }
+    }
+
+    /// Fill layer before compositing, row-major
+    pub fn fill_chars(&self) -> Vec<char> {
+        match &self.grid {
+            Grid::Tiles { layers, .. } => tile_representative_chars(&layers.fill),
+            Grid::Braille { layers, .. } => layers.chars(&layers.fill),
+        }
+    }
+
+    /// Voted edge direction per tile, row-major, or None in braille mode
+    pub fn edge_directions(&self) -> Option<&[EdgeDirection]> {
+        match &self.grid {
+            Grid::Tiles { layers, .. } => Some(&layers.directions),
+            Grid::Braille { .. } => None,
+        }
+    }
+
+    /// Fill luminance (0.0-1.0) per tile, row-major, or None in braille mode
+    pub fn tile_luminance(&self) -> Option<&[f32]> {
+        match &self.grid {
+            Grid::Tiles { layers, .. } => Some(&layers.tile_lum),
+            Grid::Braille { .. } => None,
+        }
+    }
+
+    /// Source image colors are sampled from, when colors are preserved
+    pub fn source(&self) -> Option<&RgbaImage> {
+        self.source.as_ref().filter(|_| self.preserve_colors)
+    }
+
+    /// Foreground color per cell, row-major: the configured ASCII color, or the
+    /// average source color when colors are preserved
+    pub fn colors(&self) -> Vec<[u8; 3]> {
+        match &self.grid {
+            Grid::Tiles { .. } => {
+                tile_colors(self.source(), &self.config, self.columns() * self.rows())
+            }
+            Grid::Braille { layers, .. } => layers.colors(self.source(), &self.config),
+        }
+    }
+
+    /// Step 7: draws the characters (or the edge debug view) to an image,
+    /// blended with the source by `blend_with_base`
+    pub fn render_image(&self) -> RgbaImage {
+        let image = match &self.grid {
+            Grid::Tiles { layers, chars } => layers.render(chars, &self.config, self.source()),
+            Grid::Braille { layers, dots } => layers.render(dots, &self.config, self.source()),
+        };
+        self.blend(image)
+    }
+
+    /// Step 7 with characters drawn from `glyphs` where it has them; see
+    /// [`crate::glyphs`]
+    ///
+    /// Braille output, the edge debug view and the RGB split keep the built-in
+    /// glyphs.
+    pub fn render_image_with_glyphs(&self, glyphs: &GlyphAtlas) -> RgbaImage {
+        match &self.grid {
+            Grid::Tiles { layers, chars } => self.blend(layers.render_with_glyphs(
+                chars,
+                &self.config,
+                self.source(),
+                Some(glyphs),
+            )),
+            Grid::Braille { .. } => self.render_image(),
+        }
+    }
+
+    fn blend(&self, mut image: RgbaImage) -> RgbaImage {
+        if let Some(source) = &self.source {
+            blend_with_base(&mut image, source, &self.config);
+        }
+        image
+    }
+
+    /// Plain text, one line per row
+    pub fn render_text(&self) -> String {
+        let chars = self.chars();
+        chars
+            .chunks(self.columns().max(1) as usize)
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Text with 24-bit ANSI colors, one line per row ending with a reset
+    pub fn render_ansi(&self, options: &AnsiOptions) -> String {
+        grid_to_ansi(
+            self.columns(),
+            &self.chars(),
+            &self.colors(),
+            options.background.then_some(self.config.bg_color),
+        )
+    }
+
+    /// HTML `<pre>` block; see [`crate::render::html`]
+    pub fn render_html(&self, options: &HtmlOptions) -> String {
+        grid_to_html(
+            self.columns(),
+            &self.chars(),
+            &self.colors(),
+            options.background.then_some(self.config.bg_color),
+            options,
+        )
+    }
+
+    /// SVG document with one `<text>` per character; see [`crate::render::svg`]
+    pub fn render_svg(&self, options: &SvgOptions) -> String {
+        let (width, height) = self.dimensions();
+        grid_to_svg(
+            self.columns(),
+            (width / self.columns().max(1), height / self.rows().max(1)),
+            &self.chars(),
+            &self.colors(),
+            options.background.then_some(self.config.bg_color),
+            options,
+        )
+    }
+
+    /// Output of any [`TileRenderer`], built-in or custom
+    pub fn render<R: TileRenderer>(&self, renderer: &R) -> R::Output {
+        renderer.render(self)
+    }
+
+    /// Renders the image and saves it as a PNG with the config embedded; see
+    /// [`crate::png_metadata`]
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        save_png_with_config(&self.render_image(), &self.config, path)
+    }
+
+    /// Renders the image and keeps the character grid alongside it
+    pub fn to_output(&self) -> ProcessOutput {
+        ProcessOutput {
+            image: self.render_image(),
+            columns: self.columns(),
+            rows: self.rows(),
+            chars: self.chars(),
+            edge_chars: self.edge_chars(),
+            fill_chars: self.fill_chars(),
+            colors: self.colors(),
+            bg_color: self.config.bg_color,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_image_output;
+    use image::Rgba;
+
+    fn scene() -> RgbaImage {
+        RgbaImage::from_fn(48, 32, |x, y| {
+            let v = if x < 24 { 30 } else { 220 };
+            Rgba([v, (y * 7) as u8, 60, 255])
+        })
+    }
+
+    #[test]
+    fn test_one_conversion_many_outputs() {
+        let config = AsciiConfig::default();
+        let art = AsciiArt::from_image(Cow::Owned(scene()), &config, true);
+        let output = process_image_output(&scene(), &config, true);
+
+        assert_eq!((art.columns(), art.rows()), (6, 4));
+        assert_eq!(art.dimensions(), (48, 32));
+        assert_eq!(art.render_image(), output.image);
+        assert_eq!(art.render_text(), output.to_text());
+        assert_eq!(art.colors(), output.colors);
+        assert_eq!(art.edge_directions().map(<[_]>::len), Some(24));
+        assert_eq!(art.tile_luminance().map(<[_]>::len), Some(24));
+        assert!(art.source().is_some());
+
+        let braille = AsciiConfig {
+            output_mode: OutputMode::Braille,
+            ..config
+        };
+        let art = AsciiArt::from_luminance(&luminance(&scene(), &braille), &braille, None, false);
+        assert_eq!((art.columns(), art.rows()), (24, 8));
+        assert_eq!(art.render_text().lines().count(), 8);
+        assert!(art.edge_directions().is_none());
+        assert_eq!(art.colors(), vec![braille.ascii_color; 24 * 8]);
+    }
+}
@@ -1,8 +1,12 @@
-use crate::config::AsciiConfig;
+use crate::config::{
+    AsciiConfig, Charset, CompositeMode, EdgeChars, FillDither, Palette, TileStatistic,
+};
 use crate::edges::EdgeDirection;
-use crate::lut::{get_edge_char, get_fill_char};
-use image::{GrayImage, Rgba, RgbaImage};
-use rayon::prelude::*;
+use crate::filters::SummedAreaTable;
+use crate::glyphs::GlyphAtlas;
+use crate::lut::{ascii_bitmap, denser_ramp_char, get_ramp_char};
+use crate::par::*;
+use image::{GrayImage, RgbaImage};
 
 /// Select ASCII character for a tile
 ///
@@ -22,63 +26,345 @@ use rayon::prelude::*;
 pub fn select_ascii_char(
     edge_dir: EdgeDirection,
     luminance: f32,
-    _tile_x: u32,
-    _tile_y: u32,
+    tile_x: u32,
+    tile_y: u32,
     local_x: u32,
     local_y: u32,
     config: &AsciiConfig,
 ) -> char {
     // Priority: edges first, then fill
-    // Matches shader logic at line 478-496
-    if config.draw_edges && edge_dir != EdgeDirection::None {
-        get_edge_char(edge_dir, local_x, local_y)
-    } else if config.draw_fill {
-        get_fill_char(luminance, config.invert_luminance)
+    // Matches shader logic at line 478-496 with the default composite mode
+    let edge = select_edge_char(edge_dir, local_x, local_y, config);
+    let fill = select_fill_char(luminance, tile_x, tile_y, local_x, local_y, config);
+    composite_char(edge, fill, local_x, local_y, config)
+}
+
+/// Select the edge-layer character for a pixel of a tile
+///
+/// # Returns
+/// The edge character, or a space when the tile has no edge or edges are disabled
+pub fn select_edge_char(
+    edge_dir: EdgeDirection,
+    local_x: u32,
+    local_y: u32,
+    config: &AsciiConfig,
+) -> char {
+    if config.draw_edges {
+        config.edge_chars.char_for(edge_dir, local_x, local_y)
     } else {
         ' '
     }
 }
 
-/// Downscale image luminance to 8×8 tiles by averaging
+/// Select the fill-layer character for a pixel of a tile
+///
+/// # Returns
+/// The luminance ramp character, or a space when fill is disabled
+pub fn select_fill_char(
+    luminance: f32,
+    tile_x: u32,
+    tile_y: u32,
+    local_x: u32,
+    local_y: u32,
+    config: &AsciiConfig,
+) -> char {
+    if !config.draw_fill {
+        return ' ';
+    }
+    get_ramp_char(
+        config.charset.ramp(),
+        luminance,
+        config.invert_luminance,
+        config.fill_gamma,
+        dither_threshold(config.fill_dither, tile_x, tile_y, local_x, local_y),
+    )
+}
+
+/// Combine an edge-layer and a fill-layer character for one pixel of a tile
+///
+/// A space in the edge layer means the tile has no edge.
+///
+/// # Arguments
+/// * `edge` - Edge-layer character
+/// * `fill` - Fill-layer character
+/// * `local_x` - Local X within tile (0-7)
+/// * `local_y` - Local Y within tile (0-7)
+/// * `config` - Compositing policy and edge glyphs
+pub fn composite_char(
+    edge: char,
+    fill: char,
+    local_x: u32,
+    local_y: u32,
+    config: &AsciiConfig,
+) -> char {
+    if edge == ' ' {
+        return fill;
+    }
+    match config.composite_mode {
+        CompositeMode::EdgesOverFill => edge,
+        CompositeMode::FillWhereNoEdge => ' ',
+        // The pixel shows the edge glyph where it has ink, the fill glyph elsewhere
+        CompositeMode::Blend => {
+            if glyph_pixel(edge, local_x, local_y, &config.edge_chars) {
+                edge
+            } else {
+                fill
+            }
+        }
+    }
+}
+
+/// 8×8 Bayer ordered-dither matrix (values 0-63)
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Dither threshold in [0.0, 1.0) for a pixel, or None when dithering is off
+pub(crate) fn dither_threshold(
+    mode: FillDither,
+    tile_x: u32,
+    tile_y: u32,
+    local_x: u32,
+    local_y: u32,
+) -> Option<f32> {
+    match mode {
+        FillDither::Off => None,
+        FillDither::Ordered => {
+            Some((BAYER_8X8[local_y as usize % 8][local_x as usize % 8] as f32 + 0.5) / 64.0)
+        }
+        FillDither::Noise => {
+            // Integer hash of the pixel position, stable across runs
+            let px = tile_x.wrapping_mul(8).wrapping_add(local_x);
+            let py = tile_y.wrapping_mul(8).wrapping_add(local_y);
+            let mut h = px.wrapping_mul(0x8da6_b343) ^ py.wrapping_mul(0xd816_3841);
+            h ^= h >> 13;
+            h = h.wrapping_mul(0x85eb_ca6b);
+            h ^= h >> 16;
+            Some((h & 0xffff) as f32 / 65536.0)
+        }
+    }
+}
+
+/// Downscale image luminance to tiles by averaging
 ///
 /// # Arguments
 /// * `lum` - Input luminance image
-/// * `tile_size` - Size of tiles (8)
+/// * `tile_size` - Tile width and height in pixels, e.g. (8, 8)
 ///
 /// # Returns
 /// Vec of average luminance values, one per tile
-pub fn downscale_to_tiles(lum: &GrayImage, tile_size: u32) -> Vec<f32> {
+pub fn downscale_to_tiles(lum: &GrayImage, tile_size: (u32, u32)) -> Vec<f32> {
+    downscale_to_tiles_with(lum, tile_size, TileStatistic::Mean)
+}
+
+/// Downscale image luminance to tiles using a chosen statistic
+///
+/// Mean matches the shader. Median, max and high percentiles keep thin bright
+/// features (stars, text strokes) that averaging would wash out.
+///
+/// # Arguments
+/// * `lum` - Input luminance image
+/// * `tile_size` - Tile width and height in pixels, e.g. (8, 8)
+/// * `statistic` - How to reduce each tile's pixels to one value
+///
+/// # Returns
+/// Vec of luminance values in [0.0, 1.0], one per tile
+pub fn downscale_to_tiles_with(
+    lum: &GrayImage,
+    tile_size: (u32, u32),
+    statistic: TileStatistic,
+) -> Vec<f32> {
     let (width, height) = lum.dimensions();
-    assert!(width % tile_size == 0 && height % tile_size == 0);
+    let (tile_w, tile_h) = tile_size;
+    assert!(width % tile_w == 0 && height % tile_h == 0);
 
-    let tile_width = width / tile_size;
-    let tile_height = height / tile_size;
+    let tile_width = width / tile_w;
+    let tile_height = height / tile_h;
     let num_tiles = (tile_width * tile_height) as usize;
 
-    // Parallelize tile averaging
+    // Rank used for order statistics; the mean is four lookups per tile in a
+    // summed-area table
+    let percentile = match statistic {
+        TileStatistic::Median => 50,
+        TileStatistic::Max => 100,
+        TileStatistic::Percentile(p) => p.min(100),
+        TileStatistic::Mean => {
+            let table = SummedAreaTable::new(lum);
+            let scale = 1.0 / (255 * tile_w * tile_h) as f32;
+            return (0..num_tiles as u32)
+                .map(|tile_idx| {
+                    let tile_x = tile_idx % tile_width * tile_w;
+                    let tile_y = tile_idx / tile_width * tile_h;
+                    table.sum(tile_x, tile_y, tile_w, tile_h) as f32 * scale
+                })
+                .collect();
+        }
+    };
+
+    // Parallelize tile reduction
+    (0..num_tiles)
+        .into_par_iter()
+        .map(|tile_idx| {
+            let tile_x = (tile_idx as u32) % tile_width;
+            let tile_y = (tile_idx as u32) / tile_width;
+
+            let mut values = Vec::with_capacity((tile_w * tile_h) as usize);
+            for local_y in 0..tile_h {
+                for local_x in 0..tile_w {
+                    let px = tile_x * tile_w + local_x;
+                    let py = tile_y * tile_h + local_y;
+                    values.push(lum.get_pixel(px, py)[0]);
+                }
+            }
+
+            // Nearest-rank order statistic
+            values.sort_unstable();
+            let rank = (percentile as f32 / 100.0 * (values.len() - 1) as f32).round() as usize;
+            values[rank] as f32 / 255.0
+        })
+        .collect()
+}
+
+/// Standard deviation of luminance within each tile
+///
+/// # Arguments
+/// * `lum` - Input luminance image
+/// * `tile_size` - Tile width and height in pixels, e.g. (8, 8)
+///
+/// # Returns
+/// Vec of standard deviations in 8-bit levels (0-255), one per tile
+pub fn tile_std_devs(lum: &GrayImage, tile_size: (u32, u32)) -> Vec<f32> {
+    let (width, height) = lum.dimensions();
+    let (tile_w, tile_h) = tile_size;
+    assert!(width % tile_w == 0 && height % tile_h == 0);
+
+    let tile_width = width / tile_w;
+    let num_tiles = (tile_width * (height / tile_h)) as usize;
+    let count = (tile_w * tile_h) as f32;
+
     (0..num_tiles)
         .into_par_iter()
         .map(|tile_idx| {
             let tile_x = (tile_idx as u32) % tile_width;
             let tile_y = (tile_idx as u32) / tile_width;
+
             let mut sum = 0.0;
+            let mut sum_sq = 0.0;
+            for local_y in 0..tile_h {
+                for local_x in 0..tile_w {
+                    let v = lum.get_pixel(tile_x * tile_w + local_x, tile_y * tile_h + local_y)[0]
+                        as f32;
+                    sum += v;
+                    sum_sq += v * v;
+                }
+            }
+
+            let mean = sum / count;
+            (sum_sq / count - mean * mean).max(0.0).sqrt()
+        })
+        .collect()
+}
+
+/// Promote fill characters of textured tiles to the next denser ramp character
+///
+/// Averaging a busy tile (foliage, fabric, hair) gives the same mid-gray as a
+/// flat one; picking a denser glyph for tiles whose luminance varies more than
+/// `threshold` keeps textured regions from reading as flat.
+///
+/// # Arguments
+/// * `fill_chars` - Fill layer from [`select_fill_chars`], modified in place
+/// * `tile_std` - Luminance standard deviation per tile, from [`tile_std_devs`]
+/// * `threshold` - Standard deviation (0-255) above which a tile is promoted
+/// * `ramp` - Fill ramp the characters come from, sparsest first
+pub fn promote_textured_tiles(
+    fill_chars: &mut [Vec<char>],
+    tile_std: &[f32],
+    threshold: u32,
+    ramp: &[char],
+) {
+    assert_eq!(fill_chars.len(), tile_std.len());
 
-            // Average all pixels in this tile
-            for local_y in 0..tile_size {
-                for local_x in 0..tile_size {
-                    let px = tile_x * tile_size + local_x;
-                    let py = tile_y * tile_size + local_y;
-                    sum += lum.get_pixel(px, py)[0] as f32 / 255.0;
+    fill_chars
+        .par_iter_mut()
+        .zip(tile_std.par_iter())
+        .filter(|(_, std_dev)| **std_dev > threshold as f32)
+        .for_each(|(tile_chars, _)| {
+            for ch in tile_chars.iter_mut() {
+                *ch = denser_ramp_char(ramp, *ch);
+            }
+        });
+}
+
+/// Normalize tile luminance against its local neighborhood
+///
+/// Each tile is rescaled by the mean and standard deviation of the tiles within
+/// `radius` tiles of it, so that detail in dark and bright regions spreads across
+/// the whole fill ramp instead of collapsing to one end. A value one standard
+/// deviation above the local mean maps to 0.75, one below to 0.25.
+///
+/// # Arguments
+/// * `tile_lum` - Vec of average luminance values, one per tile
+/// * `tile_width` - Number of tiles horizontally
+/// * `tile_height` - Number of tiles vertically
+/// * `radius` - Neighborhood radius in tiles
+///
+/// # Returns
+/// Vec of normalized luminance values in [0.0, 1.0], one per tile
+pub fn normalize_local_contrast(
+    tile_lum: &[f32],
+    tile_width: u32,
+    tile_height: u32,
+    radius: u32,
+) -> Vec<f32> {
+    let num_tiles = (tile_width * tile_height) as usize;
+    assert_eq!(tile_lum.len(), num_tiles);
+
+    let radius = radius as i32;
+
+    (0..num_tiles)
+        .into_par_iter()
+        .map(|tile_idx| {
+            let tile_x = (tile_idx as u32 % tile_width) as i32;
+            let tile_y = (tile_idx as u32 / tile_width) as i32;
+
+            // Gather neighborhood statistics (clamped at the image border)
+            let mut sum = 0.0;
+            let mut sum_sq = 0.0;
+            let mut count = 0.0;
+            for ny in (tile_y - radius).max(0)..=(tile_y + radius).min(tile_height as i32 - 1) {
+                for nx in (tile_x - radius).max(0)..=(tile_x + radius).min(tile_width as i32 - 1) {
+                    let v = tile_lum[(ny as u32 * tile_width + nx as u32) as usize];
+                    sum += v;
+                    sum_sq += v * v;
+                    count += 1.0;
                 }
             }
 
-            sum / (tile_size * tile_size) as f32
+            let mean = sum / count;
+            let std_dev = (sum_sq / count - mean * mean).max(0.0).sqrt();
+
+            // Flat neighborhoods keep their absolute luminance
+            if std_dev < 1e-3 {
+                return tile_lum[tile_idx];
+            }
+
+            (0.5 + 0.25 * (tile_lum[tile_idx] - mean) / std_dev).clamp(0.0, 1.0)
         })
         .collect()
 }
 
 /// Select ASCII characters for all tiles
 ///
+/// Equivalent to compositing [`select_edge_chars`] and [`select_fill_chars`]
+/// with `config.composite_mode`.
+///
 /// # Arguments
 /// * `edges` - Vec of edge directions, one per tile
 /// * `tile_lum` - Vec of average luminance values, one per tile
@@ -94,36 +380,298 @@ pub fn select_ascii_chars(
     tile_width: u32,
     tile_height: u32,
     config: &AsciiConfig,
+) -> Vec<Vec<char>> {
+    let edge_chars = select_edge_chars(edges, tile_width, tile_height, config);
+    let fill_chars = select_fill_chars(tile_lum, tile_width, tile_height, config);
+    composite_chars(&edge_chars, &fill_chars, config)
+}
+
+/// Select the edge-layer characters for all tiles
+///
+/// Tiles without an edge (or all tiles, when `draw_edges` is off) are spaces.
+///
+/// # Arguments
+/// * `edges` - Vec of edge directions, one per tile
+/// * `tile_width` - Number of tiles horizontally
+/// * `tile_height` - Number of tiles vertically
+/// * `config` - Configuration settings
+///
+/// # Returns
+/// 2D array of characters: [tile][pixel_in_tile] where pixel_in_tile is 64 chars (8x8)
+pub fn select_edge_chars(
+    edges: &[EdgeDirection],
+    tile_width: u32,
+    tile_height: u32,
+    config: &AsciiConfig,
 ) -> Vec<Vec<char>> {
     let num_tiles = (tile_width * tile_height) as usize;
     assert_eq!(edges.len(), num_tiles);
+
+    edges
+        .par_iter()
+        .map(|&edge_dir| {
+            let mut tile_chars = Vec::with_capacity(64);
+            for local_y in 0..8 {
+                for local_x in 0..8 {
+                    tile_chars.push(select_edge_char(edge_dir, local_x, local_y, config));
+                }
+            }
+            tile_chars
+        })
+        .collect()
+}
+
+/// Select the fill-layer characters for all tiles
+///
+/// Every tile gets its luminance ramp character, regardless of edges; all
+/// spaces when `draw_fill` is off.
+///
+/// # Arguments
+/// * `tile_lum` - Vec of average luminance values, one per tile
+/// * `tile_width` - Number of tiles horizontally
+/// * `tile_height` - Number of tiles vertically
+/// * `config` - Configuration settings
+///
+/// # Returns
+/// 2D array of characters: [tile][pixel_in_tile] where pixel_in_tile is 64 chars (8x8)
+pub fn select_fill_chars(
+    tile_lum: &[f32],
+    tile_width: u32,
+    tile_height: u32,
+    config: &AsciiConfig,
+) -> Vec<Vec<char>> {
+    select_fill_chars_at(tile_lum, tile_width, tile_height, (0, 0), config)
+}
+
+/// [`select_fill_chars`] for a block of tiles whose top-left tile is at
+/// `first_tile` (column, row) in the whole image, so position-dependent
+/// dithering matches converting the whole image
+pub(crate) fn select_fill_chars_at(
+    tile_lum: &[f32],
+    tile_width: u32,
+    tile_height: u32,
+    first_tile: (u32, u32),
+    config: &AsciiConfig,
+) -> Vec<Vec<char>> {
+    let num_tiles = (tile_width * tile_height) as usize;
     assert_eq!(tile_lum.len(), num_tiles);
 
     // Parallelize tile processing
     (0..num_tiles)
         .into_par_iter()
         .map(|tile_idx| {
-            let tile_x = (tile_idx as u32) % tile_width;
-            let tile_y = (tile_idx as u32) / tile_width;
-            let edge_dir = edges[tile_idx];
+            let tile_x = first_tile.0 + (tile_idx as u32) % tile_width;
+            let tile_y = first_tile.1 + (tile_idx as u32) / tile_width;
             let lum = tile_lum[tile_idx];
 
             // Generate 64 characters for this 8x8 tile
             let mut tile_chars = Vec::with_capacity(64);
-
             for local_y in 0..8 {
                 for local_x in 0..8 {
-                    let ch =
-                        select_ascii_char(edge_dir, lum, tile_x, tile_y, local_x, local_y, config);
-                    tile_chars.push(ch);
+                    tile_chars.push(select_fill_char(
+                        lum, tile_x, tile_y, local_x, local_y, config,
+                    ));
                 }
             }
-
             tile_chars
         })
         .collect()
 }
 
+/// Step 6 in structure mode: the glyph closest to each tile
+///
+/// Candidates are all printable ASCII characters with the default charset and
+/// the ramp characters otherwise.
+/// Each tile is sampled on an 8×8 grid (block averages, or the nearest pixels
+/// for tiles smaller than 8) and compared with every glyph bitmap by the sum of
+/// squared differences, counting drawn pixels as 1.0 and background as 0.0.
+/// Samples are inverted and gamma-corrected like the fill ramp. Shape and
+/// brightness both count, so a bright vertical stroke becomes `|` or `l`
+/// rather than a ramp character of the same average. All spaces when
+/// `draw_fill` is off.
+///
+/// # Arguments
+/// * `lum` - Luminance image, dimensions multiples of `tile_size`
+/// * `tile_size` - Tile width and height in pixels
+/// * `config` - Configuration settings
+///
+/// # Returns
+/// 2D array of characters: [tile][pixel_in_tile] where pixel_in_tile is 64 chars (8x8)
+pub fn select_structure_chars(
+    lum: &GrayImage,
+    tile_size: (u32, u32),
+    config: &AsciiConfig,
+) -> Vec<Vec<char>> {
+    let (tile_w, tile_h) = tile_size;
+    let tile_width = lum.width() / tile_w;
+    let num_tiles = (tile_width * (lum.height() / tile_h)) as usize;
+    if !config.draw_fill {
+        return vec![vec![' '; 64]; num_tiles];
+    }
+
+    let candidates: Vec<char> = match config.charset {
+        Charset::Ascii => (' '..='~').collect(),
+        ref charset => charset.ramp().to_vec(),
+    };
+    let glyphs: Vec<(char, [f32; 64])> = candidates
+        .into_iter()
+        .map(|ch| {
+            let mut bitmap = [0.0; 64];
+            for (index, value) in bitmap.iter_mut().enumerate() {
+                if should_draw_pixel(ch, index as u32 % 8, index as u32 / 8) {
+                    *value = 1.0;
+                }
+            }
+            (ch, bitmap)
+        })
+        .collect();
+
+    (0..num_tiles)
+        .into_par_iter()
+        .map(|tile_idx| {
+            let x0 = (tile_idx as u32 % tile_width) * tile_w;
+            let y0 = (tile_idx as u32 / tile_width) * tile_h;
+
+            let mut samples = [0.0f32; 64];
+            for (index, sample) in samples.iter_mut().enumerate() {
+                let (gx, gy) = (index as u32 % 8, index as u32 / 8);
+                let (xs, ys) = (gx * tile_w / 8, gy * tile_h / 8);
+                let (xe, ye) = (
+                    ((gx + 1) * tile_w / 8).max(xs + 1),
+                    ((gy + 1) * tile_h / 8).max(ys + 1),
+                );
+                let mut sum = 0u32;
+                for y in ys..ye {
+                    for x in xs..xe {
+                        sum += lum.get_pixel(x0 + x, y0 + y)[0] as u32;
+                    }
+                }
+                let mut value = sum as f32 / ((xe - xs) * (ye - ys) * 255) as f32;
+                if config.invert_luminance {
+                    value = 1.0 - value;
+                }
+                *sample = value.powf(config.fill_gamma);
+            }
+
+            // The first of equally close glyphs wins, so flat black is a space
+            let best = glyphs
+                .iter()
+                .map(|(ch, bitmap)| {
+                    let distance: f32 = samples
+                        .iter()
+                        .zip(bitmap)
+                        .map(|(s, g)| (s - g) * (s - g))
+                        .sum();
+                    (distance, *ch)
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map_or(' ', |(_, ch)| ch);
+            vec![best; 64]
+        })
+        .collect()
+}
+
+/// Combine edge-layer and fill-layer character grids
+///
+/// # Arguments
+/// * `edge_chars` - Edge layer from [`select_edge_chars`]
+/// * `fill_chars` - Fill layer from [`select_fill_chars`]
+/// * `config` - Compositing policy and edge glyphs
+///
+/// # Returns
+/// 2D array of characters: [tile][pixel_in_tile] where pixel_in_tile is 64 chars (8x8)
+pub fn composite_chars(
+    edge_chars: &[Vec<char>],
+    fill_chars: &[Vec<char>],
+    config: &AsciiConfig,
+) -> Vec<Vec<char>> {
+    assert_eq!(edge_chars.len(), fill_chars.len());
+
+    edge_chars
+        .par_iter()
+        .zip(fill_chars.par_iter())
+        .map(|(edge_tile, fill_tile)| {
+            edge_tile
+                .iter()
+                .zip(fill_tile)
+                .enumerate()
+                .map(|(i, (&edge, &fill))| {
+                    composite_char(edge, fill, i as u32 % 8, i as u32 / 8, config)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Pick one character to stand for each tile in text output
+///
+/// Fill dithering can mix two ramp characters within a tile; the most
+/// frequent one wins, with ties going to the character seen first.
+///
+/// # Arguments
+/// * `chars` - 2D array of characters (one vec per tile, 64 chars per tile)
+///
+/// # Returns
+/// Vec of characters, one per tile
+pub fn tile_representative_chars(chars: &[Vec<char>]) -> Vec<char> {
+    chars
+        .iter()
+        .map(|tile_chars| {
+            let mut counts: Vec<(char, usize)> = Vec::new();
+            for &ch in tile_chars {
+                match counts.iter_mut().find(|(c, _)| *c == ch) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((ch, 1)),
+                }
+            }
+            // max_by_key returns the last maximum, so scan in reverse
+            counts
+                .iter()
+                .rev()
+                .max_by_key(|(_, count)| *count)
+                .map_or(' ', |&(ch, _)| ch)
+        })
+        .collect()
+}
+
+/// Average color of each tile
+///
+/// # Arguments
+/// * `image` - Source image with dimensions that are multiples of `tile_size`
+/// * `tile_size` - Tile width and height in pixels, e.g. (8, 8)
+///
+/// # Returns
+/// Vec of RGB colors, one per tile
+pub fn tile_average_colors(image: &RgbaImage, tile_size: (u32, u32)) -> Vec<[u8; 3]> {
+    let (width, height) = image.dimensions();
+    let (tile_w, tile_h) = tile_size;
+    assert!(width % tile_w == 0 && height % tile_h == 0);
+
+    let tile_width = width / tile_w;
+    let num_tiles = (tile_width * (height / tile_h)) as usize;
+    let pixel_count = tile_w * tile_h;
+
+    (0..num_tiles)
+        .into_par_iter()
+        .map(|tile_idx| {
+            let tile_x = (tile_idx as u32) % tile_width;
+            let tile_y = (tile_idx as u32) / tile_width;
+
+            let mut sum = [0u32; 3];
+            for local_y in 0..tile_h {
+                for local_x in 0..tile_w {
+                    let pixel =
+                        image.get_pixel(tile_x * tile_w + local_x, tile_y * tile_h + local_y);
+                    for c in 0..3 {
+                        sum[c] += pixel[c] as u32;
+                    }
+                }
+            }
+            sum.map(|s| (s / pixel_count) as u8)
+        })
+        .collect()
+}
+
 /// Render ASCII characters to an image
 ///
 /// Creates an 8x8 pixel representation of each character
@@ -148,7 +696,8 @@ pub fn render_ascii_to_image(
 
 /// Render ASCII characters to an image with optional color preservation
 ///
-/// Creates an 8x8 pixel representation of each character
+/// Creates an 8x8 pixel representation of each character. Rows of tiles are
+/// rendered in parallel.
 ///
 /// # Arguments
 /// * `chars` - 2D array of characters (one vec per tile, 64 chars per tile)
@@ -166,73 +715,353 @@ pub fn render_ascii_to_image_with_source(
     config: &AsciiConfig,
     source_image: Option<&RgbaImage>,
 ) -> RgbaImage {
-    let width = tile_width * 8;
-    let height = tile_height * 8;
+    render_ascii_to_image_with_glyphs(chars, tile_width, tile_height, config, source_image, None)
+}
+
+/// Render ASCII characters to an image, drawing glyphs from an atlas
+///
+/// Same as [`render_ascii_to_image_with_source`], but characters that `glyphs`
+/// contains are drawn from its bitmaps, scaled to the tile; the others use the
+/// built-in 8×8 patterns.
+///
+/// # Arguments
+/// * `chars` - 2D array of characters (one vec per tile, 64 chars per tile)
+/// * `tile_width` - Number of tiles horizontally
+/// * `tile_height` - Number of tiles vertically
+/// * `config` - Configuration with colors
+/// * `source_image` - Optional source image to sample colors from
+/// * `glyphs` - Glyph atlas, or None for the built-in patterns only
+///
+/// # Returns
+/// RGBA image with rendered ASCII art
+pub fn render_ascii_to_image_with_glyphs(
+    chars: &[Vec<char>],
+    tile_width: u32,
+    tile_height: u32,
+    config: &AsciiConfig,
+    source_image: Option<&RgbaImage>,
+    glyphs: Option<&GlyphAtlas>,
+) -> RgbaImage {
+    render_tiles(
+        chars,
+        (tile_width, tile_height),
+        config,
+        source_image,
+        glyphs,
+        &[],
+    )
+}
+
+/// [`render_ascii_to_image_with_glyphs`] with each tile's glyphs shifted by
+/// its entry of `edge_offsets` (see [`crate::edges::tile_edge_offsets`]);
+/// empty for no shifts
+pub(crate) fn render_tiles(
+    chars: &[Vec<char>],
+    (tile_width, tile_height): (u32, u32),
+    config: &AsciiConfig,
+    source_image: Option<&RgbaImage>,
+    glyphs: Option<&GlyphAtlas>,
+    edge_offsets: &[(i32, i32)],
+) -> RgbaImage {
+    let (tile_w, tile_h) = config.tile_dimensions();
+    let width = tile_width * tile_w;
+    let height = tile_height * tile_h;
+    let (tile_w, tile_h) = (tile_w as usize, tile_h as usize);
     let mut output = RgbaImage::new(width, height);
+    if width == 0 || height == 0 {
+        return output;
+    }
 
-    let fg_color = Rgba([
-        config.ascii_color[0],
-        config.ascii_color[1],
-        config.ascii_color[2],
-        255,
-    ]);
-    let bg_color = Rgba([
-        config.bg_color[0],
-        config.bg_color[1],
-        config.bg_color[2],
-        255,
-    ]);
-
-    for tile_y in 0..tile_height {
-        for tile_x in 0..tile_width {
-            let tile_idx = (tile_y * tile_width + tile_x) as usize;
-            let tile_chars = &chars[tile_idx];
+    let [fr, fg, fb] = config.ascii_color;
+    let [br, bg, bb] = config.bg_color;
+    let fg_color = [fr, fg, fb, 255];
+    let bg_color = [br, bg, bb, 255];
+    let source = source_image.map(|src| src.as_raw().as_slice());
 
-            for local_y in 0..8 {
-                for local_x in 0..8 {
-                    let char_idx = (local_y * 8 + local_x) as usize;
-                    let ch = tile_chars[char_idx];
-
-                    let px = tile_x * 8 + local_x;
-                    let py = tile_y * 8 + local_y;
-
-                    // Determine color based on source image or config
-                    let color = if let Some(src) = source_image {
-                        // Sample color from source image at this pixel
-                        let src_pixel = src.get_pixel(px, py);
-                        if should_draw_pixel(ch, local_x, local_y) {
-                            *src_pixel // Use original color for foreground
-                        } else {
-                            // Darken the original color for background
-                            Rgba([
-                                (src_pixel[0] as f32 * 0.2) as u8,
-                                (src_pixel[1] as f32 * 0.2) as u8,
-                                (src_pixel[2] as f32 * 0.2) as u8,
-                                255,
-                            ])
-                        }
-                    } else {
-                        // Use solid colors from config
-                        if should_draw_pixel(ch, local_x, local_y) {
-                            fg_color
-                        } else {
-                            bg_color
-                        }
+    // Each band of tile_h pixel rows belongs to one row of tiles, so bands
+    // can be filled in parallel without sharing any output bytes
+    let row_bytes = width as usize * 4;
+    output
+        .par_chunks_exact_mut(row_bytes * tile_h)
+        .enumerate()
+        .for_each(|(tile_y, band)| {
+            let band_start = tile_y * row_bytes * tile_h;
+
+            for tile_x in 0..tile_width as usize {
+                let tile_idx = tile_y * tile_width as usize + tile_x;
+                let tile_chars = &chars[tile_idx];
+                let (shift_x, shift_y) = edge_offsets.get(tile_idx).copied().unwrap_or((0, 0));
+
+                for local_y in 0..tile_h {
+                    for local_x in 0..tile_w {
+                        // Pixel of the (possibly shifted) glyph that lands here
+                        let glyph_source = (local_x as i32 - shift_x, local_y as i32 - shift_y);
+                        let drawn = match glyph_source {
+                            (x, y)
+                                if (0..tile_w as i32).contains(&x)
+                                    && (0..tile_h as i32).contains(&y) =>
+                            {
+                                let (x, y) = (x as usize, y as usize);
+                                // The 8×8 glyph is scaled to the tile
+                                let (glyph_x, glyph_y) = (x * 8 / tile_w, y * 8 / tile_h);
+                                let ch = tile_chars[glyph_y * 8 + glyph_x];
+                                let atlas_pixel = glyphs.and_then(|atlas| {
+                                    let (cell_w, cell_h) = atlas.cell_size();
+                                    atlas.is_drawn(
+                                        ch,
+                                        (x as u32) * cell_w / tile_w as u32,
+                                        (y as u32) * cell_h / tile_h as u32,
+                                    )
+                                });
+                                atlas_pixel.unwrap_or_else(|| {
+                                    glyph_pixel(
+                                        ch,
+                                        glyph_x as u32,
+                                        glyph_y as u32,
+                                        &config.edge_chars,
+                                    )
+                                })
+                            }
+                            _ => false,
+                        };
+                        let offset = local_y * row_bytes + (tile_x * tile_w + local_x) * 4;
+
+                        // Determine color based on source image or config
+                        let color = match source {
+                            // Original color for foreground, darkened for background
+                            Some(src) => {
+                                let s = &src[band_start + offset..band_start + offset + 4];
+                                if drawn {
+                                    [s[0], s[1], s[2], s[3]]
+                                } else {
+                                    [
+                                        (s[0] as f32 * 0.2) as u8,
+                                        (s[1] as f32 * 0.2) as u8,
+                                        (s[2] as f32 * 0.2) as u8,
+                                        255,
+                                    ]
+                                }
+                            }
+                            // Solid colors from config
+                            None if drawn => fg_color,
+                            None => bg_color,
+                        };
+
+                        band[offset..offset + 4].copy_from_slice(&color);
+                    }
+                }
+            }
+        });
+
+    output
+}
+
+/// Render separate character layers for the red, green and blue channels
+///
+/// Each output channel is drawn from its own layer, with the red layer shifted
+/// left and the blue layer shifted right by `offset` pixels. Where all three
+/// layers agree the result looks like the normal render; where they differ or
+/// are shifted apart, colored fringes appear like misregistered print or an
+/// anaglyph.
+///
+/// # Arguments
+/// * `layers` - Composited characters of the R, G and B channels
+/// * `tile_width` - Number of tiles horizontally
+/// * `tile_height` - Number of tiles vertically
+/// * `config` - Configuration with colors
+/// * `source_image` - Optional source image to sample colors from
+/// * `offset` - Horizontal shift of the red and blue layers in pixels
+///
+/// # Returns
+/// RGBA image with the channel-separated ASCII art
+pub fn render_rgb_split(
+    layers: &[Vec<Vec<char>>; 3],
+    tile_width: u32,
+    tile_height: u32,
+    config: &AsciiConfig,
+    source_image: Option<&RgbaImage>,
+    offset: u32,
+) -> RgbaImage {
+    let (tile_w, tile_h) = config.tile_dimensions();
+    let width = tile_width * tile_w;
+    let height = tile_height * tile_h;
+    let mut output = RgbaImage::new(width, height);
+    if width == 0 || height == 0 {
+        return output;
+    }
+
+    let shifts = [-(offset as i64), 0, offset as i64];
+    let source = source_image.map(|src| src.as_raw().as_slice());
+    let row_bytes = width as usize * 4;
+
+    output
+        .par_chunks_exact_mut(row_bytes)
+        .enumerate()
+        .for_each(|(y, row)| {
+            let (tile_y, glyph_y) = (y as u32 / tile_h, y as u32 % tile_h * 8 / tile_h);
+
+            for x in 0..width as usize {
+                let offset = x * 4;
+                for (channel, shift) in shifts.iter().enumerate() {
+                    // Pixel of this channel's layer that lands here
+                    let sx = x as i64 - shift;
+                    let drawn = (0..width as i64).contains(&sx) && {
+                        let sx = sx as u32;
+                        let tile = &layers[channel][(tile_y * tile_width + sx / tile_w) as usize];
+                        let glyph_x = sx % tile_w * 8 / tile_w;
+                        let ch = tile[(glyph_y * 8 + glyph_x) as usize];
+                        glyph_pixel(ch, glyph_x, glyph_y, &config.edge_chars)
                     };
 
-                    output.put_pixel(px, py, color);
+                    row[offset + channel] = match source {
+                        // Original color for foreground, darkened for background
+                        Some(src) => {
+                            let s = src[y * row_bytes + offset + channel];
+                            if drawn { s } else { (s as f32 * 0.2) as u8 }
+                        }
+                        None if drawn => config.ascii_color[channel],
+                        None => config.bg_color[channel],
+                    };
                 }
+                row[offset + 3] = 255;
             }
-        }
+        });
+
+    output
+}
+
+/// Debug color for an edge direction
+///
+/// In the classic palette vertical is red, horizontal green, `/` blue, `\`
+/// yellow, `+` junctions magenta and `X` junctions cyan; the color-blind safe
+/// palettes use the closest hues of their scheme in the same order. Tiles
+/// without an edge return None.
+pub fn edge_debug_color(direction: EdgeDirection, palette: Palette) -> Option<[u8; 3]> {
+    let colors: [[u8; 3]; 6] = match palette {
+        Palette::Classic => [
+            [255, 64, 64],
+            [64, 255, 64],
+            [64, 128, 255],
+            [255, 224, 64],
+            [255, 64, 255],
+            [64, 255, 255],
+        ],
+        Palette::OkabeIto => [
+            [213, 94, 0],
+            [0, 158, 115],
+            [0, 114, 178],
+            [240, 228, 66],
+            [204, 121, 167],
+            [86, 180, 233],
+        ],
+        Palette::TolBright => [
+            [238, 102, 119],
+            [34, 136, 51],
+            [68, 119, 170],
+            [204, 187, 68],
+            [170, 51, 119],
+            [102, 204, 238],
+        ],
+    };
+
+    match direction {
+        EdgeDirection::Vertical => Some(colors[0]),
+        EdgeDirection::Horizontal => Some(colors[1]),
+        EdgeDirection::Diagonal1 => Some(colors[2]),
+        EdgeDirection::Diagonal2 => Some(colors[3]),
+        EdgeDirection::Cross => Some(colors[4]),
+        EdgeDirection::DiagonalCross => Some(colors[5]),
+        EdgeDirection::None => None,
+    }
+}
+
+/// Render a debug view of the edge voting stage
+///
+/// Edge tiles are drawn in their direction's [`edge_debug_color`] on a dim
+/// background of the same hue; other tiles are drawn in a gray matching their
+/// luminance on black.
+///
+/// # Arguments
+/// * `chars` - 2D array of characters (one vec per tile, 64 chars per tile)
+/// * `edges` - Vec of edge directions, one per tile
+/// * `tile_lum` - Vec of tile luminance values, one per tile
+/// * `tile_width` - Number of tiles horizontally
+/// * `tile_height` - Number of tiles vertically
+/// * `config` - Palette for the edge directions, edge glyphs and tile size
+///
+/// # Returns
+/// RGBA image with the color-coded ASCII art
+pub fn render_edge_debug(
+    chars: &[Vec<char>],
+    edges: &[EdgeDirection],
+    tile_lum: &[f32],
+    tile_width: u32,
+    tile_height: u32,
+    config: &AsciiConfig,
+) -> RgbaImage {
+    let num_tiles = (tile_width * tile_height) as usize;
+    assert_eq!(edges.len(), num_tiles);
+    assert_eq!(tile_lum.len(), num_tiles);
+
+    let (tile_w, tile_h) = config.tile_dimensions();
+    let width = tile_width * tile_w;
+    let mut output = RgbaImage::new(width, tile_height * tile_h);
+    if num_tiles == 0 {
+        return output;
     }
 
+    // One band of pixel rows per row of tiles, filled in parallel as in
+    // render_tiles
+    let row_bytes = width as usize * 4;
+    output
+        .par_chunks_exact_mut(row_bytes * tile_h as usize)
+        .enumerate()
+        .for_each(|(tile_y, band)| {
+            for tile_x in 0..tile_width {
+                let tile_idx = tile_y * tile_width as usize + tile_x as usize;
+
+                let (fg, bg) = match edge_debug_color(edges[tile_idx], config.palette) {
+                    Some(color) => (color, color.map(|c| c / 5)),
+                    None => {
+                        // Keep dark tiles visible against the black background
+                        let gray = (64.0 + tile_lum[tile_idx].clamp(0.0, 1.0) * 191.0) as u8;
+                        ([gray; 3], [0; 3])
+                    }
+                };
+
+                for local_y in 0..tile_h {
+                    for local_x in 0..tile_w {
+                        let (glyph_x, glyph_y) = (local_x * 8 / tile_w, local_y * 8 / tile_h);
+                        let ch = chars[tile_idx][(glyph_y * 8 + glyph_x) as usize];
+                        let [r, g, b] = if glyph_pixel(ch, glyph_x, glyph_y, &config.edge_chars) {
+                            fg
+                        } else {
+                            bg
+                        };
+                        let offset =
+                            local_y as usize * row_bytes + (tile_x * tile_w + local_x) as usize * 4;
+                        band[offset..offset + 4].copy_from_slice(&[r, g, b, 255]);
+                    }
+                }
+            }
+        });
+
     output
 }
 
+/// Whether pixel (x, y) of a character's glyph is ink, preferring the bitmaps
+/// supplied with custom edge characters over the built-in ones
+fn glyph_pixel(ch: char, x: u32, y: u32, edge_chars: &EdgeChars) -> bool {
+    match edge_chars.bitmap(ch) {
+        Some(bitmap) => bitmap[y as usize] & (0x80 >> x) != 0,
+        None => should_draw_pixel(ch, x, y),
+    }
+}
+
 /// Determine if a pixel should be drawn for a character at a given position
 ///
 /// This is a simple 8x8 bitmap representation of ASCII characters
-/// In a real implementation, this would use actual font rendering
+/// A [`crate::glyphs::GlyphAtlas`] replaces it with texture or font glyphs
 ///
 /// # Arguments
 /// * `ch` - The character
@@ -241,13 +1070,21 @@ pub fn render_ascii_to_image_with_source(
 ///
 /// # Returns
 /// true if pixel should be drawn (foreground color), false for background
-fn should_draw_pixel(ch: char, x: u32, y: u32) -> bool {
+pub(crate) fn should_draw_pixel(ch: char, x: u32, y: u32) -> bool {
     match ch {
         ' ' => false, // Space: always empty
 
-        '|' => x == 3 || x == 4, // Vertical bar in middle
+        '|' | '│' => x == 3 || x == 4, // Vertical bar in middle
+
+        '┃' => (2..=5).contains(&x), // Heavy vertical bar
 
-        '-' => y == 3 || y == 4, // Horizontal bar in middle
+        '-' | '─' => y == 3 || y == 4, // Horizontal bar in middle
+
+        '━' => (2..=5).contains(&y), // Heavy horizontal bar
+
+        '╱' => x + y == 7, // Thin diagonal corner to corner
+
+        '╲' => x == y,
 
         '/' => {
             // Diagonal from bottom-left to top-right
@@ -274,6 +1111,11 @@ fn should_draw_pixel(ch: char, x: u32, y: u32) -> bool {
             (x == 3 || x == 4) || (y == 3 || y == 4)
         }
 
+        'X' => {
+            // Both diagonals
+            (x == y) || (x == 7 - y)
+        }
+
         '*' => {
             // Star/asterisk - simplified
             (x == 3 || x == 4) || (y == 3 || y == 4) || (x == y) || (x == 7 - y)
@@ -289,6 +1131,14 @@ fn should_draw_pixel(ch: char, x: u32, y: u32) -> bool {
             (x + y == 7) || (x == 1 && y == 1) || (x == 6 && y == 6)
         }
 
+        '░' => x.is_multiple_of(2) && y.is_multiple_of(2), // Light shade: every fourth pixel
+
+        '▒' => (x + y).is_multiple_of(2), // Medium shade: checkerboard
+
+        '▓' => x.is_multiple_of(2) || y.is_multiple_of(2), // Dark shade: three of four pixels
+
+        '█' => true, // Full block
+
         '@' => {
             // At symbol - filled circle approximation
             let dx = x as i32 - 3;
@@ -296,17 +1146,19 @@ fn should_draw_pixel(ch: char, x: u32, y: u32) -> bool {
             dx * dx + dy * dy <= 12
         }
 
-        _ => {
+        _ => match ascii_bitmap(ch) {
+            Some(bitmap) => bitmap[y as usize] & (0x80 >> x) != 0,
             // Unknown character: use a filled square
-            true
-        }
+            None => true,
+        },
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use image::Luma;
+    use crate::lut::FILL_CHARS;
+    use image::{Luma, Rgba};
 
     #[test]
     fn test_select_ascii_char_edge() {
@@ -335,17 +1187,119 @@ mod tests {
         assert_eq!(ch, '@'); // Brightest = @
     }
 
+    #[test]
+    fn test_select_ascii_char_dithered_mixes_neighbors() {
+        let config = AsciiConfig {
+            draw_edges: false,
+            fill_dither: FillDither::Ordered,
+            ..Default::default()
+        };
+
+        // Halfway between two ramp levels: both characters appear in the tile
+        let mut tile = Vec::new();
+        for local_y in 0..8 {
+            for local_x in 0..8 {
+                tile.push(select_ascii_char(
+                    EdgeDirection::None,
+                    0.35,
+                    0,
+                    0,
+                    local_x,
+                    local_y,
+                    &config,
+                ));
+            }
+        }
+        let denser = tile.iter().filter(|&&c| c == '=').count();
+        assert_eq!(denser, 32);
+        assert_eq!(tile.iter().filter(|&&c| c == '-').count(), 32);
+    }
+
+    #[test]
+    fn test_dither_threshold_range() {
+        for mode in [FillDither::Ordered, FillDither::Noise] {
+            for y in 0..8 {
+                for x in 0..8 {
+                    let t = dither_threshold(mode, 3, 5, x, y).unwrap();
+                    assert!((0.0..1.0).contains(&t));
+                }
+            }
+        }
+        assert_eq!(dither_threshold(FillDither::Off, 0, 0, 0, 0), None);
+    }
+
     #[test]
     fn test_downscale_to_tiles() {
         // Create 16x16 image (2x2 tiles)
         let img = GrayImage::from_pixel(16, 16, Luma([128]));
-        let tiles = downscale_to_tiles(&img, 8);
+        let tiles = downscale_to_tiles(&img, (8, 8));
 
         assert_eq!(tiles.len(), 4); // 2x2 tiles
         // All tiles should have average luminance ~0.5 (128/255)
         for &lum in &tiles {
             assert!((lum - 0.5).abs() < 0.01);
         }
+
+        // 8×16 tiles: a 16×32 image with a bright bottom half is 2×2 tiles
+        let img = GrayImage::from_fn(16, 32, |_, y| Luma([if y < 16 { 0 } else { 255 }]));
+        assert_eq!(downscale_to_tiles(&img, (8, 16)), vec![0.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_normalize_local_contrast_spreads_dark_detail() {
+        // Dark 4x1 strip with small variations
+        let tile_lum = vec![0.05, 0.10, 0.05, 0.10];
+        let normalized = normalize_local_contrast(&tile_lum, 4, 1, 1);
+
+        // Brighter tiles end up above mid-gray, darker ones below
+        assert!(normalized[1] > 0.5);
+        assert!(normalized[2] < 0.5);
+    }
+
+    #[test]
+    fn test_promote_textured_tiles() {
+        // Left tile flat mid-gray, right tile a checkerboard with the same mean
+        let img = GrayImage::from_fn(16, 8, |x, y| {
+            if x < 8 {
+                Luma([128])
+            } else if (x + y) % 2 == 0 {
+                Luma([28])
+            } else {
+                Luma([228])
+            }
+        });
+        let std = tile_std_devs(&img, (8, 8));
+        assert!(std[0] < 1e-3);
+        assert!((std[1] - 100.0).abs() < 1e-3);
+
+        let mut fill = vec![vec!['='; 64], vec!['='; 64]];
+        promote_textured_tiles(&mut fill, &std, 32, &FILL_CHARS);
+        assert!(fill[0].iter().all(|&c| c == '='));
+        assert!(fill[1].iter().all(|&c| c == '+'));
+    }
+
+    #[test]
+    fn test_normalize_local_contrast_flat_unchanged() {
+        let tile_lum = vec![0.3; 9];
+        let normalized = normalize_local_contrast(&tile_lum, 3, 3, 1);
+        assert_eq!(normalized, tile_lum);
+    }
+
+    #[test]
+    fn test_downscale_to_tiles_statistics() {
+        // One bright pixel in an otherwise dark 8x8 tile
+        let mut img = GrayImage::from_pixel(8, 8, Luma([0]));
+        img.put_pixel(3, 3, Luma([255]));
+
+        let mean = downscale_to_tiles_with(&img, (8, 8), TileStatistic::Mean)[0];
+        let median = downscale_to_tiles_with(&img, (8, 8), TileStatistic::Median)[0];
+        let max = downscale_to_tiles_with(&img, (8, 8), TileStatistic::Max)[0];
+        let p0 = downscale_to_tiles_with(&img, (8, 8), TileStatistic::Percentile(0))[0];
+
+        assert!((mean - 1.0 / 64.0).abs() < 1e-6);
+        assert_eq!(median, 0.0);
+        assert_eq!(max, 1.0);
+        assert_eq!(p0, 0.0);
     }
 
     #[test]
@@ -361,6 +1315,85 @@ mod tests {
         assert_eq!(chars[1].len(), 64);
     }
 
+    #[test]
+    fn test_composite_modes() {
+        let edges = vec![EdgeDirection::Vertical, EdgeDirection::None];
+        let tile_lum = vec![1.0, 1.0];
+        let config = AsciiConfig::default();
+
+        let edge_chars = select_edge_chars(&edges, 2, 1, &config);
+        let fill_chars = select_fill_chars(&tile_lum, 2, 1, &config);
+        assert!(edge_chars[1].iter().all(|&c| c == ' '));
+        assert!(fill_chars[0].iter().all(|&c| c == '@'));
+
+        let over = composite_chars(
+            &edge_chars,
+            &fill_chars,
+            &AsciiConfig {
+                composite_mode: CompositeMode::EdgesOverFill,
+                ..config.clone()
+            },
+        );
+        assert!(over[0].iter().all(|&c| c == '|'));
+        assert!(over[1].iter().all(|&c| c == '@'));
+        assert_eq!(over, select_ascii_chars(&edges, &tile_lum, 2, 1, &config));
+
+        let knockout = composite_chars(
+            &edge_chars,
+            &fill_chars,
+            &AsciiConfig {
+                composite_mode: CompositeMode::FillWhereNoEdge,
+                ..config.clone()
+            },
+        );
+        assert!(knockout[0].iter().all(|&c| c == ' '));
+        assert!(knockout[1].iter().all(|&c| c == '@'));
+
+        // Edge strokes on the middle columns, fill glyph around them
+        let blend = composite_chars(
+            &edge_chars,
+            &fill_chars,
+            &AsciiConfig {
+                composite_mode: CompositeMode::Blend,
+                ..config.clone()
+            },
+        );
+        assert_eq!(blend[0][3], '|');
+        assert_eq!(blend[0][0], '@');
+        assert!(blend[1].iter().all(|&c| c == '@'));
+    }
+
+    #[test]
+    fn test_custom_edge_chars() {
+        let edges = vec![EdgeDirection::Horizontal, EdgeDirection::Diagonal1];
+        let config = AsciiConfig {
+            draw_fill: false,
+            edge_chars: EdgeChars::BoxDrawing,
+            ..Default::default()
+        };
+        let chars = select_ascii_chars(&edges, &[0.0, 0.0], 2, 1, &config);
+        assert!(chars[0].iter().all(|&c| c == '─'));
+        assert!(chars[1].iter().all(|&c| c == '╱'));
+        let img = render_ascii_to_image(&chars, 2, 1, &config);
+        assert_eq!(img.get_pixel(0, 3), &Rgba([255, 255, 255, 255]));
+        assert_eq!(img.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+
+        // Supplied bitmaps replace the filled-square fallback
+        let config = AsciiConfig {
+            edge_chars: EdgeChars::Custom {
+                chars: ['I', '~', 'z', 'N'],
+                bitmaps: Some([[0x80; 8], [0x01; 8], [0xff; 8], [0; 8]]),
+            },
+            ..config
+        };
+        let chars = select_ascii_chars(&edges, &[0.0, 0.0], 2, 1, &config);
+        assert_eq!(chars[0][0], '~');
+        let img = render_ascii_to_image(&chars, 2, 1, &config);
+        assert_eq!(img.get_pixel(7, 5), &Rgba([255, 255, 255, 255]));
+        assert_eq!(img.get_pixel(0, 5), &Rgba([0, 0, 0, 255]));
+        assert_eq!(img.get_pixel(8, 0), &Rgba([255, 255, 255, 255]));
+    }
+
     #[test]
     fn test_render_ascii_to_image() {
         let chars = vec![
@@ -374,6 +1407,115 @@ mod tests {
         assert_eq!(img.dimensions(), (16, 8)); // 2 tiles wide, 1 tile high, 8x8 pixels each
     }
 
+    #[test]
+    fn test_render_ascii_to_image_with_source_colors() {
+        let chars = vec![vec!['|'; 64], vec![' '; 64], vec!['-'; 64], vec!['@'; 64]];
+        let source = RgbaImage::from_fn(16, 16, |x, y| Rgba([x as u8 * 10, y as u8 * 10, 50, 255]));
+
+        let img =
+            render_ascii_to_image_with_source(&chars, 2, 2, &AsciiConfig::default(), Some(&source));
+        assert_eq!(img.dimensions(), (16, 16));
+        // Foreground pixels keep the source color, background is darkened
+        assert_eq!(*img.get_pixel(3, 0), *source.get_pixel(3, 0));
+        assert_eq!(*img.get_pixel(0, 0), Rgba([0, 0, 10, 255]));
+        assert_eq!(*img.get_pixel(8, 4), Rgba([16, 8, 10, 255]));
+        assert_eq!(*img.get_pixel(3, 11), *source.get_pixel(3, 11));
+
+        let plain = render_ascii_to_image(&chars, 2, 2, &AsciiConfig::default());
+        assert_eq!(*plain.get_pixel(3, 0), Rgba([255, 255, 255, 255]));
+        assert_eq!(*plain.get_pixel(15, 15), Rgba([0, 0, 0, 255]));
+        assert_eq!(*plain.get_pixel(0, 11), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_render_tiles_edge_offsets() {
+        let chars = vec![vec!['|'; 64], vec!['|'; 64]];
+        let config = AsciiConfig::default();
+        let white = Rgba([255, 255, 255, 255]);
+
+        // The left bar moves two pixels left, the right one stays centered
+        let img = render_tiles(&chars, (2, 1), &config, None, None, &[(-2, 0), (0, 0)]);
+        assert_eq!(*img.get_pixel(1, 4), white);
+        assert_eq!(*img.get_pixel(2, 4), white);
+        assert_ne!(*img.get_pixel(4, 4), white);
+        assert_eq!(*img.get_pixel(11, 4), white);
+
+        // No offsets is the plain render
+        let plain = render_ascii_to_image(&chars, 2, 1, &config);
+        assert_eq!(
+            render_tiles(&chars, (2, 1), &config, None, None, &[]),
+            plain
+        );
+    }
+
+    #[test]
+    fn test_render_rgb_split() {
+        let config = AsciiConfig::default();
+        let bars = vec![vec!['|'; 64]];
+        let layers = [bars.clone(), bars.clone(), bars];
+
+        // Aligned layers look like the normal white-on-black render
+        let aligned = render_rgb_split(&layers, 1, 1, &config, None, 0);
+        assert_eq!(aligned, render_ascii_to_image(&layers[0], 1, 1, &config));
+
+        // Shifted apart, the bar (x = 3..=4) splits into red, green and blue
+        let split = render_rgb_split(&layers, 1, 1, &config, None, 2);
+        assert_eq!(*split.get_pixel(1, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*split.get_pixel(3, 0), Rgba([0, 255, 0, 255]));
+        assert_eq!(*split.get_pixel(6, 0), Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_render_edge_debug() {
+        let chars = vec![vec!['|'; 64], vec!['@'; 64]];
+        let edges = vec![EdgeDirection::Vertical, EdgeDirection::None];
+        let img = render_edge_debug(&chars, &edges, &[0.5, 1.0], 2, 1, &AsciiConfig::default());
+
+        assert_eq!(img.dimensions(), (16, 8));
+        assert_eq!(*img.get_pixel(3, 0), Rgba([255, 64, 64, 255]));
+        assert_eq!(*img.get_pixel(0, 0), Rgba([51, 12, 12, 255]));
+        assert_eq!(*img.get_pixel(11, 3), Rgba([255, 255, 255, 255]));
+        assert_eq!(*img.get_pixel(15, 7), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_select_structure_chars() {
+        let config = AsciiConfig::default();
+        // Black tile, then a tile with a bright two-pixel vertical bar
+        let img = GrayImage::from_fn(16, 8, |x, _| {
+            Luma([if x == 11 || x == 12 { 255 } else { 0 }])
+        });
+        let chars = select_structure_chars(&img, (8, 8), &config);
+        assert_eq!(chars.len(), 2);
+        assert!(chars[0].iter().all(|&c| c == ' '));
+        assert!(chars[1].iter().all(|&c| c == '|'));
+
+        // Larger tiles are averaged down to the 8×8 grid
+        let img = GrayImage::from_fn(16, 16, |x, _| {
+            Luma([if (6..10).contains(&x) { 255 } else { 0 }])
+        });
+        let chars = select_structure_chars(&img, (16, 16), &config);
+        assert_eq!(chars[0][0], '|');
+
+        let no_fill = AsciiConfig {
+            draw_fill: false,
+            ..Default::default()
+        };
+        let chars = select_structure_chars(&img, (16, 16), &no_fill);
+        assert!(chars[0].iter().all(|&c| c == ' '));
+    }
+
+    #[test]
+    fn test_should_draw_pixel_ascii_table() {
+        // Characters without a hand-drawn pattern come from the table
+        assert!(should_draw_pixel('A', 2, 0));
+        assert!(!should_draw_pixel('A', 0, 0));
+        assert!(should_draw_pixel('_', 0, 7));
+        assert!(!should_draw_pixel('_', 0, 0));
+        // Characters outside printable ASCII are filled squares
+        assert!(should_draw_pixel('é', 0, 0));
+    }
+
     #[test]
     fn test_should_draw_pixel_space() {
         assert!(!should_draw_pixel(' ', 0, 0));
@@ -393,4 +1535,29 @@ mod tests {
         assert!(should_draw_pixel('-', 7, 4));
         assert!(!should_draw_pixel('-', 0, 0));
     }
+
+    #[test]
+    fn test_tile_representative_chars() {
+        let mut dithered = vec!['.'; 40];
+        dithered.extend(vec![':'; 24]);
+        let mut tied = vec![':'; 32];
+        tied.extend(vec!['.'; 32]);
+        let chars = vec![dithered, tied, vec!['|'; 64]];
+        assert_eq!(tile_representative_chars(&chars), vec!['.', ':', '|']);
+    }
+
+    #[test]
+    fn test_tile_average_colors() {
+        let img = RgbaImage::from_fn(16, 8, |x, _| {
+            if x < 8 {
+                Rgba([200, 100, 0, 255])
+            } else {
+                Rgba([0, 0, 50, 255])
+            }
+        });
+        assert_eq!(
+            tile_average_colors(&img, (8, 8)),
+            vec![[200, 100, 0], [0, 0, 50]]
+        );
+    }
 }
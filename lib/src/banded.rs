@@ -0,0 +1,177 @@
+//! Conversion of very large images in horizontal bands
+//!
+//! The edge passes (two blurs, DoG, Sobel, voting) keep several full-size
+//! `f32` buffers, about 20 bytes per pixel, which for a 10000×10000 scan is
+//! gigabytes. [`process_image_banded`] runs them on bands of tile rows
+//! instead, each extended by a halo as wide as the blur and Sobel kernels can
+//! reach, so peak memory follows the band size. Luminance, fill and the
+//! character grid are cheap (one byte per pixel or less) and stay whole-image,
+//! so settings that look at the whole image still see all of it.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::banded::process_image_banded;
+//!
+//! let input = image::open("scan.tif").unwrap().to_rgba8();
+//! let art = process_image_banded(&input, &AsciiConfig::default(), false, 32).unwrap();
+//! std::fs::write("scan.txt", art.render_text()).unwrap();
+//! ```
+
+use crate::art::AsciiArt;
+use crate::ascii::select_edge_chars;
+use crate::config::{AsciiConfig, OutputMode};
+use crate::error::{AsciiError, check_image_size};
+use crate::incremental::{Rect, compute_region_edges};
+use crate::processor::{
+    CharLayers, channel_fill_layers, compute_fill_layer, luminance, pre_filter_luminance,
+    resize_to_valid_dimensions, tile_edge_thresholds, working_multiple,
+};
+use image::RgbaImage;
+
+/// Converts an image with the edge passes run `band_rows` tile rows at a time
+///
+/// The result equals [`process_image`](crate::process_image) /
+/// [`process_image_preserve_colors`](crate::process_image_preserve_colors),
+/// except that an automatic `threshold_mode` picks its threshold per band.
+/// Braille output is converted in one piece.
+///
+/// # Arguments
+/// * `input` - The input RGBA image to convert
+/// * `config` - Configuration parameters for the ASCII conversion
+/// * `preserve_colors` - Sample colors from the source image instead of `ascii_color`
+/// * `band_rows` - Tile rows per band (at least 1); smaller bands use less
+///   memory but recompute more halo rows
+///
+/// # Errors
+/// Same as [`try_process_image`](crate::try_process_image).
+///
+/// # Note
+/// If the input image dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering.
+pub fn process_image_banded(
+    input: &RgbaImage,
+    config: &AsciiConfig,
+    preserve_colors: bool,
+    band_rows: u32,
+) -> Result<AsciiArt, AsciiError> {
+    config.validate()?;
+    check_image_size(input.width(), input.height(), working_multiple(config))?;
+
+    let working_image = resize_to_valid_dimensions(input, working_multiple(config));
+    if config.output_mode == OutputMode::Braille {
+        return Ok(AsciiArt::from_image(working_image, config, preserve_colors));
+    }
+
+    // Step 1 and the pre-filters over the whole image, one byte per pixel
+    let lum = luminance(&working_image, config);
+    let lum = pre_filter_luminance(&lum, config);
+    let (width, height) = lum.dimensions();
+    let (tile_w, tile_h) = config.tile_dimensions();
+    let (tile_width, tile_height) = (width / tile_w, height / tile_h);
+
+    // Steps 2-4 band by band; bands span the full width, so their tiles
+    // follow each other in row order
+    let thresholds = tile_edge_thresholds(&lum, config);
+    let mut directions = Vec::with_capacity((tile_width * tile_height) as usize);
+    let mut edge_offsets = Vec::new();
+    let band_height = band_rows.max(1) * tile_h;
+    for y in (0..height).step_by(band_height as usize) {
+        let tiles = Rect::new(0, y, width, band_height.min(height - y));
+        let (band_directions, band_offsets) =
+            compute_region_edges(&lum, config, tiles, thresholds.as_deref());
+        directions.extend(band_directions);
+        edge_offsets.extend(band_offsets);
+    }
+
+    // Steps 5-6 over the whole image
+    let (fill, tile_lum) = compute_fill_layer(&lum, config);
+
+    let layers = CharLayers {
+        edges: select_edge_chars(&directions, tile_width, tile_height, config),
+        fill,
+        directions,
+        edge_offsets,
+        tile_lum,
+        tile_width,
+        tile_height,
+        channel_fill: channel_fill_layers(&working_image, config),
+    };
+    Ok(AsciiArt::from_char_layers(
+        layers,
+        config,
+        (preserve_colors || config.blend_with_base > 0.0).then(|| working_image.into_owned()),
+        preserve_colors,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FillDither;
+    use crate::{process_image, process_image_preserve_colors};
+    use image::Rgba;
+
+    /// Diagonal stripes over a gradient, so every band has edges
+    fn scene() -> RgbaImage {
+        RgbaImage::from_fn(80, 100, |x, y| {
+            let v = if ((x + y) / 14) % 2 == 0 { 210 } else { 40 };
+            Rgba([v, (x * 3) as u8, (y * 2) as u8, 255])
+        })
+    }
+
+    #[test]
+    fn test_banded_matches_full() {
+        let configs = [
+            AsciiConfig::default(),
+            AsciiConfig {
+                kernel_size: 5,
+                positional_edges: true,
+                fill_dither: FillDither::Noise,
+                ..Default::default()
+            },
+            AsciiConfig {
+                saliency_strength: 0.5,
+                structure_tensor_sigma: Some(1.5),
+                non_max_suppression: true,
+                edge_dilation: 1,
+                ..Default::default()
+            },
+        ];
+        for (i, config) in configs.iter().enumerate() {
+            let expected = process_image(&scene(), config);
+            for band_rows in [1, 2, 5, 100] {
+                let art = process_image_banded(&scene(), config, false, band_rows).unwrap();
+                assert_eq!(
+                    art.chars(),
+                    expected.chars(),
+                    "config {} band {}",
+                    i,
+                    band_rows
+                );
+                assert_eq!(art.render_image(), expected.render_image());
+            }
+        }
+
+        let config = AsciiConfig::default();
+        let art = process_image_banded(&scene(), &config, true, 3).unwrap();
+        assert_eq!(
+            art.render_image(),
+            process_image_preserve_colors(&scene(), &config).render_image()
+        );
+    }
+
+    #[test]
+    fn test_banded_errors() {
+        let small = RgbaImage::new(4, 4);
+        assert!(matches!(
+            process_image_banded(&small, &AsciiConfig::default(), false, 4),
+            Err(AsciiError::ImageTooSmall { .. })
+        ));
+        let config = AsciiConfig {
+            sigma: -1.0,
+            ..Default::default()
+        };
+        assert!(process_image_banded(&scene(), &config, false, 4).is_err());
+    }
+}
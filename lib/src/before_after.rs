@@ -0,0 +1,239 @@
+//! Before/after comparison clips
+//!
+//! [`BeforeAfter`] holds an original image and its ASCII conversion and renders
+//! a looping clip that rests on each and transitions between them, the kind of
+//! comparison commonly posted alongside ASCII art. The loop is split into four
+//! equal-length phases: original, transition to ASCII, ASCII, transition back.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::before_after::{BeforeAfter, Transition};
+//!
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! BeforeAfter::new(&input, &AsciiConfig::default(), true)
+//!     .with_transition(Transition::Wipe)
+//!     .save_animation("photo_before_after.gif", 48, 16.0)
+//!     .unwrap();
+//! ```
+
+use crate::animation;
+use crate::config::AsciiConfig;
+use crate::processor::{
+    process_image, process_image_preserve_colors, resize_to_valid_dimensions, working_multiple,
+};
+use image::{Frame, Rgba, RgbaImage, imageops};
+use std::path::Path;
+
+/// How the clip moves between the original and the ASCII image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transition {
+    /// Blend the two images
+    #[default]
+    CrossFade,
+    /// Sweep a vertical divider from left to right (and back)
+    Wipe,
+}
+
+impl Transition {
+    /// All transitions, in menu order
+    pub const ALL: [Transition; 2] = [Transition::CrossFade, Transition::Wipe];
+
+    /// Human-readable name
+    pub fn name(self) -> &'static str {
+        match self {
+            Transition::CrossFade => "Cross-fade",
+            Transition::Wipe => "Wipe",
+        }
+    }
+}
+
+/// An original image and its conversion, ready to render comparison frames
+pub struct BeforeAfter {
+    before: RgbaImage,
+    after: RgbaImage,
+    transition: Transition,
+}
+
+impl BeforeAfter {
+    /// Converts `input` and pairs the result with it
+    ///
+    /// # Arguments
+    /// * `input` - The original image
+    /// * `config` - Configuration parameters for the ASCII conversion
+    /// * `preserve_colors` - Sample colors from the source image instead of `ascii_color`
+    pub fn new(input: &RgbaImage, config: &AsciiConfig, preserve_colors: bool) -> Self {
+        let after = if preserve_colors {
+            process_image_preserve_colors(input, config).render_image()
+        } else {
+            process_image(input, config).render_image()
+        };
+        let before = resize_to_valid_dimensions(input, working_multiple(config));
+        Self::from_images(&before, after)
+    }
+
+    /// Pairs an original with an output produced elsewhere (for example with
+    /// post effects applied)
+    ///
+    /// The original is resized to the output's dimensions when they differ.
+    pub fn from_images(before: &RgbaImage, after: RgbaImage) -> Self {
+        let (width, height) = after.dimensions();
+        let before = if before.dimensions() == (width, height) {
+            before.clone()
+        } else {
+            imageops::resize(before, width, height, imageops::FilterType::Lanczos3)
+        };
+        Self {
+            before,
+            after,
+            transition: Transition::default(),
+        }
+    }
+
+    /// Sets the transition (default [`Transition::CrossFade`])
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Renders the frame at position `t` in the loop
+    ///
+    /// # Arguments
+    /// * `t` - Loop position in [0.0, 1.0); values outside wrap around
+    pub fn frame(&self, t: f32) -> RgbaImage {
+        let amount = after_amount(t);
+        if amount <= 0.0 {
+            return self.before.clone();
+        }
+        if amount >= 1.0 {
+            return self.after.clone();
+        }
+
+        let (width, height) = self.after.dimensions();
+        match self.transition {
+            Transition::CrossFade => RgbaImage::from_fn(width, height, |x, y| {
+                let (a, b) = (self.before.get_pixel(x, y), self.after.get_pixel(x, y));
+                Rgba(std::array::from_fn(|c| {
+                    (a[c] as f32 + (b[c] as f32 - a[c] as f32) * amount).round() as u8
+                }))
+            }),
+            Transition::Wipe => {
+                let edge = (amount * width as f32).round() as u32;
+                RgbaImage::from_fn(width, height, |x, y| {
+                    if x < edge {
+                        *self.after.get_pixel(x, y)
+                    } else {
+                        *self.before.get_pixel(x, y)
+                    }
+                })
+            }
+        }
+    }
+
+    /// Renders one full loop of `count` frames
+    pub fn frames(&self, count: u32) -> impl Iterator<Item = RgbaImage> + '_ {
+        let count = count.max(1);
+        (0..count).map(move |i| self.frame(i as f32 / count as f32))
+    }
+
+    /// Saves one loop of `count` frames as an endlessly repeating GIF,
+    /// animated WebP or APNG, or (with the `video_io` feature) as a video
+    /// through ffmpeg, by extension
+    ///
+    /// # Arguments
+    /// * `path` - Output file (`.gif`, `.webp`, `.png`, or a video such as `.mp4`)
+    /// * `count` - Number of frames in the loop
+    /// * `fps` - Playback frame rate
+    pub fn save_animation(
+        &self,
+        path: impl AsRef<Path>,
+        count: u32,
+        fps: f32,
+    ) -> Result<(), String> {
+        let path = path.as_ref();
+        if animation::is_animation_path(path) {
+            let delay = animation::frame_delay(fps)?;
+            return animation::save_frames(
+                path,
+                self.frames(count)
+                    .map(move |image| Frame::from_parts(image, 0, 0, delay)),
+            );
+        }
+        self.save_video(path, count, fps)
+    }
+
+    #[cfg(feature = "video_io")]
+    fn save_video(&self, path: &Path, count: u32, fps: f32) -> Result<(), String> {
+        let (width, height) = self.after.dimensions();
+        let mut writer = crate::video_io::VideoWriter::create(path, width, height, fps as f64)?;
+        for frame in self.frames(count) {
+            writer.write_frame(&frame)?;
+        }
+        writer.finish()
+    }
+
+    #[cfg(not(feature = "video_io"))]
+    fn save_video(&self, path: &Path, _count: u32, _fps: f32) -> Result<(), String> {
+        Err(format!(
+            "{}: unsupported format, expected .gif, .webp or .png (video needs the `video_io` feature)",
+            path.display()
+        ))
+    }
+}
+
+/// Share of the ASCII image at loop position `t`, eased in and out
+fn after_amount(t: f32) -> f32 {
+    let t = t.rem_euclid(1.0) * 4.0;
+    let linear = match t {
+        t if t < 1.0 => 0.0,
+        t if t < 2.0 => t - 1.0,
+        t if t < 3.0 => 1.0,
+        t => 4.0 - t,
+    };
+    // Smoothstep
+    linear * linear * (3.0 - 2.0 * linear)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair() -> BeforeAfter {
+        let input = RgbaImage::from_fn(32, 16, |x, y| {
+            Rgba([(x * 8) as u8, (y * 16) as u8, 90, 255])
+        });
+        BeforeAfter::new(&input, &AsciiConfig::default(), false)
+    }
+
+    #[test]
+    fn test_loop_phases() {
+        let clip = pair();
+        assert_eq!(clip.frame(0.1), clip.before);
+        assert_eq!(clip.frame(0.6), clip.after);
+        assert_eq!(clip.frame(1.1), clip.frame(0.1));
+
+        // Halfway through a cross-fade every pixel lies between the two
+        let middle = clip.frame(0.375);
+        for (x, y, p) in middle.enumerate_pixels() {
+            let (a, b) = (clip.before.get_pixel(x, y), clip.after.get_pixel(x, y));
+            assert!((0..3).all(|c| p[c] >= a[c].min(b[c]) && p[c] <= a[c].max(b[c])));
+        }
+        assert_eq!(clip.frames(8).count(), 8);
+    }
+
+    #[test]
+    fn test_wipe() {
+        let clip = pair().with_transition(Transition::Wipe);
+        let middle = clip.frame(0.375);
+        assert_eq!(middle.get_pixel(2, 3), clip.after.get_pixel(2, 3));
+        assert_eq!(middle.get_pixel(29, 3), clip.before.get_pixel(29, 3));
+    }
+
+    #[test]
+    fn test_from_images_matches_sizes() {
+        let clip = BeforeAfter::from_images(&RgbaImage::new(40, 20), RgbaImage::new(32, 16));
+        assert_eq!(clip.before.dimensions(), (32, 16));
+        assert_eq!(after_amount(0.0), 0.0);
+        assert_eq!(after_amount(0.5), 1.0);
+        assert!((after_amount(0.375) - 0.5).abs() < 1e-6);
+    }
+}
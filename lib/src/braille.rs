@@ -0,0 +1,341 @@
+//! Unicode braille output mode
+//!
+//! With [`OutputMode::Braille`] every 2×4 pixel block of the working image
+//! becomes one braille pattern character (U+2800-U+28FF) with one dot per
+//! pixel, eight pixels per character instead of the 64 of an 8×8 tile. Edge
+//! dots are the pixels set in the thresholded DoG image (pipeline step 2), or
+//! past the gradient threshold with `EdgeMode::Sobel`; fill
+//! dots come from the luminance through ordered dithering (or noise with
+//! `FillDither::Noise`), following `invert_luminance`, `auto_invert` and
+//! `fill_gamma`. `composite_mode` combines the two per cell, the way it does
+//! per tile in ASCII mode. Tile voting, the fill ramp, `edge_debug` and
+//! `rgb_split` do not apply.
+//!
+//! The rendered image draws each dot on the pixel it came from, so it keeps
+//! the dimensions of the working image. The character grid of
+//! [`process_image_output`](crate::process_image_output) and the text
+//! renderers has `width / 2` columns and `height / 4` rows.
+
+use crate::ascii::dither_threshold;
+use crate::config::{AsciiConfig, CompositeMode, FillDither, OutputMode};
+use crate::filters::is_predominantly_bright;
+use crate::par::*;
+use crate::processor::{EdgeBuffers, adjust_luminance, pre_filter_luminance};
+use image::{GrayImage, RgbaImage};
+
+/// Pixels per braille character horizontally
+pub(crate) const CELL_WIDTH: u32 = 2;
+
+/// Pixels per braille character vertically
+pub(crate) const CELL_HEIGHT: u32 = 4;
+
+/// Bit of each dot in a braille pattern, indexed `[y][x]` within the cell
+const DOT_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Braille pattern character with the given dots set
+///
+/// Bits follow the Unicode dot numbering: 0x01, 0x02, 0x04 and 0x40 are the
+/// left column from top to bottom, 0x08, 0x10, 0x20 and 0x80 the right one.
+pub fn braille_char(dots: u8) -> char {
+    char::from_u32(0x2800 + dots as u32).expect("braille patterns are valid characters")
+}
+
+/// Edge and fill dots of one image, one per pixel, before compositing
+pub(crate) struct BrailleLayers {
+    pub(crate) edges: Vec<bool>,
+    pub(crate) fill: Vec<bool>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl BrailleLayers {
+    /// Characters per row
+    pub(crate) fn columns(&self) -> u32 {
+        self.width / CELL_WIDTH
+    }
+
+    /// Number of character rows
+    pub(crate) fn rows(&self) -> u32 {
+        self.height / CELL_HEIGHT
+    }
+
+    /// Dots of both layers combined with the configured policy
+    ///
+    /// A cell with any edge dot counts as an edge cell.
+    pub(crate) fn composite(&self, config: &AsciiConfig) -> Vec<bool> {
+        let mut dots = self.fill.clone();
+        for (cx, cy) in self.cells() {
+            let pixels = self.cell_pixels(cx, cy);
+            if !pixels.clone().any(|i| self.edges[i]) {
+                continue;
+            }
+            for i in pixels {
+                dots[i] = match config.composite_mode {
+                    CompositeMode::EdgesOverFill => self.edges[i],
+                    CompositeMode::FillWhereNoEdge => false,
+                    CompositeMode::Blend => self.edges[i] || self.fill[i],
+                };
+            }
+        }
+        dots
+    }
+
+    /// Braille character of every cell of `dots`, row-major
+    pub(crate) fn chars(&self, dots: &[bool]) -> Vec<char> {
+        self.cells()
+            .map(|(cx, cy)| {
+                let pattern = self
+                    .cell_pixels(cx, cy)
+                    .enumerate()
+                    .filter(|&(_, i)| dots[i])
+                    .fold(0, |pattern, (n, _)| {
+                        pattern | DOT_BITS[n / CELL_WIDTH as usize][n % CELL_WIDTH as usize]
+                    });
+                braille_char(pattern)
+            })
+            .collect()
+    }
+
+    /// Foreground color per cell: the average source color of its pixels when
+    /// colors are preserved, the configured ASCII color otherwise
+    pub(crate) fn colors(&self, source: Option<&RgbaImage>, config: &AsciiConfig) -> Vec<[u8; 3]> {
+        let Some(src) = source else {
+            return vec![config.ascii_color; (self.columns() * self.rows()) as usize];
+        };
+        let pixels = src.as_raw();
+        self.cells()
+            .map(|(cx, cy)| {
+                let mut sum = [0u32; 3];
+                for i in self.cell_pixels(cx, cy) {
+                    for (c, total) in sum.iter_mut().enumerate() {
+                        *total += pixels[i * 4 + c] as u32;
+                    }
+                }
+                let count = CELL_WIDTH * CELL_HEIGHT;
+                sum.map(|total| ((total + count / 2) / count) as u8)
+            })
+            .collect()
+    }
+
+    /// Draws every dot on its own pixel
+    ///
+    /// Like the tile renderer, dots take the source pixel's color when colors
+    /// are preserved and the rest is the source darkened to 20%; otherwise
+    /// `ascii_color` on `bg_color`.
+    pub(crate) fn render(
+        &self,
+        dots: &[bool],
+        config: &AsciiConfig,
+        source: Option<&RgbaImage>,
+    ) -> RgbaImage {
+        let [fr, fg, fb] = config.ascii_color;
+        let [br, bg, bb] = config.bg_color;
+        let mut output = RgbaImage::new(self.width, self.height);
+        if self.width == 0 || self.height == 0 {
+            return output;
+        }
+
+        // Rows are independent, so they are filled in parallel
+        let width = self.width as usize;
+        output
+            .par_chunks_exact_mut(width * 4)
+            .enumerate()
+            .for_each(|(y, row)| {
+                for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                    let drawn = dots[y * width + x];
+                    pixel.copy_from_slice(&match source {
+                        Some(src) => {
+                            let s = src.get_pixel(x as u32, y as u32);
+                            if drawn {
+                                s.0
+                            } else {
+                                [
+                                    (s[0] as f32 * 0.2) as u8,
+                                    (s[1] as f32 * 0.2) as u8,
+                                    (s[2] as f32 * 0.2) as u8,
+                                    255,
+                                ]
+                            }
+                        }
+                        None if drawn => [fr, fg, fb, 255],
+                        None => [br, bg, bb, 255],
+                    });
+                }
+            });
+        output
+    }
+
+    /// Cell coordinates, row-major
+    fn cells(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        (0..self.rows()).flat_map(move |cy| (0..self.columns()).map(move |cx| (cx, cy)))
+    }
+
+    /// Pixel indices of a cell, row by row
+    fn cell_pixels(&self, cx: u32, cy: u32) -> impl Iterator<Item = usize> + Clone + '_ {
+        (0..CELL_HEIGHT).flat_map(move |dy| {
+            (0..CELL_WIDTH).map(move |dx| {
+                ((cy * CELL_HEIGHT + dy) * self.width + cx * CELL_WIDTH + dx) as usize
+            })
+        })
+    }
+}
+
+/// Computes the edge and fill dots of a luminance image whose dimensions are
+/// multiples of 8
+pub(crate) fn compute_braille_layers(lum: &GrayImage, config: &AsciiConfig) -> BrailleLayers {
+    compute_braille_layers_buffered(lum, config, &mut EdgeBuffers::default())
+}
+
+/// [`compute_braille_layers`] with caller-owned scratch buffers
+pub(crate) fn compute_braille_layers_buffered(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    buffers: &mut EdgeBuffers,
+) -> BrailleLayers {
+    let (width, height) = lum.dimensions();
+    let lum = &*pre_filter_luminance(lum, config);
+
+    let edges = if config.draw_edges {
+        buffers.edge_pixels(lum, config)
+    } else {
+        vec![false; (width * height) as usize]
+    };
+
+    let fill = if config.draw_fill {
+        // Bright images (documents, whiteboards) read better flipped
+        let invert = config.invert_luminance ^ (config.auto_invert && is_predominantly_bright(lum));
+        let lum = adjust_luminance(lum, config);
+        // Single dots have no ramp to fall back on, so they are always dithered
+        let dither = match config.fill_dither {
+            FillDither::Off => FillDither::Ordered,
+            dither => dither,
+        };
+        lum.enumerate_pixels()
+            .map(|(x, y, p)| {
+                let mut level = p[0] as f32 / 255.0;
+                if invert {
+                    level = 1.0 - level;
+                }
+                let threshold =
+                    dither_threshold(dither, x / 8, y / 8, x % 8, y % 8).expect("dithering is on");
+                level.powf(config.fill_gamma) > threshold
+            })
+            .collect()
+    } else {
+        vec![false; (width * height) as usize]
+    };
+
+    BrailleLayers {
+        edges,
+        fill,
+        width,
+        height,
+    }
+}
+
+/// Runs the braille pipeline and renders the image
+pub(crate) fn process_braille(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    source: Option<&RgbaImage>,
+) -> RgbaImage {
+    debug_assert_eq!(config.output_mode, OutputMode::Braille);
+    let layers = compute_braille_layers(lum, config);
+    layers.render(&layers.composite(config), config, source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::calculate_luminance;
+    use crate::{process_image, process_image_output};
+    use image::{Luma, Rgba};
+
+    fn braille_config() -> AsciiConfig {
+        AsciiConfig {
+            output_mode: OutputMode::Braille,
+            ..AsciiConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_braille_char() {
+        assert_eq!(braille_char(0), '\u{2800}');
+        assert_eq!(braille_char(0xff), '⣿');
+        // Left column only
+        assert_eq!(braille_char(0x01 | 0x02 | 0x04 | 0x40), '⡇');
+    }
+
+    #[test]
+    fn test_cell_chars_follow_dot_layout() {
+        // Left column of the first cell, bottom row of the second
+        let mut dots = vec![false; 4 * 4];
+        for y in 0..4 {
+            dots[y * 4] = true;
+        }
+        dots[3 * 4 + 2] = true;
+        dots[3 * 4 + 3] = true;
+
+        let layers = BrailleLayers {
+            edges: vec![false; 16],
+            fill: dots.clone(),
+            width: 4,
+            height: 4,
+        };
+        assert_eq!(layers.chars(&dots), vec!['⡇', '⣀']);
+    }
+
+    #[test]
+    fn test_braille_output_grid() {
+        let input = RgbaImage::from_fn(64, 32, |x, _| {
+            let v = if x < 32 { 0 } else { 255 };
+            Rgba([v, v, v, 255])
+        });
+        let config = braille_config();
+        let output = process_image_output(&input, &config, false);
+
+        assert_eq!((output.columns, output.rows), (32, 8));
+        assert_eq!(output.chars.len(), 32 * 8);
+        assert_eq!(output.image.dimensions(), (64, 32));
+        assert_eq!(output.image, process_image(&input, &config).render_image());
+        // Black stays empty, white is fully dotted
+        assert_eq!(output.chars[0], '\u{2800}');
+        assert_eq!(output.chars[31], '⣿');
+
+        // Edges alone mark the boundary between the halves
+        let edges_only = AsciiConfig {
+            draw_fill: false,
+            ..braille_config()
+        };
+        let lum = calculate_luminance(&input);
+        let layers = compute_braille_layers(&lum, &edges_only);
+        let dots = layers.composite(&edges_only);
+        assert!(dots.iter().any(|&d| d));
+        assert!((0..32).all(|y| !dots[y * 64] && !dots[y * 64 + 63]));
+    }
+
+    #[test]
+    fn test_braille_fill_follows_luminance() {
+        let lum = GrayImage::from_fn(64, 64, |x, _| Luma([(x * 4) as u8]));
+        let config = AsciiConfig {
+            draw_edges: false,
+            ..braille_config()
+        };
+        let layers = compute_braille_layers(&lum, &config);
+        let count = |x0: usize, x1: usize| {
+            (0..64)
+                .flat_map(|y| (x0..x1).map(move |x| y * 64 + x))
+                .filter(|&i| layers.fill[i])
+                .count()
+        };
+        assert!(count(0, 16) < count(24, 40));
+        assert!(count(24, 40) < count(48, 64));
+
+        let inverted = AsciiConfig {
+            invert_luminance: true,
+            ..config
+        };
+        let flipped = compute_braille_layers(&lum, &inverted);
+        assert!(flipped.fill[0] && !flipped.fill[63]);
+    }
+}
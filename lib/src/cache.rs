@@ -0,0 +1,335 @@
+//! Content-addressed cache of rendered outputs
+//!
+//! [`RenderCache`] keys each conversion by a hash of the input pixels, the
+//! config and the color mode, so converting the same asset again (common for
+//! servers and chat bots) returns the stored result instead of rerunning the
+//! pipeline. Results are kept in memory with least-recently-used eviction and,
+//! optionally, as PNG files in a directory that survives restarts.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::cache::RenderCache;
+//!
+//! let mut cache = RenderCache::new(64).with_disk("/var/cache/ascii-rendr");
+//! let input = image::open("avatar.png").unwrap().to_rgba8();
+//! let output = cache.get_or_process(&input, &AsciiConfig::default(), true);
+//! output.save("avatar_ascii.png").unwrap();
+//! ```
+
+use crate::config::AsciiConfig;
+use crate::processor::{process_image, process_image_preserve_colors};
+use image::RgbaImage;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Identifies one conversion: input pixels, config and color mode
+///
+/// The library version is part of the key, so disk entries written by another
+/// version are not reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// Hash of the input dimensions and pixels
+    pub image_hash: u64,
+    /// Hash of the config, color mode and library version
+    pub config_hash: u64,
+}
+
+impl CacheKey {
+    /// Computes the key for converting `input` with `config`
+    pub fn new(input: &RgbaImage, config: &AsciiConfig, preserve_colors: bool) -> Self {
+        let (width, height) = input.dimensions();
+        let image_hash = fnv1a(&[&width.to_le_bytes(), &height.to_le_bytes(), input.as_raw()]);
+
+        // Debug output lists every field; floats print exactly
+        let config_text = format!("{:?}", config);
+        let config_hash = fnv1a(&[
+            env!("CARGO_PKG_VERSION").as_bytes(),
+            &[preserve_colors as u8],
+            config_text.as_bytes(),
+        ]);
+
+        Self {
+            image_hash,
+            config_hash,
+        }
+    }
+}
+
+impl fmt::Display for CacheKey {
+    /// 32 hex digits, used as the disk file name
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}{:016x}", self.image_hash, self.config_hash)
+    }
+}
+
+/// Hit and miss counts since the cache was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from memory
+    pub memory_hits: u64,
+    /// Lookups answered from the disk directory
+    pub disk_hits: u64,
+    /// Lookups that found nothing
+    pub misses: u64,
+}
+
+/// Rendered outputs keyed by [`CacheKey`], in memory and optionally on disk
+///
+/// Not synchronized; share it between threads behind a `Mutex`. Disk access is
+/// best effort: unreadable entries count as misses and failed writes are
+/// ignored, since the result can always be recomputed.
+pub struct RenderCache {
+    capacity: usize,
+    entries: HashMap<CacheKey, Entry>,
+    /// Incremented on every access; entries remember when they were last used
+    clock: u64,
+    disk_dir: Option<PathBuf>,
+    stats: CacheStats,
+}
+
+struct Entry {
+    image: Arc<RgbaImage>,
+    last_used: u64,
+}
+
+impl RenderCache {
+    /// Creates a cache holding up to `capacity` outputs in memory
+    ///
+    /// A capacity of 0 keeps nothing in memory (useful with [`with_disk`](Self::with_disk)).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            clock: 0,
+            disk_dir: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Also stores outputs as PNG files in `dir` (created when first written)
+    pub fn with_disk(mut self, dir: impl AsRef<Path>) -> Self {
+        self.disk_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Returns the cached output for this conversion, or runs it and caches it
+    pub fn get_or_process(
+        &mut self,
+        input: &RgbaImage,
+        config: &AsciiConfig,
+        preserve_colors: bool,
+    ) -> Arc<RgbaImage> {
+        let key = CacheKey::new(input, config, preserve_colors);
+        if let Some(image) = self.get(&key) {
+            return image;
+        }
+
+        let output = if preserve_colors {
+            process_image_preserve_colors(input, config).render_image()
+        } else {
+            process_image(input, config).render_image()
+        };
+        self.insert(key, output)
+    }
+
+    /// Looks up an output, checking memory first and then the disk directory
+    pub fn get(&mut self, key: &CacheKey) -> Option<Arc<RgbaImage>> {
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.last_used = self.clock;
+            self.stats.memory_hits += 1;
+            return Some(Arc::clone(&entry.image));
+        }
+
+        let from_disk = self
+            .disk_path(key)
+            .filter(|path| path.is_file())
+            .and_then(|path| image::open(path).ok())
+            .map(|image| Arc::new(image.into_rgba8()));
+        match from_disk {
+            Some(image) => {
+                self.stats.disk_hits += 1;
+                self.remember(*key, Arc::clone(&image));
+                Some(image)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Stores an output under `key` and returns it shared
+    pub fn insert(&mut self, key: CacheKey, image: RgbaImage) -> Arc<RgbaImage> {
+        if let Some(path) = self.disk_path(&key)
+            && let Some(dir) = path.parent()
+            && std::fs::create_dir_all(dir).is_ok()
+        {
+            let _ = image.save(path);
+        }
+
+        let image = Arc::new(image);
+        self.clock += 1;
+        self.remember(key, Arc::clone(&image));
+        image
+    }
+
+    /// Number of outputs held in memory
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no outputs are held in memory
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drops all outputs held in memory (disk entries are kept)
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Hit and miss counts so far
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Keeps `image` in memory, evicting the least recently used entry if full
+    fn remember(&mut self, key: CacheKey, image: Arc<RgbaImage>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity
+            && !self.entries.contains_key(&key)
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+        {
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                image,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    fn disk_path(&self, key: &CacheKey) -> Option<PathBuf> {
+        self.disk_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.png", key)))
+    }
+}
+
+/// 64-bit FNV-1a over several byte strings, each followed by a separator
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for &byte in *part {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+        hash ^= 0xff;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn gradient(shift: u8) -> RgbaImage {
+        RgbaImage::from_fn(32, 16, |x, y| {
+            let v = (x * 8 + y) as u8 ^ shift;
+            Rgba([v, v, v, 255])
+        })
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let config = AsciiConfig::default();
+        let key = CacheKey::new(&gradient(0), &config, true);
+        assert_eq!(key, CacheKey::new(&gradient(0), &config, true));
+        assert_ne!(key, CacheKey::new(&gradient(1), &config, true));
+        assert_ne!(key, CacheKey::new(&gradient(0), &config, false));
+
+        let other = AsciiConfig {
+            sigma: 1.5,
+            ..AsciiConfig::default()
+        };
+        let changed = CacheKey::new(&gradient(0), &other, true);
+        assert_eq!(changed.image_hash, key.image_hash);
+        assert_ne!(changed.config_hash, key.config_hash);
+        assert_eq!(key.to_string().len(), 32);
+    }
+
+    #[test]
+    fn test_get_or_process_hits() {
+        let config = AsciiConfig::default();
+        let mut cache = RenderCache::new(4);
+        let first = cache.get_or_process(&gradient(0), &config, false);
+        let second = cache.get_or_process(&gradient(0), &config, false);
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(*first, process_image(&gradient(0), &config).render_image());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                memory_hits: 1,
+                disk_hits: 0,
+                misses: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let config = AsciiConfig::default();
+        let mut cache = RenderCache::new(2);
+        let keys: Vec<CacheKey> = (0..3)
+            .map(|i| CacheKey::new(&gradient(i), &config, false))
+            .collect();
+
+        cache.insert(keys[0], gradient(0));
+        cache.insert(keys[1], gradient(1));
+        // Touch the first entry so the second becomes the oldest
+        assert!(cache.get(&keys[0]).is_some());
+        cache.insert(keys[2], gradient(2));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&keys[0]).is_some());
+        assert!(cache.get(&keys[1]).is_none());
+        assert!(cache.get(&keys[2]).is_some());
+
+        let mut disabled = RenderCache::new(0);
+        disabled.insert(keys[0], gradient(0));
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn test_disk_round_trip() {
+        let dir = std::env::temp_dir().join(format!("ascii-rendr-cache-{}", std::process::id()));
+        let config = AsciiConfig::default();
+        let key = CacheKey::new(&gradient(0), &config, true);
+
+        let stored =
+            RenderCache::new(0)
+                .with_disk(&dir)
+                .get_or_process(&gradient(0), &config, true);
+        let mut fresh = RenderCache::new(4).with_disk(&dir);
+        let loaded = fresh.get(&key).unwrap();
+        assert_eq!(*loaded, *stored);
+        assert_eq!(fresh.stats().disk_hits, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,188 @@
+//! Color vision deficiency simulation
+//!
+//! [`simulate_color_vision`] recolors an image the way it appears with a
+//! dichromatic color vision deficiency, so colorized output (preserved source
+//! colors, edge debug palettes) can be checked for legibility. It uses the
+//! full-severity matrices of Machado, Oliveira and Fernandes (2009), applied in
+//! linear RGB.
+
+use crate::par::*;
+use image::RgbaImage;
+
+/// Color vision to simulate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorVision {
+    /// Unchanged colors
+    #[default]
+    Normal,
+    /// Missing red cones
+    Protanopia,
+    /// Missing green cones
+    Deuteranopia,
+    /// Missing blue cones
+    Tritanopia,
+}
+
+impl ColorVision {
+    /// All modes, in menu order
+    pub const ALL: [ColorVision; 4] = [
+        ColorVision::Normal,
+        ColorVision::Protanopia,
+        ColorVision::Deuteranopia,
+        ColorVision::Tritanopia,
+    ];
+
+    /// Human-readable name
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorVision::Normal => "Normal",
+            ColorVision::Protanopia => "Protanopia",
+            ColorVision::Deuteranopia => "Deuteranopia",
+            ColorVision::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// Linear RGB transform, or None for normal vision
+    fn matrix(self) -> Option<[[f32; 3]; 3]> {
+        match self {
+            ColorVision::Normal => None,
+            ColorVision::Protanopia => Some([
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ]),
+            ColorVision::Deuteranopia => Some([
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ]),
+            ColorVision::Tritanopia => Some([
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ]),
+        }
+    }
+}
+
+/// Recolors `image` in place as seen with `vision`; alpha is kept
+pub fn simulate_color_vision(image: &mut RgbaImage, vision: ColorVision) {
+    let Some(matrix) = vision.matrix() else {
+        return;
+    };
+    let to_linear: Vec<f32> = (0..=255u8).map(srgb_to_linear).collect();
+
+    image.par_chunks_exact_mut(4).for_each(|pixel| {
+        let rgb = [
+            to_linear[pixel[0] as usize],
+            to_linear[pixel[1] as usize],
+            to_linear[pixel[2] as usize],
+        ];
+        pixel[..3].copy_from_slice(&transform(&matrix, rgb));
+    });
+}
+
+/// Simulated appearance of a single color
+pub fn simulate_color(color: [u8; 3], vision: ColorVision) -> [u8; 3] {
+    match vision.matrix() {
+        Some(matrix) => transform(&matrix, color.map(srgb_to_linear)),
+        None => color,
+    }
+}
+
+/// Applies a linear RGB matrix and encodes the result back to sRGB
+fn transform(matrix: &[[f32; 3]; 3], rgb: [f32; 3]) -> [u8; 3] {
+    matrix.map(|row| linear_to_srgb(row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]))
+}
+
+/// Decodes an sRGB byte to linear light in [0, 1]
+pub(crate) fn srgb_to_linear(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encodes linear light in [0, 1] to an sRGB byte
+pub(crate) fn linear_to_srgb(value: f32) -> u8 {
+    let c = value.clamp(0.0, 1.0);
+    let encoded = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::edge_debug_color;
+    use crate::config::Palette;
+    use crate::edges::EdgeDirection;
+
+    fn distance(a: [u8; 3], b: [u8; 3]) -> f32 {
+        a.iter()
+            .zip(b)
+            .map(|(&x, y)| (x as f32 - y as f32).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    #[test]
+    fn test_grays_and_normal_unchanged() {
+        for vision in ColorVision::ALL {
+            for v in [0, 64, 128, 255] {
+                let gray = simulate_color([v; 3], vision);
+                assert!(distance(gray, [v; 3]) <= 2.0, "{:?} {:?}", vision, gray);
+            }
+        }
+        assert_eq!(
+            simulate_color([200, 30, 90], ColorVision::Normal),
+            [200, 30, 90]
+        );
+    }
+
+    #[test]
+    fn test_red_green_confusion() {
+        // A brick red and an olive green of similar lightness
+        let (red, green) = ([200, 80, 40], [110, 130, 40]);
+        for vision in [ColorVision::Protanopia, ColorVision::Deuteranopia] {
+            let simulated = distance(simulate_color(red, vision), simulate_color(green, vision));
+            assert!(simulated < distance(red, green) / 2.0);
+        }
+    }
+
+    #[test]
+    fn test_safe_palettes_stay_distinct() {
+        let directions = [
+            EdgeDirection::Vertical,
+            EdgeDirection::Horizontal,
+            EdgeDirection::Diagonal1,
+            EdgeDirection::Diagonal2,
+            EdgeDirection::Cross,
+            EdgeDirection::DiagonalCross,
+        ];
+        let min_distance = |palette: Palette, vision: ColorVision| {
+            let colors: Vec<[u8; 3]> = directions
+                .iter()
+                .map(|&d| simulate_color(edge_debug_color(d, palette).unwrap(), vision))
+                .collect();
+            let mut min = f32::MAX;
+            for (i, &a) in colors.iter().enumerate() {
+                for &b in &colors[i + 1..] {
+                    min = min.min(distance(a, b));
+                }
+            }
+            min
+        };
+
+        for vision in [ColorVision::Protanopia, ColorVision::Deuteranopia] {
+            let classic = min_distance(Palette::Classic, vision);
+            assert!(min_distance(Palette::OkabeIto, vision) > 2.0 * classic);
+            assert!(min_distance(Palette::TolBright, vision) > 2.0 * classic);
+        }
+    }
+}
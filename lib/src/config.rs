@@ -1,85 +1,901 @@
+use crate::edges::EdgeDirection;
+use crate::error::AsciiError;
+use crate::lut::{BLOCK_CHARS, EDGE_CHARS, FILL_CHARS, get_edge_char};
+use serde::{Deserialize, Serialize};
+
+/// Dithering pattern used to mix adjacent fill characters within a tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillDither {
+    /// One character per tile (shader behavior)
+    #[default]
+    Off,
+    /// 8×8 Bayer matrix, gives a regular checkerboard-like mix
+    Ordered,
+    /// Per-pixel hash noise, gives an irregular grainy mix
+    Noise,
+}
+
+/// Characters of the fill ramp, from sparsest to densest
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Charset {
+    /// ` .:-=+*#%@` (shader behavior)
+    #[default]
+    Ascii,
+    /// Unicode shade blocks ` ░▒▓█`, for terminals and text output
+    Blocks,
+    /// Any characters, sparsest first; [`crate::glyphs::sort_by_density`]
+    /// orders them by ink coverage
+    Custom { chars: Vec<char> },
+}
+
+impl Charset {
+    /// Ramp characters from sparsest to densest
+    pub fn ramp(&self) -> &[char] {
+        match self {
+            Charset::Ascii => &FILL_CHARS,
+            Charset::Blocks => &BLOCK_CHARS,
+            Charset::Custom { chars } => chars,
+        }
+    }
+}
+
+/// How the DoG blurs are computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlurMode {
+    /// Separable Gaussian of `kernel_size` radius (shader behavior)
+    #[default]
+    Gaussian,
+    /// Three iterated box blurs matching the Gaussian's sigma; each pass costs
+    /// the same whatever the radius, so previews and video stay fast at large
+    /// sigmas. `kernel_size` is ignored.
+    Box,
+}
+
+/// Smoothing applied to the luminance before edge detection and fill
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreFilter {
+    /// Luminance is used as is (shader behavior)
+    #[default]
+    None,
+    /// Edge-preserving bilateral filter: averages neighbors within about
+    /// `2 * sigma_space` pixels whose luminance differs by little compared to
+    /// `sigma_range` (0-1), so noise is smoothed but strong edges are kept
+    Bilateral { sigma_space: f32, sigma_range: f32 },
+    /// Generalized Kuwahara filter for a painterly look: each pixel takes the
+    /// mean of the most uniform of `sectors` (4-16) wedges of a disc of
+    /// `radius` (1-16) pixels, flattening texture into patches with crisp
+    /// borders
+    Kuwahara { radius: u32, sectors: u32 },
+}
+
+impl PreFilter {
+    /// Bilateral filter settings that suit noisy photos
+    pub const BILATERAL: PreFilter = PreFilter::Bilateral {
+        sigma_space: 2.0,
+        sigma_range: 0.1,
+    };
+
+    /// Kuwahara filter settings with the usual eight sectors
+    pub const KUWAHARA: PreFilter = PreFilter::Kuwahara {
+        radius: 4,
+        sectors: 8,
+    };
+}
+
+/// How the Difference of Gaussians becomes the edge image of step 2
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeMode {
+    /// Hard binary cut at `threshold` (shader behavior)
+    #[default]
+    Dog,
+    /// Extended DoG: white at or above `epsilon` (0.001-0.1), falling off as
+    /// `1 + tanh(phi * (D - epsilon))` below it, with `phi` (1-100000) the
+    /// steepness; smoother, steadier lines than the hard cut, and `threshold`
+    /// is not used
+    Xdog { phi: f32, epsilon: f32 },
+    /// No DoG: Sobel runs on the luminance blurred with `sigma`, and pixels
+    /// whose gradient magnitude exceeds `threshold` (0.01-4.0, where a black
+    /// to white step is 4.0) vote; keeps the gradient strength that the binary
+    /// DoG mask throws away
+    Sobel { threshold: f32 },
+}
+
+impl EdgeMode {
+    /// XDoG settings with a soft band just below the default threshold
+    pub const XDOG: EdgeMode = EdgeMode::Xdog {
+        phi: 1000.0,
+        epsilon: 0.005,
+    };
+
+    /// Sobel-on-luminance settings that skip gentle shading
+    pub const SOBEL: EdgeMode = EdgeMode::Sobel { threshold: 0.2 };
+}
+
+/// How the DoG binarization threshold of [`EdgeMode::Dog`] is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdMode {
+    /// The fixed `threshold` value (shader behavior)
+    #[default]
+    Fixed,
+    /// Otsu's method on the histogram of each image's DoG values, splitting
+    /// them into the two classes with the largest between-class variance
+    Otsu,
+    /// The DoG value below which `percentile` (50-99.9) percent of the
+    /// pixels lie, so about the remaining share of each image becomes edges
+    Percentile { percentile: f32 },
+}
+
+impl ThresholdMode {
+    /// Percentile settings that keep about the top tenth of DoG values
+    pub const PERCENTILE: ThresholdMode = ThresholdMode::Percentile { percentile: 90.0 };
+}
+
+/// Formula that reduces RGB to luminance
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LuminanceMode {
+    /// ITU-R BT.601 (SD video, JPEG): 0.299, 0.587, 0.114
+    Rec601,
+    /// ITU-R BT.709 (HD video, sRGB) as in the shader: 0.2127, 0.7152, 0.0722
+    #[default]
+    Rec709,
+    /// ITU-R BT.2020 (UHD video): 0.2627, 0.6780, 0.0593
+    Rec2020,
+    /// Equal weights
+    Average,
+    /// Brightest channel, so saturated colors count as bright
+    Max,
+    /// Any non-negative red, green and blue weights, normalized to sum to 1
+    Custom { weights: [f32; 3] },
+}
+
+impl LuminanceMode {
+    /// Red, green and blue weights summing to 1, or None for [`LuminanceMode::Max`]
+    pub fn weights(&self) -> Option<[f32; 3]> {
+        match *self {
+            LuminanceMode::Rec601 => Some([0.299, 0.587, 0.114]),
+            LuminanceMode::Rec709 => Some([0.2127, 0.7152, 0.0722]),
+            LuminanceMode::Rec2020 => Some([0.2627, 0.6780, 0.0593]),
+            LuminanceMode::Average => Some([1.0 / 3.0; 3]),
+            LuminanceMode::Max => None,
+            LuminanceMode::Custom { weights } => {
+                let sum: f32 = weights.iter().sum();
+                Some(weights.map(|w| w / sum))
+            }
+        }
+    }
+}
+
+/// Contrast adjustment of the luminance before it is reduced to tiles
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LuminanceAdjust {
+    /// Luminance is used as is (shader behavior)
+    #[default]
+    None,
+    /// Stretch the luminance range to full black to white, ignoring the
+    /// darkest and brightest 1% of pixels
+    AutoContrast,
+    /// Global histogram equalization
+    Equalize,
+    /// Contrast-limited adaptive histogram equalization over a `grid`×`grid`
+    /// (1-32) arrangement of regions, with bins clipped at `clip_limit`
+    /// (1.0-16.0) times the average
+    Clahe { clip_limit: f32, grid: u32 },
+}
+
+impl LuminanceAdjust {
+    /// CLAHE settings with the common 8×8 grid
+    pub const CLAHE: LuminanceAdjust = LuminanceAdjust::Clahe {
+        clip_limit: 2.0,
+        grid: 8,
+    };
+}
+
+/// How fill characters are chosen for each tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CharSelection {
+    /// Tile luminance picks a character on the fill ramp (shader behavior)
+    #[default]
+    Luminance,
+    /// The glyph whose 8×8 bitmap best matches the tile's pixels, keeping
+    /// strokes and corners the tile average loses; any printable ASCII with
+    /// the default charset, otherwise the charset's characters
+    Structure,
+}
+
+/// How the edge and fill character layers are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompositeMode {
+    /// Edge characters replace fill on edge tiles (shader behavior)
+    #[default]
+    EdgesOverFill,
+    /// Fill only on tiles without an edge; edge tiles are left blank
+    FillWhereNoEdge,
+    /// Both glyphs are drawn on edge tiles, edge strokes on top of the fill
+    Blend,
+}
+
+/// Color set for the edge debug view
+///
+/// Besides the classic red/green/blue/yellow set, there are palettes that stay
+/// distinguishable with red-green color vision deficiencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    /// Saturated primaries (red, green, blue, yellow, magenta, cyan)
+    #[default]
+    Classic,
+    /// Okabe-Ito colors, safe for protanopia and deuteranopia
+    OkabeIto,
+    /// Paul Tol's "bright" scheme, color-blind safe with higher lightness
+    TolBright,
+}
+
+/// Statistic used to reduce each tile's luminance to a single fill value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileStatistic {
+    /// Average of all pixels (shader behavior)
+    #[default]
+    Mean,
+    /// Middle value, robust against isolated outliers
+    Median,
+    /// Brightest pixel, keeps thin bright features
+    Max,
+    /// Nearest-rank percentile (0-100)
+    Percentile(u8),
+}
+
+/// Supported values of [`AsciiConfig::tile_size`]
+pub const TILE_SIZES: [u32; 4] = [4, 8, 16, 32];
+
+/// Characters drawn for the four edge directions
+///
+/// Junction tiles keep `+` and `X`. Characters without a built-in 8×8 bitmap
+/// are drawn as filled squares in image output unless `Custom` supplies one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeChars {
+    /// `| - / \` (shader behavior)
+    #[default]
+    Ascii,
+    /// Light box-drawing lines `│ ─ ╱ ╲`
+    BoxDrawing,
+    /// Heavy box-drawing lines `┃ ━` with the `╱ ╲` diagonals
+    Heavy,
+    /// Any four characters, in vertical, horizontal, `/`, `\` order
+    Custom {
+        chars: [char; 4],
+        /// 8×8 bitmaps for `chars`, one byte per row from the top with the
+        /// leftmost pixel in the most significant bit; None uses the built-in
+        /// bitmaps
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bitmaps: Option<[[u8; 8]; 4]>,
+    },
+}
+
+impl EdgeChars {
+    /// Characters for vertical, horizontal, `/` and `\` edges
+    pub fn chars(&self) -> [char; 4] {
+        match self {
+            EdgeChars::Ascii => EDGE_CHARS.map(|row| row[0]),
+            EdgeChars::BoxDrawing => ['│', '─', '╱', '╲'],
+            EdgeChars::Heavy => ['┃', '━', '╱', '╲'],
+            EdgeChars::Custom { chars, .. } => *chars,
+        }
+    }
+
+    /// Character for a direction at a position within the tile (0-7)
+    pub fn char_for(&self, direction: EdgeDirection, tile_x: u32, tile_y: u32) -> char {
+        if let EdgeChars::Ascii = self {
+            return get_edge_char(direction, tile_x, tile_y);
+        }
+        let index = match direction {
+            EdgeDirection::Vertical => 0,
+            EdgeDirection::Horizontal => 1,
+            EdgeDirection::Diagonal1 => 2,
+            EdgeDirection::Diagonal2 => 3,
+            _ => return get_edge_char(direction, tile_x, tile_y),
+        };
+        self.chars()[index]
+    }
+
+    /// Supplied bitmap of a custom edge character, if any
+    pub fn bitmap(&self, ch: char) -> Option<&[u8; 8]> {
+        match self {
+            EdgeChars::Custom {
+                chars,
+                bitmaps: Some(bitmaps),
+            } => chars
+                .iter()
+                .position(|&c| c == ch)
+                .map(|index| &bitmaps[index]),
+            _ => None,
+        }
+    }
+}
+
+/// What each character of the output stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// One ASCII glyph per 8×8 tile (shader behavior)
+    #[default]
+    Ascii,
+    /// One Unicode braille pattern per 2×4 pixel block, see [`crate::braille`]
+    Braille,
+}
+
+/// Built-in named starting points for common use cases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// Soft edges, local contrast and a smooth tone ramp for faces and people
+    Portrait,
+    /// Fine edges, low thresholds and a long ramp to keep as much structure
+    /// as possible
+    HighDetail,
+    /// Green-on-black phosphor look in shade blocks on 8×16 terminal cells
+    Terminal,
+    /// Edges only, dark lines on a light background
+    LineArt,
+    /// Amber monochrome with ordered dithering over the classic ramp
+    Retro,
+}
+
+impl Preset {
+    /// All presets, in menu order
+    pub const ALL: [Preset; 5] = [
+        Preset::Portrait,
+        Preset::HighDetail,
+        Preset::Terminal,
+        Preset::LineArt,
+        Preset::Retro,
+    ];
+
+    /// Human-readable preset name
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Portrait => "Portrait",
+            Preset::HighDetail => "High Detail",
+            Preset::Terminal => "Terminal",
+            Preset::LineArt => "Line Art",
+            Preset::Retro => "Retro",
+        }
+    }
+}
+
 /// Configuration for ASCII art conversion
-#[derive(Debug, Clone)]
+///
+/// Serializable to and from TOML/JSON; missing fields take their default value
+/// and unknown fields are rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AsciiConfig {
+    /// Luminance
+    pub luminance_mode: LuminanceMode, // RGB weighting of the luminance, default Rec709
+    pub linear_luminance: bool, // Weight RGB in linear light instead of on the sRGB-encoded bytes, default false
+
     /// Blur settings
     pub kernel_size: u32, // 1-10, default 2
-    pub sigma: f32,       // 0.0-5.0, default 2.0
-    pub sigma_scale: f32, // DoG second sigma scale, default 1.6
+    pub sigma: f32,            // 0.0-5.0, default 2.0
+    pub sigma_scale: f32,      // DoG second sigma scale, default 1.6
+    pub blur_mode: BlurMode, // Exact Gaussian or fast box approximation of the DoG blurs, default Gaussian
+    pub pre_filter: PreFilter, // Smoothing of the luminance before edges and fill, default None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_radius: Option<u32>, // Median filter radius (1-3) against salt-and-pepper noise, applied before pre_filter, default None (off)
 
     /// Edge detection
-    pub tau: f32, // DoG threshold multiplier, default 1.0
-    pub threshold: f32,      // DoG threshold, default 0.005
-    pub edge_threshold: u32, // Pixels needed for edge (in 8x8 tile), default 8
+    pub edge_mode: EdgeMode, // Hard or soft (XDoG) thresholding of the DoG, or Sobel on the luminance, default Dog
+    pub tau: f32,                      // DoG threshold multiplier, default 1.0
+    pub threshold: f32,                // DoG threshold, default 0.005
+    pub threshold_mode: ThresholdMode, // Fixed threshold, or picked per image by Otsu or a percentile, default Fixed
+    pub edge_dilation: i32, // Grow (positive) or shrink (negative) the DoG edge lines by this many pixels (-3 to 3), default 0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structure_tensor_sigma: Option<f32>, // Edge angles from a structure tensor smoothed with this sigma (0.5-5.0) instead of per-pixel atan2, default None (off)
+    pub sobel_threshold: f32, // Sobel gradient magnitude (0.0-4.0) a DoG or XDoG edge pixel needs to vote, default 0.01
+    pub non_max_suppression: bool, // Thin Sobel edges to one pixel before tile voting, default false
+    pub edge_threshold: u32, // Pixels needed for edge (in 8x8 tile, scaled to other tile sizes), default 8
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge_junction_margin: Option<u32>, // Vote gap for +/X junction tiles, default None (off)
+    pub saliency_strength: f32, // How far saliency lowers edge_threshold on focal tiles and raises it elsewhere (0.0-1.0), default 0.0 (off)
 
     /// Colors
     pub ascii_color: [u8; 3], // RGB, default white [255, 255, 255]
     pub bg_color: [u8; 3], // RGB, default black [0, 0, 0]
 
+    /// Fill
+    pub luminance_adjust: LuminanceAdjust, // Contrast stretch or equalization of the luminance before tiling, default None
+    pub exposure: f32,    // Fill luminance multiplier (0.0-5.0), default 1.0
+    pub attenuation: f32, // Exponent applied to the exposed fill luminance (0.1-5.0), default 1.0
+    pub tile_statistic: TileStatistic, // How tile luminance is computed, default Mean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_contrast_radius: Option<u32>, // Tile radius for local contrast normalization, default None (off)
+    pub fill_gamma: f32, // Gamma applied before fill character quantization, default 1.0
+    pub fill_dither: FillDither, // Mix adjacent ramp characters within a tile, default Off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub texture_threshold: Option<u32>, // Tile luminance std dev (0-255) that promotes fill to a busier character, default None (off)
+    pub char_selection: CharSelection, // Fill by luminance ramp or best-matching glyph, default Luminance
+    pub charset: Charset,              // Fill ramp characters, default Ascii
+
     /// Rendering
     pub draw_edges: bool, // default true
-    pub draw_fill: bool,        // default true
-    pub invert_luminance: bool, // default false
+    pub draw_fill: bool,               // default true
+    pub invert_luminance: bool,        // default false
+    pub auto_invert: bool, // Flip invert_luminance for predominantly bright images, default false
+    pub composite_mode: CompositeMode, // How edge and fill layers combine, default EdgesOverFill
+    pub blend_with_base: f32, // Mix of the source image into the rendered output (0.0-1.0), default 0.0 (off)
+    pub edge_debug: bool,     // Color tiles by voted edge direction / luminance, default false
+    pub palette: Palette,     // Edge debug colors, default Classic
+    pub edge_chars: EdgeChars, // Characters for the four edge directions, default Ascii
+    pub positional_edges: bool, // Shift edge glyphs onto where the edge crosses the tile, default false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rgb_split: Option<u32>, // Render R/G/B fill layers separately, offset by this many pixels, default None (off)
+    pub output_mode: OutputMode, // ASCII tiles or braille dots, default Ascii
+    pub tile_size: u32,          // Pixels per side of an ASCII tile (4, 8, 16 or 32), default 8
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tile_height: Option<u32>, // Tile height when it differs from tile_size, e.g. 16 for terminal cells, default None (square)
 }
 
 impl Default for AsciiConfig {
     fn default() -> Self {
         Self {
+            // Luminance
+            luminance_mode: LuminanceMode::Rec709,
+            linear_luminance: false,
+
             // Blur settings
             kernel_size: 2,
             sigma: 2.0,
             sigma_scale: 1.6,
+            blur_mode: BlurMode::Gaussian,
+            pre_filter: PreFilter::None,
+            median_radius: None,
 
             // Edge detection
+            edge_mode: EdgeMode::Dog,
             tau: 1.0,
             threshold: 0.005,
+            threshold_mode: ThresholdMode::Fixed,
+            edge_dilation: 0,
+            structure_tensor_sigma: None,
+            sobel_threshold: 0.01,
+            non_max_suppression: false,
             edge_threshold: 8,
+            edge_junction_margin: None,
+            saliency_strength: 0.0,
 
             // Colors
             ascii_color: [255, 255, 255],
             bg_color: [0, 0, 0],
 
+            // Fill
+            luminance_adjust: LuminanceAdjust::None,
+            exposure: 1.0,
+            attenuation: 1.0,
+            tile_statistic: TileStatistic::Mean,
+            local_contrast_radius: None,
+            fill_gamma: 1.0,
+            fill_dither: FillDither::Off,
+            texture_threshold: None,
+            char_selection: CharSelection::Luminance,
+            charset: Charset::Ascii,
+
             // Rendering
             draw_edges: true,
             draw_fill: true,
             invert_luminance: false,
+            auto_invert: false,
+            composite_mode: CompositeMode::EdgesOverFill,
+            blend_with_base: 0.0,
+            edge_debug: false,
+            palette: Palette::Classic,
+            edge_chars: EdgeChars::Ascii,
+            positional_edges: false,
+            rgb_split: None,
+            output_mode: OutputMode::Ascii,
+            tile_size: 8,
+            tile_height: None,
         }
     }
 }
 
+/// Paul Bourke's 70-level ramp, sparsest first, used by [`Preset::HighDetail`]
+const HIGH_DETAIL_RAMP: &str =
+    " .'`^\",:;Il!i><~+_-?][}{1)(|\\/tfjrxnuvczXYUJCLQ0OZmwqpdbkhao*#MW&8%B@$";
+
 impl AsciiConfig {
+    /// Returns the tuned configuration for a built-in preset
+    pub fn preset(preset: Preset) -> Self {
+        let default = Self::default();
+        match preset {
+            Preset::Portrait => Self {
+                kernel_size: 3,
+                sigma: 2.5,
+                edge_threshold: 12,
+                local_contrast_radius: Some(4),
+                fill_gamma: 1.2,
+                fill_dither: FillDither::Ordered,
+                charset: Charset::Custom {
+                    chars: " .,:;i1tfLCG08@".chars().collect(),
+                },
+                ..default
+            },
+            Preset::HighDetail => Self {
+                sigma: 1.0,
+                threshold: 0.003,
+                edge_threshold: 4,
+                edge_junction_margin: Some(2),
+                tile_statistic: TileStatistic::Median,
+                fill_dither: FillDither::Noise,
+                charset: Charset::Custom {
+                    chars: HIGH_DETAIL_RAMP.chars().collect(),
+                },
+                ..default
+            },
+            Preset::Terminal => Self {
+                ascii_color: [51, 255, 102],
+                bg_color: [0, 12, 0],
+                charset: Charset::Blocks,
+                // Terminal cells are about twice as tall as they are wide
+                tile_height: Some(16),
+                ..default
+            },
+            Preset::LineArt => Self {
+                sigma: 1.5,
+                edge_threshold: 4,
+                edge_junction_margin: Some(4),
+                ascii_color: [20, 20, 20],
+                bg_color: [245, 242, 232],
+                draw_fill: false,
+                charset: Charset::Ascii,
+                ..default
+            },
+            Preset::Retro => Self {
+                kernel_size: 3,
+                edge_threshold: 10,
+                tile_statistic: TileStatistic::Max,
+                fill_gamma: 1.4,
+                fill_dither: FillDither::Ordered,
+                ascii_color: [255, 176, 0],
+                bg_color: [24, 12, 0],
+                charset: Charset::Ascii,
+                ..default
+            },
+        }
+    }
+
+    /// Tile width and height in pixels; `tile_height` defaults to `tile_size`
+    pub fn tile_dimensions(&self) -> (u32, u32) {
+        (self.tile_size, self.tile_height.unwrap_or(self.tile_size))
+    }
+
+    /// Converts a vote count given per 8×8 tile (`edge_threshold`,
+    /// `edge_junction_margin`) to the configured tile size
+    pub fn scale_votes(&self, votes: u32) -> u32 {
+        let (tile_width, tile_height) = self.tile_dimensions();
+        votes * tile_width * tile_height / 64
+    }
+
     /// Validates the configuration parameters
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), AsciiError> {
+        if let LuminanceMode::Custom { weights } = self.luminance_mode
+            && (weights.iter().any(|w| !w.is_finite() || *w < 0.0)
+                || weights.iter().sum::<f32>() <= 0.0)
+        {
+            return Err(AsciiError::invalid_config(
+                "luminance_mode",
+                format!(
+                    "custom luminance weights must be non-negative with a positive sum, got {:?}",
+                    weights
+                ),
+            ));
+        }
         if self.kernel_size < 1 || self.kernel_size > 10 {
-            return Err(format!(
-                "kernel_size must be between 1 and 10, got {}",
-                self.kernel_size
+            return Err(AsciiError::invalid_config(
+                "kernel_size",
+                format!(
+                    "kernel_size must be between 1 and 10, got {}",
+                    self.kernel_size
+                ),
             ));
         }
         if self.sigma < 0.0 || self.sigma > 5.0 {
-            return Err(format!(
-                "sigma must be between 0.0 and 5.0, got {}",
-                self.sigma
+            return Err(AsciiError::invalid_config(
+                "sigma",
+                format!("sigma must be between 0.0 and 5.0, got {}", self.sigma),
             ));
         }
         if self.sigma_scale < 0.0 || self.sigma_scale > 5.0 {
-            return Err(format!(
-                "sigma_scale must be between 0.0 and 5.0, got {}",
-                self.sigma_scale
+            return Err(AsciiError::invalid_config(
+                "sigma_scale",
+                format!(
+                    "sigma_scale must be between 0.0 and 5.0, got {}",
+                    self.sigma_scale
+                ),
+            ));
+        }
+        if let Some(radius) = self.median_radius
+            && !(1..=3).contains(&radius)
+        {
+            return Err(AsciiError::invalid_config(
+                "median_radius",
+                format!("median_radius must be between 1 and 3, got {}", radius),
+            ));
+        }
+        if let PreFilter::Kuwahara { radius, sectors } = self.pre_filter {
+            if !(1..=16).contains(&radius) {
+                return Err(AsciiError::invalid_config(
+                    "pre_filter",
+                    format!("kuwahara radius must be between 1 and 16, got {}", radius),
+                ));
+            }
+            if !(4..=16).contains(&sectors) {
+                return Err(AsciiError::invalid_config(
+                    "pre_filter",
+                    format!("kuwahara sectors must be between 4 and 16, got {}", sectors),
+                ));
+            }
+        }
+        if let PreFilter::Bilateral {
+            sigma_space,
+            sigma_range,
+        } = self.pre_filter
+        {
+            if !(0.5..=8.0).contains(&sigma_space) {
+                return Err(AsciiError::invalid_config(
+                    "pre_filter",
+                    format!(
+                        "bilateral sigma_space must be between 0.5 and 8.0, got {}",
+                        sigma_space
+                    ),
+                ));
+            }
+            if !(0.01..=1.0).contains(&sigma_range) {
+                return Err(AsciiError::invalid_config(
+                    "pre_filter",
+                    format!(
+                        "bilateral sigma_range must be between 0.01 and 1.0, got {}",
+                        sigma_range
+                    ),
+                ));
+            }
+        }
+        if let EdgeMode::Xdog { phi, epsilon } = self.edge_mode {
+            if !(1.0..=100_000.0).contains(&phi) {
+                return Err(AsciiError::invalid_config(
+                    "edge_mode",
+                    format!("xdog phi must be between 1 and 100000, got {}", phi),
+                ));
+            }
+            if !(0.001..=0.1).contains(&epsilon) {
+                return Err(AsciiError::invalid_config(
+                    "edge_mode",
+                    format!(
+                        "xdog epsilon must be between 0.001 and 0.1, got {}",
+                        epsilon
+                    ),
+                ));
+            }
+        }
+        if let EdgeMode::Sobel { threshold } = self.edge_mode
+            && !(0.01..=4.0).contains(&threshold)
+        {
+            return Err(AsciiError::invalid_config(
+                "edge_mode",
+                format!(
+                    "sobel threshold must be between 0.01 and 4.0, got {}",
+                    threshold
+                ),
+            ));
+        }
+        if let ThresholdMode::Percentile { percentile } = self.threshold_mode
+            && !(50.0..=99.9).contains(&percentile)
+        {
+            return Err(AsciiError::invalid_config(
+                "threshold_mode",
+                format!(
+                    "threshold percentile must be between 50 and 99.9, got {}",
+                    percentile
+                ),
+            ));
+        }
+        if !(-3..=3).contains(&self.edge_dilation) {
+            return Err(AsciiError::invalid_config(
+                "edge_dilation",
+                format!(
+                    "edge_dilation must be between -3 and 3, got {}",
+                    self.edge_dilation
+                ),
+            ));
+        }
+        if !(0.0..=4.0).contains(&self.sobel_threshold) {
+            return Err(AsciiError::invalid_config(
+                "sobel_threshold",
+                format!(
+                    "sobel_threshold must be between 0.0 and 4.0, got {}",
+                    self.sobel_threshold
+                ),
+            ));
+        }
+        if let Some(sigma) = self.structure_tensor_sigma
+            && !(0.5..=5.0).contains(&sigma)
+        {
+            return Err(AsciiError::invalid_config(
+                "structure_tensor_sigma",
+                format!(
+                    "structure_tensor_sigma must be between 0.5 and 5.0, got {}",
+                    sigma
+                ),
             ));
         }
         if self.tau < 0.0 || self.tau > 1.1 {
-            return Err(format!("tau must be between 0.0 and 1.1, got {}", self.tau));
+            return Err(AsciiError::invalid_config(
+                "tau",
+                format!("tau must be between 0.0 and 1.1, got {}", self.tau),
+            ));
         }
         if self.threshold < 0.001 || self.threshold > 0.1 {
-            return Err(format!(
-                "threshold must be between 0.001 and 0.1, got {}",
-                self.threshold
+            return Err(AsciiError::invalid_config(
+                "threshold",
+                format!(
+                    "threshold must be between 0.001 and 0.1, got {}",
+                    self.threshold
+                ),
             ));
         }
         if self.edge_threshold > 64 {
-            return Err(format!(
-                "edge_threshold must be <= 64, got {}",
-                self.edge_threshold
+            return Err(AsciiError::invalid_config(
+                "edge_threshold",
+                format!("edge_threshold must be <= 64, got {}", self.edge_threshold),
+            ));
+        }
+        if let Some(margin) = self.edge_junction_margin
+            && margin > 64
+        {
+            return Err(AsciiError::invalid_config(
+                "edge_junction_margin",
+                format!("edge_junction_margin must be <= 64, got {}", margin),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.saliency_strength) {
+            return Err(AsciiError::invalid_config(
+                "saliency_strength",
+                format!(
+                    "saliency_strength must be between 0.0 and 1.0, got {}",
+                    self.saliency_strength
+                ),
+            ));
+        }
+        if let LuminanceAdjust::Clahe { clip_limit, grid } = self.luminance_adjust {
+            if !(1.0..=16.0).contains(&clip_limit) {
+                return Err(AsciiError::invalid_config(
+                    "luminance_adjust",
+                    format!(
+                        "clahe clip_limit must be between 1.0 and 16.0, got {}",
+                        clip_limit
+                    ),
+                ));
+            }
+            if !(1..=32).contains(&grid) {
+                return Err(AsciiError::invalid_config(
+                    "luminance_adjust",
+                    format!("clahe grid must be between 1 and 32, got {}", grid),
+                ));
+            }
+        }
+        if !(0.0..=5.0).contains(&self.exposure) {
+            return Err(AsciiError::invalid_config(
+                "exposure",
+                format!(
+                    "exposure must be between 0.0 and 5.0, got {}",
+                    self.exposure
+                ),
+            ));
+        }
+        if !(0.1..=5.0).contains(&self.attenuation) {
+            return Err(AsciiError::invalid_config(
+                "attenuation",
+                format!(
+                    "attenuation must be between 0.1 and 5.0, got {}",
+                    self.attenuation
+                ),
+            ));
+        }
+        if let Some(radius) = self.local_contrast_radius
+            && !(1..=16).contains(&radius)
+        {
+            return Err(AsciiError::invalid_config(
+                "local_contrast_radius",
+                format!(
+                    "local_contrast_radius must be between 1 and 16, got {}",
+                    radius
+                ),
+            ));
+        }
+        if let Some(threshold) = self.texture_threshold
+            && !(1..=128).contains(&threshold)
+        {
+            return Err(AsciiError::invalid_config(
+                "texture_threshold",
+                format!(
+                    "texture_threshold must be between 1 and 128, got {}",
+                    threshold
+                ),
+            ));
+        }
+        if let Some(offset) = self.rgb_split
+            && offset > 16
+        {
+            return Err(AsciiError::invalid_config(
+                "rgb_split",
+                format!("rgb_split must be <= 16, got {}", offset),
+            ));
+        }
+        if let TileStatistic::Percentile(p) = self.tile_statistic
+            && p > 100
+        {
+            return Err(AsciiError::invalid_config(
+                "tile_statistic",
+                format!("tile_statistic percentile must be <= 100, got {}", p),
+            ));
+        }
+        if !TILE_SIZES.contains(&self.tile_size) {
+            return Err(AsciiError::invalid_config(
+                "tile_size",
+                format!(
+                    "tile_size must be one of 4, 8, 16 or 32, got {}",
+                    self.tile_size
+                ),
+            ));
+        }
+        if let Some(tile_height) = self.tile_height
+            && !TILE_SIZES.contains(&tile_height)
+        {
+            return Err(AsciiError::invalid_config(
+                "tile_height",
+                format!(
+                    "tile_height must be one of 4, 8, 16 or 32, got {}",
+                    tile_height
+                ),
+            ));
+        }
+        if self.edge_chars.chars().iter().any(|c| c.is_whitespace()) {
+            return Err(AsciiError::invalid_config(
+                "edge_chars",
+                "edge_chars must not contain whitespace".to_string(),
+            ));
+        }
+        if self.charset.ramp().is_empty() {
+            return Err(AsciiError::invalid_config(
+                "charset",
+                "charset must contain at least one character".to_string(),
+            ));
+        }
+        if !(0.1..=5.0).contains(&self.fill_gamma) {
+            return Err(AsciiError::invalid_config(
+                "fill_gamma",
+                format!(
+                    "fill_gamma must be between 0.1 and 5.0, got {}",
+                    self.fill_gamma
+                ),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.blend_with_base) {
+            return Err(AsciiError::invalid_config(
+                "blend_with_base",
+                format!(
+                    "blend_with_base must be between 0.0 and 1.0, got {}",
+                    self.blend_with_base
+                ),
             ));
         }
         Ok(())
@@ -96,6 +912,28 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_presets_are_valid() {
+        for preset in Preset::ALL {
+            let config = AsciiConfig::preset(preset);
+            assert!(config.validate().is_ok(), "{} is invalid", preset.name());
+        }
+    }
+
+    #[test]
+    fn test_presets_pick_charsets() {
+        let ramp = |preset| AsciiConfig::preset(preset).charset.ramp().len();
+        // Tone-heavy presets get longer ramps than the shader's ten levels
+        assert!(ramp(Preset::Portrait) > FILL_CHARS.len());
+        assert!(ramp(Preset::HighDetail) > ramp(Preset::Portrait));
+        assert_eq!(ramp(Preset::HighDetail), 70);
+
+        let terminal = AsciiConfig::preset(Preset::Terminal);
+        assert_eq!(terminal.charset, Charset::Blocks);
+        assert_eq!(terminal.tile_dimensions(), (8, 16));
+        assert_eq!(AsciiConfig::preset(Preset::Retro).charset, Charset::Ascii);
+    }
+
     #[test]
     fn test_invalid_kernel_size() {
         let config = AsciiConfig {
@@ -125,4 +963,368 @@ mod tests {
         };
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_invalid_pre_filter() {
+        let config = AsciiConfig {
+            pre_filter: PreFilter::BILATERAL,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let config = AsciiConfig {
+            pre_filter: PreFilter::Bilateral {
+                sigma_space: 0.0,
+                sigma_range: 0.1,
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            pre_filter: PreFilter::Bilateral {
+                sigma_space: 2.0,
+                sigma_range: 0.0,
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            pre_filter: PreFilter::KUWAHARA,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let config = AsciiConfig {
+            pre_filter: PreFilter::Kuwahara {
+                radius: 4,
+                sectors: 2,
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_edge_mode() {
+        for edge_mode in [EdgeMode::XDOG, EdgeMode::SOBEL] {
+            let config = AsciiConfig {
+                edge_mode,
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok());
+        }
+
+        for edge_mode in [
+            EdgeMode::Xdog {
+                phi: 0.0,
+                epsilon: 0.005,
+            },
+            EdgeMode::Xdog {
+                phi: 1000.0,
+                epsilon: 0.5,
+            },
+            EdgeMode::Sobel { threshold: 0.0 },
+        ] {
+            let config = AsciiConfig {
+                edge_mode,
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_threshold_mode() {
+        for threshold_mode in [ThresholdMode::Otsu, ThresholdMode::PERCENTILE] {
+            let config = AsciiConfig {
+                threshold_mode,
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok());
+        }
+
+        for percentile in [10.0, 100.0] {
+            let config = AsciiConfig {
+                threshold_mode: ThresholdMode::Percentile { percentile },
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_edge_dilation() {
+        for edge_dilation in [-4, 4] {
+            let config = AsciiConfig {
+                edge_dilation,
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_sobel_threshold() {
+        for sobel_threshold in [-0.1, 4.5, f32::NAN] {
+            let config = AsciiConfig {
+                sobel_threshold,
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_structure_tensor_sigma() {
+        for sigma in [0.0, 6.0] {
+            let config = AsciiConfig {
+                structure_tensor_sigma: Some(sigma),
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_median_radius() {
+        let config = AsciiConfig {
+            median_radius: Some(0),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            median_radius: Some(4),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_luminance_adjust() {
+        let config = AsciiConfig {
+            luminance_adjust: LuminanceAdjust::CLAHE,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let config = AsciiConfig {
+            luminance_adjust: LuminanceAdjust::Clahe {
+                clip_limit: 0.5,
+                grid: 8,
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            luminance_adjust: LuminanceAdjust::Clahe {
+                clip_limit: 2.0,
+                grid: 0,
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_luminance_mode() {
+        let custom = |weights| AsciiConfig {
+            luminance_mode: LuminanceMode::Custom { weights },
+            ..Default::default()
+        };
+        assert!(custom([1.0, 1.0, 0.0]).validate().is_ok());
+        assert!(custom([0.0, 0.0, 0.0]).validate().is_err());
+        assert!(custom([1.0, -0.5, 0.0]).validate().is_err());
+        assert!(custom([f32::NAN, 1.0, 0.0]).validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_tile_percentile() {
+        let config = AsciiConfig {
+            tile_statistic: TileStatistic::Percentile(101),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            tile_statistic: TileStatistic::Percentile(90),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_texture_threshold() {
+        let config = AsciiConfig {
+            texture_threshold: Some(0),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            texture_threshold: Some(24),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_rgb_split() {
+        let config = AsciiConfig {
+            rgb_split: Some(17),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            rgb_split: Some(0),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_edge_chars() {
+        assert_eq!(EdgeChars::Ascii.chars(), ['|', '-', '/', '\\']);
+        assert_eq!(
+            EdgeChars::BoxDrawing.char_for(EdgeDirection::Vertical, 0, 0),
+            '│'
+        );
+        assert_eq!(EdgeChars::Heavy.char_for(EdgeDirection::Cross, 0, 0), '+');
+
+        let custom = EdgeChars::Custom {
+            chars: ['I', '=', 'z', 'N'],
+            bitmaps: Some([[0x18; 8], [0, 0, 0, 0xff, 0xff, 0, 0, 0], [1; 8], [2; 8]]),
+        };
+        assert_eq!(custom.char_for(EdgeDirection::Horizontal, 3, 3), '=');
+        assert_eq!(custom.bitmap('I'), Some(&[0x18; 8]));
+        assert_eq!(custom.bitmap('|'), None);
+
+        let config = AsciiConfig {
+            edge_chars: EdgeChars::Custom {
+                chars: ['|', ' ', '/', '\\'],
+                bitmaps: None,
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_charset() {
+        assert_eq!(Charset::Ascii.ramp(), &FILL_CHARS);
+        assert_eq!(Charset::Blocks.ramp(), &[' ', '░', '▒', '▓', '█']);
+
+        let config = AsciiConfig {
+            charset: Charset::Custom {
+                chars: vec![' ', 'o', 'O'],
+            },
+            ..Default::default()
+        };
+        assert_eq!(config.charset.ramp(), &[' ', 'o', 'O']);
+        assert!(config.validate().is_ok());
+
+        let config = AsciiConfig {
+            charset: Charset::Custom { chars: Vec::new() },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_fill_gamma() {
+        let config = AsciiConfig {
+            fill_gamma: 0.0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            fill_gamma: 5.5,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            fill_gamma: f32::NAN,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_exposure_and_blend() {
+        for config in [
+            AsciiConfig {
+                exposure: -0.5,
+                ..Default::default()
+            },
+            AsciiConfig {
+                attenuation: 0.0,
+                ..Default::default()
+            },
+            AsciiConfig {
+                blend_with_base: 1.5,
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_edge_junction_margin() {
+        let config = AsciiConfig {
+            edge_junction_margin: Some(65),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AsciiConfig {
+            edge_junction_margin: Some(4),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_tile_size() {
+        let config = AsciiConfig {
+            tile_size: 12,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        for tile_size in TILE_SIZES {
+            let config = AsciiConfig {
+                tile_size,
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok());
+        }
+
+        let config = AsciiConfig {
+            tile_size: 16,
+            ..Default::default()
+        };
+        assert_eq!(config.scale_votes(20), 80);
+        assert_eq!(AsciiConfig::default().scale_votes(20), 20);
+
+        let config = AsciiConfig {
+            tile_height: Some(16),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.tile_dimensions(), (8, 16));
+        assert_eq!(config.scale_votes(20), 40);
+
+        let config = AsciiConfig {
+            tile_height: Some(10),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
 }
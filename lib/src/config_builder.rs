@@ -0,0 +1,199 @@
+//! Chained construction of `AsciiConfig`
+//!
+//! [`AsciiConfig::builder`] starts from the defaults, takes one setter per
+//! field and validates everything in [`AsciiConfigBuilder::build`], so an
+//! out-of-range value is reported where the config is made instead of when it
+//! is first used for processing.
+//!
+//! ```
+//! use ascii_rendr::AsciiConfig;
+//!
+//! let config = AsciiConfig::builder().sigma(2.5).draw_edges(false).build()?;
+//! assert_eq!(config.sigma, 2.5);
+//! assert!(AsciiConfig::builder().kernel_size(0).build().is_err());
+//! # Ok::<(), ascii_rendr::AsciiError>(())
+//! ```
+
+use crate::config::{
+    AsciiConfig, BlurMode, CharSelection, Charset, CompositeMode, EdgeChars, EdgeMode, FillDither,
+    LuminanceAdjust, LuminanceMode, OutputMode, Palette, PreFilter, Preset, ThresholdMode,
+    TileStatistic,
+};
+use crate::error::AsciiError;
+
+/// Builder for [`AsciiConfig`]; see [`AsciiConfig::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct AsciiConfigBuilder {
+    config: AsciiConfig,
+}
+
+/// One chained setter per config field
+macro_rules! setters {
+    ($($(#[$doc:meta])* $field:ident: $ty:ty,)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+impl AsciiConfigBuilder {
+    /// Builder starting from the settings of a preset
+    pub fn from_preset(preset: Preset) -> Self {
+        Self::from(AsciiConfig::preset(preset))
+    }
+
+    /// Validates the settings and returns the config
+    ///
+    /// # Errors
+    /// [`AsciiError::InvalidConfig`] naming the first field that is out of range.
+    pub fn build(self) -> Result<AsciiConfig, AsciiError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+
+    setters! {
+        /// RGB weighting of the luminance
+        luminance_mode: LuminanceMode,
+        /// Weight RGB in linear light
+        linear_luminance: bool,
+        /// Blur kernel radius (1-10)
+        kernel_size: u32,
+        /// First Gaussian sigma (0.0-5.0)
+        sigma: f32,
+        /// Second sigma as a multiple of the first (0.0-5.0)
+        sigma_scale: f32,
+        /// Exact Gaussian or fast box approximation of the DoG blurs
+        blur_mode: BlurMode,
+        /// Smoothing of the luminance before edges and fill
+        pre_filter: PreFilter,
+        /// Median filter radius against salt-and-pepper noise (1-3), or None
+        median_radius: Option<u32>,
+        /// Hard or soft (XDoG) thresholding of the DoG
+        edge_mode: EdgeMode,
+        /// DoG multiplier of the second blur (0.0-1.1)
+        tau: f32,
+        /// DoG threshold (0.001-0.1)
+        threshold: f32,
+        /// Fixed threshold, or one picked per image
+        threshold_mode: ThresholdMode,
+        /// Pixels to grow (positive) or shrink (negative) DoG edge lines by (-3 to 3)
+        edge_dilation: i32,
+        /// Structure tensor smoothing for edge angles (0.5-5.0), or None
+        structure_tensor_sigma: Option<f32>,
+        /// Sobel magnitude a DoG or XDoG edge pixel needs to vote (0.0-4.0)
+        sobel_threshold: f32,
+        /// Thin edges to one pixel before tile voting
+        non_max_suppression: bool,
+        /// Edge pixels needed per 8×8 tile (0-64)
+        edge_threshold: u32,
+        /// Vote gap for `+`/`X` junction tiles (0-64), or None
+        edge_junction_margin: Option<u32>,
+        /// How far saliency moves the edge threshold (0.0-1.0)
+        saliency_strength: f32,
+        /// Foreground color
+        ascii_color: [u8; 3],
+        /// Background color
+        bg_color: [u8; 3],
+        /// Contrast adjustment of the luminance before tiling
+        luminance_adjust: LuminanceAdjust,
+        /// Fill luminance multiplier (0.0-5.0)
+        exposure: f32,
+        /// Exponent on the exposed fill luminance (0.1-5.0)
+        attenuation: f32,
+        /// How tile luminance is computed
+        tile_statistic: TileStatistic,
+        /// Tile radius for local contrast normalization (1-16), or None
+        local_contrast_radius: Option<u32>,
+        /// Gamma before fill character quantization (0.1-5.0)
+        fill_gamma: f32,
+        /// Mixing of adjacent ramp characters within a tile
+        fill_dither: FillDither,
+        /// Tile luminance std dev that promotes fill (1-128), or None
+        texture_threshold: Option<u32>,
+        /// Fill by luminance ramp or best-matching glyph
+        char_selection: CharSelection,
+        /// Fill ramp characters
+        charset: Charset,
+        /// Draw edge characters
+        draw_edges: bool,
+        /// Draw fill characters
+        draw_fill: bool,
+        /// Flip the fill ramp
+        invert_luminance: bool,
+        /// Flip the fill ramp for predominantly bright images
+        auto_invert: bool,
+        /// How edge and fill layers combine
+        composite_mode: CompositeMode,
+        /// Mix of the source image into the rendered output (0.0-1.0)
+        blend_with_base: f32,
+        /// Color tiles by edge direction instead of drawing characters
+        edge_debug: bool,
+        /// Edge debug colors
+        palette: Palette,
+        /// Characters for the four edge directions
+        edge_chars: EdgeChars,
+        /// Shift edge glyphs onto where the edge crosses the tile
+        positional_edges: bool,
+        /// Pixel offset of separately rendered R/G/B layers (0-16), or None
+        rgb_split: Option<u32>,
+        /// ASCII tiles or braille dots
+        output_mode: OutputMode,
+        /// Pixels per side of an ASCII tile (4, 8, 16 or 32)
+        tile_size: u32,
+        /// Tile height when it differs from `tile_size` (4, 8, 16 or 32), or None
+        tile_height: Option<u32>,
+    }
+}
+
+impl From<AsciiConfig> for AsciiConfigBuilder {
+    /// Builder starting from an existing config
+    fn from(config: AsciiConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl AsciiConfig {
+    /// Builder starting from the default settings
+    pub fn builder() -> AsciiConfigBuilder {
+        AsciiConfigBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let config = AsciiConfig::builder()
+            .sigma(1.5)
+            .edge_junction_margin(Some(2))
+            .output_mode(OutputMode::Braille)
+            .build()
+            .unwrap();
+        assert_eq!(config.sigma, 1.5);
+        assert_eq!(config.edge_junction_margin, Some(2));
+        assert_eq!(config.output_mode, OutputMode::Braille);
+        assert_eq!(config.kernel_size, AsciiConfig::default().kernel_size);
+
+        let retro = AsciiConfigBuilder::from_preset(Preset::Retro)
+            .draw_fill(false)
+            .build()
+            .unwrap();
+        assert_eq!(
+            retro.ascii_color,
+            AsciiConfig::preset(Preset::Retro).ascii_color
+        );
+        assert!(!retro.draw_fill);
+
+        let err = AsciiConfig::builder().tau(2.0).build().unwrap_err();
+        assert!(matches!(
+            err,
+            AsciiError::InvalidConfig { field: "tau", .. }
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+//! Loading and saving `AsciiConfig` as TOML or JSON
+//!
+//! The format is chosen from the file extension (`.toml` or `.json`). Parsed
+//! configs are validated, so out-of-range values are reported with their
+//! allowed range rather than failing later at process time.
+
+use crate::config::AsciiConfig;
+use crate::error::AsciiError;
+use std::path::Path;
+
+/// Supported config file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    fn from_path(path: &Path) -> Result<Self, String> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("toml") => Ok(ConfigFormat::Toml),
+            Some("json") => Ok(ConfigFormat::Json),
+            _ => Err(format!(
+                "{}: unsupported config format (expected .toml or .json)",
+                path.display()
+            )),
+        }
+    }
+}
+
+impl AsciiConfig {
+    /// Loads and validates a configuration from a `.toml` or `.json` file
+    ///
+    /// Fields missing from the file keep their default value.
+    ///
+    /// # Errors
+    /// Returns [`AsciiError::ParseError`] if the file cannot be read, has an
+    /// unsupported extension or contains unknown fields or malformed values,
+    /// and [`AsciiError::InvalidConfig`] if it fails [`AsciiConfig::validate`];
+    /// messages are prefixed with the file path.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, AsciiError> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path).map_err(AsciiError::ParseError)?;
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            AsciiError::ParseError(format!("{}: failed to read config: {}", path.display(), e))
+        })?;
+
+        let parsed = match format {
+            ConfigFormat::Toml => Self::from_toml_str(&contents),
+            ConfigFormat::Json => Self::from_json_str(&contents),
+        };
+        parsed.map_err(|e| e.context(path.display()))
+    }
+
+    /// Parses and validates a configuration from a TOML string
+    pub fn from_toml_str(contents: &str) -> Result<Self, AsciiError> {
+        let config: Self = toml::from_str(contents)
+            .map_err(|e| AsciiError::ParseError(format!("invalid TOML: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses and validates a configuration from a JSON string
+    pub fn from_json_str(contents: &str) -> Result<Self, AsciiError> {
+        let config: Self = serde_json::from_str(contents).map_err(|e| {
+            AsciiError::ParseError(format!(
+                "invalid JSON at line {}, column {}: {}",
+                e.line(),
+                e.column(),
+                e
+            ))
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Serializes the configuration as pretty-printed TOML
+    pub fn to_toml_string(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| format!("failed to serialize config: {}", e))
+    }
+
+    /// Serializes the configuration as pretty-printed JSON
+    pub fn to_json_string(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("failed to serialize config: {}", e))
+    }
+
+    /// Saves the configuration to a `.toml` or `.json` file
+    pub fn save_to_path(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let contents = match ConfigFormat::from_path(path)? {
+            ConfigFormat::Toml => self.to_toml_string()?,
+            ConfigFormat::Json => self.to_json_string()?,
+        };
+        std::fs::write(path, contents)
+            .map_err(|e| format!("{}: failed to write config: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TileStatistic;
+
+    #[test]
+    fn test_toml_partial_config_uses_defaults() {
+        let config = AsciiConfig::from_toml_str("sigma = 1.5\ndraw_fill = false\n").unwrap();
+        assert_eq!(config.sigma, 1.5);
+        assert!(!config.draw_fill);
+        assert_eq!(config.kernel_size, AsciiConfig::default().kernel_size);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let config = AsciiConfig {
+            edge_junction_margin: Some(3),
+            tile_statistic: TileStatistic::Percentile(80),
+            ..Default::default()
+        };
+        let text = config.to_toml_string().unwrap();
+        let parsed = AsciiConfig::from_toml_str(&text).unwrap();
+        assert_eq!(parsed.edge_junction_margin, Some(3));
+        assert_eq!(parsed.tile_statistic, TileStatistic::Percentile(80));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let config = AsciiConfig {
+            ascii_color: [1, 2, 3],
+            ..Default::default()
+        };
+        let text = config.to_json_string().unwrap();
+        let parsed = AsciiConfig::from_json_str(&text).unwrap();
+        assert_eq!(parsed.ascii_color, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_unknown_field_is_reported() {
+        let err = AsciiConfig::from_toml_str("sigmaa = 1.0\n").unwrap_err();
+        assert!(matches!(err, AsciiError::ParseError(_)));
+        assert!(
+            err.to_string().contains("unknown field `sigmaa`"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_reports_allowed_range() {
+        let err = AsciiConfig::from_json_str(r#"{ "sigma": 9.0 }"#).unwrap_err();
+        assert!(matches!(
+            err,
+            AsciiError::InvalidConfig { field: "sigma", .. }
+        ));
+        assert!(err.to_string().contains("between 0.0 and 5.0"), "{}", err);
+    }
+
+    #[test]
+    fn test_unsupported_extension() {
+        let err = AsciiConfig::from_path("settings.yaml").unwrap_err();
+        assert!(
+            err.to_string().contains("unsupported config format"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Structured per-tile data
+//!
+//! [`process_image_data`] runs the pipeline without rendering and returns what
+//! was decided for every tile: the character, the voted edge direction, the
+//! luminance and the average source color. Web viewers and custom renderers
+//! can draw from this instead of the PNG; [`AsciiArtData::to_json`] gives it
+//! in a stable wire format.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::data::process_image_data;
+//!
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! let data = process_image_data(&input, &AsciiConfig::default());
+//! std::fs::write("photo.json", data.to_json()).unwrap();
+//! ```
+
+use crate::ascii::{tile_average_colors, tile_representative_chars};
+use crate::config::AsciiConfig;
+use crate::edges::EdgeDirection;
+use crate::processor::{compute_char_layers, resize_to_valid_dimensions};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+/// Character grid of one image with per-tile attributes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AsciiArtData {
+    /// Number of character columns (tiles horizontally)
+    pub columns: u32,
+    /// Number of character rows (tiles vertically)
+    pub rows: u32,
+    /// Configured foreground color
+    pub ascii_color: [u8; 3],
+    /// Configured background color
+    pub bg_color: [u8; 3],
+    /// One entry per tile, row-major
+    pub tiles: Vec<TileData>,
+}
+
+/// What the pipeline decided for one tile
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TileData {
+    /// Character shown for the tile
+    #[serde(rename = "char")]
+    pub ch: char,
+    /// Voted edge direction, or None for fill tiles
+    pub edge: Option<EdgeDirection>,
+    /// Luminance the fill character was chosen from (0.0-1.0); the tile mean
+    /// unless `tile_statistic` or `local_contrast_radius` say otherwise
+    pub luminance: f32,
+    /// Average source color
+    pub color: [u8; 3],
+}
+
+impl AsciiArtData {
+    /// Tile at (`column`, `row`)
+    pub fn tile(&self, column: u32, row: u32) -> Option<&TileData> {
+        if column >= self.columns || row >= self.rows {
+            return None;
+        }
+        self.tiles.get((row * self.columns + column) as usize)
+    }
+
+    /// JSON object with `columns`, `rows`, `ascii_color`, `bg_color` and
+    /// `tiles`, each tile as `{"char", "edge", "luminance", "color"}` with
+    /// `edge` one of `vertical`, `horizontal`, `diagonal1`, `diagonal2`,
+    /// `cross`, `diagonal_cross` or null
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("tile data serialization cannot fail")
+    }
+}
+
+/// Converts an image to per-tile data without rendering it
+///
+/// Always describes the grid of `tile_size` × `tile_height` tiles;
+/// `output_mode` and the rendering settings (`edge_debug`, `rgb_split`) do not
+/// apply.
+///
+/// # Arguments
+/// * `input` - The input RGBA image to convert
+/// * `config` - Configuration parameters for the ASCII conversion
+///
+/// # Note
+/// If the input image dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering.
+pub fn process_image_data(input: &RgbaImage, config: &AsciiConfig) -> AsciiArtData {
+    // Validate config
+    config.validate().expect("Invalid configuration");
+
+    // Automatically resize if dimensions are not multiples of the tile size
+    let working_image = resize_to_valid_dimensions(input, config.tile_dimensions());
+
+    // Steps 1-6: Luminance, edges and character selection
+    let layers = compute_char_layers(&working_image, config);
+    let chars = tile_representative_chars(&layers.composite(config));
+    let colors = tile_average_colors(&working_image, config.tile_dimensions());
+
+    let tiles = chars
+        .iter()
+        .zip(&layers.directions)
+        .zip(layers.tile_lum.iter().zip(&colors))
+        .map(|((&ch, &direction), (&luminance, &color))| TileData {
+            ch,
+            edge: (direction != EdgeDirection::None).then_some(direction),
+            luminance,
+            color,
+        })
+        .collect();
+
+    AsciiArtData {
+        columns: layers.tile_width,
+        rows: layers.tile_height,
+        ascii_color: config.ascii_color,
+        bg_color: config.bg_color,
+        tiles,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_image_output;
+    use image::Rgba;
+
+    fn scene() -> RgbaImage {
+        RgbaImage::from_fn(48, 32, |x, y| {
+            let v = if x < 24 { 20 } else { 235 };
+            Rgba([v, v.saturating_sub((y * 7) as u8), 60, 255])
+        })
+    }
+
+    #[test]
+    fn test_process_image_data() {
+        let config = AsciiConfig::default();
+        let data = process_image_data(&scene(), &config);
+        let output = process_image_output(&scene(), &config, true);
+
+        assert_eq!((data.columns, data.rows), (6, 4));
+        assert_eq!(data.tiles.len(), 24);
+        let chars: Vec<char> = data.tiles.iter().map(|t| t.ch).collect();
+        assert_eq!(chars, output.chars);
+        let colors: Vec<[u8; 3]> = data.tiles.iter().map(|t| t.color).collect();
+        assert_eq!(colors, output.colors);
+
+        // Dark left, bright right, an edge in between
+        let (left, right) = (data.tile(0, 1).unwrap(), data.tile(5, 1).unwrap());
+        assert!(left.luminance < right.luminance);
+        assert_eq!(left.edge, None);
+        assert!(data.tiles.iter().any(|t| t.edge.is_some()));
+        assert!(data.tile(6, 0).is_none());
+    }
+
+    #[test]
+    fn test_to_json() {
+        let data = AsciiArtData {
+            columns: 2,
+            rows: 1,
+            ascii_color: [255, 255, 255],
+            bg_color: [0, 0, 0],
+            tiles: vec![
+                TileData {
+                    ch: '|',
+                    edge: Some(EdgeDirection::Vertical),
+                    luminance: 0.5,
+                    color: [1, 2, 3],
+                },
+                TileData {
+                    ch: '.',
+                    edge: None,
+                    luminance: 0.25,
+                    color: [4, 5, 6],
+                },
+            ],
+        };
+        assert_eq!(
+            data.to_json(),
+            "{\"columns\":2,\"rows\":1,\"ascii_color\":[255,255,255],\"bg_color\":[0,0,0],\
+             \"tiles\":[{\"char\":\"|\",\"edge\":\"vertical\",\"luminance\":0.5,\"color\":[1,2,3]},\
+             {\"char\":\".\",\"edge\":null,\"luminance\":0.25,\"color\":[4,5,6]}]}"
+        );
+    }
+}
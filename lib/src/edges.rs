@@ -1,16 +1,52 @@
-use rayon::prelude::*;
+use crate::config::{AsciiConfig, EdgeMode};
+use crate::error::AsciiError;
+use crate::filters::{
+    DogBuffers, blur_into_checked, blur_passes, difference_of_gaussians_auto_into_checked,
+    dilate_edges, extended_difference_of_gaussians_into_checked, non_maximum_suppression,
+    sobel_filter_threshold_into_checked, structure_tensor_angles_checked,
+};
+use crate::par::*;
+use crate::progress::StageRows;
+use image::GrayImage;
+use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
 /// Edge direction classification for ASCII character selection
 ///
 /// Corresponds to the direction classification in CS_RenderASCII from AcerolaFX_ASCII.fx:427-435
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EdgeDirection {
     None = -1,
-    Vertical = 0,   // | (0° or 180°)
-    Horizontal = 1, // - (90°)
-    Diagonal1 = 2,  // / (45° to 90°, negative angles or 135° to 180°)
-    Diagonal2 = 3,  // \ (45° to 90°, positive angles or -135° to -45°)
+    Vertical = 0,      // | (0° or 180°)
+    Horizontal = 1,    // - (90°)
+    Diagonal1 = 2,     // / (45° to 90°, negative angles or 135° to 180°)
+    Diagonal2 = 3,     // \ (45° to 90°, positive angles or -135° to -45°)
+    Cross = 4,         // + (vertical and horizontal tied)
+    DiagonalCross = 5, // X (both diagonals tied)
+}
+
+impl EdgeDirection {
+    /// Direction voting buckets in tie-break priority order
+    pub const BUCKETS: [EdgeDirection; 4] = [
+        EdgeDirection::Vertical,
+        EdgeDirection::Horizontal,
+        EdgeDirection::Diagonal1,
+        EdgeDirection::Diagonal2,
+    ];
+
+    /// Junction formed by this direction and its perpendicular partner, if any
+    fn junction_with(self, other: EdgeDirection) -> Option<EdgeDirection> {
+        match (self, other) {
+            (EdgeDirection::Vertical, EdgeDirection::Horizontal)
+            | (EdgeDirection::Horizontal, EdgeDirection::Vertical) => Some(EdgeDirection::Cross),
+            (EdgeDirection::Diagonal1, EdgeDirection::Diagonal2)
+            | (EdgeDirection::Diagonal2, EdgeDirection::Diagonal1) => {
+                Some(EdgeDirection::DiagonalCross)
+            }
+            _ => None,
+        }
+    }
 }
 
 /// Classify edge direction from angle
@@ -55,6 +91,57 @@ pub fn classify_edge_direction(angle: f32) -> EdgeDirection {
     }
 }
 
+/// Resolve a tile's edge direction from its vote buckets
+///
+/// Tie-break rule: the bucket with the most votes wins, and exact ties go to the
+/// bucket that comes first in [`EdgeDirection::BUCKETS`] (vertical, horizontal,
+/// diagonal1, diagonal2). This matches the shader's `buckets[i] > maxValue` scan.
+///
+/// When `junction_margin` is set and the runner-up is the perpendicular partner
+/// of the winner (vertical/horizontal or the two diagonals), also passes the edge
+/// threshold, and trails by at most `junction_margin` votes, the tile is reported
+/// as a junction ([`EdgeDirection::Cross`] or [`EdgeDirection::DiagonalCross`]).
+///
+/// # Arguments
+/// * `buckets` - Vote counts in [`EdgeDirection::BUCKETS`] order
+/// * `edge_threshold` - Minimum votes needed to declare an edge
+/// * `junction_margin` - Maximum vote gap for a junction, or None to disable junctions
+///
+/// # Returns
+/// EdgeDirection for the tile
+pub fn resolve_edge_direction(
+    buckets: &[u32; 4],
+    edge_threshold: u32,
+    junction_margin: Option<u32>,
+) -> EdgeDirection {
+    // Stable ordering: highest count first, earlier bucket first on ties
+    let mut order = [0usize, 1, 2, 3];
+    order.sort_by(|&a, &b| buckets[b].cmp(&buckets[a]));
+
+    let (first, second) = (order[0], order[1]);
+    let max_count = buckets[first];
+
+    // Only use the edge if enough pixels voted for it
+    // Matches shader logic: if (maxValue < _EdgeThreshold) commonEdgeIndex = -1;
+    if max_count == 0 || max_count < edge_threshold {
+        return EdgeDirection::None;
+    }
+
+    let primary = EdgeDirection::BUCKETS[first];
+    if let Some(margin) = junction_margin {
+        let runner_up = buckets[second];
+        if runner_up > 0
+            && runner_up >= edge_threshold
+            && max_count - runner_up <= margin
+            && let Some(junction) = primary.junction_with(EdgeDirection::BUCKETS[second])
+        {
+            return junction;
+        }
+    }
+
+    primary
+}
+
 /// Detect edges with direction voting in 8×8 tiles
 ///
 /// This implements the tile-based edge direction voting algorithm from CS_RenderASCII:418-465
@@ -68,27 +155,423 @@ pub fn classify_edge_direction(angle: f32) -> EdgeDirection {
 /// * `edge_threshold` - Minimum number of pixels in a tile needed to declare an edge
 ///
 /// # Returns
-/// Vec of EdgeDirection, one per 8×8 tile (size: (width/8) * (height/8))
+/// Vec of EdgeDirection, one per 8×8 tile (size: (width/8) * (height/8)), or
+/// [`AsciiError::DimensionError`] when the buffers do not hold `width * height`
+/// entries or the dimensions are not multiples of 8
 pub fn detect_edges_tiled(
     angles: &[f32],
     valid_mask: &[bool],
     width: u32,
     height: u32,
     edge_threshold: u32,
-) -> Vec<EdgeDirection> {
-    assert_eq!(angles.len(), (width * height) as usize);
-    assert_eq!(valid_mask.len(), (width * height) as usize);
-    assert!(
-        width.is_multiple_of(8) && height.is_multiple_of(8),
-        "Dimensions must be multiples of 8"
+) -> Result<Vec<EdgeDirection>, AsciiError> {
+    detect_edges_tiled_with_junctions(angles, valid_mask, width, height, edge_threshold, None)
+}
+
+/// Detect edges with direction voting in 8×8 tiles, optionally reporting junctions
+///
+/// Same as [`detect_edges_tiled`], but tiles whose two strongest directions are
+/// perpendicular and within `junction_margin` votes of each other are reported as
+/// [`EdgeDirection::Cross`] or [`EdgeDirection::DiagonalCross`].
+/// See [`resolve_edge_direction`] for the exact rule.
+///
+/// # Arguments
+/// * `angles` - Vec of edge angles for each pixel (from Sobel filter)
+/// * `valid_mask` - Vec of booleans indicating which pixels have valid edges
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `edge_threshold` - Minimum number of pixels in a tile needed to declare an edge
+/// * `junction_margin` - Maximum vote gap for a junction, or None to disable junctions
+///
+/// # Returns
+/// Vec of EdgeDirection, one per 8×8 tile (size: (width/8) * (height/8)), or
+/// [`AsciiError::DimensionError`] when the buffers do not hold `width * height`
+/// entries or the dimensions are not multiples of 8
+pub fn detect_edges_tiled_with_junctions(
+    angles: &[f32],
+    valid_mask: &[bool],
+    width: u32,
+    height: u32,
+    edge_threshold: u32,
+    junction_margin: Option<u32>,
+) -> Result<Vec<EdgeDirection>, AsciiError> {
+    vote_tiles(
+        angles,
+        valid_mask,
+        width,
+        height,
+        (8, 8),
+        |_| edge_threshold,
+        junction_margin,
+    )
+}
+
+/// Detect edges with direction voting in 8×8 tiles, with a threshold per tile
+///
+/// Same as [`detect_edges_tiled_with_junctions`], but tile `i` (row by row)
+/// needs `edge_thresholds[i]` votes, so busy or important areas can be given
+/// more edges than the rest.
+///
+/// # Arguments
+/// * `angles` - Vec of edge angles for each pixel (from Sobel filter)
+/// * `valid_mask` - Vec of booleans indicating which pixels have valid edges
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `edge_thresholds` - Minimum votes per tile (size: (width/8) * (height/8))
+/// * `junction_margin` - Maximum vote gap for a junction, or None to disable junctions
+///
+/// # Returns
+/// Vec of EdgeDirection, one per 8×8 tile (size: (width/8) * (height/8)), or
+/// [`AsciiError::DimensionError`] when the buffers do not hold `width * height`
+/// entries or the dimensions are not multiples of 8
+pub fn detect_edges_tiled_adaptive(
+    angles: &[f32],
+    valid_mask: &[bool],
+    width: u32,
+    height: u32,
+    edge_thresholds: &[u32],
+    junction_margin: Option<u32>,
+) -> Result<Vec<EdgeDirection>, AsciiError> {
+    let tile_count = ((width / 8) * (height / 8)) as usize;
+    if edge_thresholds.len() != tile_count {
+        return Err(AsciiError::DimensionError(format!(
+            "expected {} edge thresholds, got {}",
+            tile_count,
+            edge_thresholds.len()
+        )));
+    }
+    vote_tiles(
+        angles,
+        valid_mask,
+        width,
+        height,
+        (8, 8),
+        |tile_idx| edge_thresholds[tile_idx],
+        junction_margin,
+    )
+}
+
+/// Detect edges with direction voting in tiles of any size
+///
+/// Same as [`detect_edges_tiled_adaptive`] with `tile_size.0` × `tile_size.1`
+/// tiles instead of 8×8, such as 8×16 tiles for terminal cells; thresholds and
+/// the junction margin count votes among the pixels of a tile.
+///
+/// # Arguments
+/// * `angles` - Vec of edge angles for each pixel (from Sobel filter)
+/// * `valid_mask` - Vec of booleans indicating which pixels have valid edges
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `tile_size` - Tile width and height in pixels
+/// * `edge_thresholds` - Minimum votes per tile (size: (width/tile_size.0) * (height/tile_size.1))
+/// * `junction_margin` - Maximum vote gap for a junction, or None to disable junctions
+///
+/// # Returns
+/// Vec of EdgeDirection, one per tile, or [`AsciiError::DimensionError`] when
+/// the buffers do not hold `width * height` entries or the dimensions are not
+/// multiples of `tile_size`
+pub fn detect_edges_tiled_sized(
+    angles: &[f32],
+    valid_mask: &[bool],
+    width: u32,
+    height: u32,
+    tile_size: (u32, u32),
+    edge_thresholds: &[u32],
+    junction_margin: Option<u32>,
+) -> Result<Vec<EdgeDirection>, AsciiError> {
+    let tile_count = match (
+        width.checked_div(tile_size.0),
+        height.checked_div(tile_size.1),
+    ) {
+        (Some(columns), Some(rows)) => (columns * rows) as usize,
+        _ => 0,
+    };
+    if edge_thresholds.len() != tile_count {
+        return Err(AsciiError::DimensionError(format!(
+            "expected {} edge thresholds, got {}",
+            tile_count,
+            edge_thresholds.len()
+        )));
+    }
+    vote_tiles(
+        angles,
+        valid_mask,
+        width,
+        height,
+        tile_size,
+        |tile_idx| edge_thresholds[tile_idx],
+        junction_margin,
+    )
+}
+
+/// Where the edge runs through each edge tile, as the shift in pixels that
+/// moves the centered edge glyph onto it
+///
+/// The shift follows the centroid of the tile's edge pixels of the voted
+/// direction: `|` and `-` glyphs move across their line, `/` and `\` glyphs
+/// sideways. It is capped at 3/8 of the tile so the glyph stays visible.
+/// Junction and non-edge tiles get (0, 0).
+///
+/// # Arguments
+/// * `angles` - Gradient angle per pixel (size: width * height)
+/// * `valid_mask` - Edge pixels (size: width * height)
+/// * `width` - Image width, a multiple of `tile_size.0`
+/// * `tile_size` - Tile width and height in pixels
+/// * `directions` - Voted direction per tile, row by row
+///
+/// # Returns
+/// Horizontal and vertical shift per tile
+pub fn tile_edge_offsets(
+    angles: &[f32],
+    valid_mask: &[bool],
+    width: u32,
+    tile_size: (u32, u32),
+    directions: &[EdgeDirection],
+) -> Vec<(i32, i32)> {
+    let (tile_w, tile_h) = tile_size;
+    let tile_width = (width / tile_w.max(1)).max(1);
+    let (scale_x, scale_y) = (tile_w as f32 / 8.0, tile_h as f32 / 8.0);
+    let (max_x, max_y) = ((tile_w * 3 / 8) as i32, (tile_h * 3 / 8) as i32);
+
+    directions
+        .par_iter()
+        .enumerate()
+        .map(|(tile_idx, &direction)| {
+            if !matches!(
+                direction,
+                EdgeDirection::Vertical
+                    | EdgeDirection::Horizontal
+                    | EdgeDirection::Diagonal1
+                    | EdgeDirection::Diagonal2
+            ) {
+                return (0, 0);
+            }
+            let tile_x = tile_idx as u32 % tile_width;
+            let tile_y = tile_idx as u32 / tile_width;
+
+            // Centroid of the pixels voting for the tile's direction
+            let (mut sum_x, mut sum_y, mut count) = (0.0, 0.0, 0);
+            for local_y in 0..tile_h {
+                for local_x in 0..tile_w {
+                    let idx =
+                        ((tile_y * tile_h + local_y) * width + tile_x * tile_w + local_x) as usize;
+                    if valid_mask[idx] && classify_edge_direction(angles[idx]) == direction {
+                        sum_x += local_x as f32 + 0.5;
+                        sum_y += local_y as f32 + 0.5;
+                        count += 1;
+                    }
+                }
+            }
+            if count == 0 {
+                return (0, 0);
+            }
+            let (cx, cy) = (sum_x / count as f32, sum_y / count as f32);
+
+            // Distance from the glyph's line through the tile center, in pixels
+            let (dx, dy) = match direction {
+                EdgeDirection::Vertical => (cx - tile_w as f32 / 2.0, 0.0),
+                EdgeDirection::Horizontal => (0.0, cy - tile_h as f32 / 2.0),
+                EdgeDirection::Diagonal1 => ((cx / scale_x + cy / scale_y - 8.0) * scale_x, 0.0),
+                _ => ((cx / scale_x - cy / scale_y) * scale_x, 0.0),
+            };
+            (
+                (dx.round() as i32).clamp(-max_x, max_x),
+                (dy.round() as i32).clamp(-max_y, max_y),
+            )
+        })
+        .collect()
+}
+
+/// Per-pixel edge detection (pipeline steps 2-3)
+///
+/// A detector marks which pixels of the luminance image lie on an edge and the
+/// gradient angle at each; the 8×8 tile voting of step 4 is the same for every
+/// detector. [`DogSobel`] is the default; others (Canny, Scharr, an edge map
+/// computed elsewhere) can be plugged into a
+/// [`Processor`](crate::Processor) with `with_edge_detector`.
+pub trait EdgeDetector: Send {
+    /// Fills `angles` and `valid_mask` with one entry per pixel of `lum`, row by row
+    ///
+    /// # Arguments
+    /// * `lum` - Luminance image, dimensions are multiples of the tile size
+    /// * `config` - Settings of the conversion
+    /// * `angles` - Gradient angle in radians [-π, π], as from atan2(Gy, Gx)
+    /// * `valid_mask` - Whether the pixel is an edge pixel that gets a vote
+    fn detect(
+        &mut self,
+        lum: &GrayImage,
+        config: &AsciiConfig,
+        angles: &mut Vec<f32>,
+        valid_mask: &mut Vec<bool>,
     );
+}
+
+/// Thresholded Difference of Gaussians followed by a Sobel filter, or the
+/// Sobel filter alone on the blurred luminance with [`EdgeMode::Sobel`]
+///
+/// Uses `edge_mode`, `sigma`, `sigma_scale`, `kernel_size`, `blur_mode`, `tau`,
+/// `threshold`, `threshold_mode`, `edge_dilation`, `structure_tensor_sigma`,
+/// `sobel_threshold` and `non_max_suppression` from the config and keeps its blur
+/// buffers between calls.
+#[derive(Debug, Clone, Default)]
+pub struct DogSobel {
+    buffers: DogBuffers,
+}
+
+impl EdgeDetector for DogSobel {
+    fn detect(
+        &mut self,
+        lum: &GrayImage,
+        config: &AsciiConfig,
+        angles: &mut Vec<f32>,
+        valid_mask: &mut Vec<bool>,
+    ) {
+        let rows = &mut StageRows::unchecked();
+        edge_image_into(lum, config, &mut self.buffers, rows);
+        sobel_gradients_into(&self.buffers.output, config, angles, valid_mask, rows);
+    }
+}
+
+/// Row passes of steps 2-3 that check a [`StageRows`], over which the
+/// progress of the stage is spread
+pub(crate) fn edge_row_passes(config: &AsciiConfig) -> usize {
+    let blurs = match config.edge_mode {
+        EdgeMode::Dog | EdgeMode::Xdog { .. } => 2,
+        EdgeMode::Sobel { .. } => 1,
+    };
+    // Three tensor components, each smoothed in two passes
+    let tensor = if config.structure_tensor_sigma.is_some() {
+        6
+    } else {
+        0
+    };
+    // The blurs, the Sobel pass and the tensor smoothing
+    blurs * blur_passes(config.blur_mode) + 1 + tensor
+}
+
+/// Step 3: Sobel on the step 2 image, with angles from the structure tensor
+/// when `structure_tensor_sigma` is set, thinned with non-maximum suppression
+/// when `non_max_suppression` is on; stops early when `rows` is cancelled
+pub(crate) fn sobel_gradients_into(
+    edge_image: &GrayImage,
+    config: &AsciiConfig,
+    angles: &mut Vec<f32>,
+    valid_mask: &mut Vec<bool>,
+    rows: &mut StageRows,
+) {
+    sobel_filter_threshold_into_checked(
+        edge_image,
+        sobel_threshold(config),
+        angles,
+        valid_mask,
+        rows,
+    );
+    if let Some(sigma) = config.structure_tensor_sigma {
+        structure_tensor_angles_checked(edge_image, sigma, angles, valid_mask, rows);
+    }
+    if config.non_max_suppression {
+        non_maximum_suppression(edge_image, angles, valid_mask);
+    }
+}
+
+/// Step 2: the image the Sobel filter reads, left in `buffers.output`; the
+/// Difference of Gaussians thresholded as `edge_mode` and `threshold_mode`
+/// say and grown or shrunk by `edge_dilation`, or the blurred luminance with
+/// [`EdgeMode::Sobel`]; stops early when `rows` is cancelled
+pub(crate) fn edge_image_into(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    buffers: &mut DogBuffers,
+    rows: &mut StageRows,
+) {
+    let sigma1 = config.sigma;
+    let sigma2 = config.sigma * config.sigma_scale;
+    match config.edge_mode {
+        EdgeMode::Dog => {
+            difference_of_gaussians_auto_into_checked(
+                lum,
+                sigma1,
+                sigma2,
+                config.kernel_size,
+                config.blur_mode,
+                config.tau,
+                config.threshold,
+                config.threshold_mode,
+                buffers,
+                rows,
+            );
+        }
+        EdgeMode::Xdog { phi, epsilon } => extended_difference_of_gaussians_into_checked(
+            lum,
+            sigma1,
+            sigma2,
+            config.kernel_size,
+            config.blur_mode,
+            config.tau,
+            phi,
+            epsilon,
+            buffers,
+            rows,
+        ),
+        EdgeMode::Sobel { .. } => blur_into_checked(
+            lum,
+            sigma1,
+            config.kernel_size,
+            config.blur_mode,
+            &mut buffers.temp,
+            &mut buffers.output,
+            rows,
+        ),
+    }
+    if config.edge_dilation != 0 && !matches!(config.edge_mode, EdgeMode::Sobel { .. }) {
+        buffers.output = dilate_edges(&buffers.output, config.edge_dilation);
+    }
+}
+
+/// Gradient magnitude a pixel of the step 2 image needs to vote
+fn sobel_threshold(config: &AsciiConfig) -> f32 {
+    match config.edge_mode {
+        EdgeMode::Sobel { threshold } => threshold,
+        EdgeMode::Dog | EdgeMode::Xdog { .. } => config.sobel_threshold,
+    }
+}
+
+/// Shared voting loop; `edge_threshold` gives the threshold of a tile index
+fn vote_tiles(
+    angles: &[f32],
+    valid_mask: &[bool],
+    width: u32,
+    height: u32,
+    tile_size: (u32, u32),
+    edge_threshold: impl Fn(usize) -> u32 + Sync,
+    junction_margin: Option<u32>,
+) -> Result<Vec<EdgeDirection>, AsciiError> {
+    let pixel_count = (width * height) as usize;
+    if angles.len() != pixel_count || valid_mask.len() != pixel_count {
+        return Err(AsciiError::DimensionError(format!(
+            "expected {} angles and mask entries for {}×{}, got {} and {}",
+            pixel_count,
+            width,
+            height,
+            angles.len(),
+            valid_mask.len()
+        )));
+    }
+    let (tile_w, tile_h) = tile_size;
+    if tile_w == 0 || tile_h == 0 || !width.is_multiple_of(tile_w) || !height.is_multiple_of(tile_h)
+    {
+        return Err(AsciiError::DimensionError(format!(
+            "Dimensions must be multiples of {}×{}, got {}×{}",
+            tile_w, tile_h, width, height
+        )));
+    }
 
-    let tile_width = width / 8;
-    let tile_height = height / 8;
+    let tile_width = width / tile_w;
+    let tile_height = height / tile_h;
     let num_tiles = (tile_width * tile_height) as usize;
 
     // Parallelize tile processing
-    (0..num_tiles)
+    let directions = (0..num_tiles)
         .into_par_iter()
         .map(|tile_idx| {
             let tile_x = (tile_idx as u32) % tile_width;
@@ -97,11 +580,11 @@ pub fn detect_edges_tiled(
             // Count edge directions in this tile
             let mut buckets = [0u32; 4]; // [Vertical, Horizontal, Diagonal1, Diagonal2]
 
-            // Scan all 64 pixels in this 8×8 tile
-            for local_y in 0..8 {
-                for local_x in 0..8 {
-                    let pixel_x = tile_x * 8 + local_x;
-                    let pixel_y = tile_y * 8 + local_y;
+            // Scan all pixels in this tile
+            for local_y in 0..tile_h {
+                for local_x in 0..tile_w {
+                    let pixel_x = tile_x * tile_w + local_x;
+                    let pixel_y = tile_y * tile_h + local_y;
                     let idx = (pixel_y * width + pixel_x) as usize;
 
                     if valid_mask[idx] {
@@ -111,38 +594,17 @@ pub fn detect_edges_tiled(
                             EdgeDirection::Horizontal => buckets[1] += 1,
                             EdgeDirection::Diagonal1 => buckets[2] += 1,
                             EdgeDirection::Diagonal2 => buckets[3] += 1,
-                            EdgeDirection::None => {}
+                            _ => {}
                         }
                     }
                 }
             }
 
             // Find the most common edge direction (max bucket)
-            let mut max_count = 0;
-            let mut common_edge = EdgeDirection::None;
-
-            for (i, &count) in buckets.iter().enumerate() {
-                if count > max_count {
-                    max_count = count;
-                    common_edge = match i {
-                        0 => EdgeDirection::Vertical,
-                        1 => EdgeDirection::Horizontal,
-                        2 => EdgeDirection::Diagonal1,
-                        3 => EdgeDirection::Diagonal2,
-                        _ => EdgeDirection::None,
-                    };
-                }
-            }
-
-            // Only use the edge if enough pixels voted for it
-            // Matches shader logic: if (maxValue < _EdgeThreshold) commonEdgeIndex = -1;
-            if max_count < edge_threshold {
-                common_edge = EdgeDirection::None;
-            }
-
-            common_edge
+            resolve_edge_direction(&buckets, edge_threshold(tile_idx), junction_margin)
         })
-        .collect()
+        .collect();
+    Ok(directions)
 }
 
 #[cfg(test)]
@@ -184,6 +646,26 @@ mod tests {
         assert_eq!(classify_edge_direction(-0.3 * PI), EdgeDirection::Diagonal1);
     }
 
+    #[test]
+    fn test_tile_edge_offsets() {
+        // Left tile: vertical edge in column 2; right tile: none
+        let (width, height) = (16, 8);
+        let angles = vec![0.0; (width * height) as usize];
+        let valid: Vec<bool> = (0..width * height).map(|i| i % width == 2).collect();
+        let edges = detect_edges_tiled(&angles, &valid, width, height, 2).unwrap();
+        assert_eq!(edges, [EdgeDirection::Vertical, EdgeDirection::None]);
+
+        let offsets = tile_edge_offsets(&angles, &valid, width, (8, 8), &edges);
+        assert_eq!(offsets, [(-2, 0), (0, 0)]);
+
+        // A centered edge needs no shift
+        let valid: Vec<bool> = (0..width * height)
+            .map(|i| i % width == 3 || i % width == 4)
+            .collect();
+        let offsets = tile_edge_offsets(&angles, &valid, width, (8, 8), &edges);
+        assert_eq!(offsets[0], (0, 0));
+    }
+
     #[test]
     fn test_detect_edges_tiled_all_none() {
         // Image with no valid edges
@@ -192,7 +674,7 @@ mod tests {
         let angles = vec![0.0; (width * height) as usize];
         let valid = vec![false; (width * height) as usize];
 
-        let edges = detect_edges_tiled(&angles, &valid, width, height, 8);
+        let edges = detect_edges_tiled(&angles, &valid, width, height, 8).unwrap();
 
         // Should be 8×8 tiles
         assert_eq!(edges.len(), 8 * 8);
@@ -211,7 +693,7 @@ mod tests {
         let angles = vec![0.0; (width * height) as usize];
         let valid = vec![true; (width * height) as usize];
 
-        let edges = detect_edges_tiled(&angles, &valid, width, height, 8);
+        let edges = detect_edges_tiled(&angles, &valid, width, height, 8).unwrap();
 
         // Should detect vertical edges in all tiles
         for edge in edges {
@@ -232,17 +714,124 @@ mod tests {
             valid[i] = true;
         }
 
-        let edges = detect_edges_tiled(&angles, &valid, width, height, 8);
+        let edges = detect_edges_tiled(&angles, &valid, width, height, 8).unwrap();
 
         // First tile should be None (7 < 8 threshold)
         assert_eq!(edges[0], EdgeDirection::None);
     }
 
     #[test]
-    #[should_panic(expected = "must be multiples of 8")]
+    fn test_detect_edges_tiled_adaptive() {
+        let width = 64;
+        let height = 64;
+        let angles = vec![0.0; (width * height) as usize];
+        let mut valid = vec![false; (width * height) as usize];
+
+        // 7 vertical edge pixels in each of the first two tiles
+        for i in (0..7).chain(8..15) {
+            valid[i] = true;
+        }
+
+        let mut thresholds = vec![8; 64];
+        thresholds[1] = 7;
+        let edges =
+            detect_edges_tiled_adaptive(&angles, &valid, width, height, &thresholds, None).unwrap();
+
+        assert_eq!(edges[0], EdgeDirection::None);
+        assert_eq!(edges[1], EdgeDirection::Vertical);
+    }
+
+    #[test]
+    fn test_detect_edges_tiled_rectangular() {
+        let (width, height) = (16, 32);
+        let angles = vec![0.0; (width * height) as usize];
+        // Vertical edge pixels in the left half of the top 8×16 tile only
+        let valid: Vec<bool> = (0..width * height)
+            .map(|i| i % width < 4 && i / width < 16)
+            .collect();
+
+        let edges =
+            detect_edges_tiled_sized(&angles, &valid, width, height, (8, 16), &[64; 4], None)
+                .unwrap();
+        assert_eq!(
+            edges,
+            vec![
+                EdgeDirection::Vertical,
+                EdgeDirection::None,
+                EdgeDirection::None,
+                EdgeDirection::None
+            ]
+        );
+
+        let err = detect_edges_tiled_sized(&angles, &valid, width, height, (8, 16), &[8], None);
+        assert!(matches!(err, Err(AsciiError::DimensionError(_))));
+    }
+
+    #[test]
+    fn test_resolve_tie_prefers_bucket_order() {
+        // Exact tie between vertical and diagonal1: vertical comes first
+        assert_eq!(
+            resolve_edge_direction(&[10, 0, 10, 0], 8, None),
+            EdgeDirection::Vertical
+        );
+        // Exact tie between the two diagonals: diagonal1 comes first
+        assert_eq!(
+            resolve_edge_direction(&[0, 0, 12, 12], 8, None),
+            EdgeDirection::Diagonal1
+        );
+    }
+
+    #[test]
+    fn test_resolve_junctions() {
+        // Vertical and horizontal within margin -> cross
+        assert_eq!(
+            resolve_edge_direction(&[12, 10, 0, 0], 8, Some(2)),
+            EdgeDirection::Cross
+        );
+        // Both diagonals within margin -> diagonal cross
+        assert_eq!(
+            resolve_edge_direction(&[0, 0, 9, 11], 8, Some(2)),
+            EdgeDirection::DiagonalCross
+        );
+        // Gap larger than margin -> plain winner
+        assert_eq!(
+            resolve_edge_direction(&[15, 10, 0, 0], 8, Some(2)),
+            EdgeDirection::Vertical
+        );
+        // Runner-up below edge threshold -> plain winner
+        assert_eq!(
+            resolve_edge_direction(&[8, 7, 0, 0], 8, Some(2)),
+            EdgeDirection::Vertical
+        );
+        // Non-perpendicular pair never forms a junction
+        assert_eq!(
+            resolve_edge_direction(&[10, 0, 10, 0], 8, Some(2)),
+            EdgeDirection::Vertical
+        );
+    }
+
+    #[test]
+    fn test_resolve_below_threshold() {
+        assert_eq!(
+            resolve_edge_direction(&[7, 7, 0, 0], 8, Some(64)),
+            EdgeDirection::None
+        );
+        assert_eq!(
+            resolve_edge_direction(&[0, 0, 0, 0], 0, None),
+            EdgeDirection::None
+        );
+    }
+
+    #[test]
     fn test_detect_edges_invalid_dimensions() {
         let angles = vec![0.0; 100];
         let valid = vec![false; 100];
-        detect_edges_tiled(&angles, &valid, 10, 10, 8); // Not multiples of 8
+        // Not multiples of 8
+        let err = detect_edges_tiled(&angles, &valid, 10, 10, 8).unwrap_err();
+        assert!(err.to_string().contains("must be multiples of 8"));
+
+        // Mask of the wrong size
+        let err = detect_edges_tiled(&angles, &valid[..99], 10, 10, 8).unwrap_err();
+        assert!(matches!(err, AsciiError::DimensionError(_)));
     }
 }
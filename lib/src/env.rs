@@ -0,0 +1,178 @@
+//! `ASCII_RENDR_*` environment variable overrides
+//!
+//! Each config field can be set with an upper-cased, prefixed variable, e.g.
+//! `ASCII_RENDR_SIGMA=1.5` or `ASCII_RENDR_ASCII_COLOR=#00ff00`. Values use the
+//! text forms accepted by [`ParamInfo::parse_value`](crate::params::ParamInfo::parse_value).
+//!
+//! Environment overrides are meant to sit under explicitly supplied settings:
+//! start from [`AsciiConfig::from_env`] and apply explicit values on top.
+
+use crate::config::AsciiConfig;
+use crate::error::AsciiError;
+use crate::params::PARAMS;
+use std::ffi::OsString;
+
+/// Prefix shared by all override variables
+pub const ENV_PREFIX: &str = "ASCII_RENDR_";
+
+impl AsciiConfig {
+    /// Default configuration with `ASCII_RENDR_*` overrides applied
+    pub fn from_env() -> Result<Self, AsciiError> {
+        let mut config = Self::default();
+        config.apply_env_overrides()?;
+        Ok(config)
+    }
+
+    /// Applies `ASCII_RENDR_*` overrides from the process environment
+    ///
+    /// Unrelated variables are skipped even if they are not valid UTF-8; a
+    /// prefixed variable whose name or value is not UTF-8 is an error.
+    ///
+    /// # Returns
+    /// Names of the fields that were overridden
+    pub fn apply_env_overrides(&mut self) -> Result<Vec<&'static str>, AsciiError> {
+        self.apply_env_overrides_from(prefixed_vars(std::env::vars_os())?)
+    }
+
+    /// Applies `ASCII_RENDR_*` overrides from an explicit set of variables
+    ///
+    /// Variables without the prefix are ignored. A prefixed variable that does
+    /// not name a config field is an error, so typos don't pass silently. The
+    /// config is left unchanged if any override fails.
+    ///
+    /// # Returns
+    /// Names of the fields that were overridden
+    pub fn apply_env_overrides_from<I, K, V>(
+        &mut self,
+        vars: I,
+    ) -> Result<Vec<&'static str>, AsciiError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut updated = self.clone();
+        let mut applied = Vec::new();
+
+        for (key, value) in vars {
+            let key = key.as_ref();
+            let Some(field) = key.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+
+            let info = PARAMS
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(field))
+                .ok_or_else(|| AsciiError::ParseError(format!("{}: no such config field", key)))?;
+
+            let invalid = |e| AsciiError::invalid_config(info.name, format!("{}: {}", key, e));
+            let value = info.parse_value(value.as_ref()).map_err(invalid)?;
+            updated.set_param(info.name, value).map_err(invalid)?;
+            applied.push(info.name);
+        }
+
+        updated.validate()?;
+        *self = updated;
+        Ok(applied)
+    }
+}
+
+/// Prefixed variables as UTF-8 strings; other variables are skipped unread
+fn prefixed_vars(
+    vars: impl IntoIterator<Item = (OsString, OsString)>,
+) -> Result<Vec<(String, String)>, AsciiError> {
+    let mut prefixed = Vec::new();
+    for (key, value) in vars {
+        if !key.as_encoded_bytes().starts_with(ENV_PREFIX.as_bytes()) {
+            continue;
+        }
+        let name = key.to_string_lossy().into_owned();
+        let key = key
+            .into_string()
+            .map_err(|_| AsciiError::ParseError(format!("{}: name is not valid UTF-8", name)))?;
+        let value = value
+            .into_string()
+            .map_err(|_| AsciiError::ParseError(format!("{}: value is not valid UTF-8", key)))?;
+        prefixed.push((key, value));
+    }
+    Ok(prefixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_env_overrides() {
+        let mut config = AsciiConfig::default();
+        let applied = config
+            .apply_env_overrides_from([
+                ("ASCII_RENDR_SIGMA", "1.5"),
+                ("ASCII_RENDR_DRAW_FILL", "false"),
+                ("ASCII_RENDR_ASCII_COLOR", "#00ff00"),
+                ("PATH", "/usr/bin"),
+            ])
+            .unwrap();
+
+        assert_eq!(applied, vec!["sigma", "draw_fill", "ascii_color"]);
+        assert_eq!(config.sigma, 1.5);
+        assert!(!config.draw_fill);
+        assert_eq!(config.ascii_color, [0, 255, 0]);
+    }
+
+    #[test]
+    fn test_unknown_env_field_is_error() {
+        let mut config = AsciiConfig::default();
+        let err = config
+            .apply_env_overrides_from([("ASCII_RENDR_SIGMAA", "1.0")])
+            .unwrap_err();
+        assert!(matches!(err, AsciiError::ParseError(_)));
+        assert!(err.to_string().contains("ASCII_RENDR_SIGMAA"));
+    }
+
+    #[test]
+    fn test_failed_override_leaves_config_unchanged() {
+        let mut config = AsciiConfig::default();
+        let result = config.apply_env_overrides_from([
+            ("ASCII_RENDR_SIGMA", "1.5"),
+            ("ASCII_RENDR_KERNEL_SIZE", "99"),
+        ]);
+        assert!(matches!(
+            result,
+            Err(AsciiError::InvalidConfig {
+                field: "kernel_size",
+                ..
+            })
+        ));
+        assert_eq!(config.sigma, AsciiConfig::default().sigma);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_variables() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let os = |s: &str| OsString::from(s);
+        let invalid = |prefix: &str| {
+            let mut bytes = prefix.as_bytes().to_vec();
+            bytes.push(0xff);
+            OsString::from_vec(bytes)
+        };
+
+        // Unrelated variables are skipped whatever they contain
+        let vars = prefixed_vars([
+            (invalid("OTHER_"), os("1")),
+            (os("OTHER"), invalid("")),
+            (os("ASCII_RENDR_SIGMA"), os("1.5")),
+        ])
+        .unwrap();
+        assert_eq!(
+            vars,
+            vec![("ASCII_RENDR_SIGMA".to_string(), "1.5".to_string())]
+        );
+
+        assert!(prefixed_vars([(invalid("ASCII_RENDR_"), os("1"))]).is_err());
+        let err = prefixed_vars([(os("ASCII_RENDR_SIGMA"), invalid("1"))]).unwrap_err();
+        assert!(err.to_string().contains("ASCII_RENDR_SIGMA"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Error type of the conversion API
+//!
+//! The `process_*` functions panic on invalid input, which suits one-off tools.
+//! Services and GUIs that take settings or images from users should call the
+//! `try_*` variants (such as [`try_process_image`](crate::try_process_image))
+//! and handle an [`AsciiError`] instead.
+
+use std::fmt;
+
+/// Why a conversion could not run
+#[derive(Debug, Clone, PartialEq)]
+pub enum AsciiError {
+    /// A configuration value is out of range
+    InvalidConfig {
+        /// Name of the offending field, as in [`params`](crate::params)
+        field: &'static str,
+        /// Human-readable description including the allowed range
+        message: String,
+    },
+    /// Buffers whose sizes do not match the image dimensions, or dimensions
+    /// that are not multiples of the tile size where the tile grid requires them
+    DimensionError(String),
+    /// The image is smaller than one tile, so there is nothing to convert
+    ImageTooSmall {
+        width: u32,
+        height: u32,
+        /// Width and height of the tile the image has to cover
+        tile_size: (u32, u32),
+    },
+    /// The conversion was stopped through a
+    /// [`CancelToken`](crate::progress::CancelToken)
+    Cancelled,
+    /// Settings that could not be read or parsed: a config file, an
+    /// environment variable or an unknown parameter name
+    ParseError(String),
+}
+
+impl AsciiError {
+    /// Shorthand for [`AsciiError::InvalidConfig`]
+    pub(crate) fn invalid_config(field: &'static str, message: String) -> Self {
+        AsciiError::InvalidConfig { field, message }
+    }
+
+    /// Prefixes the message with where the error came from, e.g. a file path
+    pub(crate) fn context(self, context: impl fmt::Display) -> Self {
+        match self {
+            AsciiError::InvalidConfig { field, message } => AsciiError::InvalidConfig {
+                field,
+                message: format!("{}: {}", context, message),
+            },
+            AsciiError::DimensionError(message) => {
+                AsciiError::DimensionError(format!("{}: {}", context, message))
+            }
+            AsciiError::ParseError(message) => {
+                AsciiError::ParseError(format!("{}: {}", context, message))
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for AsciiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsciiError::InvalidConfig { message, .. } => f.write_str(message),
+            AsciiError::DimensionError(message) => f.write_str(message),
+            AsciiError::ImageTooSmall {
+                width,
+                height,
+                tile_size,
+            } => write!(
+                f,
+                "image is {}×{}, at least {}×{} pixels are needed",
+                width, height, tile_size.0, tile_size.1
+            ),
+            AsciiError::Cancelled => f.write_str("conversion cancelled"),
+            AsciiError::ParseError(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AsciiError {}
+
+/// Fails with [`AsciiError::ImageTooSmall`] unless the image covers a tile
+pub(crate) fn check_image_size(
+    width: u32,
+    height: u32,
+    tile_size: (u32, u32),
+) -> Result<(), AsciiError> {
+    if width < tile_size.0 || height < tile_size.1 {
+        return Err(AsciiError::ImageTooSmall {
+            width,
+            height,
+            tile_size,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiConfig;
+
+    #[test]
+    fn test_errors() {
+        let config = AsciiConfig {
+            sigma: 9.0,
+            ..AsciiConfig::default()
+        };
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            err,
+            AsciiError::InvalidConfig { field: "sigma", .. }
+        ));
+        assert_eq!(err.to_string(), "sigma must be between 0.0 and 5.0, got 9");
+
+        assert_eq!(
+            check_image_size(7, 100, (8, 8)),
+            Err(AsciiError::ImageTooSmall {
+                width: 7,
+                height: 100,
+                tile_size: (8, 8)
+            })
+        );
+        assert!(check_image_size(8, 8, (8, 8)).is_ok());
+        assert!(check_image_size(8, 8, (16, 16)).is_err());
+        assert_eq!(
+            check_image_size(8, 8, (8, 16)).unwrap_err().to_string(),
+            "image is 8×8, at least 8×16 pixels are needed"
+        );
+    }
+}
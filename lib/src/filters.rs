@@ -1,4 +1,8 @@
+use crate::color_vision::{linear_to_srgb, srgb_to_linear};
+use crate::config::{BlurMode, LuminanceMode, ThresholdMode};
+use crate::progress::StageRows;
 use image::{GrayImage, Luma, RgbaImage};
+use wide::{CmpGe, f32x8};
 
 /// Calculate luminance from an RGBA image using the standard formula
 ///
@@ -11,25 +15,524 @@ use image::{GrayImage, Luma, RgbaImage};
 /// # Returns
 /// Grayscale image with luminance values
 pub fn calculate_luminance(img: &RgbaImage) -> GrayImage {
+    calculate_luminance_with(img, LuminanceMode::Rec709, false)
+}
+
+/// [`calculate_luminance`] into an existing buffer, resized as needed
+pub fn calculate_luminance_into(img: &RgbaImage, output: &mut GrayImage) {
+    calculate_luminance_with_into(img, LuminanceMode::Rec709, false, output);
+}
+
+/// Calculate luminance from an RGBA image in linear light
+///
+/// The sRGB-encoded channels are decoded to linear light, weighted with the
+/// same coefficients as [`calculate_luminance`] and encoded back to sRGB.
+/// Weighting the encoded bytes directly, as the shader does, makes saturated
+/// colors and mid-tones come out darker than they look, shifting which fill
+/// characters they get.
+///
+/// # Arguments
+/// * `img` - Input RGBA image
+///
+/// # Returns
+/// Grayscale image with sRGB-encoded luminance values
+pub fn calculate_luminance_linear(img: &RgbaImage) -> GrayImage {
+    calculate_luminance_with(img, LuminanceMode::Rec709, true)
+}
+
+/// [`calculate_luminance_linear`] into an existing buffer, resized as needed
+pub fn calculate_luminance_linear_into(img: &RgbaImage, output: &mut GrayImage) {
+    calculate_luminance_with_into(img, LuminanceMode::Rec709, true, output);
+}
+
+/// Calculate luminance from an RGBA image with any formula
+///
+/// # Arguments
+/// * `img` - Input RGBA image
+/// * `mode` - Channel weighting, see [`LuminanceMode`]
+/// * `linear` - Weight in linear light like [`calculate_luminance_linear`]
+///   instead of on the sRGB-encoded bytes
+///
+/// # Returns
+/// Grayscale image with luminance values
+pub fn calculate_luminance_with(img: &RgbaImage, mode: LuminanceMode, linear: bool) -> GrayImage {
+    let mut output = GrayImage::new(0, 0);
+    calculate_luminance_with_into(img, mode, linear, &mut output);
+    output
+}
+
+/// [`calculate_luminance_with`] into an existing buffer, resized as needed
+pub fn calculate_luminance_with_into(
+    img: &RgbaImage,
+    mode: LuminanceMode,
+    linear: bool,
+    output: &mut GrayImage,
+) {
     let (width, height) = img.dimensions();
-    let mut output = GrayImage::new(width, height);
+    reuse(output, width, height);
+
+    // Channel values in [0, 1], decoded to linear light if requested
+    let decode: Vec<f32> = if linear {
+        (0..=255u8).map(srgb_to_linear).collect()
+    } else {
+        (0..=255u8).map(|v| v as f32 / 255.0).collect()
+    };
+    let weights = mode.weights();
+
+    // Eight pixels at a time; the table lookups stay scalar, the weighting
+    // runs in SIMD lanes with the same operation order as one pixel at a time
+    for (lums, pixels) in output.chunks_mut(LANES).zip(img.as_raw().chunks(LANES * 4)) {
+        let (mut r, mut g, mut b) = ([0.0; LANES], [0.0; LANES], [0.0; LANES]);
+        for (i, pixel) in pixels.chunks_exact(4).enumerate() {
+            r[i] = decode[pixel[0] as usize];
+            g[i] = decode[pixel[1] as usize];
+            b[i] = decode[pixel[2] as usize];
+        }
+        let (r, g, b) = (f32x8::new(r), f32x8::new(g), f32x8::new(b));
+
+        let luminance = match weights {
+            Some([wr, wg, wb]) => {
+                f32x8::splat(wr) * r + f32x8::splat(wg) * g + f32x8::splat(wb) * b
+            }
+            None => r.max(g).max(b),
+        };
+
+        // Clamp to [0, 1] and convert to u8
+        let levels = if linear {
+            luminance
+        } else {
+            luminance.max(f32x8::ZERO).min(f32x8::ONE) * f32x8::splat(255.0)
+        };
+        for (lum, level) in lums.iter_mut().zip(levels.to_array()) {
+            *lum = if linear {
+                linear_to_srgb(level)
+            } else {
+                level as u8
+            };
+        }
+    }
+}
+
+/// Extract one color channel of an RGBA image as a grayscale image
+///
+/// # Arguments
+/// * `img` - Input RGBA image
+/// * `channel` - Channel index (0 = red, 1 = green, 2 = blue, 3 = alpha)
+///
+/// # Returns
+/// Grayscale image with the channel's values
+pub fn extract_channel(img: &RgbaImage, channel: usize) -> GrayImage {
+    GrayImage::from_fn(img.width(), img.height(), |x, y| {
+        Luma([img.get_pixel(x, y)[channel]])
+    })
+}
+
+/// Median luminance above which an image counts as predominantly bright
+pub const BRIGHT_MEDIAN_THRESHOLD: u8 = 153;
+
+/// Count pixels per luminance value
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+///
+/// # Returns
+/// Number of pixels for each of the 256 luminance values
+pub fn luminance_histogram(img: &GrayImage) -> [u32; 256] {
+    let mut histogram = [0u32; 256];
+    for &value in img.as_raw() {
+        histogram[value as usize] += 1;
+    }
+    histogram
+}
+
+/// Check whether an image is predominantly bright (documents, whiteboards)
+///
+/// An image is bright when its median luminance is above
+/// [`BRIGHT_MEDIAN_THRESHOLD`] (60% gray), i.e. more than half of its pixels
+/// are light.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+///
+/// # Returns
+/// true if the image is predominantly bright
+pub fn is_predominantly_bright(img: &GrayImage) -> bool {
+    let histogram = luminance_histogram(img);
+    let total: u64 = histogram.iter().map(|&count| count as u64).sum();
+    let bright: u64 = histogram[BRIGHT_MEDIAN_THRESHOLD as usize + 1..]
+        .iter()
+        .map(|&count| count as u64)
+        .sum();
+    bright * 2 > total
+}
+
+/// Apply an edge-preserving bilateral filter
+///
+/// Each pixel becomes the average of its neighbors within
+/// `ceil(2 * sigma_space)` pixels, weighted both by distance (Gaussian with
+/// `sigma_space`) and by luminance difference (Gaussian with `sigma_range`, in
+/// [0, 1] units). Noise within a region is smoothed while pixels across a
+/// strong edge barely contribute, so the edge stays sharp for the DoG.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+/// * `sigma_space` - Spatial standard deviation in pixels
+/// * `sigma_range` - Luminance standard deviation (0-1)
+///
+/// # Returns
+/// Filtered image
+pub fn bilateral_filter(img: &GrayImage, sigma_space: f32, sigma_range: f32) -> GrayImage {
+    let mut output = GrayImage::new(0, 0);
+    bilateral_filter_into(img, sigma_space, sigma_range, &mut output);
+    output
+}
+
+/// [`bilateral_filter`] into an existing buffer, resized as needed
+pub fn bilateral_filter_into(
+    img: &GrayImage,
+    sigma_space: f32,
+    sigma_range: f32,
+    output: &mut GrayImage,
+) {
+    let (width, height) = img.dimensions();
+    reuse(output, width, height);
+    let radius = (2.0 * sigma_space).ceil() as i32;
+
+    // Weights by squared distance and by luminance difference (0-255)
+    let spatial: Vec<f32> = (0..=2 * radius * radius)
+        .map(|d2| (-(d2 as f32) / (2.0 * sigma_space * sigma_space)).exp())
+        .collect();
+    let range: Vec<f32> = (0..256)
+        .map(|d| {
+            let d = d as f32 / 255.0;
+            (-d * d / (2.0 * sigma_range * sigma_range)).exp()
+        })
+        .collect();
 
     for y in 0..height {
         for x in 0..width {
-            let pixel = img.get_pixel(x, y);
-            let r = pixel[0] as f32 / 255.0;
-            let g = pixel[1] as f32 / 255.0;
-            let b = pixel[2] as f32 / 255.0;
+            let center = img.get_pixel(x, y)[0];
+            let mut sum = 0.0;
+            let mut weight_sum = 0.0;
+
+            for dy in -radius..=radius {
+                let sample_y = (y as i32 + dy).clamp(0, height as i32 - 1) as u32;
+                for dx in -radius..=radius {
+                    let sample_x = (x as i32 + dx).clamp(0, width as i32 - 1) as u32;
+                    let sample = img.get_pixel(sample_x, sample_y)[0];
+                    let weight = spatial[(dx * dx + dy * dy) as usize]
+                        * range[sample.abs_diff(center) as usize];
 
-            // Standard luminance coefficients
-            let luminance = 0.2127 * r + 0.7152 * g + 0.0722 * b;
+                    sum += sample as f32 * weight;
+                    weight_sum += weight;
+                }
+            }
 
-            // Clamp to [0, 1] and convert to u8
-            let lum_u8 = (luminance.clamp(0.0, 1.0) * 255.0) as u8;
-            output.put_pixel(x, y, Luma([lum_u8]));
+            // The center pixel always has weight 1, so weight_sum > 0
+            output.put_pixel(x, y, Luma([(sum / weight_sum).round() as u8]));
         }
     }
+}
 
+/// Apply a median filter
+///
+/// Each pixel becomes the median of the `(2 * radius + 1)²` pixels around it.
+/// Isolated outliers (salt-and-pepper noise, dead or hot sensor pixels)
+/// disappear entirely instead of being smeared into blobs as a blur would,
+/// and straight edges are kept.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+/// * `radius` - Window radius in pixels
+///
+/// # Returns
+/// Filtered image
+pub fn median_filter(img: &GrayImage, radius: u32) -> GrayImage {
+    let mut output = GrayImage::new(0, 0);
+    median_filter_into(img, radius, &mut output);
+    output
+}
+
+/// [`median_filter`] into an existing buffer, resized as needed
+pub fn median_filter_into(img: &GrayImage, radius: u32, output: &mut GrayImage) {
+    let (width, height) = img.dimensions();
+    reuse(output, width, height);
+    let r = radius as i32;
+    let mut window = Vec::with_capacity(((2 * r + 1) * (2 * r + 1)) as usize);
+
+    for y in 0..height {
+        for x in 0..width {
+            window.clear();
+            for dy in -r..=r {
+                let sample_y = (y as i32 + dy).clamp(0, height as i32 - 1) as u32;
+                for dx in -r..=r {
+                    let sample_x = (x as i32 + dx).clamp(0, width as i32 - 1) as u32;
+                    window.push(img.get_pixel(sample_x, sample_y)[0]);
+                }
+            }
+
+            let middle = window.len() / 2;
+            let (_, &mut median, _) = window.select_nth_unstable(middle);
+            output.put_pixel(x, y, Luma([median]));
+        }
+    }
+}
+
+/// Sharpness of the generalized Kuwahara sector weighting: higher values
+/// follow the most uniform sector more strictly
+const KUWAHARA_SHARPNESS: f32 = 8.0;
+
+/// Apply a generalized Kuwahara filter
+///
+/// The disc of `radius` pixels around each pixel is split into `sectors`
+/// wedges. Each wedge gets a Gaussian-weighted mean and standard deviation,
+/// and the output is the average of the means weighted by
+/// `1 / (1 + std_dev^8)` (Papari et al.), so the most uniform wedges dominate.
+/// Texture flattens into patches while borders between regions stay crisp,
+/// the painterly look of Acerola's Kuwahara shaders.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+/// * `radius` - Disc radius in pixels
+/// * `sectors` - Number of wedges (8 in the shaders)
+///
+/// # Returns
+/// Filtered image
+pub fn kuwahara_filter(img: &GrayImage, radius: u32, sectors: u32) -> GrayImage {
+    let mut output = GrayImage::new(0, 0);
+    kuwahara_filter_into(img, radius, sectors, &mut output);
+    output
+}
+
+/// [`kuwahara_filter`] into an existing buffer, resized as needed
+pub fn kuwahara_filter_into(img: &GrayImage, radius: u32, sectors: u32, output: &mut GrayImage) {
+    let (width, height) = img.dimensions();
+    reuse(output, width, height);
+    let r = radius as i32;
+    let sectors = sectors.max(1) as usize;
+
+    // Offsets inside the disc with their sector and Gaussian falloff; the
+    // center belongs to every sector
+    let sigma = radius as f32 / 2.0;
+    let sector_angle = std::f32::consts::TAU / sectors as f32;
+    let mut taps = Vec::new();
+    for dy in -r..=r {
+        for dx in -r..=r {
+            if dx * dx + dy * dy > r * r || (dx, dy) == (0, 0) {
+                continue;
+            }
+            let angle = (dy as f32).atan2(dx as f32) + std::f32::consts::PI;
+            let sector = ((angle / sector_angle) as usize).min(sectors - 1);
+            let weight = (-((dx * dx + dy * dy) as f32) / (2.0 * sigma * sigma)).exp();
+            taps.push((dx, dy, sector, weight));
+        }
+    }
+
+    // Weighted sum, sum of squares and weight per sector
+    let mut moments = vec![(0.0f32, 0.0f32, 0.0f32); sectors];
+    for y in 0..height {
+        for x in 0..width {
+            let center = img.get_pixel(x, y)[0] as f32;
+            moments.fill((center, center * center, 1.0));
+
+            for &(dx, dy, sector, weight) in &taps {
+                let sample_x = (x as i32 + dx).clamp(0, width as i32 - 1) as u32;
+                let sample_y = (y as i32 + dy).clamp(0, height as i32 - 1) as u32;
+                let sample = img.get_pixel(sample_x, sample_y)[0] as f32;
+
+                let (sum, sum_sq, weight_sum) = &mut moments[sector];
+                *sum += sample * weight;
+                *sum_sq += sample * sample * weight;
+                *weight_sum += weight;
+            }
+
+            let mut total = 0.0;
+            let mut total_weight = 0.0;
+            for &(sum, sum_sq, weight_sum) in &moments {
+                let mean = sum / weight_sum;
+                let std_dev = (sum_sq / weight_sum - mean * mean).max(0.0).sqrt();
+                let weight = 1.0 / (1.0 + std_dev.powf(KUWAHARA_SHARPNESS));
+                total += mean * weight;
+                total_weight += weight;
+            }
+
+            let result = (total / total_weight).round().clamp(0.0, 255.0);
+            output.put_pixel(x, y, Luma([result as u8]));
+        }
+    }
+}
+
+/// Fraction of pixels [`auto_contrast`] lets clip at each end of the range
+pub const AUTO_CONTRAST_CLIP: f32 = 0.01;
+
+/// Stretch an image's luminance range to full black to white
+///
+/// The darkest and brightest [`AUTO_CONTRAST_CLIP`] of the pixels are clipped,
+/// so a few outliers don't keep the rest of the image compressed. Images with
+/// a single luminance value are returned unchanged.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+///
+/// # Returns
+/// Contrast-stretched image
+pub fn auto_contrast(img: &GrayImage) -> GrayImage {
+    let histogram = luminance_histogram(img);
+    let total: u64 = histogram.iter().map(|&count| count as u64).sum();
+    let clip = (total as f32 * AUTO_CONTRAST_CLIP) as u64;
+
+    // First values whose cumulative count from either end exceeds the clip
+    let mut seen = 0u64;
+    let low = (0..256).find(|&v| {
+        seen += histogram[v] as u64;
+        seen > clip
+    });
+    seen = 0;
+    let high = (0..256).rev().find(|&v| {
+        seen += histogram[v] as u64;
+        seen > clip
+    });
+
+    match (low, high) {
+        (Some(low), Some(high)) if high > low => {
+            let scale = 255.0 / (high - low) as f32;
+            map_luminance(img, |v| {
+                ((v as f32 - low as f32) * scale).round().clamp(0.0, 255.0) as u8
+            })
+        }
+        _ => img.clone(),
+    }
+}
+
+/// Equalize an image's luminance histogram
+///
+/// Luminance values are remapped through the cumulative histogram, so each
+/// output level is about equally common and the levels a low-contrast photo
+/// crowds together are spread over the whole range.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+///
+/// # Returns
+/// Equalized image
+pub fn equalize_histogram(img: &GrayImage) -> GrayImage {
+    let lut = equalization_lut(&luminance_histogram(img), None);
+    map_luminance(img, |v| lut[v as usize])
+}
+
+/// Apply contrast-limited adaptive histogram equalization (CLAHE)
+///
+/// The image is divided into a `grid`×`grid` arrangement of regions, each
+/// equalized on its own with histogram bins clipped at `clip_limit` times the
+/// average bin count (the excess is spread over all bins) to keep noise in
+/// flat regions from being amplified. Pixels blend the mappings of the four
+/// nearest region centers, so no seams appear between regions.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+/// * `grid` - Regions per side
+/// * `clip_limit` - Maximum bin height as a multiple of the average
+///
+/// # Returns
+/// Equalized image
+pub fn clahe(img: &GrayImage, grid: u32, clip_limit: f32) -> GrayImage {
+    let (width, height) = img.dimensions();
+    if width == 0 || height == 0 {
+        return img.clone();
+    }
+    let region_w = width.div_ceil(grid.clamp(1, width));
+    let region_h = height.div_ceil(grid.clamp(1, height));
+    let (grid_x, grid_y) = (width.div_ceil(region_w), height.div_ceil(region_h));
+
+    // Clipped equalization mapping of every region
+    let luts: Vec<[u8; 256]> = (0..grid_x * grid_y)
+        .map(|region| {
+            let (rx, ry) = (region % grid_x, region / grid_x);
+            let mut histogram = [0u32; 256];
+            for y in ry * region_h..((ry + 1) * region_h).min(height) {
+                for x in rx * region_w..((rx + 1) * region_w).min(width) {
+                    histogram[img.get_pixel(x, y)[0] as usize] += 1;
+                }
+            }
+            let pixels: u32 = histogram.iter().sum();
+            let limit = ((clip_limit * pixels as f32 / 256.0) as u32).max(1);
+            equalization_lut(&histogram, Some(limit))
+        })
+        .collect();
+
+    // Position between the neighboring region centers along one axis
+    let neighbors = |pos: u32, size: u32, count: u32| {
+        let f = ((pos as f32 + 0.5) / size as f32 - 0.5).max(0.0);
+        let first = (f as u32).min(count - 1);
+        let second = (first + 1).min(count - 1);
+        (first, second, (f - first as f32).min(1.0))
+    };
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let v = img.get_pixel(x, y)[0] as usize;
+        let (x0, x1, fx) = neighbors(x, region_w, grid_x);
+        let (y0, y1, fy) = neighbors(y, region_h, grid_y);
+        let at = |rx: u32, ry: u32| luts[(ry * grid_x + rx) as usize][v] as f32;
+
+        let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+        let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+        Luma([(top * (1.0 - fy) + bottom * fy).round() as u8])
+    })
+}
+
+/// Apply exposure and attenuation to an image's luminance
+///
+/// Each value becomes `saturate(pow(v * exposure, attenuation))` on the 0-1
+/// scale, matching the `_Exposure` and `_Attenuation` settings of
+/// AcerolaFX_ASCII.fx. Exposure above 1 brightens and clips the highlights;
+/// attenuation above 1 darkens the mid-tones.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+/// * `exposure` - Brightness multiplier
+/// * `attenuation` - Exponent applied after the exposure
+///
+/// # Returns
+/// Exposed image
+pub fn expose(img: &GrayImage, exposure: f32, attenuation: f32) -> GrayImage {
+    let lut: [u8; 256] = std::array::from_fn(|v| {
+        let exposed = (v as f32 / 255.0 * exposure).max(0.0).powf(attenuation);
+        (exposed.clamp(0.0, 1.0) * 255.0).round() as u8
+    });
+    map_luminance(img, |v| lut[v as usize])
+}
+
+/// Mapping of each luminance value through the cumulative histogram, with
+/// bins optionally clipped at `limit` and the excess spread evenly
+fn equalization_lut(histogram: &[u32; 256], limit: Option<u32>) -> [u8; 256] {
+    let mut bins = histogram.map(|count| count as f32);
+    if let Some(limit) = limit {
+        let limit = limit as f32;
+        let excess: f32 = bins.iter().map(|&count| (count - limit).max(0.0)).sum();
+        for count in &mut bins {
+            *count = count.min(limit) + excess / 256.0;
+        }
+    }
+
+    let total: f32 = bins.iter().sum();
+    let mut lut = [0u8; 256];
+    if total == 0.0 {
+        return lut;
+    }
+    let mut cumulative = 0.0;
+    for (value, &count) in bins.iter().enumerate() {
+        cumulative += count;
+        lut[value] = (cumulative / total * 255.0).round() as u8;
+    }
+    lut
+}
+
+/// Remaps every pixel of `img` through `f`
+fn map_luminance(img: &GrayImage, f: impl Fn(u8) -> u8) -> GrayImage {
+    let mut output = img.clone();
+    for pixel in output.iter_mut() {
+        *pixel = f(*pixel);
+    }
     output
 }
 
@@ -51,6 +554,93 @@ pub fn gaussian(sigma: f32, pos: f32) -> f32 {
     (1.0 / (two_pi * sigma_sq).sqrt()) * (-pos * pos / (2.0 * sigma_sq)).exp()
 }
 
+/// Gaussian weights for offsets `-kernel_size..=kernel_size`
+fn gaussian_kernel(sigma: f32, kernel_size: u32) -> Vec<f32> {
+    let kernel_size = kernel_size as i32;
+    (-kernel_size..=kernel_size)
+        .map(|offset| gaussian(sigma, offset as f32))
+        .collect()
+}
+
+/// Horizontal Gaussian pass over `width`×`height` samples read through
+/// `sample(index)`, normalized to [0, 1] and kept as `f32`; stops early when
+/// `rows` is cancelled
+fn blur_h_pass(
+    width: u32,
+    height: u32,
+    sigma: f32,
+    kernel_size: u32,
+    sample: impl Fn(usize) -> f32,
+    output: &mut Vec<f32>,
+    rows: &mut StageRows,
+) {
+    let (width, height) = (width as usize, height as usize);
+    rows.begin_pass();
+    output.clear();
+    output.resize(width * height, 0.0);
+    if width == 0 {
+        return;
+    }
+    let kernel = gaussian_kernel(sigma, kernel_size);
+    let weight_sum: f32 = kernel.iter().sum();
+    let radius = kernel_size as isize;
+
+    for y in 0..height {
+        let row = y * width;
+        for x in 0..width {
+            // Convolve with horizontal Gaussian kernel
+            let mut sum = 0.0;
+            for (weight, offset) in kernel.iter().zip(-radius..=radius) {
+                let sample_x = (x as isize + offset).clamp(0, width as isize - 1) as usize;
+                sum += sample(row + sample_x) * weight;
+            }
+            output[row + x] = sum / weight_sum;
+        }
+        if !rows.row(y + 1, height) {
+            return;
+        }
+    }
+}
+
+/// Vertical Gaussian pass over `width`×`height` samples read through
+/// `sample(index)`, normalized to [0, 1] and kept as `f32`; stops early when
+/// `rows` is cancelled
+fn blur_v_pass(
+    width: u32,
+    height: u32,
+    sigma: f32,
+    kernel_size: u32,
+    sample: impl Fn(usize) -> f32,
+    output: &mut Vec<f32>,
+    rows: &mut StageRows,
+) {
+    let (width, height) = (width as usize, height as usize);
+    rows.begin_pass();
+    output.clear();
+    output.resize(width * height, 0.0);
+    if height == 0 {
+        return;
+    }
+    let kernel = gaussian_kernel(sigma, kernel_size);
+    let weight_sum: f32 = kernel.iter().sum();
+    let radius = kernel_size as isize;
+
+    for y in 0..height {
+        for x in 0..width {
+            // Convolve with vertical Gaussian kernel
+            let mut sum = 0.0;
+            for (weight, offset) in kernel.iter().zip(-radius..=radius) {
+                let sample_y = (y as isize + offset).clamp(0, height as isize - 1) as usize;
+                sum += sample(sample_y * width + x) * weight;
+            }
+            output[y * width + x] = sum / weight_sum;
+        }
+        if !rows.row(y + 1, height) {
+            return;
+        }
+    }
+}
+
 /// Apply horizontal Gaussian blur
 ///
 /// This implements the horizontal pass of the separable Gaussian blur
@@ -64,32 +654,37 @@ pub fn gaussian(sigma: f32, pos: f32) -> f32 {
 /// # Returns
 /// Horizontally blurred image
 pub fn gaussian_blur_h(img: &GrayImage, sigma: f32, kernel_size: u32) -> GrayImage {
-    let (width, height) = img.dimensions();
-    let mut output = GrayImage::new(width, height);
-    let kernel_size = kernel_size as i32;
-
-    for y in 0..height {
-        for x in 0..width {
-            let mut sum = 0.0;
-            let mut weight_sum = 0.0;
-
-            // Convolve with horizontal Gaussian kernel
-            for offset in -kernel_size..=kernel_size {
-                let sample_x = (x as i32 + offset).clamp(0, width as i32 - 1) as u32;
-                let sample = img.get_pixel(sample_x, y)[0] as f32 / 255.0;
-                let weight = gaussian(sigma, offset as f32);
-
-                sum += sample * weight;
-                weight_sum += weight;
-            }
+    let mut output = GrayImage::new(0, 0);
+    gaussian_blur_h_into(img, sigma, kernel_size, &mut output);
+    output
+}
 
-            // Normalize and convert back to u8
-            let result = (sum / weight_sum).clamp(0.0, 1.0);
-            output.put_pixel(x, y, Luma([(result * 255.0) as u8]));
-        }
-    }
+/// [`gaussian_blur_h`] into an existing buffer, resized as needed
+pub fn gaussian_blur_h_into(img: &GrayImage, sigma: f32, kernel_size: u32, output: &mut GrayImage) {
+    let mut blurred = Vec::new();
+    gaussian_blur_h_f32_into(img, sigma, kernel_size, &mut blurred);
+    quantize_into(&blurred, img.width(), img.height(), output);
+}
 
-    output
+/// [`gaussian_blur_h`] without quantizing: `output` holds one `f32` in
+/// [0, 1] per pixel, row by row
+pub fn gaussian_blur_h_f32_into(
+    img: &GrayImage,
+    sigma: f32,
+    kernel_size: u32,
+    output: &mut Vec<f32>,
+) {
+    let (width, height) = img.dimensions();
+    let raw = img.as_raw();
+    blur_h_pass(
+        width,
+        height,
+        sigma,
+        kernel_size,
+        |i| raw[i] as f32 / 255.0,
+        output,
+        &mut StageRows::unchecked(),
+    );
 }
 
 /// Apply vertical Gaussian blur
@@ -106,96 +701,736 @@ pub fn gaussian_blur_h(img: &GrayImage, sigma: f32, kernel_size: u32) -> GrayIma
 /// # Returns
 /// Vertically blurred image
 pub fn gaussian_blur_v(img: &GrayImage, sigma: f32, kernel_size: u32) -> GrayImage {
+    let mut output = GrayImage::new(0, 0);
+    gaussian_blur_v_into(img, sigma, kernel_size, &mut output);
+    output
+}
+
+/// [`gaussian_blur_v`] into an existing buffer, resized as needed
+pub fn gaussian_blur_v_into(img: &GrayImage, sigma: f32, kernel_size: u32, output: &mut GrayImage) {
     let (width, height) = img.dimensions();
-    let mut output = GrayImage::new(width, height);
-    let kernel_size = kernel_size as i32;
+    let raw = img.as_raw();
+    let mut blurred = Vec::new();
+    blur_v_pass(
+        width,
+        height,
+        sigma,
+        kernel_size,
+        |i| raw[i] as f32 / 255.0,
+        &mut blurred,
+        &mut StageRows::unchecked(),
+    );
+    quantize_into(&blurred, width, height, output);
+}
 
-    for y in 0..height {
-        for x in 0..width {
-            let mut sum = 0.0;
-            let mut weight_sum = 0.0;
+/// Vertical Gaussian pass over a `width`×`height` `f32` buffer, such as the
+/// output of [`gaussian_blur_h_f32_into`], without quantizing
+pub fn gaussian_blur_v_f32_into(
+    input: &[f32],
+    width: u32,
+    height: u32,
+    sigma: f32,
+    kernel_size: u32,
+    output: &mut Vec<f32>,
+) {
+    blur_v_pass(
+        width,
+        height,
+        sigma,
+        kernel_size,
+        |i| input[i],
+        output,
+        &mut StageRows::unchecked(),
+    );
+}
 
-            // Convolve with vertical Gaussian kernel
-            for offset in -kernel_size..=kernel_size {
-                let sample_y = (y as i32 + offset).clamp(0, height as i32 - 1) as u32;
-                let sample = img.get_pixel(x, sample_y)[0] as f32 / 255.0;
-                let weight = gaussian(sigma, offset as f32);
+/// Apply full 2D Gaussian blur (separable)
+///
+/// Both passes run in `f32`; the result is quantized to `u8` once at the end.
+///
+/// # Arguments
+/// * `img` - Input grayscale image
+/// * `sigma` - Standard deviation of the Gaussian
+/// * `kernel_size` - Radius of the kernel
+///
+/// # Returns
+/// Blurred image
+pub fn gaussian_blur(img: &GrayImage, sigma: f32, kernel_size: u32) -> GrayImage {
+    let mut output = GrayImage::new(0, 0);
+    gaussian_blur_into(img, sigma, kernel_size, &mut Vec::new(), &mut output);
+    output
+}
 
-                sum += sample * weight;
-                weight_sum += weight;
-            }
+/// [`gaussian_blur`] into an existing buffer, with `temp` holding the
+/// unquantized horizontal pass; both are resized as needed
+pub fn gaussian_blur_into(
+    img: &GrayImage,
+    sigma: f32,
+    kernel_size: u32,
+    temp: &mut Vec<f32>,
+    output: &mut GrayImage,
+) {
+    blur_into(img, sigma, kernel_size, BlurMode::Gaussian, temp, output);
+}
 
-            // Normalize and convert back to u8
-            let result = (sum / weight_sum).clamp(0.0, 1.0);
-            output.put_pixel(x, y, Luma([(result * 255.0) as u8]));
+/// [`gaussian_blur`] without quantizing: `output` holds one `f32` in [0, 1]
+/// per pixel, row by row, and `temp` the horizontal pass
+pub fn gaussian_blur_f32_into(
+    img: &GrayImage,
+    sigma: f32,
+    kernel_size: u32,
+    temp: &mut Vec<f32>,
+    output: &mut Vec<f32>,
+) {
+    gaussian_blur_f32_rows(
+        img,
+        sigma,
+        kernel_size,
+        temp,
+        output,
+        &mut StageRows::unchecked(),
+    );
+}
+
+/// [`gaussian_blur_f32_into`] checking `rows` after every row of both passes
+fn gaussian_blur_f32_rows(
+    img: &GrayImage,
+    sigma: f32,
+    kernel_size: u32,
+    temp: &mut Vec<f32>,
+    output: &mut Vec<f32>,
+    rows: &mut StageRows,
+) {
+    let (width, height) = img.dimensions();
+    let raw = img.as_raw();
+    blur_h_pass(
+        width,
+        height,
+        sigma,
+        kernel_size,
+        |i| raw[i] as f32 / 255.0,
+        temp,
+        rows,
+    );
+    blur_v_pass(width, height, sigma, kernel_size, |i| temp[i], output, rows);
+}
+
+/// [`gaussian_blur_into`] or [`box_blur_f32_into`], as `mode` says, quantized
+/// into `output`
+pub fn blur_into(
+    img: &GrayImage,
+    sigma: f32,
+    kernel_size: u32,
+    mode: BlurMode,
+    temp: &mut Vec<f32>,
+    output: &mut GrayImage,
+) {
+    blur_into_checked(
+        img,
+        sigma,
+        kernel_size,
+        mode,
+        temp,
+        output,
+        &mut StageRows::unchecked(),
+    );
+}
+
+/// [`blur_into`] checking `rows` after every row of each pass
+pub(crate) fn blur_into_checked(
+    img: &GrayImage,
+    sigma: f32,
+    kernel_size: u32,
+    mode: BlurMode,
+    temp: &mut Vec<f32>,
+    output: &mut GrayImage,
+    rows: &mut StageRows,
+) {
+    let (width, height) = img.dimensions();
+    let mut blurred = Vec::new();
+    blur_f32_into_checked(img, sigma, kernel_size, mode, temp, &mut blurred, rows);
+    quantize_into(&blurred, width, height, output);
+}
+
+/// [`gaussian_blur_f32_into`] or [`box_blur_f32_into`], as `mode` says
+pub fn blur_f32_into(
+    img: &GrayImage,
+    sigma: f32,
+    kernel_size: u32,
+    mode: BlurMode,
+    temp: &mut Vec<f32>,
+    output: &mut Vec<f32>,
+) {
+    blur_f32_into_checked(
+        img,
+        sigma,
+        kernel_size,
+        mode,
+        temp,
+        output,
+        &mut StageRows::unchecked(),
+    );
+}
+
+/// [`blur_f32_into`] checking `rows` after every row of each pass
+pub(crate) fn blur_f32_into_checked(
+    img: &GrayImage,
+    sigma: f32,
+    kernel_size: u32,
+    mode: BlurMode,
+    temp: &mut Vec<f32>,
+    output: &mut Vec<f32>,
+    rows: &mut StageRows,
+) {
+    match mode {
+        BlurMode::Gaussian => gaussian_blur_f32_rows(img, sigma, kernel_size, temp, output, rows),
+        BlurMode::Box => box_blur_f32_rows(img, sigma, temp, output, rows),
+    }
+}
+
+/// Passes of [`blur_f32_into`] in `mode`, each reporting its rows
+pub(crate) fn blur_passes(mode: BlurMode) -> usize {
+    match mode {
+        BlurMode::Gaussian => 2,
+        // Three box blurs, each a horizontal and a vertical pass
+        BlurMode::Box => 6,
+    }
+}
+
+/// Approximate a Gaussian blur of `sigma` with three iterated box blurs
+///
+/// Each box pass keeps a running sum, so the cost per pixel does not grow with
+/// the radius. The box widths come from [`box_blur_radii`]; edges are clamped
+/// like the Gaussian. `output` holds one `f32` in [0, 1] per pixel, row by
+/// row, and `temp` the last horizontal pass.
+pub fn box_blur_f32_into(img: &GrayImage, sigma: f32, temp: &mut Vec<f32>, output: &mut Vec<f32>) {
+    box_blur_f32_rows(img, sigma, temp, output, &mut StageRows::unchecked());
+}
+
+/// [`box_blur_f32_into`] checking `rows` after every line of each pass
+fn box_blur_f32_rows(
+    img: &GrayImage,
+    sigma: f32,
+    temp: &mut Vec<f32>,
+    output: &mut Vec<f32>,
+    rows: &mut StageRows,
+) {
+    let (width, height) = (img.width() as usize, img.height() as usize);
+    output.clear();
+    output.extend(img.as_raw().iter().map(|&v| v as f32 / 255.0));
+    for radius in box_blur_radii(sigma) {
+        box_pass(output, temp, width, height, radius as usize, true, rows);
+        box_pass(temp, output, width, height, radius as usize, false, rows);
+    }
+}
+
+/// Radii of the three box blurs whose combined variance best matches a
+/// Gaussian of `sigma`
+///
+/// Follows Kovesi's widths: some passes use the largest odd width below the
+/// ideal one, the rest the next odd width up. Their sum is how far the blur
+/// reaches.
+pub fn box_blur_radii(sigma: f32) -> [u32; 3] {
+    const PASSES: f32 = 3.0;
+    let variance = 12.0 * sigma * sigma;
+    let ideal = (variance / PASSES + 1.0).sqrt().floor() as u32;
+    let lower = (ideal - (ideal + 1) % 2).max(1) as f32;
+    let narrow = ((variance - PASSES * (lower * lower + 4.0 * lower + 3.0)) / (-4.0 * lower - 4.0))
+        .round()
+        .clamp(0.0, PASSES) as usize;
+    std::array::from_fn(|pass| {
+        let width = if pass < narrow { lower } else { lower + 2.0 };
+        (width as u32 - 1) / 2
+    })
+}
+
+/// One box pass of `radius` along the rows (`horizontal`) or columns of a
+/// `width`×`height` buffer, with edges clamped; stops early when `rows` is
+/// cancelled
+fn box_pass(
+    input: &[f32],
+    output: &mut Vec<f32>,
+    width: usize,
+    height: usize,
+    radius: usize,
+    horizontal: bool,
+    rows: &mut StageRows,
+) {
+    rows.begin_pass();
+    output.clear();
+    output.resize(input.len(), 0.0);
+    // Lines to run along, their length, and the index steps between and along them
+    let (lines, len, line_step, stride) = if horizontal {
+        (height, width, width, 1)
+    } else {
+        (width, height, 1, width)
+    };
+    if len == 0 {
+        return;
+    }
+    let radius = radius as isize;
+    let scale = 1.0 / (2 * radius + 1) as f64;
+
+    for line in 0..lines {
+        let start = line * line_step;
+        let at = |i: isize| input[start + i.clamp(0, len as isize - 1) as usize * stride] as f64;
+        // Running sum of the window, in f64 so long lines do not drift
+        let mut sum: f64 = (-radius..=radius).map(at).sum();
+        for i in 0..len {
+            output[start + i * stride] = (sum * scale) as f32;
+            let i = i as isize;
+            sum += at(i + radius + 1) - at(i - radius);
+        }
+        if !rows.row(line + 1, lines) {
+            return;
         }
     }
+}
 
-    output
+/// Intermediate and output buffers of [`difference_of_gaussians_into`]
+///
+/// Keeping one around between frames of the same size saves four full-size
+/// allocations per call. The blurs stay in `f32` so that differences below
+/// one 8-bit step still reach the threshold, as they do in the shader.
+#[derive(Debug, Clone)]
+pub struct DogBuffers {
+    /// Horizontal blur pass, shared by both blurs
+    pub temp: Vec<f32>,
+    /// Blur with the first sigma, one value in [0, 1] per pixel
+    pub blur1: Vec<f32>,
+    /// Blur with the second sigma, one value in [0, 1] per pixel
+    pub blur2: Vec<f32>,
+    /// Thresholded difference (0 or 255, or soft levels from XDoG)
+    pub output: GrayImage,
 }
 
-/// Apply full 2D Gaussian blur (separable)
+impl Default for DogBuffers {
+    fn default() -> Self {
+        Self {
+            temp: Vec::new(),
+            blur1: Vec::new(),
+            blur2: Vec::new(),
+            output: GrayImage::new(0, 0),
+        }
+    }
+}
+
+/// Compute Difference of Gaussians (DoG) edge detection
+///
+/// DoG = blur(sigma1) - tau * blur(sigma2)
+/// Then threshold: result >= threshold ? 1.0 : 0.0
+///
+/// This implements the core of PS_VerticalBlurAndDifference from AcerolaFX_ASCII.fx:296-317
 ///
 /// # Arguments
 /// * `img` - Input grayscale image
-/// * `sigma` - Standard deviation of the Gaussian
-/// * `kernel_size` - Radius of the kernel
+/// * `sigma1` - First Gaussian sigma (typically smaller)
+/// * `sigma2` - Second Gaussian sigma (typically larger)
+/// * `kernel_size` - Kernel radius for both blurs
+/// * `tau` - Multiplier for second blur (default 1.0)
+/// * `threshold` - Binary threshold value (default 0.005)
 ///
 /// # Returns
-/// Blurred image
-pub fn gaussian_blur(img: &GrayImage, sigma: f32, kernel_size: u32) -> GrayImage {
-    let temp = gaussian_blur_h(img, sigma, kernel_size);
-    gaussian_blur_v(&temp, sigma, kernel_size)
+/// Binary edge image (0 or 255)
+pub fn difference_of_gaussians(
+    img: &GrayImage,
+    sigma1: f32,
+    sigma2: f32,
+    kernel_size: u32,
+    tau: f32,
+    threshold: f32,
+) -> GrayImage {
+    let mut buffers = DogBuffers::default();
+    difference_of_gaussians_into(
+        img,
+        sigma1,
+        sigma2,
+        kernel_size,
+        tau,
+        threshold,
+        &mut buffers,
+    );
+    buffers.output
+}
+
+/// [`difference_of_gaussians`] into reusable buffers; the result is left in
+/// `buffers.output`
+pub fn difference_of_gaussians_into(
+    img: &GrayImage,
+    sigma1: f32,
+    sigma2: f32,
+    kernel_size: u32,
+    tau: f32,
+    threshold: f32,
+    buffers: &mut DogBuffers,
+) {
+    dog_blurs_into(
+        img,
+        sigma1,
+        sigma2,
+        kernel_size,
+        BlurMode::Gaussian,
+        buffers,
+        &mut StageRows::unchecked(),
+    );
+    let DogBuffers {
+        blur1,
+        blur2,
+        output,
+        ..
+    } = buffers;
+    dog_threshold_into(blur1, blur2, tau, threshold, output);
+}
+
+/// Lowest threshold [`difference_of_gaussians_auto_into`] picks, so that flat
+/// images, whose DoG is zero everywhere, stay free of edges
+pub const MIN_AUTO_THRESHOLD: f32 = 0.001;
+
+/// [`difference_of_gaussians_into`] with the threshold chosen by `mode`
+///
+/// With [`ThresholdMode::Fixed`] this is the plain DoG with `threshold`.
+/// Otherwise the threshold is computed from this image's DoG values with
+/// [`otsu_threshold`] or [`percentile_threshold`], and is at least
+/// [`MIN_AUTO_THRESHOLD`]. Both blurs are computed as `blur` says. The
+/// result is left in `buffers.output`.
+///
+/// # Returns
+/// The threshold that was applied
+#[allow(clippy::too_many_arguments)]
+pub fn difference_of_gaussians_auto_into(
+    img: &GrayImage,
+    sigma1: f32,
+    sigma2: f32,
+    kernel_size: u32,
+    blur: BlurMode,
+    tau: f32,
+    threshold: f32,
+    mode: ThresholdMode,
+    buffers: &mut DogBuffers,
+) -> f32 {
+    difference_of_gaussians_auto_into_checked(
+        img,
+        sigma1,
+        sigma2,
+        kernel_size,
+        blur,
+        tau,
+        threshold,
+        mode,
+        buffers,
+        &mut StageRows::unchecked(),
+    )
+}
+
+/// [`difference_of_gaussians_auto_into`] checking `rows` after every row of
+/// the blur passes
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn difference_of_gaussians_auto_into_checked(
+    img: &GrayImage,
+    sigma1: f32,
+    sigma2: f32,
+    kernel_size: u32,
+    blur: BlurMode,
+    tau: f32,
+    threshold: f32,
+    mode: ThresholdMode,
+    buffers: &mut DogBuffers,
+    rows: &mut StageRows,
+) -> f32 {
+    dog_blurs_into(img, sigma1, sigma2, kernel_size, blur, buffers, rows);
+    let DogBuffers {
+        temp,
+        blur1,
+        blur2,
+        output,
+    } = buffers;
+    if mode == ThresholdMode::Fixed {
+        dog_threshold_into(blur1, blur2, tau, threshold, output);
+        return threshold;
+    }
+
+    // The horizontal pass is done with, so it holds the differences
+    dog_differences_into(blur1, blur2, tau, temp);
+    let threshold = match mode {
+        ThresholdMode::Fixed => threshold,
+        ThresholdMode::Otsu => otsu_threshold(temp),
+        ThresholdMode::Percentile { percentile } => percentile_threshold(temp, percentile),
+    }
+    .max(MIN_AUTO_THRESHOLD);
+
+    threshold_into(temp, threshold, output);
+    threshold
+}
+
+/// Threshold that splits `values` into two classes by Otsu's method
+///
+/// The values are binned into a 256-bucket histogram between their minimum
+/// and maximum, and the split with the largest between-class variance wins.
+///
+/// # Returns
+/// The lower edge of the upper class, or the common value when all values
+/// are equal
+pub fn otsu_threshold(values: &[f32]) -> f32 {
+    let (min, max) = values
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+    if values.is_empty() || min >= max {
+        return if values.is_empty() { 0.0 } else { min };
+    }
+
+    const BINS: usize = 256;
+    let scale = (BINS - 1) as f32 / (max - min);
+    let mut histogram = [0u64; BINS];
+    for &v in values {
+        histogram[((v - min) * scale) as usize] += 1;
+    }
+
+    let total = values.len() as f64;
+    let weighted_sum: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(bin, &count)| bin as f64 * count as f64)
+        .sum();
+    let (mut below, mut below_sum) = (0.0, 0.0);
+    let (mut best_bin, mut best_variance) = (1, -1.0);
+    for (bin, &count) in histogram.iter().enumerate().take(BINS - 1) {
+        below += count as f64;
+        below_sum += bin as f64 * count as f64;
+        let above = total - below;
+        if below == 0.0 || above == 0.0 {
+            continue;
+        }
+        let mean_difference = below_sum / below - (weighted_sum - below_sum) / above;
+        let variance = below * above * mean_difference * mean_difference;
+        if variance > best_variance {
+            best_variance = variance;
+            best_bin = bin + 1;
+        }
+    }
+    min + best_bin as f32 / scale
 }
 
-/// Compute Difference of Gaussians (DoG) edge detection
-///
-/// DoG = blur(sigma1) - tau * blur(sigma2)
-/// Then threshold: result >= threshold ? 1.0 : 0.0
+/// Value below which `percentile` (0-100) percent of `values` lie
+pub fn percentile_threshold(values: &[f32], percentile: f32) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f32).round();
+    let (_, value, _) = sorted.select_nth_unstable_by(rank as usize, f32::total_cmp);
+    *value
+}
+
+/// Compute the extended Difference of Gaussians (XDoG)
 ///
-/// This implements the core of PS_VerticalBlurAndDifference from AcerolaFX_ASCII.fx:296-317
+/// Same difference as [`difference_of_gaussians`], but only values at or above
+/// `epsilon` become white; below it the output falls off smoothly as
+/// `1 + tanh(phi * (D - epsilon))` instead of dropping straight to black.
+/// Pixels near the threshold no longer flip between frames, which keeps line
+/// work steady in video.
 ///
 /// # Arguments
 /// * `img` - Input grayscale image
 /// * `sigma1` - First Gaussian sigma (typically smaller)
 /// * `sigma2` - Second Gaussian sigma (typically larger)
 /// * `kernel_size` - Kernel radius for both blurs
-/// * `tau` - Multiplier for second blur (default 1.0)
-/// * `threshold` - Binary threshold value (default 0.005)
+/// * `tau` - Multiplier for second blur
+/// * `phi` - Steepness of the falloff below `epsilon`; large values approach
+///   the hard threshold
+/// * `epsilon` - Difference at which the output becomes white
 ///
 /// # Returns
-/// Binary edge image (0 or 255)
-pub fn difference_of_gaussians(
+/// Soft edge image (0-255)
+pub fn extended_difference_of_gaussians(
     img: &GrayImage,
     sigma1: f32,
     sigma2: f32,
     kernel_size: u32,
     tau: f32,
-    threshold: f32,
+    phi: f32,
+    epsilon: f32,
 ) -> GrayImage {
-    let (width, height) = img.dimensions();
-    let mut output = GrayImage::new(width, height);
+    let mut buffers = DogBuffers::default();
+    dog_into(
+        img,
+        sigma1,
+        sigma2,
+        kernel_size,
+        BlurMode::Gaussian,
+        &mut buffers,
+        &mut StageRows::unchecked(),
+        |g1, g2| xdog_level(g1 - tau * g2, phi, epsilon),
+    );
+    buffers.output
+}
+
+/// [`extended_difference_of_gaussians`] into reusable buffers, with both blurs
+/// computed as `blur` says; the result is left in `buffers.output`
+#[allow(clippy::too_many_arguments)]
+pub fn extended_difference_of_gaussians_into(
+    img: &GrayImage,
+    sigma1: f32,
+    sigma2: f32,
+    kernel_size: u32,
+    blur: BlurMode,
+    tau: f32,
+    phi: f32,
+    epsilon: f32,
+    buffers: &mut DogBuffers,
+) {
+    extended_difference_of_gaussians_into_checked(
+        img,
+        sigma1,
+        sigma2,
+        kernel_size,
+        blur,
+        tau,
+        phi,
+        epsilon,
+        buffers,
+        &mut StageRows::unchecked(),
+    );
+}
+
+/// [`extended_difference_of_gaussians_into`] checking `rows` after every row
+/// of the blur passes
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn extended_difference_of_gaussians_into_checked(
+    img: &GrayImage,
+    sigma1: f32,
+    sigma2: f32,
+    kernel_size: u32,
+    blur: BlurMode,
+    tau: f32,
+    phi: f32,
+    epsilon: f32,
+    buffers: &mut DogBuffers,
+    rows: &mut StageRows,
+) {
+    dog_into(
+        img,
+        sigma1,
+        sigma2,
+        kernel_size,
+        blur,
+        buffers,
+        rows,
+        |g1, g2| xdog_level(g1 - tau * g2, phi, epsilon),
+    );
+}
+
+/// Soft threshold of one XDoG difference, as an 8-bit level
+fn xdog_level(dog: f32, phi: f32, epsilon: f32) -> u8 {
+    if dog >= epsilon {
+        255
+    } else {
+        ((1.0 + (phi * (dog - epsilon)).tanh()) * 255.0).round() as u8
+    }
+}
 
-    // Apply two Gaussian blurs with different sigmas
-    let blur1 = gaussian_blur(img, sigma1, kernel_size);
-    let blur2 = gaussian_blur(img, sigma2, kernel_size);
+/// Shared DoG loop: blurs `img` twice, unquantized, and maps each pair of
+/// blurred values to an output level
+#[allow(clippy::too_many_arguments)]
+fn dog_into(
+    img: &GrayImage,
+    sigma1: f32,
+    sigma2: f32,
+    kernel_size: u32,
+    blur: BlurMode,
+    buffers: &mut DogBuffers,
+    rows: &mut StageRows,
+    level: impl Fn(f32, f32) -> u8,
+) {
+    dog_blurs_into(img, sigma1, sigma2, kernel_size, blur, buffers, rows);
+    let DogBuffers {
+        blur1,
+        blur2,
+        output,
+        ..
+    } = buffers;
 
     // Compute difference and threshold
-    for y in 0..height {
-        for x in 0..width {
-            let g1 = blur1.get_pixel(x, y)[0] as f32 / 255.0;
-            let g2 = blur2.get_pixel(x, y)[0] as f32 / 255.0;
+    for ((pixel, &g1), &g2) in output.iter_mut().zip(blur1.iter()).zip(blur2.iter()) {
+        *pixel = level(g1, g2);
+    }
+}
 
-            // DoG formula from shader: D = (blur1 - tau * blur2)
-            let dog = g1 - tau * g2;
+/// Sizes `buffers.output` for `img` and fills `blur1` and `blur2` with its
+/// two blurs, kept unquantized
+fn dog_blurs_into(
+    img: &GrayImage,
+    sigma1: f32,
+    sigma2: f32,
+    kernel_size: u32,
+    blur: BlurMode,
+    buffers: &mut DogBuffers,
+    rows: &mut StageRows,
+) {
+    let (width, height) = img.dimensions();
+    let DogBuffers {
+        temp,
+        blur1,
+        blur2,
+        output,
+    } = buffers;
+    reuse(output, width, height);
+    blur_f32_into_checked(img, sigma1, kernel_size, blur, temp, blur1, rows);
+    blur_f32_into_checked(img, sigma2, kernel_size, blur, temp, blur2, rows);
+}
 
-            // Binary threshold: D >= threshold ? 1 : 0
-            let result = if dog >= threshold { 255 } else { 0 };
-            output.put_pixel(x, y, Luma([result]));
-        }
+/// Number of `f32` lanes the SIMD loops work on at a time
+const LANES: usize = 8;
+
+/// Loads up to [`LANES`] values, padding a short tail with zeros
+fn load_lanes(values: &[f32]) -> f32x8 {
+    let mut lanes = [0.0; LANES];
+    lanes[..values.len()].copy_from_slice(values);
+    f32x8::new(lanes)
+}
+
+/// Writes 255 where a lane of `mask` is set and 0 elsewhere
+fn store_mask(mask: f32x8, output: &mut [u8]) {
+    let bits = mask.move_mask();
+    for (i, pixel) in output.iter_mut().enumerate() {
+        *pixel = if bits & (1 << i) != 0 { 255 } else { 0 };
     }
+}
 
-    output
+/// Binary DoG of two blurs: 255 where `g1 - tau * g2 >= threshold`
+fn dog_threshold_into(blur1: &[f32], blur2: &[f32], tau: f32, threshold: f32, output: &mut [u8]) {
+    let (tau, threshold) = (f32x8::splat(tau), f32x8::splat(threshold));
+    for ((pixels, g1), g2) in output
+        .chunks_mut(LANES)
+        .zip(blur1.chunks(LANES))
+        .zip(blur2.chunks(LANES))
+    {
+        // DoG formula from shader: D = (blur1 - tau * blur2)
+        let dog = load_lanes(g1) - tau * load_lanes(g2);
+
+        // Binary threshold: D >= threshold ? 1 : 0
+        store_mask(dog.cmp_ge(threshold), pixels);
+    }
+}
+
+/// Replaces `differences` with `g1 - tau * g2` for each pair of blurred values
+fn dog_differences_into(blur1: &[f32], blur2: &[f32], tau: f32, differences: &mut Vec<f32>) {
+    let tau = f32x8::splat(tau);
+    differences.clear();
+    differences.reserve(blur1.len());
+    for (g1, g2) in blur1.chunks(LANES).zip(blur2.chunks(LANES)) {
+        let dog = load_lanes(g1) - tau * load_lanes(g2);
+        differences.extend_from_slice(&dog.to_array()[..g1.len()]);
+    }
+}
+
+/// 255 where a value is at least `threshold`, 0 elsewhere
+fn threshold_into(values: &[f32], threshold: f32, output: &mut [u8]) {
+    let threshold = f32x8::splat(threshold);
+    for (pixels, values) in output.chunks_mut(LANES).zip(values.chunks(LANES)) {
+        store_mask(load_lanes(values).cmp_ge(threshold), pixels);
+    }
 }
 
 /// Apply Sobel filter to detect edge gradients and directions
@@ -210,11 +1445,65 @@ pub fn difference_of_gaussians(
 /// - angles: Vec of edge angles in radians (atan2(Gy, Gx))
 /// - valid_mask: Vec of booleans indicating if the edge is valid (non-zero gradient)
 pub fn sobel_filter(edges: &GrayImage) -> (Vec<f32>, Vec<bool>) {
+    let mut angles = Vec::new();
+    let mut valid_mask = Vec::new();
+    sobel_filter_into(edges, &mut angles, &mut valid_mask);
+    (angles, valid_mask)
+}
+
+/// [`sobel_filter`] into existing vectors, which are cleared and resized to
+/// one entry per pixel
+pub fn sobel_filter_into(edges: &GrayImage, angles: &mut Vec<f32>, valid_mask: &mut Vec<bool>) {
+    sobel_filter_threshold_into(edges, SOBEL_MAGNITUDE_THRESHOLD, angles, valid_mask);
+}
+
+/// Gradient magnitude above which [`sobel_filter`] marks a pixel valid, and
+/// the default `sobel_threshold` of [`crate::AsciiConfig`]
+pub const SOBEL_MAGNITUDE_THRESHOLD: f32 = 0.01;
+
+/// [`sobel_filter_into`] with its own magnitude cutoff
+///
+/// Gradients are measured on the 0-1 scale, so a hard black-to-white step
+/// reaches a magnitude of 4.0. Run directly on a blurred luminance image
+/// instead of the binary DoG, the cutoff picks how strong a luminance change
+/// has to be to count as an edge.
+///
+/// # Arguments
+/// * `edges` - Binary edge image, or a luminance image
+/// * `magnitude_threshold` - Pixels with a larger gradient magnitude are valid
+/// * `angles` - Gradient angle per pixel, cleared and resized
+/// * `valid_mask` - Whether each pixel passes the cutoff, cleared and resized
+pub fn sobel_filter_threshold_into(
+    edges: &GrayImage,
+    magnitude_threshold: f32,
+    angles: &mut Vec<f32>,
+    valid_mask: &mut Vec<bool>,
+) {
+    sobel_filter_threshold_into_checked(
+        edges,
+        magnitude_threshold,
+        angles,
+        valid_mask,
+        &mut StageRows::unchecked(),
+    );
+}
+
+/// [`sobel_filter_threshold_into`] checking `rows` after every row
+pub(crate) fn sobel_filter_threshold_into_checked(
+    edges: &GrayImage,
+    magnitude_threshold: f32,
+    angles: &mut Vec<f32>,
+    valid_mask: &mut Vec<bool>,
+    rows: &mut StageRows,
+) {
     let (width, height) = edges.dimensions();
     let size = (width * height) as usize;
+    rows.begin_pass();
 
-    let mut angles = vec![0.0; size];
-    let mut valid_mask = vec![false; size];
+    angles.clear();
+    angles.resize(size, 0.0);
+    valid_mask.clear();
+    valid_mask.resize(size, false);
 
     // Sobel kernels
     // Gx (horizontal):     Gy (vertical):
@@ -224,24 +1513,11 @@ pub fn sobel_filter(edges: &GrayImage) -> (Vec<f32>, Vec<bool>) {
 
     for y in 1..(height - 1) {
         for x in 1..(width - 1) {
-            // Get 3x3 neighborhood
-            let nw = edges.get_pixel(x - 1, y - 1)[0] as f32;
-            let n = edges.get_pixel(x, y - 1)[0] as f32;
-            let ne = edges.get_pixel(x + 1, y - 1)[0] as f32;
-            let w = edges.get_pixel(x - 1, y)[0] as f32;
-            let e = edges.get_pixel(x + 1, y)[0] as f32;
-            let sw = edges.get_pixel(x - 1, y + 1)[0] as f32;
-            let s = edges.get_pixel(x, y + 1)[0] as f32;
-            let se = edges.get_pixel(x + 1, y + 1)[0] as f32;
-
-            // Compute Sobel gradients
-            let gx = (-nw + ne - 2.0 * w + 2.0 * e - sw + se) / 255.0;
-            let gy = (-nw - 2.0 * n - ne + sw + 2.0 * s + se) / 255.0;
-
+            let (gx, gy) = sobel_gradient(edges, x, y);
             let magnitude = (gx * gx + gy * gy).sqrt();
             let idx = (y * width + x) as usize;
 
-            if magnitude > 0.01 {
+            if magnitude > magnitude_threshold {
                 // Edge is valid if gradient magnitude is significant
                 angles[idx] = gy.atan2(gx); // angle = atan2(Gy, Gx)
                 valid_mask[idx] = true;
@@ -250,9 +1526,253 @@ pub fn sobel_filter(edges: &GrayImage) -> (Vec<f32>, Vec<bool>) {
                 valid_mask[idx] = false;
             }
         }
+        if !rows.row(y as usize + 1, height as usize - 1) {
+            return;
+        }
     }
+}
 
-    (angles, valid_mask)
+/// Grow or shrink the bright parts of an edge image
+///
+/// A positive `amount` is a grayscale dilation: each pixel takes the
+/// brightest value within `amount` pixels (a square window), thickening
+/// lines. A negative `amount` is an erosion with the darkest value, thinning
+/// lines and removing those narrower than `2 * -amount + 1` pixels. Zero
+/// returns a copy.
+///
+/// # Arguments
+/// * `edges` - Binary or soft (XDoG) edge image
+/// * `amount` - Window radius in pixels, negative to erode
+///
+/// # Returns
+/// Dilated or eroded image
+pub fn dilate_edges(edges: &GrayImage, amount: i32) -> GrayImage {
+    let (width, height) = edges.dimensions();
+    let radius = amount.unsigned_abs() as i64;
+    let pick = |a: u8, b: u8| if amount > 0 { a.max(b) } else { a.min(b) };
+
+    // Separable square window: rows, then columns
+    let mut rows = GrayImage::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let value = (x as i64 - radius..=x as i64 + radius)
+                .map(|sx| sx.clamp(0, width as i64 - 1) as u32)
+                .fold(edges.get_pixel(x, y)[0], |v, sx| {
+                    pick(v, edges.get_pixel(sx, y)[0])
+                });
+            rows.put_pixel(x, y, Luma([value]));
+        }
+    }
+    let mut output = GrayImage::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let value = (y as i64 - radius..=y as i64 + radius)
+                .map(|sy| sy.clamp(0, height as i64 - 1) as u32)
+                .fold(rows.get_pixel(x, y)[0], |v, sy| {
+                    pick(v, rows.get_pixel(x, sy)[0])
+                });
+            output.put_pixel(x, y, Luma([value]));
+        }
+    }
+    output
+}
+
+/// Sobel gradient (Gx, Gy) at an interior pixel, on the 0-1 scale
+fn sobel_gradient(edges: &GrayImage, x: u32, y: u32) -> (f32, f32) {
+    // Get 3x3 neighborhood
+    let nw = edges.get_pixel(x - 1, y - 1)[0] as f32;
+    let n = edges.get_pixel(x, y - 1)[0] as f32;
+    let ne = edges.get_pixel(x + 1, y - 1)[0] as f32;
+    let w = edges.get_pixel(x - 1, y)[0] as f32;
+    let e = edges.get_pixel(x + 1, y)[0] as f32;
+    let sw = edges.get_pixel(x - 1, y + 1)[0] as f32;
+    let s = edges.get_pixel(x, y + 1)[0] as f32;
+    let se = edges.get_pixel(x + 1, y + 1)[0] as f32;
+
+    // Compute Sobel gradients
+    let gx = (-nw + ne - 2.0 * w + 2.0 * e - sw + se) / 255.0;
+    let gy = (-nw - 2.0 * n - ne + sw + 2.0 * s + se) / 255.0;
+    (gx, gy)
+}
+
+/// Replace the gradient angles of valid pixels with the orientation of the
+/// smoothed structure tensor
+///
+/// The per-pixel `atan2(Gy, Gx)` of a binary edge image only takes a few
+/// values along the staircase of a thresholded band, so pixels of one
+/// slanted edge vote for different directions. Here the tensor products
+/// `Gx²`, `Gx·Gy` and `Gy²` are averaged with a Gaussian of `sigma` first,
+/// and the angle is the dominant gradient orientation of the neighborhood,
+/// `0.5 * atan2(2·Jxy, Jxx - Jyy)`, in (-π/2, π/2]. Invalid pixels keep
+/// their angle.
+///
+/// # Arguments
+/// * `edges` - Image the Sobel filter ran on
+/// * `sigma` - Smoothing of the tensor, in pixels
+/// * `angles` - Gradient angle per pixel, overwritten where valid
+/// * `valid_mask` - Pixels whose angle is replaced
+pub fn structure_tensor_angles(
+    edges: &GrayImage,
+    sigma: f32,
+    angles: &mut [f32],
+    valid_mask: &[bool],
+) {
+    structure_tensor_angles_checked(
+        edges,
+        sigma,
+        angles,
+        valid_mask,
+        &mut StageRows::unchecked(),
+    );
+}
+
+/// [`structure_tensor_angles`] checking `rows` after every row of the six
+/// smoothing passes
+pub(crate) fn structure_tensor_angles_checked(
+    edges: &GrayImage,
+    sigma: f32,
+    angles: &mut [f32],
+    valid_mask: &[bool],
+    rows: &mut StageRows,
+) {
+    let (width, height) = edges.dimensions();
+    let size = (width * height) as usize;
+    let (mut jxx, mut jxy, mut jyy) = (vec![0.0; size], vec![0.0; size], vec![0.0; size]);
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let idx = (y * width + x) as usize;
+            let (gx, gy) = sobel_gradient(edges, x, y);
+            jxx[idx] = gx * gx;
+            jxy[idx] = gx * gy;
+            jyy[idx] = gy * gy;
+        }
+    }
+
+    let radius = (2.0 * sigma).ceil() as u32;
+    let mut temp = Vec::new();
+    let mut smooth = |tensor: &mut Vec<f32>| {
+        blur_h_pass(width, height, sigma, radius, |i| tensor[i], &mut temp, rows);
+        blur_v_pass(width, height, sigma, radius, |i| temp[i], tensor, rows);
+    };
+    smooth(&mut jxx);
+    smooth(&mut jxy);
+    smooth(&mut jyy);
+
+    for (idx, angle) in angles.iter_mut().enumerate() {
+        if valid_mask[idx] {
+            *angle = 0.5 * (2.0 * jxy[idx]).atan2(jxx[idx] - jyy[idx]);
+        }
+    }
+}
+
+/// Thin Sobel edges to one pixel across with non-maximum suppression
+///
+/// A step in the binary DoG gives a Sobel response two pixels wide on each
+/// side of the band, so one real edge can put votes into two neighboring
+/// tiles. This keeps a valid pixel only where its gradient magnitude is a
+/// maximum along the gradient direction (quantized to 0°, 45°, 90° or 135°),
+/// as in the Canny detector. Plateaus keep their last pixel along the
+/// gradient.
+///
+/// # Arguments
+/// * `edges` - Image the Sobel filter ran on
+/// * `angles` - Gradient angle per pixel, from [`sobel_filter_threshold_into`]
+/// * `valid_mask` - Valid pixels, cleared where they are not a maximum
+pub fn non_maximum_suppression(edges: &GrayImage, angles: &[f32], valid_mask: &mut [bool]) {
+    let (width, height) = edges.dimensions();
+    let mut magnitudes = vec![0.0f32; (width * height) as usize];
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let idx = (y * width + x) as usize;
+            if valid_mask[idx] {
+                let (gx, gy) = sobel_gradient(edges, x, y);
+                magnitudes[idx] = (gx * gx + gy * gy).sqrt();
+            }
+        }
+    }
+    let magnitude_at = |x: i64, y: i64| {
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+            0.0
+        } else {
+            magnitudes[(y * width as i64 + x) as usize]
+        }
+    };
+
+    for y in 0..height as i64 {
+        for x in 0..width as i64 {
+            let idx = (y * width as i64 + x) as usize;
+            if !valid_mask[idx] {
+                continue;
+            }
+            // Neighbor step along the gradient, with y pointing down
+            let sector = ((angles[idx].to_degrees() + 202.5) / 45.0) as i64 % 4;
+            let (dx, dy) = match sector {
+                0 => (1, 0),
+                1 => (1, 1),
+                2 => (0, 1),
+                _ => (-1, 1),
+            };
+            let magnitude = magnitudes[idx];
+            valid_mask[idx] = magnitude >= magnitude_at(x - dx, y - dy)
+                && magnitude > magnitude_at(x + dx, y + dy);
+        }
+    }
+}
+
+/// Summed-area table (integral image) of a grayscale image
+///
+/// Holds the sum of all pixels above and to the left of each corner, so the
+/// sum over any rectangle takes four lookups however large it is.
+#[derive(Debug, Clone)]
+pub struct SummedAreaTable {
+    /// Image width plus one, the row stride of `sums`
+    stride: usize,
+    /// `(width + 1)`×`(height + 1)` corner sums, row by row; the first row
+    /// and column are zero
+    sums: Vec<u64>,
+}
+
+impl SummedAreaTable {
+    /// Builds the table in one pass over `img`
+    pub fn new(img: &GrayImage) -> Self {
+        let (width, height) = img.dimensions();
+        let stride = width as usize + 1;
+        let mut sums = vec![0u64; stride * (height as usize + 1)];
+        for (y, row) in img.as_raw().chunks_exact(width.max(1) as usize).enumerate() {
+            let mut row_sum = 0;
+            for (x, &v) in row.iter().enumerate() {
+                row_sum += v as u64;
+                sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row_sum;
+            }
+        }
+        Self { stride, sums }
+    }
+
+    /// Sum of the pixels in the `width`×`height` rectangle with its top-left
+    /// corner at (`x`, `y`), which must lie within the image
+    pub fn sum(&self, x: u32, y: u32, width: u32, height: u32) -> u64 {
+        let (left, top) = (x as usize, y as usize);
+        let (right, bottom) = (left + width as usize, top + height as usize);
+        let at = |x: usize, y: usize| self.sums[y * self.stride + x];
+        at(right, bottom) + at(left, top) - at(left, bottom) - at(right, top)
+    }
+}
+
+/// Quantize `width`×`height` samples in [0, 1] into `output`, rounding to
+/// the nearest 8-bit level
+fn quantize_into(samples: &[f32], width: u32, height: u32, output: &mut GrayImage) {
+    reuse(output, width, height);
+    for (pixel, &value) in output.iter_mut().zip(samples) {
+        *pixel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+}
+
+/// Makes `buffer` a `width`×`height` image, keeping its allocation when the
+/// size already matches
+fn reuse(buffer: &mut GrayImage, width: u32, height: u32) {
+    if buffer.dimensions() != (width, height) {
+        *buffer = GrayImage::new(width, height);
+    }
 }
 
 #[cfg(test)]
@@ -282,6 +1802,218 @@ mod tests {
         assert!((127..=129).contains(&val));
     }
 
+    #[test]
+    fn test_luminance_linear() {
+        // Gray and the extremes are unchanged
+        for v in [0, 128, 255] {
+            let img = RgbaImage::from_pixel(4, 4, image::Rgba([v, v, v, 255]));
+            let lum = calculate_luminance_linear(&img);
+            assert!(lum.get_pixel(0, 0)[0].abs_diff(v) <= 1);
+        }
+
+        // Pure red is brighter in linear light than on the encoded bytes
+        let red = RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255]));
+        assert_eq!(calculate_luminance(&red).get_pixel(0, 0)[0], 54);
+        assert_eq!(calculate_luminance_linear(&red).get_pixel(0, 0)[0], 127);
+    }
+
+    #[test]
+    fn test_luminance_modes() {
+        let img = RgbaImage::from_pixel(4, 4, image::Rgba([200, 100, 40, 255]));
+        let lum = |mode| calculate_luminance_with(&img, mode, false).get_pixel(0, 0)[0];
+
+        assert_eq!(
+            lum(LuminanceMode::Rec709),
+            calculate_luminance(&img).get_pixel(0, 0)[0]
+        );
+        assert_eq!(lum(LuminanceMode::Rec601), 123);
+        assert_eq!(lum(LuminanceMode::Average), 113);
+        assert_eq!(lum(LuminanceMode::Max), 200);
+        // Custom weights are normalized
+        let red_only = LuminanceMode::Custom {
+            weights: [2.0, 0.0, 0.0],
+        };
+        assert_eq!(lum(red_only), 200);
+    }
+
+    #[test]
+    fn test_luminance_lanes_match_scalar() {
+        // 13 wide, so rows straddle the SIMD lanes and leave a short tail
+        let img = RgbaImage::from_fn(13, 7, |x, y| {
+            let v = (x * 37 + y * 91) as u8;
+            image::Rgba([v, v.wrapping_mul(3), v ^ 0x5a, 255])
+        });
+        for mode in [
+            LuminanceMode::Rec601,
+            LuminanceMode::Rec709,
+            LuminanceMode::Average,
+            LuminanceMode::Max,
+        ] {
+            for linear in [false, true] {
+                let decode = |v: u8| {
+                    if linear {
+                        srgb_to_linear(v)
+                    } else {
+                        v as f32 / 255.0
+                    }
+                };
+                let lum = calculate_luminance_with(&img, mode, linear);
+                for (x, y, pixel) in img.enumerate_pixels() {
+                    let [r, g, b] = [0, 1, 2].map(|c| decode(pixel[c]));
+                    let luminance = match mode.weights() {
+                        Some([wr, wg, wb]) => wr * r + wg * g + wb * b,
+                        None => r.max(g).max(b),
+                    };
+                    let expected = if linear {
+                        linear_to_srgb(luminance)
+                    } else {
+                        (luminance.clamp(0.0, 1.0) * 255.0) as u8
+                    };
+                    assert_eq!(lum.get_pixel(x, y)[0], expected, "{:?} {}", mode, linear);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_predominantly_bright() {
+        // White page with a dark stripe covering a third of it
+        let page = GrayImage::from_fn(30, 30, |x, _| Luma([if x < 10 { 20 } else { 240 }]));
+        assert_eq!(luminance_histogram(&page)[20], 300);
+        assert!(is_predominantly_bright(&page));
+
+        let dark = GrayImage::from_fn(30, 30, |x, _| Luma([if x < 20 { 20 } else { 240 }]));
+        assert!(!is_predominantly_bright(&dark));
+        assert!(!is_predominantly_bright(&GrayImage::new(0, 0)));
+    }
+
+    #[test]
+    fn test_bilateral_filter_keeps_edges() {
+        // Noisy dark and bright halves
+        let noisy = GrayImage::from_fn(32, 16, |x, y| {
+            let base = if x < 16 { 40 } else { 210 };
+            let noise = if (x * 7 + y * 13) % 5 == 0 { 12 } else { 0 };
+            Luma([base + noise])
+        });
+        let filtered = bilateral_filter(&noisy, 2.0, 0.1);
+        assert_eq!(filtered.dimensions(), (32, 16));
+
+        // Noise is smoothed within each half
+        let spread = |img: &GrayImage| {
+            let values: Vec<u8> = (0..16).map(|y| img.get_pixel(6, y)[0]).collect();
+            values.iter().max().unwrap() - values.iter().min().unwrap()
+        };
+        assert!(spread(&filtered) < spread(&noisy));
+
+        // The step between the halves stays sharp
+        assert!(filtered.get_pixel(15, 8)[0] < 60);
+        assert!(filtered.get_pixel(16, 8)[0] > 190);
+    }
+
+    #[test]
+    fn test_auto_contrast() {
+        // Murky gradient between 100 and 140
+        let murky = GrayImage::from_fn(41, 4, |x, _| Luma([100 + x as u8]));
+        let stretched = auto_contrast(&murky);
+        assert_eq!(stretched.get_pixel(0, 0)[0], 0);
+        assert_eq!(stretched.get_pixel(40, 0)[0], 255);
+        assert!((120..=135).contains(&stretched.get_pixel(20, 0)[0]));
+
+        let flat = GrayImage::from_pixel(8, 8, Luma([90]));
+        assert_eq!(auto_contrast(&flat), flat);
+    }
+
+    #[test]
+    fn test_equalize_histogram() {
+        // Most pixels crowded in a narrow dark band
+        let img = GrayImage::from_fn(64, 4, |x, _| {
+            Luma([if x < 48 { 30 + (x / 8) as u8 } else { 200 }])
+        });
+        let equalized = equalize_histogram(&img);
+        let levels: Vec<u8> = (0..6)
+            .map(|band| equalized.get_pixel(band * 8, 0)[0])
+            .collect();
+
+        // The six dark levels spread out evenly over the range
+        assert!(levels.windows(2).all(|w| w[1] - w[0] >= 30), "{:?}", levels);
+        assert_eq!(equalized.get_pixel(63, 0)[0], 255);
+    }
+
+    #[test]
+    fn test_clahe() {
+        // Low-contrast detail in a dark and in a bright half
+        let img = GrayImage::from_fn(64, 64, |x, y| {
+            let base = if x < 32 { 40 } else { 200 };
+            Luma([base + ((x + y) % 4) as u8 * 3])
+        });
+        let equalized = clahe(&img, 4, 2.0);
+        assert_eq!(equalized.dimensions(), (64, 64));
+
+        // Local detail gets stronger on both sides
+        let spread = |img: &GrayImage, x0: u32| {
+            let values: Vec<u8> = (x0..x0 + 4).map(|x| img.get_pixel(x, 16)[0]).collect();
+            values.iter().max().unwrap() - values.iter().min().unwrap()
+        };
+        assert!(spread(&equalized, 8) > spread(&img, 8));
+        assert!(spread(&equalized, 48) > spread(&img, 48));
+        assert_eq!(clahe(&GrayImage::new(0, 0), 4, 2.0).dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn test_expose() {
+        let img = GrayImage::from_fn(4, 1, |x, _| Luma([[0, 64, 128, 255][x as usize]]));
+        let values = |img: &GrayImage| img.pixels().map(|p| p[0]).collect::<Vec<_>>();
+
+        assert_eq!(values(&expose(&img, 1.0, 1.0)), values(&img));
+        assert_eq!(values(&expose(&img, 2.0, 1.0)), vec![0, 128, 255, 255]);
+        assert_eq!(values(&expose(&img, 1.0, 2.0)), vec![0, 16, 64, 255]);
+    }
+
+    #[test]
+    fn test_median_filter_removes_speckles() {
+        // Dark and bright halves with isolated white and black specks
+        let speckled = GrayImage::from_fn(24, 12, |x, y| {
+            let base = if x < 12 { 40 } else { 200 };
+            match (x * 5 + y * 3) % 11 {
+                0 => Luma([255]),
+                1 => Luma([0]),
+                _ => Luma([base]),
+            }
+        });
+        let filtered = median_filter(&speckled, 1);
+
+        for y in 0..12 {
+            for x in 1..11 {
+                assert_eq!(filtered.get_pixel(x, y)[0], 40, "({}, {})", x, y);
+                assert_eq!(filtered.get_pixel(x + 12, y)[0], 200, "({}, {})", x + 12, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_kuwahara_filter_flattens_texture() {
+        // Fine checkerboard texture on the left, flat bright right half
+        let textured = GrayImage::from_fn(32, 16, |x, y| {
+            if x >= 16 {
+                Luma([220])
+            } else if (x + y).is_multiple_of(2) {
+                Luma([50])
+            } else {
+                Luma([90])
+            }
+        });
+        let filtered = kuwahara_filter(&textured, 4, 8);
+        assert_eq!(filtered.dimensions(), (32, 16));
+
+        // The texture collapses to its average, the border stays sharp
+        for x in 2..14 {
+            let v = filtered.get_pixel(x, 8)[0];
+            assert!((60..=80).contains(&v), "{} at x={}", v, x);
+        }
+        assert!(filtered.get_pixel(14, 8)[0] < 100);
+        assert_eq!(filtered.get_pixel(17, 8)[0], 220);
+    }
+
     #[test]
     fn test_gaussian_at_center() {
         let sigma = 1.0;
@@ -317,6 +2049,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_xdog_softens_the_threshold() {
+        let img = GrayImage::from_fn(32, 8, |x, _| Luma([if x < 16 { 100 } else { 112 }]));
+        let hard = difference_of_gaussians(&img, 1.0, 1.6, 4, 1.0, 0.005);
+        let soft = extended_difference_of_gaussians(&img, 1.0, 1.6, 4, 1.0, 1000.0, 0.005);
+
+        // White wherever the hard threshold passes, gray levels around it
+        for (h, s) in hard.pixels().zip(soft.pixels()) {
+            if h[0] == 255 {
+                assert_eq!(s[0], 255);
+            }
+        }
+        assert!(hard.pixels().any(|p| p[0] == 255));
+        assert!(soft.pixels().any(|p| p[0] > 0 && p[0] < 255));
+
+        // Flat regions stay black, and a steep falloff matches the hard threshold
+        assert_eq!(soft.get_pixel(2, 4)[0], 0);
+        let steep = extended_difference_of_gaussians(&img, 1.0, 1.6, 4, 1.0, 1e6, 0.005);
+        assert_eq!(steep, hard);
+    }
+
+    #[test]
+    fn test_gaussian_blur_f32_is_unquantized() {
+        // A one-level step blurs into values between the two 8-bit levels
+        let img = GrayImage::from_fn(16, 4, |x, _| Luma([if x < 8 { 100 } else { 101 }]));
+        let mut blurred = Vec::new();
+        gaussian_blur_f32_into(&img, 2.0, 4, &mut Vec::new(), &mut blurred);
+        assert_eq!(blurred.len(), 16 * 4);
+        let step = blurred[8] * 255.0;
+        assert!(step > 100.0 && step < 101.0);
+        assert!(step.fract() != 0.0);
+
+        // The quantized blur rounds once at the end
+        let quantized = gaussian_blur(&img, 2.0, 4);
+        assert_eq!(quantized.get_pixel(8, 0)[0], step.round() as u8);
+    }
+
+    #[test]
+    fn test_dog_detects_sub_level_differences() {
+        // The difference across this faint step stays below 1/255, so it is
+        // only visible when the blurs are not quantized between stages
+        let img = GrayImage::from_fn(32, 8, |x, _| Luma([if x < 16 { 100 } else { 101 }]));
+        let mut buffers = DogBuffers::default();
+        difference_of_gaussians_into(&img, 1.0, 1.6, 4, 1.0, 0.0002, &mut buffers);
+        let max_dog = buffers
+            .blur1
+            .iter()
+            .zip(&buffers.blur2)
+            .map(|(g1, g2)| g1 - g2)
+            .fold(f32::MIN, f32::max);
+        assert!(max_dog < 1.0 / 255.0);
+        assert!(buffers.output.pixels().any(|p| p[0] == 255));
+        assert!(buffers.output.pixels().any(|p| p[0] == 0));
+    }
+
     #[test]
     fn test_sobel_filter_dimensions() {
         let edges = GrayImage::new(64, 64);
@@ -324,4 +2111,240 @@ mod tests {
         assert_eq!(angles.len(), 64 * 64);
         assert_eq!(valid.len(), 64 * 64);
     }
+
+    #[test]
+    fn test_sobel_filter_threshold() {
+        // Gentle ramp on the left, hard step on the right
+        let img = GrayImage::from_fn(32, 8, |x, _| {
+            Luma([if x < 16 { 50 + x as u8 * 2 } else { 220 }])
+        });
+        let valid = |threshold| {
+            let (mut angles, mut valid) = (Vec::new(), Vec::new());
+            sobel_filter_threshold_into(&img, threshold, &mut angles, &mut valid);
+            (valid[4 * 32 + 8], valid[4 * 32 + 16])
+        };
+
+        assert_eq!(valid(SOBEL_MAGNITUDE_THRESHOLD), (true, true));
+        assert_eq!(valid(0.5), (false, true));
+        assert_eq!(valid(4.0), (false, false));
+    }
+
+    #[test]
+    fn test_non_maximum_suppression() {
+        // White band in columns 8-11: Sobel marks two columns on each side
+        let img = GrayImage::from_fn(20, 8, |x, _| {
+            Luma([if (8..12).contains(&x) { 255 } else { 0 }])
+        });
+        let (angles, mut valid) = sobel_filter(&img);
+        let columns = |valid: &[bool]| {
+            let mut columns: Vec<usize> = (0..20).filter(|&x| valid[4 * 20 + x]).collect();
+            columns.dedup();
+            columns
+        };
+        assert_eq!(columns(&valid), [7, 8, 11, 12]);
+
+        non_maximum_suppression(&img, &angles, &mut valid);
+        assert_eq!(columns(&valid), [8, 12]);
+    }
+
+    #[test]
+    fn test_otsu_threshold() {
+        // Two clusters: the split falls between them
+        let values: Vec<f32> = (0..100)
+            .map(|i| {
+                if i < 70 {
+                    0.01 * (i % 5) as f32
+                } else {
+                    0.5 + 0.01 * (i % 3) as f32
+                }
+            })
+            .collect();
+        let threshold = otsu_threshold(&values);
+        assert!(threshold > 0.04 && threshold <= 0.5, "{}", threshold);
+
+        assert_eq!(otsu_threshold(&[0.2; 8]), 0.2);
+        assert_eq!(otsu_threshold(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_percentile_threshold() {
+        let values: Vec<f32> = (0..=100).rev().map(|i| i as f32).collect();
+        assert_eq!(percentile_threshold(&values, 90.0), 90.0);
+        assert_eq!(percentile_threshold(&values, 100.0), 100.0);
+        assert_eq!(percentile_threshold(&values, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_dog_lanes_match_scalar() {
+        let img = GrayImage::from_fn(21, 11, |x, y| Luma([((x * x + y * 29) % 256) as u8]));
+        let (tau, threshold) = (0.98, 0.004);
+        let mut buffers = DogBuffers::default();
+        difference_of_gaussians_into(&img, 1.0, 1.6, 3, tau, threshold, &mut buffers);
+
+        let mut expected = DogBuffers::default();
+        dog_into(
+            &img,
+            1.0,
+            1.6,
+            3,
+            BlurMode::Gaussian,
+            &mut expected,
+            &mut StageRows::unchecked(),
+            |g1, g2| {
+                if g1 - tau * g2 >= threshold { 255 } else { 0 }
+            },
+        );
+        assert_eq!(buffers.output, expected.output);
+
+        let mut differences = Vec::new();
+        dog_differences_into(&buffers.blur1, &buffers.blur2, tau, &mut differences);
+        let scalar: Vec<f32> = buffers
+            .blur1
+            .iter()
+            .zip(&buffers.blur2)
+            .map(|(&g1, &g2)| g1 - tau * g2)
+            .collect();
+        assert_eq!(differences, scalar);
+    }
+
+    #[test]
+    fn test_box_blur_approximates_gaussian() {
+        for sigma in [0.5, 1.0, 2.0, 3.5] {
+            // The three boxes add up to about the Gaussian's variance
+            let variance: f32 = box_blur_radii(sigma)
+                .iter()
+                .map(|&r| ((2 * r + 1).pow(2) - 1) as f32 / 12.0)
+                .sum();
+            assert!(
+                (variance - sigma * sigma).abs() < 1.0,
+                "{} {}",
+                sigma,
+                variance
+            );
+        }
+
+        let img = GrayImage::from_fn(40, 24, |x, y| {
+            Luma([if (x / 10 + y / 12) % 2 == 0 { 30 } else { 220 }])
+        });
+        let (mut gaussian, mut boxed) = (Vec::new(), Vec::new());
+        gaussian_blur_f32_into(&img, 2.0, 6, &mut Vec::new(), &mut gaussian);
+        box_blur_f32_into(&img, 2.0, &mut Vec::new(), &mut boxed);
+        let max_diff = gaussian
+            .iter()
+            .zip(&boxed)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f32::max);
+        assert!(max_diff < 0.05, "{}", max_diff);
+
+        // Flat images stay flat
+        let flat = GrayImage::from_pixel(9, 5, Luma([77]));
+        let mut blurred = GrayImage::new(0, 0);
+        blur_into(&flat, 3.0, 2, BlurMode::Box, &mut Vec::new(), &mut blurred);
+        assert_eq!(blurred, flat);
+    }
+
+    #[test]
+    fn test_summed_area_table() {
+        let img = GrayImage::from_fn(7, 5, |x, y| Luma([(x * 30 + y * 7) as u8]));
+        let table = SummedAreaTable::new(&img);
+        for (x, y, width, height) in [(0, 0, 7, 5), (2, 1, 3, 4), (6, 4, 1, 1), (3, 2, 0, 2)] {
+            let expected: u64 = (y..y + height)
+                .flat_map(|py| (x..x + width).map(move |px| (px, py)))
+                .map(|(px, py)| img.get_pixel(px, py)[0] as u64)
+                .sum();
+            assert_eq!(table.sum(x, y, width, height), expected);
+        }
+    }
+
+    #[test]
+    fn test_difference_of_gaussians_auto() {
+        let img = GrayImage::from_fn(32, 32, |x, y| {
+            Luma([if (x / 8 + y / 8) % 2 == 0 { 40 } else { 200 }])
+        });
+        let mut buffers = DogBuffers::default();
+        let threshold = difference_of_gaussians_auto_into(
+            &img,
+            1.0,
+            1.6,
+            3,
+            BlurMode::Gaussian,
+            1.0,
+            0.005,
+            ThresholdMode::Fixed,
+            &mut buffers,
+        );
+        assert_eq!(threshold, 0.005);
+        assert_eq!(
+            buffers.output,
+            difference_of_gaussians(&img, 1.0, 1.6, 3, 1.0, 0.005)
+        );
+
+        let threshold = difference_of_gaussians_auto_into(
+            &img,
+            1.0,
+            1.6,
+            3,
+            BlurMode::Gaussian,
+            1.0,
+            0.005,
+            ThresholdMode::PERCENTILE,
+            &mut buffers,
+        );
+        assert!(threshold >= MIN_AUTO_THRESHOLD);
+        let edges = buffers.output.iter().filter(|&&v| v == 255).count();
+        // About the top tenth, give or take ties at the percentile
+        assert!(edges > 0 && edges <= 32 * 32 / 5, "{}", edges);
+
+        // Flat images stay free of edges
+        let flat = GrayImage::from_pixel(16, 16, Luma([128]));
+        difference_of_gaussians_auto_into(
+            &flat,
+            1.0,
+            1.6,
+            3,
+            BlurMode::Gaussian,
+            1.0,
+            0.005,
+            ThresholdMode::Otsu,
+            &mut buffers,
+        );
+        assert!(buffers.output.iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn test_structure_tensor_angles() {
+        use crate::edges::classify_edge_direction;
+
+        // Binary step along a shallow line, a staircase of pixel corners
+        let img = GrayImage::from_fn(32, 32, |x, y| Luma([if x > 3 * y { 255 } else { 0 }]));
+        let (mut angles, valid) = sobel_filter(&img);
+        // Share of edge pixels in the most common direction class
+        let agreement = |angles: &[f32]| {
+            let mut counts = [0u32; 7];
+            for i in (0..angles.len()).filter(|&i| valid[i]) {
+                counts[(classify_edge_direction(angles[i]) as i32 + 1) as usize] += 1;
+            }
+            let total: u32 = counts.iter().sum();
+            *counts.iter().max().unwrap() as f32 / total as f32
+        };
+
+        assert!(agreement(&angles) < 0.9);
+        structure_tensor_angles(&img, 1.5, &mut angles, &valid);
+        assert_eq!(agreement(&angles), 1.0);
+    }
+
+    #[test]
+    fn test_dilate_edges() {
+        let mut img = GrayImage::new(9, 9);
+        img.put_pixel(4, 4, Luma([255]));
+
+        let dilated = dilate_edges(&img, 1);
+        let white = |img: &GrayImage| img.iter().filter(|&&v| v == 255).count();
+        assert_eq!(white(&dilated), 9);
+        assert_eq!(dilated.get_pixel(3, 5)[0], 255);
+
+        assert_eq!(white(&dilate_edges(&dilated, -1)), 1);
+        assert_eq!(white(&dilate_edges(&img, -1)), 0);
+        assert_eq!(dilate_edges(&img, 0), img);
+    }
 }
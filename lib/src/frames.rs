@@ -0,0 +1,197 @@
+//! Conversion of frame sequences
+//!
+//! [`process_frames`] is the building block for video, GIF and time-lapse
+//! pipelines: it converts a stream of frames with warm [`Processor`]s, one per
+//! worker thread, so the per-frame buffers are allocated once per worker, and
+//! converts a batch of frames at a time in parallel. Results come out lazily
+//! and in input order. [`process_animated_frames`] does the same with
+//! settings that change over time, see [`crate::keyframes`].
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::frames::process_frames;
+//!
+//! let frames = (1..=3).map(|i| image::open(format!("frame{}.png", i)).unwrap().to_rgba8());
+//! for (i, art) in process_frames(frames, &AsciiConfig::default(), true).enumerate() {
+//!     art.render_image().save(format!("ascii{}.png", i + 1)).unwrap();
+//! }
+//! ```
+
+use crate::art::AsciiArt;
+use crate::config::AsciiConfig;
+use crate::keyframes::Animation;
+use crate::par::*;
+use crate::processor::Processor;
+use image::RgbaImage;
+use std::collections::VecDeque;
+
+/// Converts a sequence of frames, several at a time
+///
+/// Each worker thread keeps its own [`Processor`], so every worker holds one
+/// set of full-size buffers. Frames are pulled from `frames` one batch (one
+/// frame per worker) at a time; without the `rayon` feature the batch is a
+/// single frame converted on the calling thread. Every result equals
+/// [`process_image`](crate::process_image) /
+/// [`process_image_preserve_colors`](crate::process_image_preserve_colors) on
+/// that frame.
+///
+/// # Arguments
+/// * `frames` - The input RGBA frames, of any (even varying) size
+/// * `config` - Configuration parameters for the ASCII conversion
+/// * `preserve_colors` - Sample colors from the frames instead of `ascii_color`
+///
+/// # Panics
+/// If `config` is invalid.
+pub fn process_frames<I>(
+    frames: I,
+    config: &AsciiConfig,
+    preserve_colors: bool,
+) -> Frames<I::IntoIter>
+where
+    I: IntoIterator<Item = RgbaImage>,
+{
+    #[cfg(feature = "rayon")]
+    let workers = rayon::current_num_threads();
+    #[cfg(not(feature = "rayon"))]
+    let workers = 1;
+
+    Frames {
+        frames: frames.into_iter(),
+        processors: (0..workers.max(1))
+            .map(|_| Processor::new(config, preserve_colors))
+            .collect(),
+        converted: VecDeque::new(),
+        animation: None,
+        index: 0,
+    }
+}
+
+/// [`process_frames`] with the settings of `animation` at each frame's time
+///
+/// Frame `i` is shown at `i / fps` seconds and converted with
+/// [`Animation::config_at`] of that time.
+///
+/// # Panics
+/// If `fps` is not positive, or the animated settings are invalid.
+pub fn process_animated_frames<I>(
+    frames: I,
+    animation: &Animation,
+    fps: f32,
+    preserve_colors: bool,
+) -> Frames<I::IntoIter>
+where
+    I: IntoIterator<Item = RgbaImage>,
+{
+    assert!(
+        fps.is_finite() && fps > 0.0,
+        "frame rate must be positive, got {}",
+        fps
+    );
+    Frames {
+        animation: Some((animation.clone(), fps as f64)),
+        ..process_frames(frames, &animation.config_at(0.0), preserve_colors)
+    }
+}
+
+/// Iterator over converted frames, see [`process_frames`]
+pub struct Frames<I> {
+    frames: I,
+    /// One warm processor per worker
+    processors: Vec<Processor>,
+    /// Converted frames of the current batch not yet returned
+    converted: VecDeque<AsciiArt>,
+    /// Settings over time and the frame rate, for animated conversion
+    animation: Option<(Animation, f64)>,
+    /// Index of the next frame pulled from `frames`
+    index: usize,
+}
+
+impl<I: Iterator<Item = RgbaImage>> Iterator for Frames<I> {
+    type Item = AsciiArt;
+
+    fn next(&mut self) -> Option<AsciiArt> {
+        if self.converted.is_empty() {
+            let batch: Vec<RgbaImage> = self.frames.by_ref().take(self.processors.len()).collect();
+            if let Some((animation, fps)) = &self.animation {
+                for (i, processor) in self.processors.iter_mut().take(batch.len()).enumerate() {
+                    processor.set_config(&animation.config_at((self.index + i) as f64 / fps));
+                }
+            }
+            self.index += batch.len();
+            let converted: Vec<AsciiArt> = self
+                .processors
+                .par_iter_mut()
+                .zip(batch.into_par_iter())
+                .map(|(processor, frame)| processor.process(&frame))
+                .collect();
+            self.converted = converted.into();
+        }
+        self.converted.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::ParamValue;
+    use crate::processor::{process_image, process_image_preserve_colors};
+
+    #[test]
+    fn test_frames_match_process_image() {
+        let config = AsciiConfig::default();
+        let frames: Vec<RgbaImage> = (0..9)
+            .map(|i| {
+                RgbaImage::from_fn(48 + i * 8, 40, |x, y| {
+                    let v = if (x + i * 5) / 12 % 2 == 0 { 40 } else { 210 };
+                    image::Rgba([v, (y * 6) as u8, (i * 25) as u8, 255])
+                })
+            })
+            .collect();
+
+        let converted: Vec<RgbaImage> = process_frames(frames.clone(), &config, true)
+            .map(|art| art.render_image())
+            .collect();
+        assert_eq!(converted.len(), frames.len());
+        for (frame, output) in frames.iter().zip(&converted) {
+            assert_eq!(
+                output,
+                &process_image_preserve_colors(frame, &config).render_image()
+            );
+        }
+
+        assert_eq!(process_frames(Vec::new(), &config, false).count(), 0);
+    }
+
+    #[test]
+    fn test_animated_frames_follow_keyframes() {
+        let animation = Animation::new(&AsciiConfig::default())
+            .keyframe("blend_with_base", 0.0, ParamValue::Float(1.0))
+            .unwrap()
+            .keyframe("blend_with_base", 1.0, ParamValue::Float(0.0))
+            .unwrap();
+        let still = RgbaImage::from_fn(48, 40, |x, y| {
+            image::Rgba([
+                (x * 5) as u8,
+                if (x / 8 + y / 8) % 2 == 0 { 30 } else { 220 },
+                90,
+                255,
+            ])
+        });
+
+        let converted: Vec<RgbaImage> = process_animated_frames(
+            std::iter::repeat_n(still.clone(), 9),
+            &animation,
+            8.0,
+            false,
+        )
+        .map(|art| art.render_image())
+        .collect();
+        assert_eq!(converted.len(), 9);
+        // A full blend shows the photo, no blend the plain conversion
+        assert_eq!(converted[0], still);
+        for (i, output) in converted.iter().enumerate() {
+            let config = animation.config_at(i as f64 / 8.0);
+            assert_eq!(output, &process_image(&still, &config).render_image());
+        }
+    }
+}
@@ -0,0 +1,553 @@
+//! Glyph bitmaps loaded from textures
+//!
+//! The image output draws characters from hand-coded 8×8 patterns. A
+//! [`GlyphAtlas`] replaces them with glyphs sliced from a texture such as the
+//! `fillASCII.png` and `edgesASCII.png` strips of the original Acerola shader:
+//! white glyphs on black (or transparent) cells of 8×8 pixels, laid out left to
+//! right and then top to bottom. Each cell is assigned the character at the
+//! same position in the list passed to [`GlyphAtlas::from_png`]; characters the
+//! atlas does not contain keep their built-in pattern.
+//!
+//! With the `font` feature, [`GlyphAtlas::from_font`] rasterizes the glyphs
+//! from a TTF/OTF font instead, at any cell size. Glyphs are scaled to the
+//! tile, so a 16×32 atlas keeps its detail with `tile_size = 16` and
+//! `tile_height = 32`.
+//!
+//! Classic bitmap fonts load with [`GlyphAtlas::from_bdf`], one cell per
+//! font bounding box, so an 8×8 font such as the IBM VGA one maps pixel for
+//! pixel onto the default tiles. PCF fonts can be converted with `pcf2bdf`.
+//!
+//! [`sort_by_density`] orders a character set into a fill ramp by the ink
+//! coverage of its glyphs, from an atlas or the built-in bitmaps.
+//!
+//! ```no_run
+//! use ascii_rendr::glyphs::GlyphAtlas;
+//! use ascii_rendr::lut::FILL_CHARS;
+//! use ascii_rendr::{AsciiConfig, process_image};
+//!
+//! // One cell per ramp level, darkest first
+//! let mut atlas = GlyphAtlas::from_png("fillASCII.png", &FILL_CHARS).unwrap();
+//! atlas
+//!     .extend(&GlyphAtlas::from_png("edgesASCII.png", &['|', '-', '/', '\\']).unwrap())
+//!     .unwrap();
+//!
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! let art = process_image(&input, &AsciiConfig::default());
+//! art.render(&atlas).save("photo_ascii.png").unwrap();
+//! ```
+
+use crate::art::AsciiArt;
+use crate::ascii::should_draw_pixel;
+use crate::render::renderer::TileRenderer;
+use image::RgbaImage;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Side of a texture cell in pixels
+pub const ATLAS_CELL_SIZE: u32 = 8;
+
+/// Coverage at or above which a glyph pixel counts as drawn
+const COVERAGE_THRESHOLD: u8 = 128;
+
+/// Character bitmaps of one cell size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlyphAtlas {
+    cell_width: u32,
+    cell_height: u32,
+    /// Coverage (0-255) per glyph pixel, row-major
+    glyphs: HashMap<char, Vec<u8>>,
+}
+
+impl GlyphAtlas {
+    /// Empty atlas for glyphs of `cell_width`×`cell_height` pixels
+    pub fn new(cell_width: u32, cell_height: u32) -> Self {
+        Self {
+            cell_width,
+            cell_height,
+            glyphs: HashMap::new(),
+        }
+    }
+
+    /// Loads a texture of 8×8 cells and assigns `chars` to them in order
+    ///
+    /// See [`GlyphAtlas::from_image`].
+    pub fn from_png(path: impl AsRef<Path>, chars: &[char]) -> Result<Self, String> {
+        let path = path.as_ref();
+        let texture = image::open(path)
+            .map_err(|e| format!("{}: {}", path.display(), e))?
+            .to_rgba8();
+        Self::from_image(&texture, chars).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Slices `texture` into 8×8 cells, left to right and then top to bottom,
+    /// and assigns `chars` to them in order
+    ///
+    /// A pixel's coverage is its brightness scaled by its alpha, so white
+    /// glyphs on black or on a transparent background both work. Fails if the
+    /// texture dimensions are not multiples of 8 or it has fewer cells than
+    /// `chars`.
+    pub fn from_image(texture: &RgbaImage, chars: &[char]) -> Result<Self, String> {
+        let (width, height) = texture.dimensions();
+        if width == 0
+            || height == 0
+            || !width.is_multiple_of(ATLAS_CELL_SIZE)
+            || !height.is_multiple_of(ATLAS_CELL_SIZE)
+        {
+            return Err(format!(
+                "glyph texture must be a non-empty multiple of {0}×{0} pixels, got {1}×{2}",
+                ATLAS_CELL_SIZE, width, height
+            ));
+        }
+        let columns = width / ATLAS_CELL_SIZE;
+        let cells = (columns * (height / ATLAS_CELL_SIZE)) as usize;
+        if chars.len() > cells {
+            return Err(format!(
+                "glyph texture has {} cells, but {} characters were given",
+                cells,
+                chars.len()
+            ));
+        }
+
+        let mut atlas = Self::new(ATLAS_CELL_SIZE, ATLAS_CELL_SIZE);
+        for (index, &ch) in chars.iter().enumerate() {
+            let (x0, y0) = (
+                index as u32 % columns * ATLAS_CELL_SIZE,
+                index as u32 / columns * ATLAS_CELL_SIZE,
+            );
+            let mut coverage = Vec::with_capacity((ATLAS_CELL_SIZE * ATLAS_CELL_SIZE) as usize);
+            for y in y0..y0 + ATLAS_CELL_SIZE {
+                for x in x0..x0 + ATLAS_CELL_SIZE {
+                    let [r, g, b, a] = texture.get_pixel(x, y).0;
+                    let luma = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+                    coverage.push((luma * a as u32 / 255) as u8);
+                }
+            }
+            atlas.glyphs.insert(ch, coverage);
+        }
+        Ok(atlas)
+    }
+
+    /// Reads a TTF/OTF font and rasterizes `chars` into
+    /// `cell_width`×`cell_height` cells
+    ///
+    /// See [`GlyphAtlas::from_font`].
+    #[cfg(feature = "font")]
+    pub fn from_font_file(
+        path: impl AsRef<Path>,
+        cell_width: u32,
+        cell_height: u32,
+        chars: &[char],
+    ) -> Result<Self, String> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::from_font(&data, cell_width, cell_height, chars)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Rasterizes `chars` from TTF/OTF font data into
+    /// `cell_width`×`cell_height` cells
+    ///
+    /// The font is sized so one line (ascent to descent) fills the cell
+    /// height, or smaller if its widest glyph would overflow the cell width.
+    /// Glyphs sit on a shared baseline, centered on their advance; characters
+    /// the font lacks are left out so they keep their built-in pattern.
+    #[cfg(feature = "font")]
+    pub fn from_font(
+        data: &[u8],
+        cell_width: u32,
+        cell_height: u32,
+        chars: &[char],
+    ) -> Result<Self, String> {
+        if cell_width == 0 || cell_height == 0 {
+            return Err(format!(
+                "glyph cells must be non-empty, got {}×{}",
+                cell_width, cell_height
+            ));
+        }
+        let font = fontdue::Font::from_bytes(data, fontdue::FontSettings::default())
+            .map_err(|e| e.to_string())?;
+        let chars: Vec<char> = chars
+            .iter()
+            .copied()
+            .filter(|&ch| font.lookup_glyph_index(ch) != 0)
+            .collect();
+
+        let line = font
+            .horizontal_line_metrics(1.0)
+            .ok_or("font has no horizontal metrics")?;
+        let mut px = cell_height as f32 / (line.ascent - line.descent);
+        let widest = chars
+            .iter()
+            .map(|&ch| font.metrics(ch, px).advance_width)
+            .fold(0.0, f32::max);
+        if widest > cell_width as f32 {
+            px *= cell_width as f32 / widest;
+        }
+        let line = font
+            .horizontal_line_metrics(px)
+            .ok_or("font has no horizontal metrics")?;
+        // Leftover height is split above and below the line
+        let baseline = ((cell_height as f32 - (line.ascent - line.descent)) / 2.0 + line.ascent)
+            .round() as i32;
+
+        let mut atlas = Self::new(cell_width, cell_height);
+        for ch in chars {
+            let (metrics, bitmap) = font.rasterize(ch, px);
+            let left =
+                ((cell_width as f32 - metrics.advance_width) / 2.0).round() as i32 + metrics.xmin;
+            let top = baseline - metrics.ymin - metrics.height as i32;
+            let mut coverage = vec![0; (cell_width * cell_height) as usize];
+            for (index, &value) in bitmap.iter().enumerate() {
+                let x = left + (index % metrics.width) as i32;
+                let y = top + (index / metrics.width) as i32;
+                if (0..cell_width as i32).contains(&x) && (0..cell_height as i32).contains(&y) {
+                    coverage[(y as u32 * cell_width + x as u32) as usize] = value;
+                }
+            }
+            atlas.glyphs.insert(ch, coverage);
+        }
+        Ok(atlas)
+    }
+
+    /// Loads every glyph of a BDF bitmap font
+    ///
+    /// See [`GlyphAtlas::from_bdf_str`].
+    pub fn from_bdf(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let source =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::from_bdf_str(&source).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Parses a BDF bitmap font into cells the size of its `FONTBOUNDINGBOX`
+    ///
+    /// Glyphs are placed on the font's baseline by their `BBX` offsets and
+    /// clipped to the cell. `ENCODING` values are read as Unicode code points,
+    /// which holds for ISO 10646 and ISO 8859-1 fonts; unencoded glyphs are
+    /// skipped.
+    pub fn from_bdf_str(source: &str) -> Result<Self, String> {
+        let mut lines = source
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()));
+        let parse = |line: usize, field: Option<&str>| -> Result<i32, String> {
+            field
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| format!("line {}: expected a number", line))
+        };
+
+        // Font bounding box: width, height and offset of its bottom-left corner
+        let mut bounds = None;
+        for (number, line) in lines.by_ref() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("FONTBOUNDINGBOX") => {
+                    let mut values = [0; 4];
+                    for value in &mut values {
+                        *value = parse(number, fields.next())?;
+                    }
+                    bounds = Some(values);
+                }
+                Some("CHARS") => break,
+                _ => {}
+            }
+        }
+        let [cell_w, cell_h, font_x, font_y] = bounds.ok_or("missing FONTBOUNDINGBOX")?;
+        if cell_w <= 0 || cell_h <= 0 {
+            return Err(format!(
+                "FONTBOUNDINGBOX must be non-empty, got {}×{}",
+                cell_w, cell_h
+            ));
+        }
+        // Row of the baseline, counted from the top of the cell
+        let baseline = cell_h + font_y;
+
+        let mut atlas = Self::new(cell_w as u32, cell_h as u32);
+        let mut ch = None;
+        let mut bbx = [0; 4];
+        while let Some((number, line)) = lines.next() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("STARTCHAR") => {
+                    ch = None;
+                    bbx = [cell_w, cell_h, font_x, font_y];
+                }
+                Some("ENCODING") => {
+                    ch = u32::try_from(parse(number, fields.next())?)
+                        .ok()
+                        .and_then(char::from_u32);
+                }
+                Some("BBX") => {
+                    for value in &mut bbx {
+                        *value = parse(number, fields.next())?;
+                    }
+                }
+                Some("BITMAP") => {
+                    let [width, height, x_offset, y_offset] = bbx;
+                    let left = x_offset - font_x;
+                    let top = baseline - y_offset - height;
+                    let mut coverage = vec![0; (cell_w * cell_h) as usize];
+                    for row in 0..height.max(0) {
+                        let (number, hex) = lines
+                            .next()
+                            .ok_or_else(|| format!("line {}: truncated BITMAP", number))?;
+                        let nibbles = hex
+                            .chars()
+                            .map(|digit| digit.to_digit(16))
+                            .collect::<Option<Vec<u32>>>()
+                            .ok_or_else(|| {
+                                format!("line {}: invalid bitmap row {:?}", number, hex)
+                            })?;
+                        let row_bits = nibbles.len() as i32 * 4;
+                        for column in 0..width.min(row_bits) {
+                            let (x, y) = (left + column, top + row);
+                            let nibble = nibbles[(column / 4) as usize];
+                            if nibble >> (3 - column % 4) & 1 == 1
+                                && (0..cell_w).contains(&x)
+                                && (0..cell_h).contains(&y)
+                            {
+                                coverage[(y * cell_w + x) as usize] = 255;
+                            }
+                        }
+                    }
+                    if let Some(ch) = ch {
+                        atlas.glyphs.insert(ch, coverage);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(atlas)
+    }
+
+    /// Width and height of a glyph in pixels
+    pub fn cell_size(&self) -> (u32, u32) {
+        (self.cell_width, self.cell_height)
+    }
+
+    /// Whether the atlas has a glyph for `ch`
+    pub fn contains(&self, ch: char) -> bool {
+        self.glyphs.contains_key(&ch)
+    }
+
+    /// Adds or replaces the glyph of `ch`, one coverage value (0-255) per
+    /// pixel, row-major
+    pub fn insert(&mut self, ch: char, coverage: Vec<u8>) -> Result<(), String> {
+        let expected = (self.cell_width * self.cell_height) as usize;
+        if coverage.len() != expected {
+            return Err(format!(
+                "glyph for {:?} has {} pixels, expected {}",
+                ch,
+                coverage.len(),
+                expected
+            ));
+        }
+        self.glyphs.insert(ch, coverage);
+        Ok(())
+    }
+
+    /// Adds the glyphs of `other`, replacing those of the same characters
+    ///
+    /// Fails if the cell sizes differ.
+    pub fn extend(&mut self, other: &GlyphAtlas) -> Result<(), String> {
+        if other.cell_size() != self.cell_size() {
+            return Err(format!(
+                "cannot merge {}×{} glyphs into a {}×{} atlas",
+                other.cell_width, other.cell_height, self.cell_width, self.cell_height
+            ));
+        }
+        self.glyphs.extend(
+            other
+                .glyphs
+                .iter()
+                .map(|(&ch, coverage)| (ch, coverage.clone())),
+        );
+        Ok(())
+    }
+
+    /// Coverage of pixel (`x`, `y`) of the glyph for `ch`, or None if the
+    /// atlas has no such glyph or the pixel is outside the cell
+    pub fn coverage(&self, ch: char, x: u32, y: u32) -> Option<u8> {
+        if x >= self.cell_width || y >= self.cell_height {
+            return None;
+        }
+        let glyph = self.glyphs.get(&ch)?;
+        Some(glyph[(y * self.cell_width + x) as usize])
+    }
+
+    /// Whether pixel (`x`, `y`) of the glyph for `ch` is drawn, or None if the
+    /// atlas has no such glyph
+    pub fn is_drawn(&self, ch: char, x: u32, y: u32) -> Option<bool> {
+        self.coverage(ch, x, y)
+            .map(|coverage| coverage >= COVERAGE_THRESHOLD)
+    }
+}
+
+/// Share of a character's glyph pixels that are drawn, from 0.0 (blank) to
+/// 1.0 (solid)
+///
+/// Uses the glyph of `atlas` when it has one and the built-in 8×8 bitmap
+/// otherwise, so the result matches what image output draws.
+pub fn ink_coverage(ch: char, atlas: Option<&GlyphAtlas>) -> f32 {
+    let (width, height) = match atlas {
+        Some(atlas) if atlas.contains(ch) => atlas.cell_size(),
+        _ => (ATLAS_CELL_SIZE, ATLAS_CELL_SIZE),
+    };
+    let mut drawn = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = atlas
+                .and_then(|atlas| atlas.is_drawn(ch, x, y))
+                .unwrap_or_else(|| should_draw_pixel(ch, x, y));
+            drawn += pixel as u32;
+        }
+    }
+    drawn as f32 / (width * height).max(1) as f32
+}
+
+/// Sorts `chars` into a fill ramp, from the sparsest glyph to the densest
+///
+/// Densities come from [`ink_coverage`]; characters of equal density keep
+/// their order. Custom character sets then need no hand ordering.
+pub fn sort_by_density(chars: &mut [char], atlas: Option<&GlyphAtlas>) {
+    let mut ramp: Vec<(f32, char)> = chars
+        .iter()
+        .map(|&ch| (ink_coverage(ch, atlas), ch))
+        .collect();
+    ramp.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for (slot, (_, ch)) in chars.iter_mut().zip(ramp) {
+        *slot = ch;
+    }
+}
+
+impl TileRenderer for GlyphAtlas {
+    type Output = RgbaImage;
+
+    fn render(&self, art: &AsciiArt) -> RgbaImage {
+        art.render_image_with_glyphs(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AsciiConfig;
+    use crate::process_image;
+    use image::Rgba;
+
+    /// Two cells: a full block, then a left bar drawn in opaque white on a
+    /// transparent background
+    fn texture() -> RgbaImage {
+        RgbaImage::from_fn(16, 8, |x, _| {
+            if x <= 8 {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([255, 255, 255, 0])
+            }
+        })
+    }
+
+    #[test]
+    fn test_from_image() {
+        let atlas = GlyphAtlas::from_image(&texture(), &['@', '|']).unwrap();
+        assert_eq!(atlas.cell_size(), (8, 8));
+        assert!(atlas.contains('@') && !atlas.contains('.'));
+        assert_eq!(atlas.is_drawn('@', 5, 5), Some(true));
+        assert_eq!(atlas.is_drawn('|', 0, 3), Some(true));
+        assert_eq!(atlas.is_drawn('|', 1, 3), Some(false));
+        assert_eq!(atlas.coverage('.', 0, 0), None);
+        assert_eq!(atlas.coverage('|', 8, 0), None);
+
+        assert!(GlyphAtlas::from_image(&texture(), &['a', 'b', 'c']).is_err());
+        assert!(GlyphAtlas::from_image(&RgbaImage::new(12, 8), &['a']).is_err());
+    }
+
+    #[test]
+    fn test_extend_and_insert() {
+        let mut atlas = GlyphAtlas::from_image(&texture(), &['@']).unwrap();
+        atlas
+            .extend(&GlyphAtlas::from_image(&texture(), &['#', '@']).unwrap())
+            .unwrap();
+        assert!(atlas.contains('#'));
+        assert_eq!(atlas.is_drawn('@', 4, 0), Some(false));
+
+        assert!(atlas.insert('x', vec![255; 64]).is_ok());
+        assert!(atlas.insert('x', vec![255; 63]).is_err());
+        assert!(atlas.extend(&GlyphAtlas::new(16, 16)).is_err());
+    }
+
+    #[test]
+    fn test_render_with_atlas() {
+        // Bright flat input: every tile is '@'
+        let input = RgbaImage::from_pixel(16, 16, Rgba([255, 255, 255, 255]));
+        let art = process_image(&input, &AsciiConfig::default());
+        assert!(art.chars().iter().all(|&c| c == '@'));
+
+        // '@' drawn as a solid block
+        let atlas = GlyphAtlas::from_image(&texture(), &['@']).unwrap();
+        let image = art.render(&atlas);
+        assert_eq!(image.dimensions(), (16, 16));
+        assert!(image.pixels().all(|p| p.0 == [255, 255, 255, 255]));
+
+        // Characters outside the atlas keep their built-in glyphs
+        let other = GlyphAtlas::from_image(&texture(), &['#']).unwrap();
+        assert_eq!(art.render(&other), art.render_image());
+    }
+
+    #[test]
+    fn test_sort_by_density() {
+        // Built-in bitmaps
+        let mut chars = ['@', ' ', '+', '.'];
+        sort_by_density(&mut chars, None);
+        assert_eq!(chars, [' ', '.', '+', '@']);
+        assert_eq!(ink_coverage(' ', None), 0.0);
+
+        // The atlas glyph of '.' is a solid block, and '|' a one pixel bar
+        let atlas = GlyphAtlas::from_image(&texture(), &['.', '|']).unwrap();
+        assert_eq!(ink_coverage('.', Some(&atlas)), 1.0);
+        assert_eq!(ink_coverage('|', Some(&atlas)), 0.125);
+        let mut chars = ['.', '+', '|'];
+        sort_by_density(&mut chars, Some(&atlas));
+        assert_eq!(chars, ['|', '+', '.']);
+    }
+
+    #[test]
+    fn test_from_bdf_str() {
+        let bdf = "STARTFONT 2.1
+FONTBOUNDINGBOX 4 4 0 -1
+CHARS 2
+STARTCHAR underscore
+ENCODING 95
+BBX 4 1 0 -1
+BITMAP
+F0
+ENDCHAR
+STARTCHAR unencoded
+ENCODING -1
+BBX 4 4 0 -1
+BITMAP
+F0
+F0
+F0
+F0
+ENDCHAR
+ENDFONT
+";
+        let atlas = GlyphAtlas::from_bdf_str(bdf).unwrap();
+        assert_eq!(atlas.cell_size(), (4, 4));
+        assert_eq!(atlas.glyphs.len(), 1);
+        // One row below the baseline, at the bottom of the cell
+        assert_eq!(atlas.is_drawn('_', 3, 3), Some(true));
+        assert_eq!(atlas.is_drawn('_', 0, 2), Some(false));
+
+        assert!(GlyphAtlas::from_bdf_str("STARTFONT 2.1\nCHARS 0\n").is_err());
+        assert!(GlyphAtlas::from_bdf_str(&bdf.replace("F0\nENDCHAR", "XY\nENDCHAR")).is_err());
+    }
+
+    #[cfg(feature = "font")]
+    #[test]
+    fn test_from_font_invalid() {
+        assert!(GlyphAtlas::from_font(b"not a font", 8, 16, &['a']).is_err());
+        assert!(GlyphAtlas::from_font(&[], 0, 16, &['a']).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+//! GStreamer video filter element (`asciirendr`)
+//!
+//! Wraps the processing pipeline in an RGBA-in/RGBA-out `VideoFilter` so it can
+//! be dropped into existing media pipelines. Every config field is exposed as a
+//! GObject property with dashes instead of underscores (`edge-threshold`,
+//! `ascii-color`, ...). Optional numeric fields use `-1` for "off", colors are
+//! `#rrggbb` strings and choices use their config-file names (an empty string
+//! switches an optional choice off).
+//!
+//! ```text
+//! gst-launch-1.0 v4l2src ! videoconvert ! asciirendr sigma=1.5 preserve-colors=true \
+//!     ! videoconvert ! autovideosink
+//! ```
+//!
+//! Applications can call [`register`] with `None` to use the element without
+//! installing the plugin; `gst-plugin/` builds the loadable plugin library.
+//!
+//! Frames whose dimensions are not multiples of the tile size are processed on
+//! the top-left region that is, and the remaining strip is filled with the
+//! background color, so output caps always match input caps.
+
+use gstreamer as gst;
+use gstreamer::glib;
+use gstreamer::prelude::*;
+use gstreamer_video as gst_video;
+
+mod imp {
+    use super::*;
+    use crate::config::AsciiConfig;
+    use crate::params::{PARAMS, ParamKind, ParamValue, param_info};
+    use crate::processor::Processor;
+    use gst::subclass::prelude::*;
+    use gst_video::subclass::prelude::*;
+    use gstreamer_base::subclass::BaseTransformMode;
+    use image::RgbaImage;
+    use std::sync::{LazyLock, Mutex};
+
+    static CAT: LazyLock<gst::DebugCategory> = LazyLock::new(|| {
+        gst::DebugCategory::new(
+            "asciirendr",
+            gst::DebugColorFlags::empty(),
+            Some("ASCII art video filter"),
+        )
+    });
+
+    #[derive(Default)]
+    struct Settings {
+        config: AsciiConfig,
+        preserve_colors: bool,
+    }
+
+    /// Streaming state, created on the first frame
+    struct State {
+        processor: Processor,
+        /// Cropped input frame, reused between frames
+        input: RgbaImage,
+    }
+
+    #[derive(Default)]
+    pub struct AsciiRendrFilter {
+        settings: Mutex<Settings>,
+        state: Mutex<Option<State>>,
+    }
+
+    impl AsciiRendrFilter {
+        /// Hands changed settings to the processor, if it exists yet
+        ///
+        /// Callers hold the settings lock; it is always taken before the state lock.
+        fn update_processor(&self, settings: &Settings) {
+            if let Some(state) = self.state.lock().unwrap().as_mut() {
+                state.processor.set_config(&settings.config);
+                state
+                    .processor
+                    .set_preserve_colors(settings.preserve_colors);
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AsciiRendrFilter {
+        const NAME: &'static str = "GstAsciiRendr";
+        type Type = super::AsciiRendrFilter;
+        type ParentType = gst_video::VideoFilter;
+    }
+
+    /// GObject property name for a config field
+    fn property_name(field: &str) -> String {
+        field.replace('_', "-")
+    }
+
+    impl ObjectImpl for AsciiRendrFilter {
+        fn properties() -> &'static [glib::ParamSpec] {
+            static PROPERTIES: LazyLock<Vec<glib::ParamSpec>> = LazyLock::new(|| {
+                let mut properties = vec![
+                    glib::ParamSpecBoolean::builder("preserve-colors")
+                        .nick("Preserve colors")
+                        .blurb("Keep colors from the source frame instead of ascii-color")
+                        .default_value(false)
+                        .mutable_playing()
+                        .build(),
+                ];
+
+                // One property per config field, generated from the parameter metadata
+                for info in PARAMS {
+                    let name = property_name(info.name);
+                    let spec = match (info.kind, info.default_value()) {
+                        (ParamKind::Int { min, max }, default) => {
+                            let default = match default {
+                                ParamValue::Int(v) => v as i32,
+                                _ => -1,
+                            };
+                            glib::ParamSpecInt::builder(&name)
+                                .blurb(info.doc)
+                                .minimum(if info.optional { -1 } else { min as i32 })
+                                .maximum(max as i32)
+                                .default_value(default)
+                                .mutable_playing()
+                                .build()
+                        }
+                        (ParamKind::Float { min, max }, default) => {
+                            let default = match default {
+                                ParamValue::Float(v) => v,
+                                _ => -1.0,
+                            };
+                            glib::ParamSpecDouble::builder(&name)
+                                .blurb(info.doc)
+                                .minimum(if info.optional { -1.0 } else { min })
+                                .maximum(max)
+                                .default_value(default)
+                                .mutable_playing()
+                                .build()
+                        }
+                        (ParamKind::Bool, ParamValue::Bool(default)) => {
+                            glib::ParamSpecBoolean::builder(&name)
+                                .blurb(info.doc)
+                                .default_value(default)
+                                .mutable_playing()
+                                .build()
+                        }
+                        _ => glib::ParamSpecString::builder(&name)
+                            .blurb(info.doc)
+                            .mutable_playing()
+                            .build(),
+                    };
+                    properties.push(spec);
+                }
+
+                properties
+            });
+
+            PROPERTIES.as_ref()
+        }
+
+        fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            let mut settings = self.settings.lock().unwrap();
+
+            if pspec.name() == "preserve-colors" {
+                settings.preserve_colors = value.get().expect("type checked upstream");
+                self.update_processor(&settings);
+                return;
+            }
+
+            let field = pspec.name().replace('-', "_");
+            let Some(info) = param_info(&field) else {
+                return;
+            };
+
+            let param = match info.kind {
+                ParamKind::Int { .. } => {
+                    let v: i32 = value.get().expect("type checked upstream");
+                    if info.optional && v < 0 {
+                        ParamValue::Unset
+                    } else {
+                        ParamValue::Int(v as i64)
+                    }
+                }
+                ParamKind::Float { .. } => {
+                    let v: f64 = value.get().expect("type checked upstream");
+                    if info.optional && v < 0.0 {
+                        ParamValue::Unset
+                    } else {
+                        ParamValue::Float(v)
+                    }
+                }
+                ParamKind::Bool => ParamValue::Bool(value.get().expect("type checked upstream")),
+                ParamKind::Color | ParamKind::Choice { .. } => {
+                    // A missing or empty string switches optional choices off
+                    let text: Option<String> = value.get().expect("type checked upstream");
+                    match info.parse_value(text.as_deref().unwrap_or("")) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            gst::warning!(CAT, imp = self, "{}", e);
+                            return;
+                        }
+                    }
+                }
+            };
+
+            if let Err(e) = settings.config.set_param(info.name, param) {
+                gst::warning!(CAT, imp = self, "{}", e);
+                return;
+            }
+
+            self.update_processor(&settings);
+        }
+
+        fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            let settings = self.settings.lock().unwrap();
+
+            if pspec.name() == "preserve-colors" {
+                return settings.preserve_colors.to_value();
+            }
+
+            let field = pspec.name().replace('-', "_");
+            match settings.config.get_param(&field) {
+                Some(ParamValue::Int(v)) => (v as i32).to_value(),
+                // Must match the property type: -1 for numbers, no string otherwise
+                Some(ParamValue::Unset) => match param_info(&field).map(|info| info.kind) {
+                    Some(ParamKind::Int { .. }) => (-1i32).to_value(),
+                    Some(ParamKind::Float { .. }) => (-1.0f64).to_value(),
+                    _ => None::<String>.to_value(),
+                },
+                Some(ParamValue::Float(v)) => v.to_value(),
+                Some(ParamValue::Bool(v)) => v.to_value(),
+                Some(ParamValue::Color([r, g, b])) => {
+                    format!("#{:02x}{:02x}{:02x}", r, g, b).to_value()
+                }
+                Some(ParamValue::Choice(v)) => v.to_value(),
+                None => unreachable!("property without matching config field"),
+            }
+        }
+    }
+
+    impl GstObjectImpl for AsciiRendrFilter {}
+
+    impl ElementImpl for AsciiRendrFilter {
+        fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+            static ELEMENT_METADATA: LazyLock<gst::subclass::ElementMetadata> =
+                LazyLock::new(|| {
+                    gst::subclass::ElementMetadata::new(
+                        "ASCII renderer",
+                        "Filter/Effect/Video",
+                        "Renders video frames as ASCII art (Acerola shader algorithm)",
+                        "elwqnn",
+                    )
+                });
+
+            Some(&*ELEMENT_METADATA)
+        }
+
+        fn pad_templates() -> &'static [gst::PadTemplate] {
+            static PAD_TEMPLATES: LazyLock<Vec<gst::PadTemplate>> = LazyLock::new(|| {
+                let caps = gst_video::VideoCapsBuilder::new()
+                    .format(gst_video::VideoFormat::Rgba)
+                    .build();
+                vec![
+                    gst::PadTemplate::new(
+                        "src",
+                        gst::PadDirection::Src,
+                        gst::PadPresence::Always,
+                        &caps,
+                    )
+                    .unwrap(),
+                    gst::PadTemplate::new(
+                        "sink",
+                        gst::PadDirection::Sink,
+                        gst::PadPresence::Always,
+                        &caps,
+                    )
+                    .unwrap(),
+                ]
+            });
+
+            PAD_TEMPLATES.as_ref()
+        }
+    }
+
+    impl BaseTransformImpl for AsciiRendrFilter {
+        const MODE: BaseTransformMode = BaseTransformMode::NeverInPlace;
+        const PASSTHROUGH_ON_SAME_CAPS: bool = false;
+        const TRANSFORM_IP_ON_PASSTHROUGH: bool = false;
+
+        fn stop(&self) -> Result<(), gst::ErrorMessage> {
+            // Release the frame buffers
+            *self.state.lock().unwrap() = None;
+            Ok(())
+        }
+    }
+
+    impl VideoFilterImpl for AsciiRendrFilter {
+        fn transform_frame(
+            &self,
+            in_frame: &gst_video::VideoFrameRef<&gst::BufferRef>,
+            out_frame: &mut gst_video::VideoFrameRef<&mut gst::BufferRef>,
+        ) -> Result<gst::FlowSuccess, gst::FlowError> {
+            let settings = self.settings.lock().unwrap();
+            let mut guard = self.state.lock().unwrap();
+            let state = guard.get_or_insert_with(|| State {
+                processor: Processor::new(&settings.config, settings.preserve_colors),
+                input: RgbaImage::new(0, 0),
+            });
+            // Property changes reach the processor through `update_processor`
+            drop(settings);
+            let config = state.processor.config();
+
+            let width = in_frame.width() as usize;
+            let height = in_frame.height() as usize;
+            let in_stride = in_frame.plane_stride()[0] as usize;
+            let out_stride = out_frame.plane_stride()[0] as usize;
+            let in_data = in_frame.plane_data(0).map_err(|_| gst::FlowError::Error)?;
+
+            // Largest top-left region whose dimensions are multiples of the tile size
+            let (tile_w, tile_h) = config.tile_dimensions();
+            let crop_width = width / tile_w as usize * tile_w as usize;
+            let crop_height = height / tile_h as usize * tile_h as usize;
+
+            let bg = [
+                config.bg_color[0],
+                config.bg_color[1],
+                config.bg_color[2],
+                255,
+            ];
+
+            let output = if crop_width == 0 || crop_height == 0 {
+                None
+            } else {
+                if state.input.dimensions() != (crop_width as u32, crop_height as u32) {
+                    state.input = RgbaImage::new(crop_width as u32, crop_height as u32);
+                }
+                for (y, row) in state.input.chunks_exact_mut(crop_width * 4).enumerate() {
+                    let start = y * in_stride;
+                    row.copy_from_slice(&in_data[start..start + crop_width * 4]);
+                }
+
+                Some(state.processor.process(&state.input).render_image())
+            };
+
+            let out_data = out_frame
+                .plane_data_mut(0)
+                .map_err(|_| gst::FlowError::Error)?;
+
+            for y in 0..height {
+                let row = &mut out_data[y * out_stride..y * out_stride + width * 4];
+                for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                    let color = match &output {
+                        Some(img) if x < crop_width && y < crop_height => {
+                            img.get_pixel(x as u32, y as u32).0
+                        }
+                        _ => bg,
+                    };
+                    pixel.copy_from_slice(&color);
+                }
+            }
+
+            Ok(gst::FlowSuccess::Ok)
+        }
+    }
+}
+
+glib::wrapper! {
+    /// The `asciirendr` video filter element
+    pub struct AsciiRendrFilter(ObjectSubclass<imp::AsciiRendrFilter>)
+        @extends gst_video::VideoFilter, gstreamer_base::BaseTransform, gst::Element, gst::Object;
+}
+
+/// Registers the `asciirendr` element
+///
+/// Pass the plugin from a `plugin_init` function, or `None` to register the
+/// element statically inside an application.
+pub fn register(plugin: Option<&gst::Plugin>) -> Result<(), glib::BoolError> {
+    gst::Element::register(
+        plugin,
+        "asciirendr",
+        gst::Rank::NONE,
+        AsciiRendrFilter::static_type(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::{PARAMS, ParamKind};
+
+    #[test]
+    fn every_param_round_trips_through_its_property() {
+        gst::init().unwrap();
+        let element = glib::Object::new::<AsciiRendrFilter>();
+
+        for info in PARAMS {
+            let name = info.name.replace('_', "-");
+            let default = element.property_value(&name);
+            element.set_property_from_value(&name, &default);
+            assert_eq!(
+                format!("{:?}", element.property_value(&name)),
+                format!("{:?}", default),
+                "{}",
+                name
+            );
+
+            if info.optional {
+                let off = match info.kind {
+                    ParamKind::Int { .. } => (-1i32).to_value(),
+                    ParamKind::Float { .. } => (-1.0f64).to_value(),
+                    _ => None::<String>.to_value(),
+                };
+                element.set_property_from_value(&name, &off);
+                assert_eq!(
+                    format!("{:?}", element.property_value(&name)),
+                    format!("{:?}", off),
+                    "{}",
+                    name
+                );
+            }
+        }
+    }
+}
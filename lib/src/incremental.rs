@@ -0,0 +1,756 @@
+//! Incremental reprocessing of changed image regions
+//!
+//! [`AsciiProcessor`] keeps the source image, its luminance, the character
+//! layers and the rendered output between calls. After part of the source
+//! changes (a brush stroke, a damaged area of a screen capture),
+//! [`AsciiProcessor::reprocess_region`] reruns the blur, DoG, Sobel and voting
+//! passes only for the tiles the change can reach and patches those tiles of
+//! the output, giving the same result as converting the whole image again.
+//!
+//! For video, [`AsciiProcessor::next_frame`] finds the tiles whose pixels
+//! differ from the previous frame and reprocesses just those; tiles of a
+//! static background keep their glyphs and colors.
+//!
+//! ```no_run
+//! use ascii_rendr::{AsciiConfig, AsciiProcessor};
+//! use ascii_rendr::incremental::Rect;
+//!
+//! let input = image::open("canvas.png").unwrap().to_rgba8();
+//! let mut processor = AsciiProcessor::new(&input, &AsciiConfig::default(), false);
+//!
+//! // Paint a white square, then update just that part of the output
+//! for y in 40..60 {
+//!     for x in 40..60 {
+//!         processor.image_mut().put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+//!     }
+//! }
+//! let updated = processor.reprocess_region(Rect::new(40, 40, 20, 20));
+//! println!("redrew {:?}", updated);
+//! ```
+
+use crate::ascii::select_edge_chars;
+use crate::braille::process_braille;
+use crate::config::{AsciiConfig, BlurMode, LuminanceAdjust, OutputMode, PreFilter, ThresholdMode};
+use crate::edges::{EdgeDirection, tile_edge_offsets};
+use crate::filters::box_blur_radii;
+use crate::processor::{
+    CharLayers, blend_with_base, compute_char_layers, compute_edge_gradients, compute_fill_layer,
+    compute_fill_layer_at, luminance, resize_to_valid_dimensions, tile_edge_thresholds,
+    vote_edge_directions, working_multiple,
+};
+use image::{GrayImage, RgbaImage, imageops};
+
+/// Axis-aligned rectangle in pixel coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// Rectangle with its top-left corner at (`x`, `y`)
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Whether the rectangle covers no pixels
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// This rectangle extended by `margin` on every side, within a
+    /// `width`×`height` image
+    pub(crate) fn grow(&self, margin: u32, width: u32, height: u32) -> Rect {
+        let x = self.x.saturating_sub(margin);
+        let y = self.y.saturating_sub(margin);
+        Rect::new(
+            x,
+            y,
+            (self.x + self.width + margin).min(width) - x,
+            (self.y + self.height + margin).min(height) - y,
+        )
+    }
+
+    /// The smallest run of whole tiles of `tile_size` (width, height) covering
+    /// this rectangle
+    pub(crate) fn align_to_tiles(&self, tile_size: (u32, u32)) -> Rect {
+        let (tile_w, tile_h) = tile_size;
+        let x = self.x / tile_w * tile_w;
+        let y = self.y / tile_h * tile_h;
+        Rect::new(
+            x,
+            y,
+            (self.x + self.width).div_ceil(tile_w) * tile_w - x,
+            (self.y + self.height).div_ceil(tile_h) * tile_h - y,
+        )
+    }
+
+    /// Number of pixels covered
+    fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// The smallest rectangle covering both
+    fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect::new(
+            x,
+            y,
+            (self.x + self.width).max(other.x + other.width) - x,
+            (self.y + self.height).max(other.y + other.height) - y,
+        )
+    }
+
+    /// The part of this rectangle inside a `width`×`height` image
+    fn clamp_to(&self, width: u32, height: u32) -> Rect {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+        Rect::new(
+            x,
+            y,
+            self.x.saturating_add(self.width).min(width) - x,
+            self.y.saturating_add(self.height).min(height) - y,
+        )
+    }
+}
+
+/// Converter that remembers its last result for fast partial updates
+///
+/// Regions are in the coordinates of the working image, which is the input
+/// rounded down to multiples of the tile size (and identical to it when the input already
+/// has such dimensions).
+pub struct AsciiProcessor {
+    config: AsciiConfig,
+    preserve_colors: bool,
+    /// Working image the output was converted from
+    image: RgbaImage,
+    /// Luminance of `image`
+    lum: GrayImage,
+    layers: CharLayers,
+    output: RgbaImage,
+}
+
+impl AsciiProcessor {
+    /// Converts `input` in full, keeping the intermediate results
+    ///
+    /// # Arguments
+    /// * `input` - The input RGBA image to convert
+    /// * `config` - Configuration parameters for the ASCII conversion
+    /// * `preserve_colors` - Sample colors from the source image instead of `ascii_color`
+    pub fn new(input: &RgbaImage, config: &AsciiConfig, preserve_colors: bool) -> Self {
+        // Validate config
+        config.validate().expect("Invalid configuration");
+
+        // Automatically resize if dimensions are not multiples of the tile size
+        let image = resize_to_valid_dimensions(input, working_multiple(config));
+        let lum = luminance(&image, config);
+        let layers = compute_char_layers(&image, config);
+        let output = if config.output_mode == OutputMode::Braille {
+            let mut output = process_braille(&lum, config, preserve_colors.then_some(&image));
+            blend_with_base(&mut output, &image, config);
+            output
+        } else {
+            Self::render(&layers, config, &image, preserve_colors)
+        };
+
+        Self {
+            config: config.clone(),
+            preserve_colors,
+            image: image.into_owned(),
+            lum,
+            layers,
+            output,
+        }
+    }
+
+    /// Rendered output, equal to converting the current image in full
+    pub fn output(&self) -> &RgbaImage {
+        &self.output
+    }
+
+    /// Current working image
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    /// Working image for in-place edits; call [`reprocess_region`](Self::reprocess_region)
+    /// with the edited area afterwards
+    pub fn image_mut(&mut self) -> &mut RgbaImage {
+        &mut self.image
+    }
+
+    /// Copies `patch` into the working image at (`x`, `y`) and updates the output
+    ///
+    /// Parts of the patch outside the image are ignored. Returns the area of the
+    /// output that was redrawn.
+    pub fn update(&mut self, x: u32, y: u32, patch: &RgbaImage) -> Rect {
+        imageops::replace(&mut self.image, patch, x as i64, y as i64);
+        self.reprocess_region(Rect::new(x, y, patch.width(), patch.height()))
+    }
+
+    /// Reruns the pipeline for the tiles affected by a change inside `rect`
+    ///
+    /// Edges are recomputed for every tile within blur range of the change, so
+    /// the result matches a full conversion. Settings that tie tiles together
+    /// (`auto_invert`, `local_contrast_radius`, `rgb_split`,
+    /// `saliency_strength`, `pre_filter`, `median_radius`, `luminance_adjust`,
+    /// an automatic `threshold_mode`) and braille mode fall back to processing
+    /// the whole image.
+    ///
+    /// # Returns
+    /// The area of the output that was redrawn (empty when `rect` lies outside
+    /// the image)
+    pub fn reprocess_region(&mut self, rect: Rect) -> Rect {
+        let (width, height) = self.image.dimensions();
+        let rect = rect.clamp_to(width, height);
+        if rect.is_empty() {
+            return rect;
+        }
+
+        if self.needs_full_pass() {
+            *self = Self::new(&self.image, &self.config, self.preserve_colors);
+            return Rect::new(0, 0, width, height);
+        }
+        self.reprocess_rects(&[rect])[0]
+    }
+
+    /// Converts the next frame of a video, redoing only the tiles whose pixels
+    /// differ from the previous frame
+    ///
+    /// The output then equals converting `input` in full. Tiles within blur
+    /// range of a changed tile are reprocessed too, as with
+    /// [`reprocess_region`](Self::reprocess_region), whose whole-image
+    /// settings also fall back to a full pass here when anything changed, as
+    /// do changes spread over most of the frame. A frame of a different size
+    /// starts over.
+    ///
+    /// # Returns
+    /// The areas of the output that were redrawn, none when the frame is
+    /// unchanged
+    pub fn next_frame(&mut self, input: &RgbaImage) -> Vec<Rect> {
+        let frame = resize_to_valid_dimensions(input, working_multiple(&self.config));
+        let (width, height) = frame.dimensions();
+        if self.image.dimensions() != (width, height) {
+            *self = Self::new(&frame, &self.config, self.preserve_colors);
+            return vec![Rect::new(0, 0, width, height)];
+        }
+
+        let changed = changed_tiles(&self.image, &frame, self.config.tile_dimensions());
+        if changed.is_empty() {
+            return Vec::new();
+        }
+        self.image.copy_from_slice(frame.as_raw());
+        if self.needs_full_pass() {
+            *self = Self::new(&self.image, &self.config, self.preserve_colors);
+            return vec![Rect::new(0, 0, width, height)];
+        }
+        self.reprocess_rects(&changed)
+    }
+
+    /// Whether a setting ties tiles together, so no part of the output can be
+    /// updated on its own
+    fn needs_full_pass(&self) -> bool {
+        self.config.auto_invert
+            || self.config.local_contrast_radius.is_some()
+            || self.config.rgb_split.is_some()
+            || self.config.saliency_strength > 0.0
+            || self.config.pre_filter != PreFilter::None
+            || self.config.median_radius.is_some()
+            || self.config.luminance_adjust != LuminanceAdjust::None
+            || self.config.threshold_mode != ThresholdMode::Fixed
+            || self.config.output_mode == OutputMode::Braille
+    }
+
+    /// Reprocesses the tiles affected by changes inside `rects`, which lie
+    /// within the image and are not empty, returning the redrawn areas
+    ///
+    /// Each affected area runs the edge passes on a band around it; once the
+    /// bands add up to half the image, one full pass is cheaper and runs
+    /// instead.
+    fn reprocess_rects(&mut self, rects: &[Rect]) -> Vec<Rect> {
+        let (width, height) = self.image.dimensions();
+
+        // A change reaches tiles up to this far away
+        let margin = edge_reach(&self.config);
+        let (tile_w, tile_h) = self.config.tile_dimensions();
+        let affected: Vec<Rect> = rects
+            .iter()
+            .map(|rect| {
+                rect.grow(margin, width, height)
+                    .align_to_tiles((tile_w, tile_h))
+            })
+            .collect();
+        let band_area = |tiles: &Rect| tiles.grow(margin, width, height).area();
+        if affected.iter().map(band_area).sum::<u64>() * 2 >= width as u64 * height as u64 {
+            *self = Self::new(&self.image, &self.config, self.preserve_colors);
+            return vec![Rect::new(0, 0, width, height)];
+        }
+        let affected = merge_overlapping(affected, band_area);
+
+        // Step 1 for the changed pixels only, before any edges read them
+        for rect in rects {
+            let changed = imageops::crop_imm(&self.image, rect.x, rect.y, rect.width, rect.height);
+            imageops::replace(
+                &mut self.lum,
+                &luminance(&changed.to_image(), &self.config),
+                rect.x as i64,
+                rect.y as i64,
+            );
+        }
+
+        // Whole-image input of step 4, shared by every rectangle
+        let thresholds = tile_edge_thresholds(&self.lum, &self.config);
+
+        let mut redrawn = Vec::with_capacity(affected.len());
+        for tiles in affected {
+            let (first_column, first_row) = (tiles.x / tile_w, tiles.y / tile_h);
+
+            // Steps 5-6 depend on each tile alone once the whole-image
+            // settings have forced a full pass
+            let tiles_lum =
+                imageops::crop_imm(&self.lum, tiles.x, tiles.y, tiles.width, tiles.height)
+                    .to_image();
+            let fill = compute_fill_layer_at(&tiles_lum, &self.config, (first_column, first_row));
+            let region = region_layers(&self.lum, &self.config, tiles, thresholds.as_deref(), fill);
+
+            for row in 0..region.tile_height {
+                for column in 0..region.tile_width {
+                    let local = (row * region.tile_width + column) as usize;
+                    let tile = ((first_row + row) * self.layers.tile_width + first_column + column)
+                        as usize;
+                    self.layers.directions[tile] = region.directions[local];
+                    self.layers.edges[tile] = region.edges[local].clone();
+                    self.layers.fill[tile] = region.fill[local].clone();
+                    self.layers.tile_lum[tile] = region.tile_lum[local];
+                }
+            }
+
+            // Step 7 for the affected tiles, patched into the output
+            let source =
+                imageops::crop_imm(&self.image, tiles.x, tiles.y, tiles.width, tiles.height)
+                    .to_image();
+            let patch = Self::render(&region, &self.config, &source, self.preserve_colors);
+            imageops::replace(&mut self.output, &patch, tiles.x as i64, tiles.y as i64);
+            redrawn.push(tiles);
+        }
+        redrawn
+    }
+
+    fn render(
+        layers: &CharLayers,
+        config: &AsciiConfig,
+        source: &RgbaImage,
+        preserve_colors: bool,
+    ) -> RgbaImage {
+        let chars = layers.composite(config);
+        let mut output = layers.render(&chars, config, preserve_colors.then_some(source));
+        blend_with_base(&mut output, source, config);
+        output
+    }
+}
+
+/// Merges tile rectangles into their bounding box wherever that box's
+/// `band_area` is no larger than the two bands together, so pixels shared by
+/// nearby bands go through the edge passes once
+fn merge_overlapping(mut rects: Vec<Rect>, band_area: impl Fn(&Rect) -> u64) -> Vec<Rect> {
+    let mut merged = true;
+    while merged {
+        merged = false;
+        let mut i = 0;
+        while i < rects.len() {
+            let mut j = i + 1;
+            while j < rects.len() {
+                let bounds = rects[i].union(&rects[j]);
+                if band_area(&bounds) <= band_area(&rects[i]) + band_area(&rects[j]) {
+                    rects[i] = bounds;
+                    rects.swap_remove(j);
+                    merged = true;
+                } else {
+                    j += 1;
+                }
+            }
+            i += 1;
+        }
+    }
+    rects
+}
+
+/// How many pixels away a luminance change can alter edge pixels: blur reads
+/// `kernel_size` pixels around each pixel (the sum of the box radii with
+/// [`BlurMode::Box`]), `edge_dilation` moves lines by its
+/// radius, Sobel reads one more, the structure tensor smoothing its kernel
+/// radius and non-maximum suppression another
+fn edge_reach(config: &AsciiConfig) -> u32 {
+    let tensor = config
+        .structure_tensor_sigma
+        .map_or(0, |sigma| (2.0 * sigma).ceil() as u32);
+    let blur = match config.blur_mode {
+        BlurMode::Gaussian => config.kernel_size,
+        BlurMode::Box => {
+            let sigma = config.sigma * config.sigma_scale.max(1.0);
+            box_blur_radii(sigma).iter().sum()
+        }
+    };
+    blur + config.edge_dilation.unsigned_abs() + 1 + tensor + config.non_max_suppression as u32
+}
+
+/// Tiles whose pixels differ between two images of the same size, as
+/// tile-aligned rectangles
+///
+/// Runs of changed tiles in a tile row become one rectangle, which grows
+/// downwards while the rows below change over the same columns.
+fn changed_tiles(previous: &RgbaImage, next: &RgbaImage, tile_size: (u32, u32)) -> Vec<Rect> {
+    let (tile_w, tile_h) = tile_size;
+    let (columns, rows) = (next.width() / tile_w, next.height() / tile_h);
+    let row_bytes = next.width() as usize * 4;
+    let tile_bytes = tile_w as usize * 4;
+
+    let mut rects: Vec<Rect> = Vec::new();
+    // Rectangles that end on the previous tile row, open to growing
+    let mut open = Vec::new();
+    for row in 0..rows {
+        let changed: Vec<bool> = (0..columns as usize)
+            .map(|column| {
+                (row * tile_h..(row + 1) * tile_h).any(|y| {
+                    let start = y as usize * row_bytes + column * tile_bytes;
+                    let range = start..start + tile_bytes;
+                    previous.as_raw()[range.clone()] != next.as_raw()[range]
+                })
+            })
+            .collect();
+
+        let mut still_open = Vec::new();
+        let mut column = 0;
+        while column < changed.len() {
+            if !changed[column] {
+                column += 1;
+                continue;
+            }
+            let start = column;
+            while column < changed.len() && changed[column] {
+                column += 1;
+            }
+            let (x, run_width) = (start as u32 * tile_w, (column - start) as u32 * tile_w);
+            let extended = open.iter().copied().find(|&index: &usize| {
+                let rect = rects[index];
+                rect.x == x && rect.width == run_width
+            });
+            match extended {
+                Some(index) => {
+                    rects[index].height += tile_h;
+                    still_open.push(index);
+                }
+                None => {
+                    still_open.push(rects.len());
+                    rects.push(Rect::new(x, row * tile_h, run_width, tile_h));
+                }
+            }
+        }
+        open = still_open;
+    }
+    rects
+}
+
+/// Runs pipeline steps 2-6 for the tiles inside `tiles`, a tile-aligned
+/// rectangle of the image whose luminance is `lum`
+///
+/// Edges come from a band of `lum` just wide enough around the tiles to match
+/// processing the whole image. Steps 5-6 are cheap and depend on tile
+/// positions (dithering) and, with some settings, on the whole image, so the
+/// fill layer is computed in full and cropped. `rgb_split` channel layers are
+/// not computed.
+pub(crate) fn compute_region_layers(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    tiles: Rect,
+) -> CharLayers {
+    // Saliency is estimated on the whole image
+    let thresholds = tile_edge_thresholds(lum, config);
+    // Steps 5-6 for the whole image, keeping the requested tiles
+    let (fill, tile_lum) = compute_fill_layer(lum, config);
+    let tile_size = config.tile_dimensions();
+    let indices = tile_indices(lum.width() / tile_size.0, tiles, tile_size);
+    region_layers(
+        lum,
+        config,
+        tiles,
+        thresholds.as_deref(),
+        (
+            indices.iter().map(|&i| fill[i].clone()).collect(),
+            indices.iter().map(|&i| tile_lum[i]).collect(),
+        ),
+    )
+}
+
+/// [`compute_region_layers`] with the whole-image edge `thresholds` and the
+/// steps 5-6 output of the requested tiles (`fill`, tile luminance) already
+/// computed
+fn region_layers(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    tiles: Rect,
+    thresholds: Option<&[u32]>,
+    (fill, tile_lum): (Vec<Vec<char>>, Vec<f32>),
+) -> CharLayers {
+    let (tile_w, tile_h) = config.tile_dimensions();
+    let (columns, rows) = (tiles.width / tile_w, tiles.height / tile_h);
+    let (directions, edge_offsets) = compute_region_edges(lum, config, tiles, thresholds);
+
+    CharLayers {
+        edges: select_edge_chars(&directions, columns, rows, config),
+        fill,
+        directions,
+        edge_offsets,
+        tile_lum,
+        tile_width: columns,
+        tile_height: rows,
+        channel_fill: None,
+    }
+}
+
+/// Runs pipeline steps 2-4 for the tiles inside `tiles`, a tile-aligned
+/// rectangle of the image whose luminance is `lum`, on a band of `lum` just
+/// wide enough around them to match processing the whole image
+///
+/// `thresholds` holds the votes needed by every tile of the whole image, as
+/// from [`tile_edge_thresholds`].
+///
+/// # Returns
+/// The voted direction of each tile in `tiles`, row by row, and their glyph
+/// offsets when `positional_edges` is on
+pub(crate) fn compute_region_edges(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    tiles: Rect,
+    thresholds: Option<&[u32]>,
+) -> (Vec<EdgeDirection>, Vec<(i32, i32)>) {
+    let (width, height) = lum.dimensions();
+    let band = tiles.grow(edge_reach(config), width, height);
+
+    // Steps 2-4 on the band, voting only on the requested tiles
+    let lum_band = imageops::crop_imm(lum, band.x, band.y, band.width, band.height);
+    let (angles, valid_mask) = compute_edge_gradients(&lum_band.to_image(), config);
+    let mut tile_angles = Vec::with_capacity((tiles.width * tiles.height) as usize);
+    let mut tile_mask = Vec::with_capacity(tile_angles.capacity());
+    for y in tiles.y..tiles.y + tiles.height {
+        let start = ((y - band.y) * band.width + tiles.x - band.x) as usize;
+        let end = start + tiles.width as usize;
+        tile_angles.extend_from_slice(&angles[start..end]);
+        tile_mask.extend_from_slice(&valid_mask[start..end]);
+    }
+    let tile_size = config.tile_dimensions();
+    let thresholds = thresholds.map(|thresholds| {
+        tile_indices(width / tile_size.0, tiles, tile_size)
+            .iter()
+            .map(|&i| thresholds[i])
+            .collect()
+    });
+    let directions = vote_edge_directions(
+        &tile_angles,
+        &tile_mask,
+        tiles.width,
+        tiles.height,
+        thresholds,
+        config,
+    );
+
+    let edge_offsets = if config.positional_edges {
+        tile_edge_offsets(
+            &tile_angles,
+            &tile_mask,
+            tiles.width,
+            tile_size,
+            &directions,
+        )
+    } else {
+        Vec::new()
+    };
+    (directions, edge_offsets)
+}
+
+/// Indices, in an image `image_columns` tiles wide, of the tiles inside the
+/// tile-aligned rectangle `tiles`, row by row
+fn tile_indices(image_columns: u32, tiles: Rect, tile_size: (u32, u32)) -> Vec<usize> {
+    let (tile_w, tile_h) = tile_size;
+    let (first_column, first_row) = (tiles.x / tile_w, tiles.y / tile_h);
+    let (columns, rows) = (tiles.width / tile_w, tiles.height / tile_h);
+    (first_row..first_row + rows)
+        .flat_map(|row| {
+            (first_column..first_column + columns)
+                .map(move |column| (row * image_columns + column) as usize)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FillDither;
+    use crate::{process_image, process_image_preserve_colors};
+    use image::Rgba;
+
+    /// Colored stripes with a soft gradient, so edges and fill both vary
+    fn scene() -> RgbaImage {
+        RgbaImage::from_fn(96, 72, |x, y| {
+            let v = if (x / 20 + y / 24) % 2 == 0 { 200 } else { 50 };
+            Rgba([v, (x * 2) as u8, (y * 3) as u8, 255])
+        })
+    }
+
+    fn paint(image: &mut RgbaImage, rect: Rect, color: [u8; 4]) {
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                image.put_pixel(x, y, Rgba(color));
+            }
+        }
+    }
+
+    #[test]
+    fn test_reprocess_region_matches_full() {
+        for (kernel_size, preserve_colors) in [(1, false), (2, true), (4, false)] {
+            let config = AsciiConfig {
+                kernel_size,
+                ..Default::default()
+            };
+            let mut processor = AsciiProcessor::new(&scene(), &config, preserve_colors);
+
+            let rect = Rect::new(37, 29, 13, 9);
+            paint(processor.image_mut(), rect, [255, 255, 255, 255]);
+            let updated = processor.reprocess_region(rect);
+
+            // Only tiles around the change are redrawn
+            assert!(updated.width < 96 && updated.height < 72);
+            assert_eq!((updated.x % 8, updated.y % 8), (0, 0));
+
+            let mut expected_input = scene();
+            paint(&mut expected_input, rect, [255, 255, 255, 255]);
+            let expected = if preserve_colors {
+                process_image_preserve_colors(&expected_input, &config).render_image()
+            } else {
+                process_image(&expected_input, &config).render_image()
+            };
+            assert_eq!(processor.output(), &expected, "kernel_size {}", kernel_size);
+        }
+    }
+
+    #[test]
+    fn test_next_frame_redraws_changed_tiles() {
+        let config = AsciiConfig::default();
+        let mut processor = AsciiProcessor::new(&scene(), &config, true);
+        assert!(processor.next_frame(&scene()).is_empty());
+
+        // Two separate moving objects on a static background
+        let mut frame = scene();
+        paint(&mut frame, Rect::new(3, 2, 10, 10), [255, 255, 255, 255]);
+        paint(&mut frame, Rect::new(80, 60, 6, 4), [0, 0, 255, 255]);
+        let redrawn = processor.next_frame(&frame);
+        assert_eq!(redrawn.len(), 2);
+        assert!(
+            redrawn
+                .iter()
+                .all(|rect| rect.width < 48 && rect.height < 48)
+        );
+        assert_eq!(
+            processor.output(),
+            &process_image_preserve_colors(&frame, &config).render_image()
+        );
+        assert_eq!(processor.image(), &frame);
+
+        // A new size starts over
+        let small = RgbaImage::from_fn(40, 24, |x, y| *frame.get_pixel(x, y));
+        assert_eq!(processor.next_frame(&small), vec![Rect::new(0, 0, 40, 24)]);
+        assert_eq!(
+            processor.output(),
+            &process_image_preserve_colors(&small, &config).render_image()
+        );
+    }
+
+    #[test]
+    fn test_next_frame_merges_and_falls_back() {
+        // Noise dithering depends on tile positions, so the fill reselected
+        // for changed tiles must use their place in the whole image
+        let config = AsciiConfig {
+            fill_dither: FillDither::Noise,
+            ..Default::default()
+        };
+        let mut processor = AsciiProcessor::new(&scene(), &config, false);
+
+        // Nearby changes share one band
+        let mut frame = scene();
+        paint(&mut frame, Rect::new(20, 20, 2, 2), [90, 90, 90, 255]);
+        paint(&mut frame, Rect::new(28, 30, 2, 2), [90, 90, 90, 255]);
+        let redrawn = processor.next_frame(&frame);
+        assert_eq!(redrawn.len(), 1);
+        assert!(redrawn[0].width < 96 && redrawn[0].height < 72);
+        assert_eq!(
+            processor.output(),
+            &process_image(&frame, &config).render_image()
+        );
+
+        // Changes all over the frame take one full pass
+        for y in (5..72).step_by(16) {
+            for x in (5..96).step_by(16) {
+                paint(&mut frame, Rect::new(x, y, 2, 2), [10, 200, 10, 255]);
+            }
+        }
+        assert_eq!(processor.next_frame(&frame), vec![Rect::new(0, 0, 96, 72)]);
+        assert_eq!(
+            processor.output(),
+            &process_image(&frame, &config).render_image()
+        );
+    }
+
+    #[test]
+    fn test_changed_tiles() {
+        let previous = scene();
+        let mut next = scene();
+        // An L shape: two tiles in one row, then only the first below
+        paint(&mut next, Rect::new(17, 9, 12, 2), [1, 2, 3, 255]);
+        paint(&mut next, Rect::new(17, 17, 1, 1), [1, 2, 3, 255]);
+        assert_eq!(
+            changed_tiles(&previous, &next, (8, 8)),
+            vec![Rect::new(16, 8, 16, 8), Rect::new(16, 16, 8, 8)]
+        );
+        // A block of changes grows into one rectangle
+        paint(&mut next, Rect::new(16, 8, 16, 16), [1, 2, 3, 255]);
+        assert_eq!(
+            changed_tiles(&previous, &next, (8, 8)),
+            vec![Rect::new(16, 8, 16, 16)]
+        );
+        assert!(changed_tiles(&previous, &previous, (8, 8)).is_empty());
+    }
+
+    #[test]
+    fn test_update_patch_and_fallback() {
+        let config = AsciiConfig {
+            auto_invert: true,
+            ..Default::default()
+        };
+        let mut processor = AsciiProcessor::new(&scene(), &config, false);
+        let patch = RgbaImage::from_pixel(16, 16, Rgba([0, 0, 0, 255]));
+
+        // Whole-image settings redraw everything
+        assert_eq!(processor.update(88, 64, &patch), Rect::new(0, 0, 96, 72));
+
+        let mut expected_input = scene();
+        paint(&mut expected_input, Rect::new(88, 64, 8, 8), [0, 0, 0, 255]);
+        assert_eq!(
+            processor.output(),
+            &process_image(&expected_input, &config).render_image()
+        );
+
+        assert!(
+            processor
+                .reprocess_region(Rect::new(200, 0, 5, 5))
+                .is_empty()
+        );
+    }
+}
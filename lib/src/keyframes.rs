@@ -0,0 +1,285 @@
+//! Keyframed parameter animation
+//!
+//! [`Animation`] moves `AsciiConfig` parameters from one keyframe to the next
+//! over time: a `sigma` ramp, an `edge_threshold` sweep, or a photo that
+//! dissolves into ASCII as `blend_with_base` falls from 1 to 0. Parameters are
+//! addressed by name as in [`crate::params`], so any field can be animated.
+//! [`process_animated_frames`](crate::frames::process_animated_frames) renders
+//! a frame sequence with it.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::frames::process_animated_frames;
+//! use ascii_rendr::keyframes::{Animation, Easing};
+//! use ascii_rendr::params::ParamValue;
+//!
+//! let photo = image::open("photo.jpg").unwrap().to_rgba8();
+//! let animation = Animation::new(&AsciiConfig::default())
+//!     .keyframe("blend_with_base", 0.0, ParamValue::Float(1.0))?
+//!     .keyframe_eased("blend_with_base", 2.0, ParamValue::Float(0.0), Easing::EaseInOut)?;
+//!
+//! // Two seconds at 12 frames per second
+//! let frames = std::iter::repeat_n(photo, 24);
+//! for (i, art) in process_animated_frames(frames, &animation, 12.0, true).enumerate() {
+//!     art.render_image().save(format!("dissolve{:02}.png", i)).unwrap();
+//! }
+//! # Ok::<(), String>(())
+//! ```
+
+use crate::config::AsciiConfig;
+use crate::params::ParamValue;
+
+/// How a parameter moves from a keyframe to the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Starts and ends slowly (smoothstep)
+    EaseInOut,
+    /// Keeps the value until the next keyframe, then jumps
+    Step,
+}
+
+impl Easing {
+    /// Progress along a segment for a share `t` in [0.0, 1.0] of its time
+    fn apply(self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::Step => 0.0,
+        }
+    }
+}
+
+/// A parameter value at a point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframe {
+    /// Seconds from the start of the animation
+    pub time: f64,
+    pub value: ParamValue,
+    /// How the value moves towards the next keyframe
+    pub easing: Easing,
+}
+
+/// Parameter keyframes on top of a base config
+///
+/// Numbers and colors are interpolated between keyframes (integers rounded);
+/// booleans, choices and switching an optional parameter on or off keep the
+/// earlier value until the next keyframe. Before its first keyframe and after
+/// its last, a parameter holds that keyframe's value; parameters without
+/// keyframes keep their base value.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    base: AsciiConfig,
+    /// Keyframes per parameter name, sorted by time
+    tracks: Vec<(String, Vec<Keyframe>)>,
+}
+
+impl Animation {
+    /// Starts an animation without keyframes
+    ///
+    /// # Panics
+    /// If `base` is invalid.
+    pub fn new(base: &AsciiConfig) -> Self {
+        base.validate().expect("Invalid configuration");
+        Self {
+            base: base.clone(),
+            tracks: Vec::new(),
+        }
+    }
+
+    /// Adds a keyframe with [`Easing::Linear`] towards the next one
+    pub fn keyframe(self, name: &str, time: f64, value: ParamValue) -> Result<Self, String> {
+        self.keyframe_eased(name, time, value, Easing::Linear)
+    }
+
+    /// Adds a keyframe for parameter `name` at `time` seconds, replacing one
+    /// already there
+    ///
+    /// # Errors
+    /// If `time` is negative or not finite, or [`AsciiConfig::set_param`]
+    /// rejects the name or value.
+    pub fn keyframe_eased(
+        mut self,
+        name: &str,
+        time: f64,
+        value: ParamValue,
+        easing: Easing,
+    ) -> Result<Self, String> {
+        if !(time.is_finite() && time >= 0.0) {
+            return Err(format!(
+                "keyframe time for `{}` must not be negative, got {}",
+                name, time
+            ));
+        }
+        self.base.clone().set_param(name, value.clone())?;
+
+        let keyframe = Keyframe {
+            time,
+            value,
+            easing,
+        };
+        let track = match self.tracks.iter().position(|(track, _)| track == name) {
+            Some(index) => &mut self.tracks[index].1,
+            None => {
+                self.tracks.push((name.to_string(), Vec::new()));
+                &mut self.tracks.last_mut().unwrap().1
+            }
+        };
+        match track.binary_search_by(|key| key.time.total_cmp(&time)) {
+            Ok(index) => track[index] = keyframe,
+            Err(index) => track.insert(index, keyframe),
+        }
+        Ok(self)
+    }
+
+    /// Settings the animation starts from
+    pub fn base(&self) -> &AsciiConfig {
+        &self.base
+    }
+
+    /// Keyframes of parameter `name`, sorted by time
+    pub fn keyframes(&self, name: &str) -> &[Keyframe] {
+        self.tracks
+            .iter()
+            .find(|(track, _)| track == name)
+            .map_or(&[], |(_, keys)| keys)
+    }
+
+    /// Time of the last keyframe, in seconds
+    pub fn duration(&self) -> f64 {
+        self.tracks
+            .iter()
+            .filter_map(|(_, keys)| keys.last())
+            .map(|key| key.time)
+            .fold(0.0, f64::max)
+    }
+
+    /// Settings at `time` seconds
+    ///
+    /// # Panics
+    /// If the interpolated settings are invalid together, e.g. keyframes
+    /// that break a constraint between two parameters.
+    pub fn config_at(&self, time: f64) -> AsciiConfig {
+        let mut config = self.base.clone();
+        for (name, keys) in &self.tracks {
+            config
+                .set_param(name, value_at(keys, time))
+                .expect("keyframe values are checked when added");
+        }
+        config.validate().expect("Invalid animated configuration");
+        config
+    }
+}
+
+/// Value of a parameter track at `time`
+fn value_at(keys: &[Keyframe], time: f64) -> ParamValue {
+    let next = keys.partition_point(|key| key.time <= time);
+    let (from, to) = match (next.checked_sub(1), keys.get(next)) {
+        (Some(previous), Some(to)) => (&keys[previous], to),
+        (Some(previous), None) => return keys[previous].value.clone(),
+        (None, _) => return keys[0].value.clone(),
+    };
+
+    let t = from
+        .easing
+        .apply(((time - from.time) / (to.time - from.time)).clamp(0.0, 1.0));
+    let mix = |a: f64, b: f64| a + (b - a) * t;
+    match (&from.value, &to.value) {
+        (ParamValue::Int(a), ParamValue::Int(b)) => {
+            ParamValue::Int(mix(*a as f64, *b as f64).round() as i64)
+        }
+        (ParamValue::Float(a), ParamValue::Float(b)) => ParamValue::Float(mix(*a, *b)),
+        (ParamValue::Color(a), ParamValue::Color(b)) => {
+            ParamValue::Color(std::array::from_fn(|i| {
+                mix(a[i] as f64, b[i] as f64).round() as u8
+            }))
+        }
+        // Values without steps in between hold until the next keyframe
+        (value, _) => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_at_interpolates() {
+        let animation = Animation::new(&AsciiConfig::default())
+            .keyframe("sigma", 1.0, ParamValue::Float(1.0))
+            .unwrap()
+            .keyframe("sigma", 3.0, ParamValue::Float(3.0))
+            .unwrap()
+            .keyframe("kernel_size", 0.0, ParamValue::Int(1))
+            .unwrap()
+            .keyframe("kernel_size", 2.0, ParamValue::Int(4))
+            .unwrap()
+            .keyframe("ascii_color", 0.0, ParamValue::Color([0, 0, 0]))
+            .unwrap()
+            .keyframe("ascii_color", 2.0, ParamValue::Color([200, 100, 50]))
+            .unwrap()
+            .keyframe("draw_edges", 0.0, ParamValue::Bool(false))
+            .unwrap()
+            .keyframe("draw_edges", 1.5, ParamValue::Bool(true))
+            .unwrap();
+        assert_eq!(animation.duration(), 3.0);
+
+        let config = animation.config_at(1.0);
+        assert_eq!(config.sigma, 1.0);
+        assert_eq!(config.kernel_size, 3);
+        assert_eq!(config.ascii_color, [100, 50, 25]);
+        assert!(!config.draw_edges);
+
+        // Values hold before the first and after the last keyframe
+        assert_eq!(animation.config_at(0.0).sigma, 1.0);
+        assert_eq!(animation.config_at(2.0).sigma, 2.0);
+        assert_eq!(animation.config_at(9.0).sigma, 3.0);
+        assert!(animation.config_at(1.5).draw_edges);
+        assert_eq!(
+            animation.config_at(0.0).edge_threshold,
+            AsciiConfig::default().edge_threshold
+        );
+    }
+
+    #[test]
+    fn test_easing_and_replaced_keyframes() {
+        let eased = Animation::new(&AsciiConfig::default())
+            .keyframe_eased("sigma", 0.0, ParamValue::Float(0.0), Easing::EaseInOut)
+            .unwrap()
+            .keyframe("sigma", 4.0, ParamValue::Float(4.0))
+            .unwrap();
+        assert_eq!(eased.config_at(1.0).sigma, 0.625);
+        assert_eq!(eased.config_at(2.0).sigma, 2.0);
+
+        let stepped = eased
+            .keyframe_eased("sigma", 0.0, ParamValue::Float(1.0), Easing::Step)
+            .unwrap();
+        assert_eq!(stepped.keyframes("sigma").len(), 2);
+        assert_eq!(stepped.config_at(3.9).sigma, 1.0);
+        assert_eq!(stepped.config_at(4.0).sigma, 4.0);
+    }
+
+    #[test]
+    fn test_keyframe_errors() {
+        let animation = Animation::new(&AsciiConfig::default());
+        assert!(
+            animation
+                .clone()
+                .keyframe("sigma", -1.0, ParamValue::Float(1.0))
+                .is_err()
+        );
+        assert!(
+            animation
+                .clone()
+                .keyframe("no_such_param", 0.0, ParamValue::Int(1))
+                .is_err()
+        );
+        assert!(
+            animation
+                .keyframe("kernel_size", 0.0, ParamValue::Int(99))
+                .is_err()
+        );
+    }
+}
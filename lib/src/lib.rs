@@ -10,17 +10,73 @@
 //!
 //! let input = image::open("photo.jpg").unwrap().to_rgba8();
 //! let config = AsciiConfig::default();
-//! let output = process_image(&input, &config);
+//! let output = process_image(&input, &config).render_image();
 //! output.save("ascii_art.png").unwrap();
 //! ```
 
+pub mod adaptive;
+pub mod animation;
+pub mod art;
 pub mod ascii;
+pub mod banded;
+pub mod before_after;
+pub mod braille;
+pub mod cache;
+pub mod color_vision;
 pub mod config;
+mod config_builder;
+mod config_file;
+pub mod data;
 pub mod edges;
+mod env;
+pub mod error;
 pub mod filters;
+pub mod frames;
+pub mod glyphs;
+#[cfg(feature = "gstreamer")]
+pub mod gst_element;
+pub mod incremental;
+pub mod keyframes;
 pub mod lut;
+#[cfg(feature = "ndi")]
+pub mod ndi;
+pub mod output;
+mod par;
+pub mod params;
+pub mod plugin;
+pub mod png_metadata;
 pub mod processor;
+pub mod progress;
+pub mod random;
+pub mod regions;
+pub mod render;
+pub mod saliency;
+pub mod segmentation;
+pub mod shimmer;
+pub mod text_rows;
+#[cfg(feature = "video_io")]
+pub mod video_io;
+pub mod watermark;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+pub mod yuv;
 
 // Re-export main types for convenience
-pub use config::AsciiConfig;
-pub use processor::{process_image, process_image_preserve_colors};
+pub use art::AsciiArt;
+pub use config::{
+    AsciiConfig, BlurMode, CharSelection, Charset, CompositeMode, EdgeChars, EdgeMode, FillDither,
+    LuminanceAdjust, LuminanceMode, OutputMode, Palette, PreFilter, Preset, TILE_SIZES,
+    ThresholdMode, TileStatistic,
+};
+pub use config_builder::AsciiConfigBuilder;
+pub use edges::{DogSobel, EdgeDetector};
+pub use env::ENV_PREFIX;
+pub use error::AsciiError;
+pub use incremental::AsciiProcessor;
+pub use output::{ImageView, ProcessOutput};
+pub use processor::{
+    Processor, ProcessorOptions, process_gray, process_image, process_image_output,
+    process_image_preserve_colors, process_yuv, try_process_image,
+    try_process_image_preserve_colors,
+};
+pub use render::renderer::TileRenderer;
@@ -1,14 +1,19 @@
 //! ASCII character lookup tables
 //!
 //! These define the character sets used for edges and luminance-based fill.
-//! Later these can be loaded from PNG files (edgesASCII.png, fillASCII.png).
+//! Image output draws them from built-in 8×8 bitmaps; to use the glyphs of
+//! `edgesASCII.png`/`fillASCII.png` instead, see [`crate::glyphs::GlyphAtlas`].
 
 use crate::edges::EdgeDirection;
 
 /// Edge characters organized by direction
 ///
-/// Each edge direction gets 8 characters (one for each position in the 8x8 tile)
-/// This is a simplified version - in the full shader, these come from a texture
+/// Each edge direction gets 8 characters, one per glyph row of the 8×8 tile.
+/// They are all the same: image output draws pixel (x, y) of a tile from pixel
+/// (x, y) of the character at that position, so a uniform tile already draws
+/// the whole glyph, as the shader does when sampling its edge texture. Where
+/// the glyph sits in the tile is set by `positional_edges`, which shifts it
+/// onto the detected edge.
 pub const EDGE_CHARS: [[char; 8]; 4] = [
     // Vertical: |
     ['|', '|', '|', '|', '|', '|', '|', '|'],
@@ -20,6 +25,11 @@ pub const EDGE_CHARS: [[char; 8]; 4] = [
     ['\\', '\\', '\\', '\\', '\\', '\\', '\\', '\\'],
 ];
 
+/// Junction characters for tiles where two perpendicular directions tie
+///
+/// Index 0 is used for vertical + horizontal, index 1 for both diagonals
+pub const JUNCTION_CHARS: [char; 2] = ['+', 'X'];
+
 /// Fill characters organized by luminance level
 ///
 /// 10 levels from darkest (space) to brightest (@)
@@ -37,6 +47,145 @@ pub const FILL_CHARS: [char; 10] = [
     '@', // 9: brightest
 ];
 
+/// Unicode shade blocks from empty to full, for terminals and text output
+pub const BLOCK_CHARS: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Characters grouped by how much ink their rendered 8x8 glyph covers
+///
+/// Characters in the same class read as the same brightness, so they can be
+/// swapped for one another without changing tone (used by the shimmer animation).
+/// Ordered from sparsest to densest.
+pub const SHIMMER_CLASSES: [&[char]; 7] = [
+    &[' '],
+    &['.', ':'],
+    &['%'],
+    &['-', '|', '=', '/', '\\', 'X'],
+    &['+', '#'],
+    &['@'],
+    &['*'],
+];
+
+/// 8×8 bitmaps of the printable ASCII characters (0x20-0x7E)
+///
+/// One byte per row from the top, with the leftmost pixel in the most
+/// significant bit (the layout of custom edge bitmaps). Taken from the public
+/// domain font8x8 by Daniel Hepper. Image output prefers the hand-drawn
+/// patterns of the ramp and edge characters, whose densities the fill ramp and
+/// [`SHIMMER_CLASSES`] are tuned to, and uses this table for the rest.
+pub const ASCII_BITMAPS: [[u8; 8]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x18, 0x3c, 0x3c, 0x18, 0x18, 0x00, 0x18, 0x00], // !
+    [0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // "
+    [0x6c, 0x6c, 0xfe, 0x6c, 0xfe, 0x6c, 0x6c, 0x00], // #
+    [0x30, 0x7c, 0xc0, 0x78, 0x0c, 0xf8, 0x30, 0x00], // $
+    [0x00, 0xc6, 0xcc, 0x18, 0x30, 0x66, 0xc6, 0x00], // %
+    [0x38, 0x6c, 0x38, 0x76, 0xdc, 0xcc, 0x76, 0x00], // &
+    [0x60, 0x60, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x00], // '
+    [0x18, 0x30, 0x60, 0x60, 0x60, 0x30, 0x18, 0x00], // (
+    [0x60, 0x30, 0x18, 0x18, 0x18, 0x30, 0x60, 0x00], // )
+    [0x00, 0x66, 0x3c, 0xff, 0x3c, 0x66, 0x00, 0x00], // *
+    [0x00, 0x30, 0x30, 0xfc, 0x30, 0x30, 0x00, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x30, 0x60], // ,
+    [0x00, 0x00, 0x00, 0xfc, 0x00, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x30, 0x00], // .
+    [0x06, 0x0c, 0x18, 0x30, 0x60, 0xc0, 0x80, 0x00], // /
+    [0x7c, 0xc6, 0xce, 0xde, 0xf6, 0xe6, 0x7c, 0x00], // 0
+    [0x30, 0x70, 0x30, 0x30, 0x30, 0x30, 0xfc, 0x00], // 1
+    [0x78, 0xcc, 0x0c, 0x38, 0x60, 0xcc, 0xfc, 0x00], // 2
+    [0x78, 0xcc, 0x0c, 0x38, 0x0c, 0xcc, 0x78, 0x00], // 3
+    [0x1c, 0x3c, 0x6c, 0xcc, 0xfe, 0x0c, 0x1e, 0x00], // 4
+    [0xfc, 0xc0, 0xf8, 0x0c, 0x0c, 0xcc, 0x78, 0x00], // 5
+    [0x38, 0x60, 0xc0, 0xf8, 0xcc, 0xcc, 0x78, 0x00], // 6
+    [0xfc, 0xcc, 0x0c, 0x18, 0x30, 0x30, 0x30, 0x00], // 7
+    [0x78, 0xcc, 0xcc, 0x78, 0xcc, 0xcc, 0x78, 0x00], // 8
+    [0x78, 0xcc, 0xcc, 0x7c, 0x0c, 0x18, 0x70, 0x00], // 9
+    [0x00, 0x30, 0x30, 0x00, 0x00, 0x30, 0x30, 0x00], // :
+    [0x00, 0x30, 0x30, 0x00, 0x00, 0x30, 0x30, 0x60], // ;
+    [0x18, 0x30, 0x60, 0xc0, 0x60, 0x30, 0x18, 0x00], // <
+    [0x00, 0x00, 0xfc, 0x00, 0x00, 0xfc, 0x00, 0x00], // =
+    [0x60, 0x30, 0x18, 0x0c, 0x18, 0x30, 0x60, 0x00], // >
+    [0x78, 0xcc, 0x0c, 0x18, 0x30, 0x00, 0x30, 0x00], // ?
+    [0x7c, 0xc6, 0xde, 0xde, 0xde, 0xc0, 0x78, 0x00], // @
+    [0x30, 0x78, 0xcc, 0xcc, 0xfc, 0xcc, 0xcc, 0x00], // A
+    [0xfc, 0x66, 0x66, 0x7c, 0x66, 0x66, 0xfc, 0x00], // B
+    [0x3c, 0x66, 0xc0, 0xc0, 0xc0, 0x66, 0x3c, 0x00], // C
+    [0xf8, 0x6c, 0x66, 0x66, 0x66, 0x6c, 0xf8, 0x00], // D
+    [0xfe, 0x62, 0x68, 0x78, 0x68, 0x62, 0xfe, 0x00], // E
+    [0xfe, 0x62, 0x68, 0x78, 0x68, 0x60, 0xf0, 0x00], // F
+    [0x3c, 0x66, 0xc0, 0xc0, 0xce, 0x66, 0x3e, 0x00], // G
+    [0xcc, 0xcc, 0xcc, 0xfc, 0xcc, 0xcc, 0xcc, 0x00], // H
+    [0x78, 0x30, 0x30, 0x30, 0x30, 0x30, 0x78, 0x00], // I
+    [0x1e, 0x0c, 0x0c, 0x0c, 0xcc, 0xcc, 0x78, 0x00], // J
+    [0xe6, 0x66, 0x6c, 0x78, 0x6c, 0x66, 0xe6, 0x00], // K
+    [0xf0, 0x60, 0x60, 0x60, 0x62, 0x66, 0xfe, 0x00], // L
+    [0xc6, 0xee, 0xfe, 0xfe, 0xd6, 0xc6, 0xc6, 0x00], // M
+    [0xc6, 0xe6, 0xf6, 0xde, 0xce, 0xc6, 0xc6, 0x00], // N
+    [0x38, 0x6c, 0xc6, 0xc6, 0xc6, 0x6c, 0x38, 0x00], // O
+    [0xfc, 0x66, 0x66, 0x7c, 0x60, 0x60, 0xf0, 0x00], // P
+    [0x78, 0xcc, 0xcc, 0xcc, 0xdc, 0x78, 0x1c, 0x00], // Q
+    [0xfc, 0x66, 0x66, 0x7c, 0x6c, 0x66, 0xe6, 0x00], // R
+    [0x78, 0xcc, 0xe0, 0x70, 0x1c, 0xcc, 0x78, 0x00], // S
+    [0xfc, 0xb4, 0x30, 0x30, 0x30, 0x30, 0x78, 0x00], // T
+    [0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xfc, 0x00], // U
+    [0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0x78, 0x30, 0x00], // V
+    [0xc6, 0xc6, 0xc6, 0xd6, 0xfe, 0xee, 0xc6, 0x00], // W
+    [0xc6, 0xc6, 0x6c, 0x38, 0x38, 0x6c, 0xc6, 0x00], // X
+    [0xcc, 0xcc, 0xcc, 0x78, 0x30, 0x30, 0x78, 0x00], // Y
+    [0xfe, 0xc6, 0x8c, 0x18, 0x32, 0x66, 0xfe, 0x00], // Z
+    [0x78, 0x60, 0x60, 0x60, 0x60, 0x60, 0x78, 0x00], // [
+    [0xc0, 0x60, 0x30, 0x18, 0x0c, 0x06, 0x02, 0x00], // \
+    [0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0x78, 0x00], // ]
+    [0x10, 0x38, 0x6c, 0xc6, 0x00, 0x00, 0x00, 0x00], // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff], // _
+    [0x30, 0x30, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00], // `
+    [0x00, 0x00, 0x78, 0x0c, 0x7c, 0xcc, 0x76, 0x00], // a
+    [0xe0, 0x60, 0x60, 0x7c, 0x66, 0x66, 0xdc, 0x00], // b
+    [0x00, 0x00, 0x78, 0xcc, 0xc0, 0xcc, 0x78, 0x00], // c
+    [0x1c, 0x0c, 0x0c, 0x7c, 0xcc, 0xcc, 0x76, 0x00], // d
+    [0x00, 0x00, 0x78, 0xcc, 0xfc, 0xc0, 0x78, 0x00], // e
+    [0x38, 0x6c, 0x60, 0xf0, 0x60, 0x60, 0xf0, 0x00], // f
+    [0x00, 0x00, 0x76, 0xcc, 0xcc, 0x7c, 0x0c, 0xf8], // g
+    [0xe0, 0x60, 0x6c, 0x76, 0x66, 0x66, 0xe6, 0x00], // h
+    [0x30, 0x00, 0x70, 0x30, 0x30, 0x30, 0x78, 0x00], // i
+    [0x0c, 0x00, 0x0c, 0x0c, 0x0c, 0xcc, 0xcc, 0x78], // j
+    [0xe0, 0x60, 0x66, 0x6c, 0x78, 0x6c, 0xe6, 0x00], // k
+    [0x70, 0x30, 0x30, 0x30, 0x30, 0x30, 0x78, 0x00], // l
+    [0x00, 0x00, 0xcc, 0xfe, 0xfe, 0xd6, 0xc6, 0x00], // m
+    [0x00, 0x00, 0xf8, 0xcc, 0xcc, 0xcc, 0xcc, 0x00], // n
+    [0x00, 0x00, 0x78, 0xcc, 0xcc, 0xcc, 0x78, 0x00], // o
+    [0x00, 0x00, 0xdc, 0x66, 0x66, 0x7c, 0x60, 0xf0], // p
+    [0x00, 0x00, 0x76, 0xcc, 0xcc, 0x7c, 0x0c, 0x1e], // q
+    [0x00, 0x00, 0xdc, 0x76, 0x66, 0x60, 0xf0, 0x00], // r
+    [0x00, 0x00, 0x7c, 0xc0, 0x78, 0x0c, 0xf8, 0x00], // s
+    [0x10, 0x30, 0x7c, 0x30, 0x30, 0x34, 0x18, 0x00], // t
+    [0x00, 0x00, 0xcc, 0xcc, 0xcc, 0xcc, 0x76, 0x00], // u
+    [0x00, 0x00, 0xcc, 0xcc, 0xcc, 0x78, 0x30, 0x00], // v
+    [0x00, 0x00, 0xc6, 0xd6, 0xfe, 0xfe, 0x6c, 0x00], // w
+    [0x00, 0x00, 0xc6, 0x6c, 0x38, 0x6c, 0xc6, 0x00], // x
+    [0x00, 0x00, 0xcc, 0xcc, 0xcc, 0x7c, 0x0c, 0xf8], // y
+    [0x00, 0x00, 0xfc, 0x98, 0x30, 0x64, 0xfc, 0x00], // z
+    [0x1c, 0x30, 0x30, 0xe0, 0x30, 0x30, 0x1c, 0x00], // {
+    [0x18, 0x18, 0x18, 0x00, 0x18, 0x18, 0x18, 0x00], // |
+    [0xe0, 0x30, 0x30, 0x1c, 0x30, 0x30, 0xe0, 0x00], // }
+    [0x76, 0xdc, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ~
+];
+
+/// Bitmap of a printable ASCII character from [`ASCII_BITMAPS`]
+pub fn ascii_bitmap(ch: char) -> Option<&'static [u8; 8]> {
+    match ch {
+        ' '..='~' => Some(&ASCII_BITMAPS[ch as usize - 0x20]),
+        _ => None,
+    }
+}
+
+/// Brightness class of a character from [`SHIMMER_CLASSES`], if any
+pub fn shimmer_class(ch: char) -> Option<&'static [char]> {
+    SHIMMER_CLASSES
+        .iter()
+        .copied()
+        .find(|class| class.contains(&ch))
+}
+
 /// Get the appropriate edge character for a direction and tile position
 ///
 /// # Arguments
@@ -54,6 +203,8 @@ pub fn get_edge_char(direction: EdgeDirection, tile_x: u32, tile_y: u32) -> char
         EdgeDirection::Horizontal => EDGE_CHARS[1][tile_y as usize],
         EdgeDirection::Diagonal1 => EDGE_CHARS[2][tile_y as usize],
         EdgeDirection::Diagonal2 => EDGE_CHARS[3][tile_y as usize],
+        EdgeDirection::Cross => JUNCTION_CHARS[0],
+        EdgeDirection::DiagonalCross => JUNCTION_CHARS[1],
         EdgeDirection::None => ' ',
     }
 }
@@ -67,25 +218,138 @@ pub fn get_edge_char(direction: EdgeDirection, tile_x: u32, tile_y: u32) -> char
 /// # Returns
 /// The character to use for this luminance
 pub fn get_fill_char(luminance: f32, invert: bool) -> char {
+    get_fill_char_with_gamma(luminance, invert, 1.0)
+}
+
+/// Get the appropriate fill character for a luminance value with a gamma bias
+///
+/// The gamma curve is applied to the (optionally inverted) luminance right before
+/// quantization, so it only changes which ramp character is picked. Values above
+/// 1.0 favor sparser characters, values below 1.0 favor denser ones.
+///
+/// # Arguments
+/// * `luminance` - Normalized luminance value [0.0, 1.0]
+/// * `invert` - Whether to invert the luminance mapping
+/// * `gamma` - Exponent applied before quantization (1.0 = linear)
+///
+/// # Returns
+/// The character to use for this luminance
+pub fn get_fill_char_with_gamma(luminance: f32, invert: bool, gamma: f32) -> char {
+    get_ramp_char(&FILL_CHARS, luminance, invert, gamma, None)
+}
+
+/// Get a fill character, dithering between the two nearest ramp characters
+///
+/// The fractional part of the ramp position decides how often the next denser
+/// character is picked: if it exceeds `threshold`, the denser neighbor is used.
+/// Feeding a spatially varying threshold (ordered or noise) mixes both
+/// characters in proportion to the fractional luminance.
+///
+/// # Arguments
+/// * `luminance` - Normalized luminance value [0.0, 1.0]
+/// * `invert` - Whether to invert the luminance mapping
+/// * `gamma` - Exponent applied before quantization (1.0 = linear)
+/// * `threshold` - Dither threshold in [0.0, 1.0)
+///
+/// # Returns
+/// The character to use at this position
+pub fn get_fill_char_dithered(luminance: f32, invert: bool, gamma: f32, threshold: f32) -> char {
+    get_ramp_char(&FILL_CHARS, luminance, invert, gamma, Some(threshold))
+}
+
+/// Get the character of any ramp for a luminance value
+///
+/// Like [`get_fill_char_with_gamma`] on [`FILL_CHARS`], or
+/// [`get_fill_char_dithered`] when a dither `threshold` is given, for a ramp
+/// of one or more characters ordered from sparsest to densest.
+///
+/// # Arguments
+/// * `ramp` - Characters from sparsest to densest
+/// * `luminance` - Normalized luminance value [0.0, 1.0]
+/// * `invert` - Whether to invert the luminance mapping
+/// * `gamma` - Exponent applied before quantization (1.0 = linear)
+/// * `threshold` - Dither threshold in [0.0, 1.0), or None to quantize
+///
+/// # Returns
+/// The character to use for this luminance
+pub fn get_ramp_char(
+    ramp: &[char],
+    luminance: f32,
+    invert: bool,
+    gamma: f32,
+    threshold: Option<f32>,
+) -> char {
+    // Quantize to one of the ramp levels
+    // Shader logic: luminance = max(0, (floor(luminance * 10) - 1)) / 10.0f;
+    // We just need the index, so: floor(luminance * levels)
+    let last = ramp.len() - 1;
+    let level = fill_level(luminance, invert, gamma) * ramp.len() as f32;
+    let index = (level.floor() as usize).min(last);
+    let fraction = level - index as f32;
+
+    match threshold {
+        Some(threshold) if index < last && fraction > threshold => ramp[index + 1],
+        _ => ramp[index],
+    }
+}
+
+/// Next denser character on the fill ramp
+///
+/// `@` and characters that are not on the ramp are returned unchanged.
+pub fn denser_fill_char(ch: char) -> char {
+    denser_ramp_char(&FILL_CHARS, ch)
+}
+
+/// Next denser character on any ramp
+///
+/// The densest character and characters that are not on the ramp are
+/// returned unchanged.
+pub fn denser_ramp_char(ramp: &[char], ch: char) -> char {
+    match ramp.iter().position(|&c| c == ch) {
+        Some(index) if index + 1 < ramp.len() => ramp[index + 1],
+        _ => ch,
+    }
+}
+
+/// Continuous position on the fill ramp in [0.0, 1.0]
+fn fill_level(luminance: f32, invert: bool, gamma: f32) -> f32 {
     let mut lum = luminance.clamp(0.0, 1.0);
 
     if invert {
         lum = 1.0 - lum;
     }
 
-    // Quantize to 0-9 range
-    // Shader logic: luminance = max(0, (floor(luminance * 10) - 1)) / 10.0f;
-    // We just need the index, so: floor(luminance * 10)
-    let index = (lum * 10.0).floor() as usize;
-    let index = index.min(9); // Clamp to 0-9
-
-    FILL_CHARS[index]
+    lum.powf(gamma)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_get_ramp_char() {
+        assert_eq!(get_ramp_char(&BLOCK_CHARS, 0.0, false, 1.0, None), ' ');
+        assert_eq!(get_ramp_char(&BLOCK_CHARS, 0.5, false, 1.0, None), '▒');
+        assert_eq!(get_ramp_char(&BLOCK_CHARS, 1.0, false, 1.0, None), '█');
+        assert_eq!(get_ramp_char(&BLOCK_CHARS, 1.0, true, 1.0, None), ' ');
+        // 0.5 sits halfway through level 2
+        assert_eq!(get_ramp_char(&BLOCK_CHARS, 0.5, false, 1.0, Some(0.4)), '▓');
+        assert_eq!(get_ramp_char(&['#'], 0.3, false, 1.0, Some(0.0)), '#');
+
+        assert_eq!(denser_ramp_char(&BLOCK_CHARS, '▒'), '▓');
+        assert_eq!(denser_ramp_char(&BLOCK_CHARS, '█'), '█');
+        assert_eq!(denser_ramp_char(&BLOCK_CHARS, '@'), '@');
+    }
+
+    #[test]
+    fn test_ascii_bitmap() {
+        assert_eq!(ascii_bitmap(' '), Some(&[0; 8]));
+        assert_eq!(ascii_bitmap('_').unwrap()[7], 0xff);
+        assert!(ascii_bitmap('~').is_some());
+        assert_eq!(ascii_bitmap('\x7f'), None);
+        assert_eq!(ascii_bitmap('é'), None);
+    }
+
     #[test]
     fn test_get_edge_char_vertical() {
         assert_eq!(get_edge_char(EdgeDirection::Vertical, 0, 0), '|');
@@ -107,6 +371,12 @@ mod tests {
         assert_eq!(get_edge_char(EdgeDirection::Diagonal2, 0, 0), '\\');
     }
 
+    #[test]
+    fn test_get_edge_char_junctions() {
+        assert_eq!(get_edge_char(EdgeDirection::Cross, 0, 0), '+');
+        assert_eq!(get_edge_char(EdgeDirection::DiagonalCross, 0, 0), 'X');
+    }
+
     #[test]
     fn test_get_edge_char_none() {
         assert_eq!(get_edge_char(EdgeDirection::None, 0, 0), ' ');
@@ -136,6 +406,54 @@ mod tests {
         assert_eq!(get_fill_char(1.0, true), ' ');
     }
 
+    #[test]
+    fn test_get_fill_char_gamma() {
+        // Gamma 1.0 matches the plain mapping
+        assert_eq!(
+            get_fill_char_with_gamma(0.55, false, 1.0),
+            get_fill_char(0.55, false)
+        );
+        // Gamma > 1 picks a sparser character, gamma < 1 a denser one
+        assert_eq!(get_fill_char_with_gamma(0.55, false, 2.0), FILL_CHARS[3]);
+        assert_eq!(get_fill_char_with_gamma(0.55, false, 0.5), FILL_CHARS[7]);
+        // Endpoints are unaffected
+        assert_eq!(get_fill_char_with_gamma(0.0, false, 2.0), ' ');
+        assert_eq!(get_fill_char_with_gamma(1.0, false, 0.5), '@');
+    }
+
+    #[test]
+    fn test_get_fill_char_dithered() {
+        // 0.35 sits halfway between ramp levels 3 and 4
+        assert_eq!(
+            get_fill_char_dithered(0.35, false, 1.0, 0.25),
+            FILL_CHARS[4]
+        );
+        assert_eq!(
+            get_fill_char_dithered(0.35, false, 1.0, 0.75),
+            FILL_CHARS[3]
+        );
+        // Brightest level has no denser neighbor
+        assert_eq!(get_fill_char_dithered(1.0, false, 1.0, 0.0), '@');
+        assert_eq!(get_fill_char_dithered(0.0, false, 1.0, 0.0), ' ');
+    }
+
+    #[test]
+    fn test_denser_fill_char() {
+        assert_eq!(denser_fill_char(' '), '.');
+        assert_eq!(denser_fill_char('#'), '%');
+        assert_eq!(denser_fill_char('@'), '@');
+        assert_eq!(denser_fill_char('|'), '|');
+    }
+
+    #[test]
+    fn test_shimmer_classes_cover_fill_ramp() {
+        for ch in FILL_CHARS {
+            assert!(shimmer_class(ch).is_some_and(|class| class.contains(&ch)));
+        }
+        assert_eq!(shimmer_class('.'), Some(&['.', ':'][..]));
+        assert_eq!(shimmer_class('?'), None);
+    }
+
     #[test]
     #[should_panic(expected = "Tile coordinates must be 0-7")]
     fn test_get_edge_char_invalid_coords() {
@@ -0,0 +1,223 @@
+//! NDI video output
+//!
+//! [`NdiSender`] broadcasts RGBA frames as an NDI source on the local network,
+//! where OBS, vMix and other NDI-aware software can pick it up. The NDI runtime
+//! is loaded at run time (from `NDI_RUNTIME_DIR_V6`/`NDI_RUNTIME_DIR_V5` or the
+//! system library path), so building needs no SDK; install the free NDI Tools
+//! or runtime on machines that send.
+
+use image::RgbaImage;
+use libloading::Library;
+use std::ffi::{CString, c_char, c_int, c_void};
+use std::path::PathBuf;
+
+/// `NDIlib_FourCC_video_type_RGBA`
+const FOURCC_RGBA: c_int = i32::from_le_bytes(*b"RGBA");
+/// `NDIlib_frame_format_type_progressive`
+const FRAME_FORMAT_PROGRESSIVE: c_int = 1;
+/// `NDIlib_send_timecode_synthesize`
+const TIMECODE_SYNTHESIZE: i64 = i64::MAX;
+
+#[repr(C)]
+struct SendCreate {
+    p_ndi_name: *const c_char,
+    p_groups: *const c_char,
+    clock_video: bool,
+    clock_audio: bool,
+}
+
+#[repr(C)]
+struct VideoFrameV2 {
+    xres: c_int,
+    yres: c_int,
+    fourcc: c_int,
+    frame_rate_n: c_int,
+    frame_rate_d: c_int,
+    picture_aspect_ratio: f32,
+    frame_format_type: c_int,
+    timecode: i64,
+    p_data: *mut u8,
+    line_stride_in_bytes: c_int,
+    p_metadata: *const c_char,
+    timestamp: i64,
+}
+
+type InitializeFn = unsafe extern "C" fn() -> bool;
+type DestroyFn = unsafe extern "C" fn();
+type SendCreateFn = unsafe extern "C" fn(*const SendCreate) -> *mut c_void;
+type SendDestroyFn = unsafe extern "C" fn(*mut c_void);
+type SendVideoFn = unsafe extern "C" fn(*mut c_void, *const VideoFrameV2);
+
+/// Runtime library file name for this platform
+fn library_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "Processing.NDI.Lib.x64.dll"
+    } else if cfg!(target_os = "macos") {
+        "libndi.dylib"
+    } else {
+        "libndi.so"
+    }
+}
+
+/// Candidate runtime locations, most specific first
+fn library_candidates() -> Vec<PathBuf> {
+    let name = library_name();
+    let mut candidates: Vec<PathBuf> = ["NDI_RUNTIME_DIR_V6", "NDI_RUNTIME_DIR_V5"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .map(|dir| PathBuf::from(dir).join(name))
+        .collect();
+    candidates.push(PathBuf::from(name));
+    if cfg!(target_os = "linux") {
+        candidates.push(PathBuf::from("libndi.so.6"));
+        candidates.push(PathBuf::from("libndi.so.5"));
+    }
+    candidates
+}
+
+/// Converts frames per second to the numerator/denominator pair NDI expects
+fn frame_rate_ratio(fps: f64) -> (c_int, c_int) {
+    ((fps * 1000.0).round() as c_int, 1000)
+}
+
+/// An NDI source that sends RGBA video frames
+pub struct NdiSender {
+    instance: *mut c_void,
+    frame_rate: (c_int, c_int),
+    send_video: SendVideoFn,
+    send_destroy: SendDestroyFn,
+    destroy: DestroyFn,
+    // Keeps the function pointers above valid; dropped last
+    _library: Library,
+}
+
+impl NdiSender {
+    /// Loads the NDI runtime and announces a source called `name`
+    ///
+    /// Sending is clocked to `frame_rate`, so [`NdiSender::send_frame`] blocks
+    /// as needed to keep receivers at a steady rate.
+    ///
+    /// # Errors
+    /// Returns an error if the runtime cannot be found, the CPU is not
+    /// supported by NDI, or the source cannot be created.
+    pub fn new(name: &str, frame_rate: f64) -> Result<Self, String> {
+        if !frame_rate.is_finite() || frame_rate <= 0.0 {
+            return Err(format!("invalid frame rate: {}", frame_rate));
+        }
+        let c_name = CString::new(name).map_err(|_| "NDI source name contains NUL")?;
+
+        let library = library_candidates()
+            .into_iter()
+            // SAFETY: the NDI runtime has no load-time initialization side effects
+            .find_map(|path| unsafe { Library::new(&path) }.ok())
+            .ok_or_else(|| {
+                format!(
+                    "NDI runtime ({}) not found; install NDI Tools or set NDI_RUNTIME_DIR_V6",
+                    library_name()
+                )
+            })?;
+
+        // SAFETY: signatures match the NDI SDK headers (Processing.NDI.Lib.h)
+        let (initialize, destroy, send_create, send_destroy, send_video) = unsafe {
+            let lookup_error = |e: libloading::Error| format!("incompatible NDI runtime: {}", e);
+            (
+                *library
+                    .get::<InitializeFn>(b"NDIlib_initialize\0")
+                    .map_err(lookup_error)?,
+                *library
+                    .get::<DestroyFn>(b"NDIlib_destroy\0")
+                    .map_err(lookup_error)?,
+                *library
+                    .get::<SendCreateFn>(b"NDIlib_send_create\0")
+                    .map_err(lookup_error)?,
+                *library
+                    .get::<SendDestroyFn>(b"NDIlib_send_destroy\0")
+                    .map_err(lookup_error)?,
+                *library
+                    .get::<SendVideoFn>(b"NDIlib_send_send_video_v2\0")
+                    .map_err(lookup_error)?,
+            )
+        };
+
+        // SAFETY: plain FFI calls; `settings` and `c_name` outlive send_create
+        let instance = unsafe {
+            if !initialize() {
+                return Err("NDI is not supported on this CPU".to_string());
+            }
+            let settings = SendCreate {
+                p_ndi_name: c_name.as_ptr(),
+                p_groups: std::ptr::null(),
+                clock_video: true,
+                clock_audio: false,
+            };
+            let instance = send_create(&settings);
+            if instance.is_null() {
+                destroy();
+                return Err(format!("failed to create NDI source '{}'", name));
+            }
+            instance
+        };
+
+        Ok(Self {
+            instance,
+            frame_rate: frame_rate_ratio(frame_rate),
+            send_video,
+            send_destroy,
+            destroy,
+            _library: library,
+        })
+    }
+
+    /// Sends one frame to all connected receivers
+    ///
+    /// Frames may change size between calls.
+    pub fn send_frame(&mut self, frame: &RgbaImage) {
+        let (width, height) = frame.dimensions();
+        let video = VideoFrameV2 {
+            xres: width as c_int,
+            yres: height as c_int,
+            fourcc: FOURCC_RGBA,
+            frame_rate_n: self.frame_rate.0,
+            frame_rate_d: self.frame_rate.1,
+            picture_aspect_ratio: 0.0, // square pixels
+            frame_format_type: FRAME_FORMAT_PROGRESSIVE,
+            timecode: TIMECODE_SYNTHESIZE,
+            // NDI only reads from the buffer
+            p_data: frame.as_raw().as_ptr() as *mut u8,
+            line_stride_in_bytes: (width * 4) as c_int,
+            p_metadata: std::ptr::null(),
+            timestamp: 0,
+        };
+
+        // SAFETY: synchronous send; the frame buffer outlives the call
+        unsafe { (self.send_video)(self.instance, &video) };
+    }
+}
+
+impl Drop for NdiSender {
+    fn drop(&mut self) {
+        // SAFETY: instance was created by this runtime and is not used again
+        unsafe {
+            (self.send_destroy)(self.instance);
+            (self.destroy)();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fourcc_rgba() {
+        // NDI_LIB_FOURCC('R', 'G', 'B', 'A')
+        let expected = b'R' as i32 | (b'G' as i32) << 8 | (b'B' as i32) << 16 | (b'A' as i32) << 24;
+        assert_eq!(FOURCC_RGBA, expected);
+    }
+
+    #[test]
+    fn test_frame_rate_ratio() {
+        assert_eq!(frame_rate_ratio(30.0), (30000, 1000));
+        assert_eq!(frame_rate_ratio(29.97), (29970, 1000));
+    }
+}
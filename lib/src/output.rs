@@ -0,0 +1,202 @@
+//! Processed frames with their character grid
+//!
+//! [`ProcessOutput`] keeps the rendered image together with one character and
+//! one color per tile, so the same result can be shown as an image, plain text,
+//! ANSI-colored terminal output or JSON for network viewers.
+
+use crate::render::ansi::grid_to_ansi;
+use crate::render::html::{HtmlOptions, render_html};
+use image::{ImageFormat, RgbaImage};
+use serde::Serialize;
+use std::io::Cursor;
+
+/// Result of [`crate::process_image_output`]
+#[derive(Debug, Clone)]
+pub struct ProcessOutput {
+    /// Rendered ASCII art image
+    pub image: RgbaImage,
+    /// Number of character columns (tiles horizontally)
+    pub columns: u32,
+    /// Number of character rows (tiles vertically)
+    pub rows: u32,
+    /// One character per tile, row-major
+    pub chars: Vec<char>,
+    /// Edge layer before compositing, row-major; spaces where there is no edge
+    pub edge_chars: Vec<char>,
+    /// Fill layer before compositing, row-major
+    pub fill_chars: Vec<char>,
+    /// Foreground color per tile, row-major: the configured ASCII color, or the
+    /// average source color when colors are preserved
+    pub colors: Vec<[u8; 3]>,
+    /// Configured background color
+    pub bg_color: [u8; 3],
+}
+
+impl ProcessOutput {
+    /// Characters of one row as a string
+    pub fn row_text(&self, row: u32) -> String {
+        let start = (row * self.columns) as usize;
+        self.chars[start..start + self.columns as usize]
+            .iter()
+            .collect()
+    }
+
+    /// Plain text, one line per row
+    pub fn to_text(&self) -> String {
+        (0..self.rows)
+            .map(|row| self.row_text(row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Text with 24-bit ANSI foreground and background colors
+    ///
+    /// Each line ends with a reset, so the output can be printed as-is.
+    pub fn to_ansi(&self) -> String {
+        grid_to_ansi(self.columns, &self.chars, &self.colors, Some(self.bg_color))
+    }
+
+    /// HTML `<pre>` block with one inline-colored `<span>` per run of equal colors
+    ///
+    /// See [`render_html`] for a CSS class palette.
+    pub fn to_html(&self) -> String {
+        render_html(self, &HtmlOptions::default())
+    }
+
+    /// Rich display hook for evcxr (Rust Jupyter kernel): colored ASCII as HTML
+    ///
+    /// Use [`ProcessOutput::as_image`] to show the rendered image instead.
+    pub fn evcxr_display(&self) {
+        println!(
+            "EVCXR_BEGIN_CONTENT text/html\n{}\nEVCXR_END_CONTENT",
+            self.to_html()
+        );
+    }
+
+    /// View that displays as the rendered image in evcxr
+    pub fn as_image(&self) -> ImageView<'_> {
+        ImageView(self)
+    }
+
+    /// JSON object with `columns`, `rows`, `lines`, `colors` and `bg_color`
+    pub fn to_json(&self) -> String {
+        let frame = JsonFrame {
+            columns: self.columns,
+            rows: self.rows,
+            lines: (0..self.rows).map(|row| self.row_text(row)).collect(),
+            colors: &self.colors,
+            bg_color: self.bg_color,
+        };
+        serde_json::to_string(&frame).expect("frame serialization cannot fail")
+    }
+}
+
+/// Displays a [`ProcessOutput`] as an inline PNG in evcxr
+pub struct ImageView<'a>(&'a ProcessOutput);
+
+impl ImageView<'_> {
+    /// Rich display hook for evcxr (Rust Jupyter kernel)
+    pub fn evcxr_display(&self) {
+        let mut png = Vec::new();
+        match self
+            .0
+            .image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        {
+            Ok(()) => println!(
+                "EVCXR_BEGIN_CONTENT image/png\n{}\nEVCXR_END_CONTENT",
+                base64_encode(&png)
+            ),
+            Err(e) => eprintln!("failed to encode image: {}", e),
+        }
+    }
+}
+
+/// Standard base64 with padding
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Wire format of [`ProcessOutput::to_json`]
+#[derive(Serialize)]
+struct JsonFrame<'a> {
+    columns: u32,
+    rows: u32,
+    lines: Vec<String>,
+    colors: &'a [[u8; 3]],
+    bg_color: [u8; 3],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ProcessOutput {
+        ProcessOutput {
+            image: RgbaImage::new(24, 16),
+            columns: 3,
+            rows: 2,
+            chars: vec!['|', '-', ' ', '.', '.', '@'],
+            edge_chars: vec!['|', '-', ' ', ' ', ' ', ' '],
+            fill_chars: vec!['.', ' ', ' ', '.', '.', '@'],
+            colors: vec![
+                [255, 0, 0],
+                [255, 0, 0],
+                [0, 255, 0],
+                [1, 2, 3],
+                [1, 2, 3],
+                [1, 2, 3],
+            ],
+            bg_color: [0, 0, 0],
+        }
+    }
+
+    #[test]
+    fn test_to_text() {
+        assert_eq!(sample().to_text(), "|- \n..@");
+    }
+
+    #[test]
+    fn test_to_ansi_skips_repeated_colors() {
+        let ansi = sample().to_ansi();
+        assert_eq!(ansi.lines().count(), 2);
+        assert_eq!(ansi.matches("\x1b[38;2;255;0;0m").count(), 1);
+        assert_eq!(ansi.matches("\x1b[38;2;1;2;3m").count(), 1);
+        assert!(ansi.ends_with("@\x1b[0m"));
+    }
+
+    #[test]
+    fn test_to_html_escapes_and_groups_colors() {
+        let mut output = sample();
+        output.chars[2] = '<';
+        let html = output.to_html();
+        assert!(html.contains("|-</span><span style=\"color:rgb(0,255,0)\">&lt;</span>"));
+        assert_eq!(html.matches("<span").count(), 3);
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+}
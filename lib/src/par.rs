@@ -0,0 +1,75 @@
+//! Data-parallel iteration through rayon, or plain sequential iterators when
+//! the `rayon` feature is off
+//!
+//! Modules import `crate::par::*` instead of `rayon::prelude::*`. Without the
+//! feature, `par_iter()`, `into_par_iter()` and friends return the matching
+//! `std` iterators, whose adaptors share the rayon names used here (`map`,
+//! `zip`, `enumerate`, `filter`, `for_each`, `collect`), so the call sites
+//! compile unchanged and run on the calling thread.
+
+#[cfg(feature = "rayon")]
+pub(crate) use rayon::prelude::*;
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) use serial::*;
+
+#[cfg(not(feature = "rayon"))]
+mod serial {
+    use std::slice::ChunksExactMut;
+
+    /// Sequential `into_par_iter`
+    pub(crate) trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Self::IntoIter {
+            self.into_iter()
+        }
+    }
+
+    impl<I: IntoIterator> IntoParallelIterator for I {}
+
+    /// Sequential `par_iter`
+    pub(crate) trait IntoParallelRefIterator<'a> {
+        type Iter: Iterator;
+
+        fn par_iter(&'a self) -> Self::Iter;
+    }
+
+    impl<'a, T: 'a + ?Sized> IntoParallelRefIterator<'a> for T
+    where
+        &'a T: IntoIterator,
+    {
+        type Iter = <&'a T as IntoIterator>::IntoIter;
+
+        fn par_iter(&'a self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+
+    /// Sequential `par_iter_mut`
+    pub(crate) trait IntoParallelRefMutIterator<'a> {
+        type Iter: Iterator;
+
+        fn par_iter_mut(&'a mut self) -> Self::Iter;
+    }
+
+    impl<'a, T: 'a + ?Sized> IntoParallelRefMutIterator<'a> for T
+    where
+        &'a mut T: IntoIterator,
+    {
+        type Iter = <&'a mut T as IntoIterator>::IntoIter;
+
+        fn par_iter_mut(&'a mut self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+
+    /// Sequential `par_chunks_exact_mut`
+    pub(crate) trait ParallelSliceMut<T> {
+        fn par_chunks_exact_mut(&mut self, chunk_size: usize) -> ChunksExactMut<'_, T>;
+    }
+
+    impl<T> ParallelSliceMut<T> for [T] {
+        fn par_chunks_exact_mut(&mut self, chunk_size: usize) -> ChunksExactMut<'_, T> {
+            self.chunks_exact_mut(chunk_size)
+        }
+    }
+}
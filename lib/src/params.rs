@@ -0,0 +1,1407 @@
+//! Machine-readable description of every `AsciiConfig` parameter
+//!
+//! UIs, command-line front ends and network APIs can use [`PARAMS`] to generate
+//! controls and validation instead of hardcoding ranges, and read or write
+//! fields by name with [`AsciiConfig::get_param`] / [`AsciiConfig::set_param`].
+//! Defaults are not duplicated here; they come from `AsciiConfig::default()`.
+
+use crate::config::{
+    AsciiConfig, BlurMode, CharSelection, Charset, CompositeMode, EdgeChars, EdgeMode, FillDither,
+    LuminanceAdjust, LuminanceMode, OutputMode, Palette, PreFilter, TILE_SIZES, ThresholdMode,
+    TileStatistic,
+};
+use serde::{Deserialize, Serialize};
+
+/// Pipeline stage a parameter affects
+///
+/// Stages are ordered as they run, so the smallest of several stages is the
+/// earliest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Luminance extraction from the source colors
+    Luminance,
+    /// Gaussian blurs feeding the DoG
+    Blur,
+    /// DoG thresholding and Sobel gradients
+    EdgeDetection,
+    /// Per-tile edge direction voting
+    EdgeVoting,
+    /// Tile luminance and fill character selection
+    Fill,
+    /// Final glyph rasterization and colors
+    Rendering,
+}
+
+/// Type and allowed range of a parameter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    /// Integer in `min..=max`
+    Int { min: i64, max: i64 },
+    /// Floating point value in `min..=max`
+    Float { min: f64, max: f64 },
+    /// On/off switch
+    Bool,
+    /// RGB color
+    Color,
+    /// One of a fixed set of names
+    Choice { options: &'static [&'static str] },
+}
+
+/// Description of a single config field
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ParamInfo {
+    /// Field name, as used in config files
+    pub name: &'static str,
+    /// Type and allowed range
+    pub kind: ParamKind,
+    /// Whether the parameter can be switched off ([`ParamValue::Unset`])
+    pub optional: bool,
+    /// Pipeline stage the parameter affects
+    pub stage: PipelineStage,
+    /// One-line description
+    pub doc: &'static str,
+}
+
+impl ParamInfo {
+    /// Default value of this parameter
+    pub fn default_value(&self) -> ParamValue {
+        AsciiConfig::default()
+            .get_param(self.name)
+            .expect("PARAMS entry without matching config field")
+    }
+
+    /// Parses a value for this parameter from text
+    ///
+    /// Accepted forms: integers and floats as usual, booleans as
+    /// `true/false/1/0/yes/no/on/off`, colors as `#rrggbb` or `r,g,b`, choices by
+    /// name, and `off`/`none` (or an empty string) to unset an optional parameter.
+    /// Ranges are not checked here; [`AsciiConfig::set_param`] does that.
+    pub fn parse_value(&self, text: &str) -> Result<ParamValue, String> {
+        let text = text.trim();
+        if self.optional && matches!(text.to_ascii_lowercase().as_str(), "" | "off" | "none") {
+            return Ok(ParamValue::Unset);
+        }
+
+        let invalid = || format!("invalid value `{}` for `{}`", text, self.name);
+        match self.kind {
+            ParamKind::Int { .. } => text.parse().map(ParamValue::Int).map_err(|_| invalid()),
+            ParamKind::Float { .. } => text.parse().map(ParamValue::Float).map_err(|_| invalid()),
+            ParamKind::Bool => match text.to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => Ok(ParamValue::Bool(true)),
+                "false" | "0" | "no" | "off" => Ok(ParamValue::Bool(false)),
+                _ => Err(invalid()),
+            },
+            ParamKind::Color => parse_color(text).map(ParamValue::Color).ok_or_else(invalid),
+            ParamKind::Choice { .. } => Ok(ParamValue::Choice(text.to_ascii_lowercase())),
+        }
+    }
+
+    /// Value at position `t` in [0.0, 1.0] along this parameter's range
+    ///
+    /// Meant for continuous controllers (MIDI knobs, faders): numbers are
+    /// interpolated between `min` and `max` (integers rounded), booleans switch
+    /// at 0.5 and choices split the range evenly between the fixed options
+    /// (templated ones such as `percentile:<0-100>` are skipped). Colors have
+    /// no single axis and return `None`.
+    pub fn value_at(&self, t: f64) -> Option<ParamValue> {
+        let t = t.clamp(0.0, 1.0);
+        match self.kind {
+            ParamKind::Int { min, max } => Some(ParamValue::Int(
+                min + ((max - min) as f64 * t).round() as i64,
+            )),
+            ParamKind::Float { min, max } => Some(ParamValue::Float(min + (max - min) * t)),
+            ParamKind::Bool => Some(ParamValue::Bool(t >= 0.5)),
+            ParamKind::Choice { options } => {
+                let fixed: Vec<&str> = options
+                    .iter()
+                    .copied()
+                    .filter(|o| !o.contains('<'))
+                    .collect();
+                let index = ((t * fixed.len() as f64) as usize).min(fixed.len().checked_sub(1)?);
+                Some(ParamValue::Choice(fixed[index].to_string()))
+            }
+            ParamKind::Color => None,
+        }
+    }
+}
+
+/// Parses `#rrggbb` or `r,g,b`
+fn parse_color(text: &str) -> Option<[u8; 3]> {
+    if let Some(hex) = text.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some([channel(0)?, channel(2)?, channel(4)?]);
+    }
+
+    let mut parts = text.split(',').map(|p| p.trim().parse::<u8>());
+    let color = [
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    ];
+    parts.next().is_none().then_some(color)
+}
+
+/// A parameter value read from or written to a config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Color([u8; 3]),
+    Choice(String),
+    /// Optional parameter switched off
+    Unset,
+}
+
+/// Every `AsciiConfig` field, in the order they appear in the struct
+pub const PARAMS: &[ParamInfo] = &[
+    ParamInfo {
+        name: "luminance_mode",
+        kind: ParamKind::Choice {
+            options: &[
+                "rec601",
+                "rec709",
+                "rec2020",
+                "average",
+                "max",
+                "custom:<r>,<g>,<b>",
+            ],
+        },
+        optional: false,
+        stage: PipelineStage::Luminance,
+        doc: "Formula that weights red, green and blue into luminance",
+    },
+    ParamInfo {
+        name: "linear_luminance",
+        kind: ParamKind::Bool,
+        optional: false,
+        stage: PipelineStage::Luminance,
+        doc: "Weight RGB in linear light instead of on the sRGB-encoded bytes",
+    },
+    ParamInfo {
+        name: "kernel_size",
+        kind: ParamKind::Int { min: 1, max: 10 },
+        optional: false,
+        stage: PipelineStage::Blur,
+        doc: "Radius of the Gaussian blur kernels",
+    },
+    ParamInfo {
+        name: "sigma",
+        kind: ParamKind::Float { min: 0.0, max: 5.0 },
+        optional: false,
+        stage: PipelineStage::Blur,
+        doc: "Standard deviation of the first Gaussian",
+    },
+    ParamInfo {
+        name: "sigma_scale",
+        kind: ParamKind::Float { min: 0.0, max: 5.0 },
+        optional: false,
+        stage: PipelineStage::Blur,
+        doc: "Scale of the second Gaussian's sigma in the DoG",
+    },
+    ParamInfo {
+        name: "blur_mode",
+        kind: ParamKind::Choice {
+            options: &["gaussian", "box"],
+        },
+        optional: false,
+        stage: PipelineStage::Blur,
+        doc: "Exact Gaussian or three-pass box approximation of the DoG blurs",
+    },
+    ParamInfo {
+        name: "pre_filter",
+        kind: ParamKind::Choice {
+            options: &[
+                "none",
+                "bilateral",
+                "bilateral:<sigma_space>,<sigma_range>",
+                "kuwahara",
+                "kuwahara:<radius>,<sectors>",
+            ],
+        },
+        optional: false,
+        stage: PipelineStage::Blur,
+        doc: "Edge-preserving smoothing or painterly stylization of the luminance before edge detection and fill",
+    },
+    ParamInfo {
+        name: "median_radius",
+        kind: ParamKind::Int { min: 1, max: 3 },
+        optional: true,
+        stage: PipelineStage::Blur,
+        doc: "Median filter radius that removes salt-and-pepper noise before edge detection",
+    },
+    ParamInfo {
+        name: "edge_mode",
+        kind: ParamKind::Choice {
+            options: &[
+                "dog",
+                "xdog",
+                "xdog:<phi>,<epsilon>",
+                "sobel",
+                "sobel:<threshold>",
+            ],
+        },
+        optional: false,
+        stage: PipelineStage::EdgeDetection,
+        doc: "Hard binary DoG threshold, the soft extended DoG (XDoG) or Sobel on the luminance",
+    },
+    ParamInfo {
+        name: "tau",
+        kind: ParamKind::Float { min: 0.0, max: 1.1 },
+        optional: false,
+        stage: PipelineStage::EdgeDetection,
+        doc: "Multiplier for the second blur in the DoG",
+    },
+    ParamInfo {
+        name: "threshold",
+        kind: ParamKind::Float {
+            min: 0.001,
+            max: 0.1,
+        },
+        optional: false,
+        stage: PipelineStage::EdgeDetection,
+        doc: "DoG binarization threshold",
+    },
+    ParamInfo {
+        name: "threshold_mode",
+        kind: ParamKind::Choice {
+            options: &["fixed", "otsu", "percentile", "percentile:<percentile>"],
+        },
+        optional: false,
+        stage: PipelineStage::EdgeDetection,
+        doc: "Fixed DoG threshold, or one picked per image by Otsu's method or a percentile",
+    },
+    ParamInfo {
+        name: "edge_dilation",
+        kind: ParamKind::Int { min: -3, max: 3 },
+        optional: false,
+        stage: PipelineStage::EdgeDetection,
+        doc: "Pixels the DoG edge lines grow by (positive) or shrink by (negative)",
+    },
+    ParamInfo {
+        name: "structure_tensor_sigma",
+        kind: ParamKind::Float { min: 0.5, max: 5.0 },
+        optional: true,
+        stage: PipelineStage::EdgeDetection,
+        doc: "Edge angles from a smoothed structure tensor instead of per-pixel Sobel angles",
+    },
+    ParamInfo {
+        name: "sobel_threshold",
+        kind: ParamKind::Float { min: 0.0, max: 4.0 },
+        optional: false,
+        stage: PipelineStage::EdgeDetection,
+        doc: "Sobel gradient magnitude a DoG or XDoG edge pixel needs to take part in tile voting",
+    },
+    ParamInfo {
+        name: "non_max_suppression",
+        kind: ParamKind::Bool,
+        optional: false,
+        stage: PipelineStage::EdgeDetection,
+        doc: "Thin Sobel edges to one pixel with non-maximum suppression before tile voting",
+    },
+    ParamInfo {
+        name: "edge_threshold",
+        kind: ParamKind::Int { min: 0, max: 64 },
+        optional: false,
+        stage: PipelineStage::EdgeVoting,
+        doc: "Edge pixels needed in a tile to draw an edge character",
+    },
+    ParamInfo {
+        name: "edge_junction_margin",
+        kind: ParamKind::Int { min: 0, max: 64 },
+        optional: true,
+        stage: PipelineStage::EdgeVoting,
+        doc: "Vote gap under which perpendicular directions form a +/X junction",
+    },
+    ParamInfo {
+        name: "saliency_strength",
+        kind: ParamKind::Float { min: 0.0, max: 1.0 },
+        optional: false,
+        stage: PipelineStage::EdgeVoting,
+        doc: "How far saliency lowers the edge threshold on focal tiles and raises it elsewhere",
+    },
+    ParamInfo {
+        name: "ascii_color",
+        kind: ParamKind::Color,
+        optional: false,
+        stage: PipelineStage::Rendering,
+        doc: "Foreground color of the characters",
+    },
+    ParamInfo {
+        name: "bg_color",
+        kind: ParamKind::Color,
+        optional: false,
+        stage: PipelineStage::Rendering,
+        doc: "Background color",
+    },
+    ParamInfo {
+        name: "luminance_adjust",
+        kind: ParamKind::Choice {
+            options: &[
+                "none",
+                "auto_contrast",
+                "equalize",
+                "clahe",
+                "clahe:<clip_limit>,<grid>",
+            ],
+        },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Contrast stretch or histogram equalization of the luminance before tiling",
+    },
+    ParamInfo {
+        name: "exposure",
+        kind: ParamKind::Float { min: 0.0, max: 5.0 },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Multiplier on the fill luminance, as the shader's _Exposure",
+    },
+    ParamInfo {
+        name: "attenuation",
+        kind: ParamKind::Float { min: 0.1, max: 5.0 },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Exponent applied to the exposed fill luminance, as the shader's _Attenuation",
+    },
+    ParamInfo {
+        name: "tile_statistic",
+        kind: ParamKind::Choice {
+            options: &["mean", "median", "max", "percentile:<0-100>"],
+        },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "How a tile's pixels are reduced to one luminance value",
+    },
+    ParamInfo {
+        name: "local_contrast_radius",
+        kind: ParamKind::Int { min: 1, max: 16 },
+        optional: true,
+        stage: PipelineStage::Fill,
+        doc: "Tile radius for local contrast normalization",
+    },
+    ParamInfo {
+        name: "fill_gamma",
+        kind: ParamKind::Float { min: 0.1, max: 5.0 },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Gamma applied before fill character quantization",
+    },
+    ParamInfo {
+        name: "fill_dither",
+        kind: ParamKind::Choice {
+            options: &["off", "ordered", "noise"],
+        },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Pattern for mixing adjacent ramp characters within a tile",
+    },
+    ParamInfo {
+        name: "texture_threshold",
+        kind: ParamKind::Int { min: 1, max: 128 },
+        optional: true,
+        stage: PipelineStage::Fill,
+        doc: "Tile luminance std dev (0-255) above which fill uses the next denser character",
+    },
+    ParamInfo {
+        name: "char_selection",
+        kind: ParamKind::Choice {
+            options: &["luminance", "structure"],
+        },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Fill by luminance ramp or by the glyph that best matches each tile's pixels",
+    },
+    ParamInfo {
+        name: "charset",
+        kind: ParamKind::Choice {
+            options: &["ascii", "blocks"],
+        },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Fill ramp characters; custom ramps are set in config files",
+    },
+    ParamInfo {
+        name: "draw_edges",
+        kind: ParamKind::Bool,
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Draw edge characters on edge tiles",
+    },
+    ParamInfo {
+        name: "draw_fill",
+        kind: ParamKind::Bool,
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Draw luminance-based fill characters",
+    },
+    ParamInfo {
+        name: "invert_luminance",
+        kind: ParamKind::Bool,
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Invert the brightness-to-character mapping",
+    },
+    ParamInfo {
+        name: "auto_invert",
+        kind: ParamKind::Bool,
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "Flip invert_luminance when the image is predominantly bright",
+    },
+    ParamInfo {
+        name: "composite_mode",
+        kind: ParamKind::Choice {
+            options: &["edges_over_fill", "fill_where_no_edge", "blend"],
+        },
+        optional: false,
+        stage: PipelineStage::Fill,
+        doc: "How the edge and fill character layers are combined",
+    },
+    ParamInfo {
+        name: "blend_with_base",
+        kind: ParamKind::Float { min: 0.0, max: 1.0 },
+        optional: false,
+        stage: PipelineStage::Rendering,
+        doc: "Mix of the source image into the rendered output",
+    },
+    ParamInfo {
+        name: "edge_debug",
+        kind: ParamKind::Bool,
+        optional: false,
+        stage: PipelineStage::Rendering,
+        doc: "Color edge tiles by direction and fill tiles by luminance (debug view)",
+    },
+    ParamInfo {
+        name: "palette",
+        kind: ParamKind::Choice {
+            options: &["classic", "okabe_ito", "tol_bright"],
+        },
+        optional: false,
+        stage: PipelineStage::Rendering,
+        doc: "Edge debug colors; okabe_ito and tol_bright are color-blind safe",
+    },
+    ParamInfo {
+        name: "edge_chars",
+        kind: ParamKind::Choice {
+            options: &["ascii", "box_drawing", "heavy"],
+        },
+        optional: false,
+        stage: PipelineStage::Rendering,
+        doc: "Characters for edge tiles; custom sets with bitmaps are set in config files",
+    },
+    ParamInfo {
+        name: "positional_edges",
+        kind: ParamKind::Bool,
+        optional: false,
+        stage: PipelineStage::Rendering,
+        doc: "Shift edge glyphs in image output onto where the edge crosses the tile",
+    },
+    ParamInfo {
+        name: "rgb_split",
+        kind: ParamKind::Int { min: 0, max: 16 },
+        optional: true,
+        stage: PipelineStage::Rendering,
+        doc: "Render the R, G and B fill layers separately, shifted apart by this many pixels",
+    },
+    ParamInfo {
+        name: "output_mode",
+        kind: ParamKind::Choice {
+            options: &["ascii", "braille"],
+        },
+        optional: false,
+        stage: PipelineStage::Rendering,
+        doc: "One ASCII glyph per tile, or one braille character per 2x4 pixels",
+    },
+    ParamInfo {
+        name: "tile_size",
+        kind: ParamKind::Choice {
+            options: &["4", "8", "16", "32"],
+        },
+        optional: false,
+        stage: PipelineStage::Blur,
+        doc: "Edge length of a tile in pixels; the input is resized to a multiple of it",
+    },
+    ParamInfo {
+        name: "tile_height",
+        kind: ParamKind::Choice {
+            options: &["4", "8", "16", "32"],
+        },
+        optional: true,
+        stage: PipelineStage::Blur,
+        doc: "Tile height in pixels when it differs from tile_size, e.g. 16 for terminal cells",
+    },
+];
+
+/// Looks up a parameter description by field name
+pub fn param_info(name: &str) -> Option<&'static ParamInfo> {
+    PARAMS.iter().find(|p| p.name == name)
+}
+
+fn luminance_mode_name(mode: LuminanceMode) -> String {
+    match mode {
+        LuminanceMode::Rec601 => "rec601".to_string(),
+        LuminanceMode::Rec709 => "rec709".to_string(),
+        LuminanceMode::Rec2020 => "rec2020".to_string(),
+        LuminanceMode::Average => "average".to_string(),
+        LuminanceMode::Max => "max".to_string(),
+        LuminanceMode::Custom { weights: [r, g, b] } => format!("custom:{},{},{}", r, g, b),
+    }
+}
+
+fn parse_luminance_mode(name: &str) -> Option<LuminanceMode> {
+    match name {
+        "rec601" => Some(LuminanceMode::Rec601),
+        "rec709" => Some(LuminanceMode::Rec709),
+        "rec2020" => Some(LuminanceMode::Rec2020),
+        "average" => Some(LuminanceMode::Average),
+        "max" => Some(LuminanceMode::Max),
+        _ => {
+            let mut parts = name.strip_prefix("custom:")?.split(',');
+            let mut next = || parts.next()?.trim().parse().ok();
+            let weights = [next()?, next()?, next()?];
+            parts
+                .next()
+                .is_none()
+                .then_some(LuminanceMode::Custom { weights })
+        }
+    }
+}
+
+fn luminance_adjust_name(adjust: LuminanceAdjust) -> String {
+    match adjust {
+        LuminanceAdjust::None => "none".to_string(),
+        LuminanceAdjust::AutoContrast => "auto_contrast".to_string(),
+        LuminanceAdjust::Equalize => "equalize".to_string(),
+        LuminanceAdjust::Clahe { clip_limit, grid } => format!("clahe:{},{}", clip_limit, grid),
+    }
+}
+
+fn parse_luminance_adjust(name: &str) -> Option<LuminanceAdjust> {
+    match name {
+        "none" => Some(LuminanceAdjust::None),
+        "auto_contrast" => Some(LuminanceAdjust::AutoContrast),
+        "equalize" => Some(LuminanceAdjust::Equalize),
+        "clahe" => Some(LuminanceAdjust::CLAHE),
+        _ => {
+            let (clip_limit, grid) = name.strip_prefix("clahe:")?.split_once(',')?;
+            Some(LuminanceAdjust::Clahe {
+                clip_limit: clip_limit.trim().parse().ok()?,
+                grid: grid.trim().parse().ok()?,
+            })
+        }
+    }
+}
+
+fn tile_statistic_name(statistic: TileStatistic) -> String {
+    match statistic {
+        TileStatistic::Mean => "mean".to_string(),
+        TileStatistic::Median => "median".to_string(),
+        TileStatistic::Max => "max".to_string(),
+        TileStatistic::Percentile(p) => format!("percentile:{}", p),
+    }
+}
+
+fn parse_tile_statistic(name: &str) -> Option<TileStatistic> {
+    match name {
+        "mean" => Some(TileStatistic::Mean),
+        "median" => Some(TileStatistic::Median),
+        "max" => Some(TileStatistic::Max),
+        _ => name
+            .strip_prefix("percentile:")
+            .and_then(|p| p.parse().ok())
+            .map(TileStatistic::Percentile),
+    }
+}
+
+fn pre_filter_name(filter: PreFilter) -> String {
+    match filter {
+        PreFilter::None => "none".to_string(),
+        PreFilter::Bilateral {
+            sigma_space,
+            sigma_range,
+        } => format!("bilateral:{},{}", sigma_space, sigma_range),
+        PreFilter::Kuwahara { radius, sectors } => format!("kuwahara:{},{}", radius, sectors),
+    }
+}
+
+fn parse_pre_filter(name: &str) -> Option<PreFilter> {
+    match name {
+        "none" => Some(PreFilter::None),
+        "bilateral" => Some(PreFilter::BILATERAL),
+        "kuwahara" => Some(PreFilter::KUWAHARA),
+        _ => {
+            let (kind, args) = name.split_once(':')?;
+            let (first, second) = args.split_once(',')?;
+            let (first, second) = (first.trim(), second.trim());
+            match kind {
+                "bilateral" => Some(PreFilter::Bilateral {
+                    sigma_space: first.parse().ok()?,
+                    sigma_range: second.parse().ok()?,
+                }),
+                "kuwahara" => Some(PreFilter::Kuwahara {
+                    radius: first.parse().ok()?,
+                    sectors: second.parse().ok()?,
+                }),
+                _ => None,
+            }
+        }
+    }
+}
+
+fn edge_mode_name(mode: EdgeMode) -> String {
+    match mode {
+        EdgeMode::Dog => "dog".to_string(),
+        EdgeMode::Xdog { phi, epsilon } => format!("xdog:{},{}", phi, epsilon),
+        EdgeMode::Sobel { threshold } => format!("sobel:{}", threshold),
+    }
+}
+
+fn parse_edge_mode(name: &str) -> Option<EdgeMode> {
+    match name {
+        "dog" => Some(EdgeMode::Dog),
+        "xdog" => Some(EdgeMode::XDOG),
+        "sobel" => Some(EdgeMode::SOBEL),
+        _ => {
+            if let Some(threshold) = name.strip_prefix("sobel:") {
+                return Some(EdgeMode::Sobel {
+                    threshold: threshold.trim().parse().ok()?,
+                });
+            }
+            let (phi, epsilon) = name.strip_prefix("xdog:")?.split_once(',')?;
+            Some(EdgeMode::Xdog {
+                phi: phi.trim().parse().ok()?,
+                epsilon: epsilon.trim().parse().ok()?,
+            })
+        }
+    }
+}
+
+fn threshold_mode_name(mode: ThresholdMode) -> String {
+    match mode {
+        ThresholdMode::Fixed => "fixed".to_string(),
+        ThresholdMode::Otsu => "otsu".to_string(),
+        ThresholdMode::Percentile { percentile } => format!("percentile:{}", percentile),
+    }
+}
+
+fn parse_threshold_mode(name: &str) -> Option<ThresholdMode> {
+    match name {
+        "fixed" => Some(ThresholdMode::Fixed),
+        "otsu" => Some(ThresholdMode::Otsu),
+        "percentile" => Some(ThresholdMode::PERCENTILE),
+        _ => Some(ThresholdMode::Percentile {
+            percentile: name.strip_prefix("percentile:")?.trim().parse().ok()?,
+        }),
+    }
+}
+
+fn blur_mode_name(mode: BlurMode) -> &'static str {
+    match mode {
+        BlurMode::Gaussian => "gaussian",
+        BlurMode::Box => "box",
+    }
+}
+
+fn parse_blur_mode(name: &str) -> Option<BlurMode> {
+    match name {
+        "gaussian" => Some(BlurMode::Gaussian),
+        "box" => Some(BlurMode::Box),
+        _ => None,
+    }
+}
+
+fn fill_dither_name(dither: FillDither) -> &'static str {
+    match dither {
+        FillDither::Off => "off",
+        FillDither::Ordered => "ordered",
+        FillDither::Noise => "noise",
+    }
+}
+
+fn parse_fill_dither(name: &str) -> Option<FillDither> {
+    match name {
+        "off" => Some(FillDither::Off),
+        "ordered" => Some(FillDither::Ordered),
+        "noise" => Some(FillDither::Noise),
+        _ => None,
+    }
+}
+
+fn char_selection_name(selection: CharSelection) -> &'static str {
+    match selection {
+        CharSelection::Luminance => "luminance",
+        CharSelection::Structure => "structure",
+    }
+}
+
+fn parse_char_selection(name: &str) -> Option<CharSelection> {
+    match name {
+        "luminance" => Some(CharSelection::Luminance),
+        "structure" => Some(CharSelection::Structure),
+        _ => None,
+    }
+}
+
+fn charset_name(charset: &Charset) -> &'static str {
+    match charset {
+        Charset::Ascii => "ascii",
+        Charset::Blocks => "blocks",
+        Charset::Custom { .. } => "custom",
+    }
+}
+
+fn parse_charset(name: &str) -> Option<Charset> {
+    match name {
+        "ascii" => Some(Charset::Ascii),
+        "blocks" => Some(Charset::Blocks),
+        _ => None,
+    }
+}
+
+fn composite_mode_name(mode: CompositeMode) -> &'static str {
+    match mode {
+        CompositeMode::EdgesOverFill => "edges_over_fill",
+        CompositeMode::FillWhereNoEdge => "fill_where_no_edge",
+        CompositeMode::Blend => "blend",
+    }
+}
+
+fn parse_composite_mode(name: &str) -> Option<CompositeMode> {
+    match name {
+        "edges_over_fill" => Some(CompositeMode::EdgesOverFill),
+        "fill_where_no_edge" => Some(CompositeMode::FillWhereNoEdge),
+        "blend" => Some(CompositeMode::Blend),
+        _ => None,
+    }
+}
+
+fn palette_name(palette: Palette) -> &'static str {
+    match palette {
+        Palette::Classic => "classic",
+        Palette::OkabeIto => "okabe_ito",
+        Palette::TolBright => "tol_bright",
+    }
+}
+
+fn parse_palette(name: &str) -> Option<Palette> {
+    match name {
+        "classic" => Some(Palette::Classic),
+        "okabe_ito" => Some(Palette::OkabeIto),
+        "tol_bright" => Some(Palette::TolBright),
+        _ => None,
+    }
+}
+
+fn edge_chars_name(edge_chars: EdgeChars) -> &'static str {
+    match edge_chars {
+        EdgeChars::Ascii => "ascii",
+        EdgeChars::BoxDrawing => "box_drawing",
+        EdgeChars::Heavy => "heavy",
+        EdgeChars::Custom { .. } => "custom",
+    }
+}
+
+fn parse_edge_chars(name: &str) -> Option<EdgeChars> {
+    match name {
+        "ascii" => Some(EdgeChars::Ascii),
+        "box_drawing" => Some(EdgeChars::BoxDrawing),
+        "heavy" => Some(EdgeChars::Heavy),
+        _ => None,
+    }
+}
+
+fn output_mode_name(mode: OutputMode) -> &'static str {
+    match mode {
+        OutputMode::Ascii => "ascii",
+        OutputMode::Braille => "braille",
+    }
+}
+
+fn parse_output_mode(name: &str) -> Option<OutputMode> {
+    match name {
+        "ascii" => Some(OutputMode::Ascii),
+        "braille" => Some(OutputMode::Braille),
+        _ => None,
+    }
+}
+
+impl AsciiConfig {
+    /// Reads a parameter by field name
+    ///
+    /// Returns None if there is no parameter with that name.
+    pub fn get_param(&self, name: &str) -> Option<ParamValue> {
+        let optional =
+            |value: Option<u32>| value.map_or(ParamValue::Unset, |v| ParamValue::Int(v as i64));
+
+        Some(match name {
+            "luminance_mode" => ParamValue::Choice(luminance_mode_name(self.luminance_mode)),
+            "linear_luminance" => ParamValue::Bool(self.linear_luminance),
+            "kernel_size" => ParamValue::Int(self.kernel_size as i64),
+            "sigma" => ParamValue::Float(self.sigma as f64),
+            "sigma_scale" => ParamValue::Float(self.sigma_scale as f64),
+            "blur_mode" => ParamValue::Choice(blur_mode_name(self.blur_mode).to_string()),
+            "pre_filter" => ParamValue::Choice(pre_filter_name(self.pre_filter)),
+            "median_radius" => optional(self.median_radius),
+            "edge_mode" => ParamValue::Choice(edge_mode_name(self.edge_mode)),
+            "tau" => ParamValue::Float(self.tau as f64),
+            "threshold" => ParamValue::Float(self.threshold as f64),
+            "threshold_mode" => ParamValue::Choice(threshold_mode_name(self.threshold_mode)),
+            "edge_dilation" => ParamValue::Int(self.edge_dilation as i64),
+            "structure_tensor_sigma" => self
+                .structure_tensor_sigma
+                .map_or(ParamValue::Unset, |v| ParamValue::Float(v as f64)),
+            "sobel_threshold" => ParamValue::Float(self.sobel_threshold as f64),
+            "non_max_suppression" => ParamValue::Bool(self.non_max_suppression),
+            "edge_threshold" => ParamValue::Int(self.edge_threshold as i64),
+            "edge_junction_margin" => optional(self.edge_junction_margin),
+            "saliency_strength" => ParamValue::Float(self.saliency_strength as f64),
+            "ascii_color" => ParamValue::Color(self.ascii_color),
+            "bg_color" => ParamValue::Color(self.bg_color),
+            "luminance_adjust" => ParamValue::Choice(luminance_adjust_name(self.luminance_adjust)),
+            "exposure" => ParamValue::Float(self.exposure as f64),
+            "attenuation" => ParamValue::Float(self.attenuation as f64),
+            "tile_statistic" => ParamValue::Choice(tile_statistic_name(self.tile_statistic)),
+            "local_contrast_radius" => optional(self.local_contrast_radius),
+            "fill_gamma" => ParamValue::Float(self.fill_gamma as f64),
+            "fill_dither" => ParamValue::Choice(fill_dither_name(self.fill_dither).to_string()),
+            "texture_threshold" => optional(self.texture_threshold),
+            "char_selection" => {
+                ParamValue::Choice(char_selection_name(self.char_selection).to_string())
+            }
+            "charset" => ParamValue::Choice(charset_name(&self.charset).to_string()),
+            "draw_edges" => ParamValue::Bool(self.draw_edges),
+            "draw_fill" => ParamValue::Bool(self.draw_fill),
+            "invert_luminance" => ParamValue::Bool(self.invert_luminance),
+            "auto_invert" => ParamValue::Bool(self.auto_invert),
+            "composite_mode" => {
+                ParamValue::Choice(composite_mode_name(self.composite_mode).to_string())
+            }
+            "blend_with_base" => ParamValue::Float(self.blend_with_base as f64),
+            "edge_debug" => ParamValue::Bool(self.edge_debug),
+            "palette" => ParamValue::Choice(palette_name(self.palette).to_string()),
+            "edge_chars" => ParamValue::Choice(edge_chars_name(self.edge_chars).to_string()),
+            "positional_edges" => ParamValue::Bool(self.positional_edges),
+            "rgb_split" => optional(self.rgb_split),
+            "output_mode" => ParamValue::Choice(output_mode_name(self.output_mode).to_string()),
+            "tile_size" => ParamValue::Choice(self.tile_size.to_string()),
+            "tile_height" => self
+                .tile_height
+                .map_or(ParamValue::Unset, |h| ParamValue::Choice(h.to_string())),
+            _ => return None,
+        })
+    }
+
+    /// Writes a parameter by field name
+    ///
+    /// The value is checked against the parameter's kind and range from [`PARAMS`]
+    /// before anything is changed.
+    ///
+    /// # Errors
+    /// Returns a message if the name is unknown, the value has the wrong type, or
+    /// it falls outside the allowed range.
+    pub fn set_param(&mut self, name: &str, value: ParamValue) -> Result<(), String> {
+        let info = param_info(name).ok_or_else(|| format!("unknown parameter `{}`", name))?;
+
+        let type_error = || format!("wrong value type for `{}`: expected {:?}", name, info.kind);
+        let int_in_range = |value: &ParamValue| match (value, info.kind) {
+            (ParamValue::Int(v), ParamKind::Int { min, max }) => {
+                if (min..=max).contains(v) {
+                    Ok(*v)
+                } else {
+                    Err(format!(
+                        "{} must be between {} and {}, got {}",
+                        name, min, max, v
+                    ))
+                }
+            }
+            _ => Err(type_error()),
+        };
+        let int = |value: &ParamValue| int_in_range(value).map(|v| v as u32);
+        let signed_int = |value: &ParamValue| int_in_range(value).map(|v| v as i32);
+        let float = |value: &ParamValue| match (value, info.kind) {
+            (ParamValue::Float(v), ParamKind::Float { min, max }) => {
+                if (min..=max).contains(v) {
+                    Ok(*v as f32)
+                } else {
+                    Err(format!(
+                        "{} must be between {} and {}, got {}",
+                        name, min, max, v
+                    ))
+                }
+            }
+            _ => Err(type_error()),
+        };
+        let optional_int = |value: &ParamValue| match value {
+            ParamValue::Unset => Ok(None),
+            other => int(other).map(Some),
+        };
+        let boolean = |value: &ParamValue| match value {
+            ParamValue::Bool(v) => Ok(*v),
+            _ => Err(type_error()),
+        };
+        let color = |value: &ParamValue| match value {
+            ParamValue::Color(v) => Ok(*v),
+            _ => Err(type_error()),
+        };
+        let choice = |value: &ParamValue| match value {
+            ParamValue::Choice(v) => Ok(v.clone()),
+            _ => Err(type_error()),
+        };
+        let unknown_choice = |v: &str| {
+            format!(
+                "invalid choice `{}` for `{}`: expected one of {:?}",
+                v, name, info.kind
+            )
+        };
+        let tile_size = |value: &ParamValue| {
+            let v = choice(value)?;
+            v.parse()
+                .ok()
+                .filter(|size| TILE_SIZES.contains(size))
+                .ok_or_else(|| unknown_choice(&v))
+        };
+
+        match name {
+            "luminance_mode" => {
+                let v = choice(&value)?;
+                self.luminance_mode = parse_luminance_mode(&v)
+                    .filter(|&luminance_mode| {
+                        AsciiConfig {
+                            luminance_mode,
+                            ..AsciiConfig::default()
+                        }
+                        .validate()
+                        .is_ok()
+                    })
+                    .ok_or_else(|| unknown_choice(&v))?;
+            }
+            "linear_luminance" => self.linear_luminance = boolean(&value)?,
+            "kernel_size" => self.kernel_size = int(&value)?,
+            "sigma" => self.sigma = float(&value)?,
+            "sigma_scale" => self.sigma_scale = float(&value)?,
+            "blur_mode" => {
+                let v = choice(&value)?;
+                self.blur_mode = parse_blur_mode(&v).ok_or_else(|| unknown_choice(&v))?;
+            }
+            "pre_filter" => {
+                let v = choice(&value)?;
+                // Settings inside the choice are range-checked by validate
+                self.pre_filter = parse_pre_filter(&v)
+                    .filter(|&pre_filter| {
+                        AsciiConfig {
+                            pre_filter,
+                            ..AsciiConfig::default()
+                        }
+                        .validate()
+                        .is_ok()
+                    })
+                    .ok_or_else(|| unknown_choice(&v))?;
+            }
+            "median_radius" => self.median_radius = optional_int(&value)?,
+            "edge_mode" => {
+                let v = choice(&value)?;
+                self.edge_mode = parse_edge_mode(&v)
+                    .filter(|&edge_mode| {
+                        AsciiConfig {
+                            edge_mode,
+                            ..AsciiConfig::default()
+                        }
+                        .validate()
+                        .is_ok()
+                    })
+                    .ok_or_else(|| unknown_choice(&v))?;
+            }
+            "tau" => self.tau = float(&value)?,
+            "threshold" => self.threshold = float(&value)?,
+            "threshold_mode" => {
+                let v = choice(&value)?;
+                self.threshold_mode = parse_threshold_mode(&v)
+                    .filter(|&threshold_mode| {
+                        AsciiConfig {
+                            threshold_mode,
+                            ..AsciiConfig::default()
+                        }
+                        .validate()
+                        .is_ok()
+                    })
+                    .ok_or_else(|| unknown_choice(&v))?;
+            }
+            "edge_dilation" => self.edge_dilation = signed_int(&value)?,
+            "structure_tensor_sigma" => {
+                self.structure_tensor_sigma = match value {
+                    ParamValue::Unset => None,
+                    other => Some(float(&other)?),
+                }
+            }
+            "sobel_threshold" => self.sobel_threshold = float(&value)?,
+            "non_max_suppression" => self.non_max_suppression = boolean(&value)?,
+            "edge_threshold" => self.edge_threshold = int(&value)?,
+            "edge_junction_margin" => self.edge_junction_margin = optional_int(&value)?,
+            "saliency_strength" => self.saliency_strength = float(&value)?,
+            "ascii_color" => self.ascii_color = color(&value)?,
+            "bg_color" => self.bg_color = color(&value)?,
+            "luminance_adjust" => {
+                let v = choice(&value)?;
+                self.luminance_adjust = parse_luminance_adjust(&v)
+                    .filter(|&luminance_adjust| {
+                        AsciiConfig {
+                            luminance_adjust,
+                            ..AsciiConfig::default()
+                        }
+                        .validate()
+                        .is_ok()
+                    })
+                    .ok_or_else(|| unknown_choice(&v))?;
+            }
+            "exposure" => self.exposure = float(&value)?,
+            "attenuation" => self.attenuation = float(&value)?,
+            "tile_statistic" => {
+                let v = choice(&value)?;
+                self.tile_statistic = parse_tile_statistic(&v)
+                    .filter(|s| !matches!(s, TileStatistic::Percentile(p) if *p > 100))
+                    .ok_or_else(|| unknown_choice(&v))?;
+            }
+            "local_contrast_radius" => self.local_contrast_radius = optional_int(&value)?,
+            "fill_gamma" => self.fill_gamma = float(&value)?,
+            "fill_dither" => {
+                let v = choice(&value)?;
+                self.fill_dither = parse_fill_dither(&v).ok_or_else(|| unknown_choice(&v))?;
+            }
+            "texture_threshold" => self.texture_threshold = optional_int(&value)?,
+            "char_selection" => {
+                let v = choice(&value)?;
+                self.char_selection = parse_char_selection(&v).ok_or_else(|| unknown_choice(&v))?;
+            }
+            "charset" => {
+                let v = choice(&value)?;
+                self.charset = parse_charset(&v).ok_or_else(|| unknown_choice(&v))?;
+            }
+            "draw_edges" => self.draw_edges = boolean(&value)?,
+            "draw_fill" => self.draw_fill = boolean(&value)?,
+            "invert_luminance" => self.invert_luminance = boolean(&value)?,
+            "auto_invert" => self.auto_invert = boolean(&value)?,
+            "composite_mode" => {
+                let v = choice(&value)?;
+                self.composite_mode = parse_composite_mode(&v).ok_or_else(|| unknown_choice(&v))?;
+            }
+            "blend_with_base" => self.blend_with_base = float(&value)?,
+            "edge_debug" => self.edge_debug = boolean(&value)?,
+            "palette" => {
+                let v = choice(&value)?;
+                self.palette = parse_palette(&v).ok_or_else(|| unknown_choice(&v))?;
+            }
+            "edge_chars" => {
+                let v = choice(&value)?;
+                self.edge_chars = parse_edge_chars(&v).ok_or_else(|| unknown_choice(&v))?;
+            }
+            "positional_edges" => self.positional_edges = boolean(&value)?,
+            "rgb_split" => self.rgb_split = optional_int(&value)?,
+            "output_mode" => {
+                let v = choice(&value)?;
+                self.output_mode = parse_output_mode(&v).ok_or_else(|| unknown_choice(&v))?;
+            }
+            "tile_size" => self.tile_size = tile_size(&value)?,
+            "tile_height" => {
+                self.tile_height = match value {
+                    ParamValue::Unset => None,
+                    other => Some(tile_size(&other)?),
+                }
+            }
+            _ => return Err(format!("unknown parameter `{}`", name)),
+        }
+
+        Ok(())
+    }
+
+    /// Earliest pipeline stage affected by a parameter that differs between
+    /// the two configs, or None when every parameter is the same
+    ///
+    /// Stages before the returned one can keep their results from `other`.
+    pub fn first_changed_stage(&self, other: &AsciiConfig) -> Option<PipelineStage> {
+        PARAMS
+            .iter()
+            .filter(|info| self.get_param(info.name) != other.get_param(info.name))
+            .map(|info| info.stage)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_param_has_a_default() {
+        for info in PARAMS {
+            let value = info.default_value();
+            if !info.optional {
+                assert_ne!(value, ParamValue::Unset, "{}", info.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_param_ranges_match_validate() {
+        for info in PARAMS {
+            let (low, high, below, above) = match info.kind {
+                ParamKind::Int { min, max } => (
+                    ParamValue::Int(min),
+                    ParamValue::Int(max),
+                    (min > 0).then(|| ParamValue::Int(min - 1)),
+                    ParamValue::Int(max + 1),
+                ),
+                ParamKind::Float { min, max } => (
+                    ParamValue::Float(min),
+                    ParamValue::Float(max),
+                    Some(ParamValue::Float(min - 0.0001)),
+                    ParamValue::Float(max + 0.0001),
+                ),
+                _ => continue,
+            };
+
+            for value in [low, high] {
+                let mut config = AsciiConfig::default();
+                config.set_param(info.name, value).unwrap();
+                assert!(config.validate().is_ok(), "{} bound rejected", info.name);
+            }
+
+            let mut config = AsciiConfig::default();
+            assert!(config.set_param(info.name, above).is_err(), "{}", info.name);
+            if let Some(below) = below {
+                assert!(config.set_param(info.name, below).is_err(), "{}", info.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_get_round_trip() {
+        let mut config = AsciiConfig::default();
+        config
+            .set_param("tile_statistic", ParamValue::Choice("percentile:75".into()))
+            .unwrap();
+        config
+            .set_param("edge_junction_margin", ParamValue::Int(3))
+            .unwrap();
+        config
+            .set_param("fill_dither", ParamValue::Choice("noise".into()))
+            .unwrap();
+        config
+            .set_param("tile_size", ParamValue::Choice("16".into()))
+            .unwrap();
+
+        assert_eq!(config.tile_statistic, TileStatistic::Percentile(75));
+        assert_eq!(config.tile_size, 16);
+        config
+            .set_param("tile_height", ParamValue::Choice("32".into()))
+            .unwrap();
+        assert_eq!(config.tile_dimensions(), (16, 32));
+        config.set_param("tile_height", ParamValue::Unset).unwrap();
+        assert_eq!(config.get_param("tile_height"), Some(ParamValue::Unset));
+        assert_eq!(
+            config.get_param("tile_statistic"),
+            Some(ParamValue::Choice("percentile:75".into()))
+        );
+        assert_eq!(
+            config.get_param("edge_junction_margin"),
+            Some(ParamValue::Int(3))
+        );
+
+        config
+            .set_param("edge_junction_margin", ParamValue::Unset)
+            .unwrap();
+        assert_eq!(config.edge_junction_margin, None);
+
+        config
+            .set_param("pre_filter", ParamValue::Choice("bilateral:1.5,0.2".into()))
+            .unwrap();
+        assert_eq!(
+            config.pre_filter,
+            PreFilter::Bilateral {
+                sigma_space: 1.5,
+                sigma_range: 0.2
+            }
+        );
+        assert_eq!(
+            config.get_param("pre_filter"),
+            Some(ParamValue::Choice("bilateral:1.5,0.2".into()))
+        );
+        config
+            .set_param("edge_mode", ParamValue::Choice("xdog".into()))
+            .unwrap();
+        assert_eq!(config.edge_mode, EdgeMode::XDOG);
+        config
+            .set_param("edge_mode", ParamValue::Choice("sobel:0.5".into()))
+            .unwrap();
+        assert_eq!(config.edge_mode, EdgeMode::Sobel { threshold: 0.5 });
+        config
+            .set_param("edge_mode", ParamValue::Choice("xdog".into()))
+            .unwrap();
+        assert_eq!(
+            config.get_param("edge_mode"),
+            Some(ParamValue::Choice("xdog:1000,0.005".into()))
+        );
+        config
+            .set_param(
+                "luminance_mode",
+                ParamValue::Choice("custom:1,0.5,0".into()),
+            )
+            .unwrap();
+        assert_eq!(
+            config.get_param("luminance_mode"),
+            Some(ParamValue::Choice("custom:1,0.5,0".into()))
+        );
+        config
+            .set_param("luminance_adjust", ParamValue::Choice("clahe:3,4".into()))
+            .unwrap();
+        assert_eq!(
+            config.luminance_adjust,
+            LuminanceAdjust::Clahe {
+                clip_limit: 3.0,
+                grid: 4
+            }
+        );
+        config
+            .set_param("pre_filter", ParamValue::Choice("kuwahara".into()))
+            .unwrap();
+        assert_eq!(
+            config.get_param("pre_filter"),
+            Some(ParamValue::Choice("kuwahara:4,8".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let info = |name| param_info(name).unwrap();
+        assert_eq!(info("kernel_size").parse_value("4"), Ok(ParamValue::Int(4)));
+        assert_eq!(
+            info("sigma").parse_value(" 1.5 "),
+            Ok(ParamValue::Float(1.5))
+        );
+        assert_eq!(
+            info("draw_fill").parse_value("off"),
+            Ok(ParamValue::Bool(false))
+        );
+        assert_eq!(
+            info("ascii_color").parse_value("#ff8000"),
+            Ok(ParamValue::Color([255, 128, 0]))
+        );
+        assert_eq!(
+            info("bg_color").parse_value("1, 2, 3"),
+            Ok(ParamValue::Color([1, 2, 3]))
+        );
+        assert_eq!(
+            info("edge_junction_margin").parse_value("none"),
+            Ok(ParamValue::Unset)
+        );
+        assert!(info("kernel_size").parse_value("off").is_err());
+        assert!(info("bg_color").parse_value("1,2").is_err());
+        assert!(info("bg_color").parse_value("#12345").is_err());
+    }
+
+    #[test]
+    fn test_set_param_errors() {
+        let mut config = AsciiConfig::default();
+        assert!(config.set_param("nope", ParamValue::Bool(true)).is_err());
+        assert!(config.set_param("sigma", ParamValue::Bool(true)).is_err());
+        assert!(config.set_param("draw_fill", ParamValue::Unset).is_err());
+        assert!(
+            config
+                .set_param("fill_dither", ParamValue::Choice("lots".into()))
+                .is_err()
+        );
+        assert!(
+            config
+                .set_param(
+                    "tile_statistic",
+                    ParamValue::Choice("percentile:101".into())
+                )
+                .is_err()
+        );
+        assert!(
+            config
+                .set_param("tile_size", ParamValue::Choice("12".into()))
+                .is_err()
+        );
+        assert!(
+            config
+                .set_param("pre_filter", ParamValue::Choice("bilateral:2,0".into()))
+                .is_err()
+        );
+        assert!(
+            config
+                .set_param("pre_filter", ParamValue::Choice("kuwahara:0,8".into()))
+                .is_err()
+        );
+        assert!(
+            config
+                .set_param("luminance_adjust", ParamValue::Choice("clahe:2,64".into()))
+                .is_err()
+        );
+        assert!(
+            config
+                .set_param("luminance_mode", ParamValue::Choice("custom:0,0,0".into()))
+                .is_err()
+        );
+        assert!(
+            config
+                .set_param("edge_mode", ParamValue::Choice("xdog:0,0.005".into()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_value_at() {
+        let sigma = param_info("sigma").unwrap();
+        assert_eq!(sigma.value_at(0.0), Some(ParamValue::Float(0.0)));
+        assert_eq!(sigma.value_at(1.0), Some(ParamValue::Float(5.0)));
+
+        let kernel = param_info("kernel_size").unwrap();
+        assert_eq!(kernel.value_at(0.5), Some(ParamValue::Int(6)));
+
+        let statistic = param_info("tile_statistic").unwrap();
+        assert_eq!(
+            statistic.value_at(1.0),
+            Some(ParamValue::Choice("max".to_string()))
+        );
+
+        let mut config = AsciiConfig::default();
+        for info in PARAMS {
+            if let Some(value) = info.value_at(1.0) {
+                assert!(config.set_param(info.name, value).is_ok(), "{}", info.name);
+            }
+        }
+        assert!(param_info("bg_color").unwrap().value_at(0.5).is_none());
+    }
+
+    #[test]
+    fn test_first_changed_stage() {
+        let config = AsciiConfig::default();
+        assert_eq!(config.first_changed_stage(&config.clone()), None);
+
+        let recolored = AsciiConfig {
+            ascii_color: [255, 0, 0],
+            invert_luminance: true,
+            ..config.clone()
+        };
+        assert_eq!(
+            recolored.first_changed_stage(&config),
+            Some(PipelineStage::Fill)
+        );
+
+        let blurred = AsciiConfig {
+            sigma: 3.0,
+            ..recolored.clone()
+        };
+        assert_eq!(
+            blurred.first_changed_stage(&config),
+            Some(PipelineStage::Blur)
+        );
+        assert!(PipelineStage::Luminance < PipelineStage::Rendering);
+    }
+}
@@ -0,0 +1,85 @@
+//! C ABI for post-processing plugins
+//!
+//! A plugin is a dynamic library (`.so`, `.dylib` or `.dll`) exporting a
+//! function named `ascii_rendr_post_effect` that returns a pointer to a static
+//! [`PostEffectDescriptor`]. Hosts such as the GUI call the descriptor's
+//! `process` function on the rendered RGBA output, in place.
+//!
+//! Rust plugins only need [`export_post_effect!`](crate::export_post_effect) in a
+//! `cdylib` crate; see `examples/invert_plugin.rs`. Plugins written in other
+//! languages implement the same layout:
+//!
+//! ```c
+//! typedef struct {
+//!     uint32_t abi_version;  /* must equal 1 */
+//!     const char *name;      /* NUL-terminated */
+//!     void (*process)(uint8_t *rgba, uint32_t width, uint32_t height);
+//! } PostEffectDescriptor;
+//!
+//! const PostEffectDescriptor *ascii_rendr_post_effect(void);
+//! ```
+
+use std::ffi::c_char;
+
+/// Version of [`PostEffectDescriptor`]; hosts reject plugins built for another version
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// NUL-terminated name of the entry point every plugin exports
+pub const PLUGIN_ENTRY_SYMBOL: &[u8] = b"ascii_rendr_post_effect\0";
+
+/// Signature of the exported entry point
+pub type PostEffectEntry = unsafe extern "C" fn() -> *const PostEffectDescriptor;
+
+/// Static description of a post-processing plugin
+#[repr(C)]
+pub struct PostEffectDescriptor {
+    /// Must be [`PLUGIN_ABI_VERSION`]
+    pub abi_version: u32,
+    /// NUL-terminated display name
+    pub name: *const c_char,
+    /// Processes a tightly packed RGBA8 image (`width * height * 4` bytes) in place
+    pub process: unsafe extern "C" fn(rgba: *mut u8, width: u32, height: u32),
+}
+
+// The descriptor only holds pointers to static data and code
+unsafe impl Sync for PostEffectDescriptor {}
+
+/// Exports a post-processing function from a `cdylib` crate
+///
+/// The function receives the RGBA bytes of the rendered image along with its
+/// width and height. Panics are caught so they never unwind into the host.
+///
+/// ```ignore
+/// fn darken(rgba: &mut [u8], _width: u32, _height: u32) {
+///     for px in rgba.chunks_exact_mut(4) {
+///         px[..3].iter_mut().for_each(|c| *c /= 2);
+///     }
+/// }
+///
+/// ascii_rendr::export_post_effect!("Darken", darken);
+/// ```
+#[macro_export]
+macro_rules! export_post_effect {
+    ($name:literal, $func:path) => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn ascii_rendr_post_effect() -> *const $crate::plugin::PostEffectDescriptor {
+            unsafe extern "C" fn process(rgba: *mut u8, width: u32, height: u32) {
+                let len = width as usize * height as usize * 4;
+                // SAFETY: the host passes a buffer of exactly width * height RGBA pixels
+                let pixels = unsafe { ::std::slice::from_raw_parts_mut(rgba, len) };
+                let _ = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+                    $func(pixels, width, height)
+                }));
+            }
+
+            static DESCRIPTOR: $crate::plugin::PostEffectDescriptor =
+                $crate::plugin::PostEffectDescriptor {
+                    abi_version: $crate::plugin::PLUGIN_ABI_VERSION,
+                    name: concat!($name, "\0").as_ptr() as *const ::std::ffi::c_char,
+                    process,
+                };
+
+            &DESCRIPTOR
+        }
+    };
+}
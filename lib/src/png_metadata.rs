@@ -0,0 +1,246 @@
+//! Settings embedded in output PNGs
+//!
+//! [`save_png_with_config`] writes the `AsciiConfig` used for a conversion into
+//! an `iTXt` chunk (keyword `ascii-rendr:config`, uncompressed JSON) of the
+//! saved PNG. Image viewers ignore the chunk;
+//! [`AsciiConfig::from_png_metadata`] reads it back so an earlier result can be
+//! reproduced or tweaked from its exact settings.
+//!
+//! ```no_run
+//! use ascii_rendr::{AsciiConfig, process_image};
+//! use ascii_rendr::png_metadata::save_png_with_config;
+//!
+//! let config = AsciiConfig::default();
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! save_png_with_config(&process_image(&input, &config).render_image(), &config, "out.png").unwrap();
+//!
+//! let restored = AsciiConfig::from_png_metadata("out.png").unwrap();
+//! assert_eq!(restored.sigma, config.sigma);
+//! ```
+
+use crate::config::AsciiConfig;
+use crate::error::AsciiError;
+use image::{ImageFormat, RgbaImage};
+use std::io::Cursor;
+use std::path::Path;
+
+/// Keyword of the text chunk holding the config
+pub const CONFIG_KEYWORD: &str = "ascii-rendr:config";
+
+/// Eight-byte signature every PNG starts with
+pub(crate) const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Saves `image` as a PNG with `config` embedded
+pub fn save_png_with_config(
+    image: &RgbaImage,
+    config: &AsciiConfig,
+    path: impl AsRef<Path>,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("{}: failed to encode PNG: {}", path.display(), e))?;
+    let png = embed_config(&png, config)?;
+    std::fs::write(path, png).map_err(|e| format!("{}: failed to write: {}", path.display(), e))
+}
+
+/// Inserts `config` as an `iTXt` chunk right after the header of an encoded PNG
+///
+/// An existing config chunk is replaced.
+pub fn embed_config(png: &[u8], config: &AsciiConfig) -> Result<Vec<u8>, String> {
+    let chunks = parse_chunks(png)?;
+    let json =
+        serde_json::to_string(config).map_err(|e| format!("failed to serialize config: {}", e))?;
+
+    // Keyword, no compression, empty language tag and translated keyword
+    let mut data = CONFIG_KEYWORD.as_bytes().to_vec();
+    data.extend_from_slice(&[0, 0, 0, 0, 0]);
+    data.extend_from_slice(json.as_bytes());
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    for (index, chunk) in chunks.iter().enumerate() {
+        if chunk.keyword() == Some(CONFIG_KEYWORD) {
+            continue;
+        }
+        out.extend_from_slice(chunk.raw);
+        // IHDR must come first; everything else may follow it
+        if index == 0 {
+            write_chunk(&mut out, b"iTXt", &data);
+        }
+    }
+    Ok(out)
+}
+
+/// Config stored in an encoded PNG, if it has one
+///
+/// Both `iTXt` (as written by [`embed_config`]) and uncompressed `tEXt` chunks
+/// are recognized.
+pub fn read_config(png: &[u8]) -> Result<Option<AsciiConfig>, AsciiError> {
+    for chunk in parse_chunks(png).map_err(AsciiError::ParseError)? {
+        if let Some(text) = chunk.text()
+            && chunk.keyword() == Some(CONFIG_KEYWORD)
+        {
+            let text = std::str::from_utf8(text).map_err(|_| {
+                AsciiError::ParseError("config chunk is not valid UTF-8".to_string())
+            })?;
+            return AsciiConfig::from_json_str(text).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+impl AsciiConfig {
+    /// Restores the settings a PNG was produced with
+    ///
+    /// # Errors
+    /// Returns [`AsciiError::ParseError`] if the file cannot be read, is not a
+    /// PNG or has no embedded config, and [`AsciiError::InvalidConfig`] if the
+    /// config is invalid; messages are prefixed with the file path.
+    pub fn from_png_metadata(path: impl AsRef<Path>) -> Result<Self, AsciiError> {
+        let path = path.as_ref();
+        let png = std::fs::read(path).map_err(|e| {
+            AsciiError::ParseError(format!("{}: failed to read: {}", path.display(), e))
+        })?;
+        read_config(&png)
+            .and_then(|config| {
+                config.ok_or_else(|| AsciiError::ParseError("no embedded config".to_string()))
+            })
+            .map_err(|e| e.context(path.display()))
+    }
+}
+
+/// One chunk of an encoded PNG
+pub(crate) struct Chunk<'a> {
+    pub(crate) kind: [u8; 4],
+    pub(crate) data: &'a [u8],
+    /// Length, type, data and CRC as stored
+    pub(crate) raw: &'a [u8],
+}
+
+impl Chunk<'_> {
+    /// Keyword of a `tEXt` or `iTXt` chunk
+    fn keyword(&self) -> Option<&str> {
+        if &self.kind != b"tEXt" && &self.kind != b"iTXt" {
+            return None;
+        }
+        let end = self.data.iter().position(|&b| b == 0)?;
+        std::str::from_utf8(&self.data[..end]).ok()
+    }
+
+    /// Text of a `tEXt` or uncompressed `iTXt` chunk
+    fn text(&self) -> Option<&[u8]> {
+        let start = self.data.iter().position(|&b| b == 0)? + 1;
+        match &self.kind {
+            b"tEXt" => Some(&self.data[start..]),
+            b"iTXt" => {
+                let rest = self.data.get(start..)?;
+                // Compressed text is not supported
+                if rest.first() != Some(&0) {
+                    return None;
+                }
+                // Skip the compression bytes, language tag and translated keyword
+                let rest = rest.get(2..)?;
+                let language_end = rest.iter().position(|&b| b == 0)? + 1;
+                let rest = &rest[language_end..];
+                let translated_end = rest.iter().position(|&b| b == 0)? + 1;
+                Some(&rest[translated_end..])
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Splits an encoded PNG into its chunks, starting with IHDR
+pub(crate) fn parse_chunks(png: &[u8]) -> Result<Vec<Chunk<'_>>, String> {
+    if !png.starts_with(&PNG_SIGNATURE) {
+        return Err("not a PNG file".to_string());
+    }
+    let mut chunks = Vec::new();
+    let mut offset = PNG_SIGNATURE.len();
+    while offset < png.len() {
+        let header = png
+            .get(offset..offset + 8)
+            .ok_or("truncated PNG chunk header")?;
+        let length = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+        let end = offset + 12 + length;
+        let raw = png.get(offset..end).ok_or("truncated PNG chunk")?;
+        chunks.push(Chunk {
+            kind: header[4..8].try_into().unwrap(),
+            data: &raw[8..8 + length],
+            raw,
+        });
+        offset = end;
+    }
+    match chunks.first() {
+        Some(chunk) if &chunk.kind == b"IHDR" => Ok(chunks),
+        _ => Err("PNG does not start with IHDR".to_string()),
+    }
+}
+
+/// Appends a chunk with its length and CRC
+pub(crate) fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// CRC-32 (ISO-HDLC) as used by PNG chunks
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal PNG: signature, IHDR and IEND (no image data needed here)
+    fn bare_png() -> Vec<u8> {
+        let mut png = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 6, 0, 0, 0]);
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_embed_and_read_config() {
+        let png = bare_png();
+        assert_eq!(read_config(&png).unwrap().map(|c| c.sigma), None);
+
+        let config = AsciiConfig {
+            sigma: 1.25,
+            ..AsciiConfig::default()
+        };
+        let embedded = embed_config(&png, &config).unwrap();
+        let chunks = parse_chunks(&embedded).unwrap();
+        let kinds: Vec<&[u8; 4]> = chunks.iter().map(|c| &c.kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"iTXt", b"IEND"]);
+
+        // Embedding again replaces the chunk
+        let twice = embed_config(&embedded, &AsciiConfig::default()).unwrap();
+        assert_eq!(parse_chunks(&twice).unwrap().len(), 3);
+        assert_eq!(read_config(&twice).unwrap().unwrap().sigma, 2.0);
+
+        assert!(read_config(b"GIF89a").is_err());
+    }
+}
@@ -1,39 +1,492 @@
+use crate::adaptive::{FrameBudget, SKIP_EDGES_LEVEL, degrade};
+use crate::art::AsciiArt;
 use crate::ascii::{
-    downscale_to_tiles, render_ascii_to_image, render_ascii_to_image_with_source,
-    select_ascii_chars,
+    composite_chars, downscale_to_tiles_with, normalize_local_contrast, promote_textured_tiles,
+    render_edge_debug, render_rgb_split, render_tiles, select_edge_chars, select_fill_chars_at,
+    select_structure_chars, tile_average_colors, tile_std_devs,
 };
-use crate::config::AsciiConfig;
-use crate::edges::detect_edges_tiled;
-use crate::filters::{calculate_luminance, difference_of_gaussians, sobel_filter};
-use image::{RgbaImage, imageops};
+use crate::braille::compute_braille_layers_buffered;
+use crate::config::{AsciiConfig, CharSelection, EdgeMode, LuminanceAdjust, OutputMode, PreFilter};
+use crate::edges::{
+    EdgeDetector, EdgeDirection, detect_edges_tiled_sized, edge_image_into, edge_row_passes,
+    sobel_gradients_into, tile_edge_offsets,
+};
+use crate::error::{AsciiError, check_image_size};
+use crate::filters::{
+    DogBuffers, auto_contrast, bilateral_filter, calculate_luminance_with_into, clahe,
+    equalize_histogram, expose, extract_channel, is_predominantly_bright, kuwahara_filter,
+    median_filter,
+};
+use crate::glyphs::GlyphAtlas;
+use crate::output::ProcessOutput;
+use crate::params::PipelineStage;
+use crate::progress::{CancelToken, StageRows, finish_stage};
+use crate::saliency::tile_saliency;
+use crate::yuv::YuvFrame;
+use image::{DynamicImage, GrayImage, ImageBuffer, Pixel, RgbaImage, imageops};
+use std::borrow::Cow;
+use std::time::{Duration, Instant};
+
+pub use crate::render::html::{HtmlOptions, HtmlPalette, render_html};
+pub use crate::render::svg::{SvgOptions, render_svg};
 
-/// Resize image to nearest dimensions that are multiples of 8
+/// Resize image to nearest dimensions that are multiples of the tile size
 ///
 /// # Arguments
-/// * `input` - The input RGBA image to resize
+/// * `input` - The input RGBA or grayscale image to resize
+/// * `multiple` - Required divisors of the width and height, see [`working_multiple`]
 ///
 /// # Returns
-/// A tuple of (resized_image, was_resized) where was_resized indicates if resizing occurred
-fn resize_to_valid_dimensions(input: &RgbaImage) -> (RgbaImage, bool) {
+/// The resized image, or the input itself, borrowed, when its dimensions
+/// already fit
+pub(crate) fn resize_to_valid_dimensions<P>(
+    input: &ImageBuffer<P, Vec<P::Subpixel>>,
+    multiple: (u32, u32),
+) -> Cow<'_, ImageBuffer<P, Vec<P::Subpixel>>>
+where
+    P: Pixel + 'static,
+{
     let (width, height) = input.dimensions();
 
-    // Calculate target dimensions (round down to nearest multiple of 8)
-    let target_width = (width / 8) * 8;
-    let target_height = (height / 8) * 8;
+    // Calculate target dimensions (round down to nearest multiple)
+    let target_width = (width / multiple.0) * multiple.0;
+    let target_height = (height / multiple.1) * multiple.1;
 
-    // If already valid dimensions, return original image
+    // If already valid dimensions, borrow the original image
     if width == target_width && height == target_height {
-        return (input.clone(), false);
+        return Cow::Borrowed(input);
     }
 
     // Resize using Lanczos3 filter for high quality
-    let resized = imageops::resize(
+    Cow::Owned(imageops::resize(
         input,
         target_width,
         target_height,
         imageops::FilterType::Lanczos3,
-    );
-    (resized, true)
+    ))
+}
+
+/// Edge and fill character layers of one image, before compositing
+pub(crate) struct CharLayers {
+    pub(crate) edges: Vec<Vec<char>>,
+    pub(crate) fill: Vec<Vec<char>>,
+    /// Voted edge direction per tile
+    pub(crate) directions: Vec<EdgeDirection>,
+    /// Pixel shift of each tile's edge glyph, when `positional_edges` is on
+    pub(crate) edge_offsets: Vec<(i32, i32)>,
+    /// Fill luminance per tile
+    pub(crate) tile_lum: Vec<f32>,
+    pub(crate) tile_width: u32,
+    pub(crate) tile_height: u32,
+    /// Fill layers of the R, G and B channels, when `rgb_split` is on and the
+    /// source has color
+    pub(crate) channel_fill: Option<[Vec<Vec<char>>; 3]>,
+}
+
+impl CharLayers {
+    /// Layers composited with the configured policy
+    pub(crate) fn composite(&self, config: &AsciiConfig) -> Vec<Vec<char>> {
+        composite_chars(&self.edges, &self.fill, config)
+    }
+
+    /// Step 7: renders composited characters, or the edge debug view when enabled
+    pub(crate) fn render(
+        &self,
+        chars: &[Vec<char>],
+        config: &AsciiConfig,
+        source: Option<&RgbaImage>,
+    ) -> RgbaImage {
+        self.render_with_glyphs(chars, config, source, None)
+    }
+
+    /// Step 7 drawing characters from `glyphs` where it has them; the edge
+    /// debug and RGB split views keep the built-in glyphs
+    pub(crate) fn render_with_glyphs(
+        &self,
+        chars: &[Vec<char>],
+        config: &AsciiConfig,
+        source: Option<&RgbaImage>,
+        glyphs: Option<&GlyphAtlas>,
+    ) -> RgbaImage {
+        if config.edge_debug {
+            render_edge_debug(
+                chars,
+                &self.directions,
+                &self.tile_lum,
+                self.tile_width,
+                self.tile_height,
+                config,
+            )
+        } else if let Some(offset) = config.rgb_split {
+            // Gray sources have identical channels, so they share the fill layer
+            let layers = [0, 1, 2].map(|channel| {
+                let fill = self
+                    .channel_fill
+                    .as_ref()
+                    .map_or(&self.fill, |f| &f[channel]);
+                composite_chars(&self.edges, fill, config)
+            });
+            render_rgb_split(
+                &layers,
+                self.tile_width,
+                self.tile_height,
+                config,
+                source,
+                offset,
+            )
+        } else {
+            render_tiles(
+                chars,
+                (self.tile_width, self.tile_height),
+                config,
+                source,
+                glyphs,
+                &self.edge_offsets,
+            )
+        }
+    }
+}
+
+/// Divisors of the working image width and height: the tile dimensions, or 8
+/// for braille output, whose 2×4 cells are laid out independently of the tiles
+pub(crate) fn working_multiple(config: &AsciiConfig) -> (u32, u32) {
+    match config.output_mode {
+        OutputMode::Ascii => config.tile_dimensions(),
+        OutputMode::Braille => (8, 8),
+    }
+}
+
+/// Step 1: luminance of an RGBA image with the configured `luminance_mode`,
+/// in linear light when `linear_luminance` is on
+pub(crate) fn luminance(img: &RgbaImage, config: &AsciiConfig) -> GrayImage {
+    let mut output = GrayImage::new(0, 0);
+    luminance_into(img, config, &mut output);
+    output
+}
+
+/// [`luminance`] into an existing buffer, resized as needed
+pub(crate) fn luminance_into(img: &RgbaImage, config: &AsciiConfig, output: &mut GrayImage) {
+    calculate_luminance_with_into(img, config.luminance_mode, config.linear_luminance, output);
+}
+
+/// Runs pipeline steps 1-6, keeping the edge and fill layers separate
+pub(crate) fn compute_char_layers(working_image: &RgbaImage, config: &AsciiConfig) -> CharLayers {
+    // Step 1: Extract luminance
+    let lum = luminance(working_image, config);
+
+    let mut layers = compute_char_layers_from_luminance(&lum, config);
+    layers.channel_fill = channel_fill_layers(working_image, config);
+    layers
+}
+
+/// Steps 5-6 again on each color channel, when `rgb_split` is on
+pub(crate) fn channel_fill_layers(
+    working_image: &RgbaImage,
+    config: &AsciiConfig,
+) -> Option<[Vec<Vec<char>>; 3]> {
+    config.rgb_split?;
+    Some(
+        [0, 1, 2]
+            .map(|channel| compute_fill_layer(&extract_channel(working_image, channel), config).0),
+    )
+}
+
+/// Runs pipeline steps 2-6 on a luminance image whose dimensions are multiples
+/// of the tile size
+pub(crate) fn compute_char_layers_from_luminance(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+) -> CharLayers {
+    compute_char_layers_buffered(lum, config, &mut EdgeBuffers::default())
+}
+
+/// [`compute_char_layers_from_luminance`] with caller-owned scratch buffers
+fn compute_char_layers_buffered(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    buffers: &mut EdgeBuffers,
+) -> CharLayers {
+    let lum = &*pre_filter_luminance(lum, config);
+
+    // Steps 2-4: DoG, Sobel and edge voting
+    let edges = compute_edge_directions(lum, config, buffers);
+    compute_char_layers_from_edges(lum, edges, buffers, config)
+}
+
+/// Runs pipeline steps 5-6 on a pre-filtered luminance image, with the edge
+/// directions voted from the gradients left in `buffers`
+fn compute_char_layers_from_edges(
+    lum: &GrayImage,
+    edges: Vec<EdgeDirection>,
+    buffers: &EdgeBuffers,
+    config: &AsciiConfig,
+) -> CharLayers {
+    let (width, height) = lum.dimensions();
+    let (tile_w, tile_h) = config.tile_dimensions();
+    let tile_width = width / tile_w;
+    let tile_height = height / tile_h;
+
+    let edge_offsets = if config.positional_edges {
+        tile_edge_offsets(
+            &buffers.angles,
+            &buffers.valid_mask,
+            width,
+            (tile_w, tile_h),
+            &edges,
+        )
+    } else {
+        Vec::new()
+    };
+
+    // Steps 5-6: Tile luminance and fill characters
+    let (fill, tile_lum) = compute_fill_layer(lum, config);
+
+    CharLayers {
+        edges: select_edge_chars(&edges, tile_width, tile_height, config),
+        fill,
+        directions: edges,
+        edge_offsets,
+        tile_lum,
+        tile_width,
+        tile_height,
+        channel_fill: None,
+    }
+}
+
+/// Denoises the luminance with `median_radius` and smooths it with the
+/// configured [`PreFilter`] before steps 2-6, borrowing it unchanged when both
+/// are off
+pub(crate) fn pre_filter_luminance<'a>(
+    lum: &'a GrayImage,
+    config: &AsciiConfig,
+) -> Cow<'a, GrayImage> {
+    let lum = match config.median_radius {
+        Some(radius) => Cow::Owned(median_filter(lum, radius)),
+        None => Cow::Borrowed(lum),
+    };
+    match config.pre_filter {
+        PreFilter::None => lum,
+        PreFilter::Bilateral {
+            sigma_space,
+            sigma_range,
+        } => Cow::Owned(bilateral_filter(&lum, sigma_space, sigma_range)),
+        PreFilter::Kuwahara { radius, sectors } => {
+            Cow::Owned(kuwahara_filter(&lum, radius, sectors))
+        }
+    }
+}
+
+/// Applies the configured [`LuminanceAdjust`], then `exposure` and
+/// `attenuation`, before step 5, borrowing the luminance unchanged when all
+/// are off
+pub(crate) fn adjust_luminance<'a>(lum: &'a GrayImage, config: &AsciiConfig) -> Cow<'a, GrayImage> {
+    let lum = match config.luminance_adjust {
+        LuminanceAdjust::None => Cow::Borrowed(lum),
+        LuminanceAdjust::AutoContrast => Cow::Owned(auto_contrast(lum)),
+        LuminanceAdjust::Equalize => Cow::Owned(equalize_histogram(lum)),
+        LuminanceAdjust::Clahe { clip_limit, grid } => Cow::Owned(clahe(lum, grid, clip_limit)),
+    };
+    if config.exposure == 1.0 && config.attenuation == 1.0 {
+        lum
+    } else {
+        Cow::Owned(expose(&lum, config.exposure, config.attenuation))
+    }
+}
+
+/// Runs pipeline steps 2-4: voted edge direction per tile
+fn compute_edge_directions(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    buffers: &mut EdgeBuffers,
+) -> Vec<EdgeDirection> {
+    let (width, height) = lum.dimensions();
+    let thresholds = tile_edge_thresholds(lum, config);
+    let (angles, valid_mask) = buffers.gradients(lum, config, &mut StageRows::unchecked());
+    vote_edge_directions(angles, valid_mask, width, height, thresholds, config)
+}
+
+/// Step 4: tile-based edge direction voting with the configured tile size
+///
+/// `thresholds` holds the votes needed per tile (see [`tile_edge_thresholds`]);
+/// None uses the scaled `edge_threshold` everywhere.
+pub(crate) fn vote_edge_directions(
+    angles: &[f32],
+    valid_mask: &[bool],
+    width: u32,
+    height: u32,
+    thresholds: Option<Vec<u32>>,
+    config: &AsciiConfig,
+) -> Vec<EdgeDirection> {
+    let tile_size = config.tile_dimensions();
+    let thresholds = thresholds.unwrap_or_else(|| {
+        let tile_count = (width / tile_size.0) * (height / tile_size.1);
+        vec![config.scale_votes(config.edge_threshold); tile_count as usize]
+    });
+    detect_edges_tiled_sized(
+        angles,
+        valid_mask,
+        width,
+        height,
+        tile_size,
+        &thresholds,
+        config.edge_junction_margin.map(|m| config.scale_votes(m)),
+    )
+    .expect("edge detector fills one entry per pixel")
+}
+
+/// Edge threshold of every tile, scaled by saliency, or None when
+/// `saliency_strength` is off
+///
+/// The most salient tile needs `edge_threshold * (1 - strength)` votes and the
+/// least salient `edge_threshold * (1 + strength)`, scaled to the tile size.
+pub(crate) fn tile_edge_thresholds(lum: &GrayImage, config: &AsciiConfig) -> Option<Vec<u32>> {
+    if config.saliency_strength <= 0.0 {
+        return None;
+    }
+    let (tile_w, tile_h) = config.tile_dimensions();
+    let saliency = tile_saliency(lum, lum.width() / tile_w, lum.height() / tile_h);
+    Some(
+        saliency
+            .iter()
+            .map(|s| {
+                let scale = 1.0 + config.saliency_strength * (1.0 - 2.0 * s);
+                let threshold = ((config.edge_threshold as f32 * scale).round() as u32).min(64);
+                config.scale_votes(threshold)
+            })
+            .collect(),
+    )
+}
+
+/// Runs pipeline steps 2-3: per-pixel edge angles and validity
+pub(crate) fn compute_edge_gradients(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+) -> (Vec<f32>, Vec<bool>) {
+    let mut buffers = EdgeBuffers::default();
+    buffers.gradients(lum, config, &mut StageRows::unchecked());
+    (buffers.angles, buffers.valid_mask)
+}
+
+/// Edge detector and full-size scratch space of pipeline steps 2-3
+#[derive(Default)]
+pub(crate) struct EdgeBuffers {
+    /// Detector from [`Processor::with_edge_detector`], None for the built-in
+    /// [`DogSobel`](crate::edges::DogSobel) passes, which check their rows
+    detector: Option<Box<dyn EdgeDetector>>,
+    /// Step 2 image of the built-in detector, and the DoG output for braille
+    /// dots, which skip the detector
+    dog: DogBuffers,
+    angles: Vec<f32>,
+    valid_mask: Vec<bool>,
+}
+
+impl EdgeBuffers {
+    /// Step 2 as one flag per pixel: set in the thresholded Difference of
+    /// Gaussians (DoG), from half white up with XDoG, or past the gradient
+    /// threshold with `EdgeMode::Sobel`
+    pub(crate) fn edge_pixels(&mut self, lum: &GrayImage, config: &AsciiConfig) -> Vec<bool> {
+        let rows = &mut StageRows::unchecked();
+        edge_image_into(lum, config, &mut self.dog, rows);
+        if let EdgeMode::Sobel { .. } = config.edge_mode {
+            sobel_gradients_into(
+                &self.dog.output,
+                config,
+                &mut self.angles,
+                &mut self.valid_mask,
+                rows,
+            );
+            return self.valid_mask.clone();
+        }
+        self.dog.output.as_raw().iter().map(|&v| v >= 128).collect()
+    }
+
+    /// Steps 2-3: per-pixel edge angles and validity
+    ///
+    /// The built-in passes stop early when `rows` is cancelled, leaving
+    /// partial results; a custom detector always runs to the end.
+    fn gradients(
+        &mut self,
+        lum: &GrayImage,
+        config: &AsciiConfig,
+        rows: &mut StageRows,
+    ) -> (&[f32], &[bool]) {
+        match &mut self.detector {
+            Some(detector) => detector.detect(lum, config, &mut self.angles, &mut self.valid_mask),
+            None => {
+                edge_image_into(lum, config, &mut self.dog, rows);
+                sobel_gradients_into(
+                    &self.dog.output,
+                    config,
+                    &mut self.angles,
+                    &mut self.valid_mask,
+                    rows,
+                );
+            }
+        }
+        (&self.angles, &self.valid_mask)
+    }
+}
+
+/// Runs pipeline steps 5-6 for the fill layer, returning it with the tile luminance
+pub(crate) fn compute_fill_layer(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+) -> (Vec<Vec<char>>, Vec<f32>) {
+    compute_fill_layer_at(lum, config, (0, 0))
+}
+
+/// [`compute_fill_layer`] for a tile-aligned crop of the luminance image whose
+/// top-left tile is at `first_tile` (column, row)
+///
+/// Only matches the whole-image result when no setting looks beyond each tile
+/// (`auto_invert`, `local_contrast_radius`, `luminance_adjust`).
+pub(crate) fn compute_fill_layer_at(
+    lum: &GrayImage,
+    config: &AsciiConfig,
+    first_tile: (u32, u32),
+) -> (Vec<Vec<char>>, Vec<f32>) {
+    let tile_size = config.tile_dimensions();
+    let tile_width = lum.width() / tile_size.0;
+    let tile_height = lum.height() / tile_size.1;
+
+    // Bright images (documents, whiteboards) read better with the ramp flipped
+    let flipped;
+    let config = if config.auto_invert && is_predominantly_bright(lum) {
+        flipped = AsciiConfig {
+            invert_luminance: !config.invert_luminance,
+            ..config.clone()
+        };
+        &flipped
+    } else {
+        config
+    };
+    let lum = &*adjust_luminance(lum, config);
+
+    // Step 5: Downscale luminance to tiles
+    let mut tile_lum = downscale_to_tiles_with(lum, tile_size, config.tile_statistic);
+    if let Some(radius) = config.local_contrast_radius {
+        tile_lum = normalize_local_contrast(&tile_lum, tile_width, tile_height, radius);
+    }
+
+    // Step 6: Select fill characters for each tile
+    let fill = match config.char_selection {
+        CharSelection::Luminance => {
+            let mut fill =
+                select_fill_chars_at(&tile_lum, tile_width, tile_height, first_tile, config);
+            if let Some(threshold) = config.texture_threshold {
+                promote_textured_tiles(
+                    &mut fill,
+                    &tile_std_devs(lum, tile_size),
+                    threshold,
+                    config.charset.ramp(),
+                );
+            }
+            fill
+        }
+        CharSelection::Structure => select_structure_chars(lum, tile_size, config),
+    };
+
+    (fill, tile_lum)
 }
 
 /// Processes an input image and converts it to ASCII art
@@ -42,157 +495,606 @@ fn resize_to_valid_dimensions(input: &RgbaImage) -> (RgbaImage, bool) {
 /// 1. Extract luminance from color image
 /// 2. Apply Difference of Gaussians (DoG) for edge detection
 /// 3. Apply Sobel filter to get edge directions
-/// 4. Tile-based edge direction voting (8×8 tiles)
+/// 4. Tile-based edge direction voting (`tile_size` × `tile_size` tiles)
 /// 5. Downscale luminance to tiles
 /// 6. Select ASCII characters based on edges and luminance
 /// 7. Render characters to output image
 ///
+/// Step 7 is left to the caller: [`AsciiArt::render_image`] draws the image,
+/// [`AsciiArt::render_text`] and friends write the same grid as text.
+///
 /// # Arguments
 /// * `input` - The input RGBA image to convert
 /// * `config` - Configuration parameters for the ASCII conversion
 ///
 /// # Returns
-/// An RGBA image containing the ASCII art representation
+/// The character grid with its edge directions and tile luminances
 ///
 /// # Note
-/// If the input image dimensions are not multiples of 8, it will be automatically
-/// resized (rounded down) to the nearest valid dimensions using Lanczos3 filtering.
-pub fn process_image(input: &RgbaImage, config: &AsciiConfig) -> RgbaImage {
+/// If the input image dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering.
+pub fn process_image(input: &RgbaImage, config: &AsciiConfig) -> AsciiArt {
     // Validate config
     config.validate().expect("Invalid configuration");
 
-    // Automatically resize if dimensions are not multiples of 8
-    let (working_image, _was_resized) = resize_to_valid_dimensions(input);
-    let (width, height) = working_image.dimensions();
+    // Automatically resize if dimensions are not multiples of the tile size
+    let working_image = resize_to_valid_dimensions(input, working_multiple(config));
 
-    // Step 1: Extract luminance
-    let lum = calculate_luminance(&working_image);
+    // Steps 1-6: Luminance, edges and character selection
+    AsciiArt::from_image(working_image, config, false)
+}
 
-    // Step 2: Difference of Gaussians (DoG) for edge detection
-    let sigma1 = config.sigma;
-    let sigma2 = config.sigma * config.sigma_scale;
-    let dog = difference_of_gaussians(
-        &lum,
-        sigma1,
-        sigma2,
-        config.kernel_size,
-        config.tau,
-        config.threshold,
-    );
+/// Non-panicking [`process_image`]
+///
+/// # Errors
+/// [`AsciiError::InvalidConfig`] when a setting is out of range and
+/// [`AsciiError::ImageTooSmall`] when the input is smaller than one tile.
+pub fn try_process_image(input: &RgbaImage, config: &AsciiConfig) -> Result<AsciiArt, AsciiError> {
+    config.validate()?;
+    check_image_size(input.width(), input.height(), working_multiple(config))?;
 
-    // Step 3: Sobel filter for edge gradients
-    let (angles, valid_mask) = sobel_filter(&dog);
+    let working_image = resize_to_valid_dimensions(input, working_multiple(config));
+    Ok(AsciiArt::from_image(working_image, config, false))
+}
+
+/// Non-panicking [`process_image_preserve_colors`]
+///
+/// # Errors
+/// Same as [`try_process_image`].
+pub fn try_process_image_preserve_colors(
+    input: &RgbaImage,
+    config: &AsciiConfig,
+) -> Result<AsciiArt, AsciiError> {
+    config.validate()?;
+    check_image_size(input.width(), input.height(), working_multiple(config))?;
+
+    let working_image = resize_to_valid_dimensions(input, working_multiple(config));
+    Ok(AsciiArt::from_image(working_image, config, true))
+}
 
-    // Step 4: Tile-based edge detection (8×8 tiles with voting)
-    let edges = detect_edges_tiled(&angles, &valid_mask, width, height, config.edge_threshold);
+/// Processes a grayscale image and converts it to ASCII art
+///
+/// The image is used as luminance directly, skipping step 1. Useful for sources
+/// that are grayscale already (scans, depth maps, scientific imagery), which
+/// would otherwise have to be expanded to RGBA only to be collapsed again.
+///
+/// # Arguments
+/// * `input` - The grayscale image to convert
+/// * `config` - Configuration parameters for the ASCII conversion
+///
+/// # Returns
+/// The character grid with its edge directions and tile luminances
+///
+/// # Note
+/// If the input image dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering.
+pub fn process_gray(input: &GrayImage, config: &AsciiConfig) -> AsciiArt {
+    // Validate config
+    config.validate().expect("Invalid configuration");
 
-    // Step 5: Downscale luminance to 8×8 tiles
-    let tile_lum = downscale_to_tiles(&lum, 8);
+    // Automatically resize if dimensions are not multiples of the tile size
+    let lum = resize_to_valid_dimensions(input, working_multiple(config));
 
-    // Step 6: Select ASCII characters for each tile
-    let tile_width = width / 8;
-    let tile_height = height / 8;
-    let chars = select_ascii_chars(&edges, &tile_lum, tile_width, tile_height, config);
+    // The luminance doubles as the base to blend with
+    let source = (config.blend_with_base > 0.0)
+        .then(|| DynamicImage::ImageLuma8(lum.clone().into_owned()).to_rgba8());
 
-    // Step 7: Render ASCII characters to image
-    render_ascii_to_image(&chars, tile_width, tile_height, config)
+    // Steps 2-6: Edges and character selection
+    AsciiArt::from_luminance(&lum, config, source, false)
+}
+
+/// Processes a YUV 4:2:0 video frame and converts it to ASCII art
+///
+/// The Y plane is used as luminance directly; chroma is only converted to RGB
+/// when `preserve_colors` is set.
+///
+/// # Arguments
+/// * `frame` - The I420 or NV12 frame to convert
+/// * `config` - Configuration parameters for the ASCII conversion
+/// * `preserve_colors` - Sample colors from the frame instead of `ascii_color`
+///
+/// # Returns
+/// The character grid with its edge directions and tile luminances
+///
+/// # Note
+/// If the frame dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering.
+pub fn process_yuv(frame: &YuvFrame, config: &AsciiConfig, preserve_colors: bool) -> AsciiArt {
+    // Validate config
+    config.validate().expect("Invalid configuration");
+
+    // Automatically resize if dimensions are not multiples of the tile size
+    let luma = frame.luma();
+    let lum = resize_to_valid_dimensions(&luma, working_multiple(config));
+    // Convert chroma only when it is needed
+    let source = (preserve_colors || config.blend_with_base > 0.0).then(|| {
+        let rgba = frame.to_rgba();
+        match resize_to_valid_dimensions(&rgba, working_multiple(config)) {
+            Cow::Owned(resized) => resized,
+            Cow::Borrowed(_) => rgba,
+        }
+    });
+
+    // Steps 2-6: Edges and character selection
+    AsciiArt::from_luminance(&lum, config, source, preserve_colors)
 }
 
 /// Processes an input image and converts it to ASCII art while preserving original colors
 ///
-/// This is the same as process_image but preserves colors from the source image
-/// instead of using solid colors from the config.
+/// This is the same as process_image but keeps the source image, so rendering
+/// uses its colors instead of solid colors from the config.
 ///
 /// # Arguments
 /// * `input` - The input RGBA image to convert
 /// * `config` - Configuration parameters for the ASCII conversion
 ///
 /// # Returns
-/// An RGBA image containing the ASCII art representation with preserved colors
+/// The character grid with its edge directions, tile luminances and source colors
 ///
 /// # Note
-/// If the input image dimensions are not multiples of 8, it will be automatically
-/// resized (rounded down) to the nearest valid dimensions using Lanczos3 filtering.
-pub fn process_image_preserve_colors(input: &RgbaImage, config: &AsciiConfig) -> RgbaImage {
+/// If the input image dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering.
+pub fn process_image_preserve_colors(input: &RgbaImage, config: &AsciiConfig) -> AsciiArt {
     // Validate config
     config.validate().expect("Invalid configuration");
 
-    // Automatically resize if dimensions are not multiples of 8
-    let (working_image, _was_resized) = resize_to_valid_dimensions(input);
-    let (width, height) = working_image.dimensions();
+    // Automatically resize if dimensions are not multiples of the tile size
+    let working_image = resize_to_valid_dimensions(input, working_multiple(config));
 
-    // Step 1: Extract luminance
-    let lum = calculate_luminance(&working_image);
-
-    // Step 2: Difference of Gaussians (DoG) for edge detection
-    let sigma1 = config.sigma;
-    let sigma2 = config.sigma * config.sigma_scale;
-    let dog = difference_of_gaussians(
-        &lum,
-        sigma1,
-        sigma2,
-        config.kernel_size,
-        config.tau,
-        config.threshold,
-    );
-
-    // Step 3: Sobel filter for edge gradients
-    let (angles, valid_mask) = sobel_filter(&dog);
-
-    // Step 4: Tile-based edge detection (8×8 tiles with voting)
-    let edges = detect_edges_tiled(&angles, &valid_mask, width, height, config.edge_threshold);
-
-    // Step 5: Downscale luminance to 8×8 tiles
-    let tile_lum = downscale_to_tiles(&lum, 8);
-
-    // Step 6: Select ASCII characters for each tile
-    let tile_width = width / 8;
-    let tile_height = height / 8;
-    let chars = select_ascii_chars(&edges, &tile_lum, tile_width, tile_height, config);
-
-    // Step 7: Render ASCII characters to image with color preservation
-    render_ascii_to_image_with_source(
-        &chars,
-        tile_width,
-        tile_height,
-        config,
-        Some(&working_image),
-    )
+    // Steps 1-6: Luminance, edges and character selection
+    AsciiArt::from_image(working_image, config, true)
+}
+
+/// Processes an input image and keeps the character grid alongside the image
+///
+/// Same pipeline as [`process_image`] / [`process_image_preserve_colors`], but
+/// the result is rendered right away and also carries one character and color
+/// per tile for text, ANSI or JSON output, plus the uncomposited edge and fill
+/// layers. In braille mode there is one character per 2×4 pixel block instead.
+///
+/// # Arguments
+/// * `input` - The input RGBA image to convert
+/// * `config` - Configuration parameters for the ASCII conversion
+/// * `preserve_colors` - Sample colors from the source image instead of `ascii_color`
+///
+/// # Returns
+/// The rendered image together with its per-tile characters and colors
+pub fn process_image_output(
+    input: &RgbaImage,
+    config: &AsciiConfig,
+    preserve_colors: bool,
+) -> ProcessOutput {
+    // Validate config
+    config.validate().expect("Invalid configuration");
+
+    // Automatically resize if dimensions are not multiples of the tile size
+    let working_image = resize_to_valid_dimensions(input, working_multiple(config));
+
+    // Steps 1-7, keeping the per-tile characters and colors
+    AsciiArt::from_image(working_image, config, preserve_colors).to_output()
+}
+
+/// Execution settings of a [`Processor`], as opposed to the conversion
+/// settings in [`AsciiConfig`]
+///
+/// ```
+/// use ascii_rendr::{AsciiConfig, Processor, ProcessorOptions};
+///
+/// let processor = Processor::new(&AsciiConfig::default(), false)
+///     .with_options(ProcessorOptions::new().threads(2));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessorOptions {
+    threads: Option<usize>,
+    target: Option<Duration>,
+}
+
+impl ProcessorOptions {
+    /// Default options: the parallel stages run on rayon's global pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs the parallel stages on a dedicated pool of `threads` threads
+    /// instead of rayon's global pool, so a server or plugin can bound the
+    /// CPUs a conversion takes; 0 sizes the pool by the CPU count
+    ///
+    /// Without the `rayon` feature every stage runs on the calling thread and
+    /// this setting is ignored.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Keeps conversions within a frame budget of `ms` milliseconds by
+    /// lowering quality while they take longer and restoring it once they
+    /// are fast again, see [`crate::adaptive`]
+    ///
+    /// Results then only equal [`process_image`] at full quality, see
+    /// [`Processor::quality_level`].
+    ///
+    /// # Panics
+    /// If `ms` is not positive.
+    pub fn target_ms(mut self, ms: f64) -> Self {
+        assert!(
+            ms.is_finite() && ms > 0.0,
+            "frame budget must be positive, got {} ms",
+            ms
+        );
+        self.target = Some(Duration::from_secs_f64(ms / 1000.0));
+        self
+    }
+}
+
+/// Converter that keeps its full-size scratch buffers between calls
+///
+/// [`process_image`] allocates the luminance, blur, DoG and gradient buffers
+/// afresh for every image. A `Processor` reuses them from one call to the next,
+/// which saves most of the per-frame allocations when converting a stream of
+/// same-sized frames (video, live previews). Results are identical to
+/// [`process_image`] / [`process_image_preserve_colors`].
+///
+/// It also keeps the luminance, pre-filtered luminance, gradients and voted
+/// edge directions of the last image. Converting the same image again after
+/// [`set_config`](Self::set_config) reruns only the stages whose settings
+/// changed (see [`AsciiConfig::first_changed_stage`]), so tweaking colors,
+/// the ramp or `invert_luminance` skips the blurs, DoG and Sobel passes.
+///
+/// ```no_run
+/// use ascii_rendr::{AsciiConfig, Processor};
+///
+/// let mut processor = Processor::new(&AsciiConfig::default(), true);
+/// for path in ["frame1.png", "frame2.png"] {
+///     let frame = image::open(path).unwrap().to_rgba8();
+///     println!("{}", processor.process(&frame).render_text());
+/// }
+/// ```
+pub struct Processor {
+    config: AsciiConfig,
+    preserve_colors: bool,
+    /// Step 1 output
+    lum: GrayImage,
+    /// Steps 2-3 edge detector and scratch space
+    edges: EdgeBuffers,
+    /// Stage results of the last call, for reuse on the same input
+    stages: Option<StageCache>,
+    options: ProcessorOptions,
+    /// Quality control from [`ProcessorOptions::target_ms`]
+    budget: Option<FrameBudget>,
+    /// Pool from [`ProcessorOptions::threads`], None for the global pool
+    #[cfg(feature = "rayon")]
+    pool: Option<std::sync::Arc<rayon::ThreadPool>>,
+}
+
+/// Stage results of the last [`Processor::process`] call
+#[derive(Default)]
+struct StageCache {
+    /// Input they were computed from
+    input: RgbaImage,
+    /// Settings they were computed with
+    config: AsciiConfig,
+    /// Luminance after `median_radius` and `pre_filter`, or None when both
+    /// are off and steps 2-6 read the step 1 luminance
+    filtered: Option<GrayImage>,
+    /// Step 4 output
+    directions: Vec<EdgeDirection>,
+}
+
+impl Processor {
+    /// Creates a processor with empty buffers; they are sized on first use
+    ///
+    /// # Arguments
+    /// * `config` - Configuration parameters for the ASCII conversion
+    /// * `preserve_colors` - Sample colors from the source image instead of `ascii_color`
+    pub fn new(config: &AsciiConfig, preserve_colors: bool) -> Self {
+        // Validate config
+        config.validate().expect("Invalid configuration");
+
+        Self {
+            config: config.clone(),
+            preserve_colors,
+            lum: GrayImage::new(0, 0),
+            edges: EdgeBuffers::default(),
+            stages: None,
+            options: ProcessorOptions::default(),
+            budget: None,
+            #[cfg(feature = "rayon")]
+            pool: None,
+        }
+    }
+
+    /// Applies execution settings such as the thread count and frame budget
+    ///
+    /// # Panics
+    /// If the threads of a dedicated pool cannot be spawned.
+    pub fn with_options(mut self, options: ProcessorOptions) -> Self {
+        #[cfg(feature = "rayon")]
+        {
+            self.pool = options.threads.map(|threads| {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .expect("Failed to spawn processing threads");
+                std::sync::Arc::new(pool)
+            });
+        }
+        self.budget = options.target.map(FrameBudget::new);
+        self.options = options;
+        self
+    }
+
+    /// Execution settings in use
+    pub fn options(&self) -> &ProcessorOptions {
+        &self.options
+    }
+
+    /// Quality level the next conversion runs at, from 0 (full quality) to
+    /// [`adaptive::MAX_LEVEL`](crate::adaptive::MAX_LEVEL); always 0 without
+    /// [`ProcessorOptions::target_ms`]
+    ///
+    /// The settings used are [`adaptive::degrade`](crate::adaptive::degrade)
+    /// of [`config`](Self::config) at this level.
+    pub fn quality_level(&self) -> usize {
+        self.budget.as_ref().map_or(0, FrameBudget::level)
+    }
+
+    /// Replaces the default [`DogSobel`](crate::edges::DogSobel) edge detection of steps 2-3
+    ///
+    /// Braille output draws its dots from the DoG directly and is not affected.
+    pub fn with_edge_detector(mut self, detector: impl EdgeDetector + 'static) -> Self {
+        self.set_edge_detector(detector);
+        self
+    }
+
+    /// Switches the edge detector, keeping the other buffers
+    pub fn set_edge_detector(&mut self, detector: impl EdgeDetector + 'static) {
+        self.edges.detector = Some(Box::new(detector));
+        self.stages = None;
+    }
+
+    /// Settings used for conversion
+    pub fn config(&self) -> &AsciiConfig {
+        &self.config
+    }
+
+    /// Changes the settings, keeping the buffers
+    pub fn set_config(&mut self, config: &AsciiConfig) {
+        config.validate().expect("Invalid configuration");
+        self.config = config.clone();
+    }
+
+    /// Whether colors are sampled from the source image
+    pub fn preserve_colors(&self) -> bool {
+        self.preserve_colors
+    }
+
+    /// Switches between source colors and `ascii_color`
+    pub fn set_preserve_colors(&mut self, preserve_colors: bool) {
+        self.preserve_colors = preserve_colors;
+    }
+
+    /// Converts one image, reusing the buffers of earlier calls
+    ///
+    /// # Note
+    /// If the input image dimensions are not multiples of the tile size, it will
+    /// be automatically resized (rounded down) to the nearest valid dimensions
+    /// using Lanczos3 filtering.
+    pub fn process(&mut self, input: &RgbaImage) -> AsciiArt {
+        self.process_with_progress(input, &CancelToken::new(), &mut |_, _| {})
+            .expect("conversion without cancellation cannot fail")
+    }
+
+    /// [`process`](Self::process) reporting each finished stage to `progress`
+    /// and stopping when `cancel` is cancelled, see
+    /// [`process_image_with_progress`](crate::progress::process_image_with_progress)
+    ///
+    /// Stages reused from the last call are reported as they are skipped. A
+    /// cancelled call leaves nothing to reuse, so the next one runs all stages.
+    ///
+    /// # Errors
+    /// [`AsciiError::Cancelled`] when `cancel` was cancelled before the last
+    /// stage finished.
+    pub fn process_with_progress(
+        &mut self,
+        input: &RgbaImage,
+        cancel: &CancelToken,
+        progress: &mut (dyn FnMut(PipelineStage, f32) + Send),
+    ) -> Result<AsciiArt, AsciiError> {
+        // Only a frame budget needs the clock
+        let start = self.budget.is_some().then(Instant::now);
+
+        #[cfg(feature = "rayon")]
+        let result = match self.pool.clone() {
+            Some(pool) => pool.install(|| self.run_stages(input, cancel, progress)),
+            None => self.run_stages(input, cancel, progress),
+        };
+        #[cfg(not(feature = "rayon"))]
+        let result = self.run_stages(input, cancel, progress);
+
+        if let (Some(budget), Some(start), Ok(_)) = (&mut self.budget, start, &result) {
+            budget.record(start.elapsed());
+        }
+        result
+    }
+
+    /// Body of [`process_with_progress`](Self::process_with_progress), on
+    /// whichever pool the caller runs it
+    fn run_stages(
+        &mut self,
+        input: &RgbaImage,
+        cancel: &CancelToken,
+        progress: &mut (dyn FnMut(PipelineStage, f32) + Send),
+    ) -> Result<AsciiArt, AsciiError> {
+        // Whatever was cached may no longer match once a stage is cut short
+        let cached = self.stages.take();
+        if cancel.is_cancelled() {
+            return Err(AsciiError::Cancelled);
+        }
+
+        // A frame budget may lower the quality
+        let level = self.quality_level();
+        let degraded = (level > 0).then(|| degrade(&self.config, level));
+        let config = degraded.as_ref().unwrap_or(&self.config);
+        let skip_edges = level >= SKIP_EDGES_LEVEL;
+
+        // Automatically resize if dimensions are not multiples of the tile size,
+        // without copying input that already fits
+        let multiple = working_multiple(config);
+        let working_image = resize_to_valid_dimensions(input, multiple);
+        let working_image = &*working_image;
+        let source =
+            (self.preserve_colors || config.blend_with_base > 0.0).then(|| working_image.clone());
+
+        if config.output_mode == OutputMode::Braille {
+            luminance_into(working_image, config, &mut self.lum);
+            finish_stage(PipelineStage::Luminance, cancel, progress)?;
+            let layers = compute_braille_layers_buffered(&self.lum, config, &mut self.edges);
+            progress(PipelineStage::Fill, 1.0);
+            return Ok(AsciiArt::from_braille_layers(
+                layers,
+                config,
+                source,
+                self.preserve_colors,
+            ));
+        }
+
+        // Rerun the stages from the first one whose settings changed, or all
+        // of them for new input
+        let mut stages = cached.unwrap_or_default();
+        let stale = if stages.input == *input && working_multiple(&stages.config) == multiple {
+            config
+                .first_changed_stage(&stages.config)
+                .unwrap_or(PipelineStage::Rendering)
+        } else {
+            stages.input.clone_from(input);
+            PipelineStage::Luminance
+        };
+        stages.config.clone_from(config);
+
+        // Step 1: Extract luminance
+        if stale <= PipelineStage::Luminance {
+            luminance_into(working_image, config, &mut self.lum);
+        }
+        finish_stage(PipelineStage::Luminance, cancel, progress)?;
+        if stale <= PipelineStage::Blur {
+            stages.filtered = match pre_filter_luminance(&self.lum, config) {
+                Cow::Owned(filtered) => Some(filtered),
+                Cow::Borrowed(_) => None,
+            };
+        }
+        finish_stage(PipelineStage::Blur, cancel, progress)?;
+        let lum = stages.filtered.as_ref().unwrap_or(&self.lum);
+
+        // Steps 2-4: DoG, Sobel and edge voting
+        if stale <= PipelineStage::EdgeDetection && !skip_edges {
+            let mut rows = StageRows::new(
+                PipelineStage::EdgeDetection,
+                edge_row_passes(config),
+                cancel,
+                progress,
+            );
+            self.edges.gradients(lum, config, &mut rows);
+        }
+        finish_stage(PipelineStage::EdgeDetection, cancel, progress)?;
+        if skip_edges {
+            let (tile_w, tile_h) = config.tile_dimensions();
+            let tiles = (lum.width() / tile_w * (lum.height() / tile_h)) as usize;
+            stages.directions = vec![EdgeDirection::None; tiles];
+        } else if stale <= PipelineStage::EdgeVoting {
+            stages.directions = vote_edge_directions(
+                &self.edges.angles,
+                &self.edges.valid_mask,
+                lum.width(),
+                lum.height(),
+                tile_edge_thresholds(lum, config),
+                config,
+            );
+        }
+        finish_stage(PipelineStage::EdgeVoting, cancel, progress)?;
+
+        // Steps 5-6: Character selection
+        let mut layers =
+            compute_char_layers_from_edges(lum, stages.directions.clone(), &self.edges, config);
+        layers.channel_fill = channel_fill_layers(working_image, config);
+        progress(PipelineStage::Fill, 1.0);
+        let art = AsciiArt::from_char_layers(layers, config, source, self.preserve_colors);
+        // Without the edge pass the gradients and directions are placeholders
+        // that a later call must not reuse
+        if !skip_edges {
+            self.stages = Some(stages);
+        }
+        Ok(art)
+    }
+}
+
+/// Foreground color per tile: the average source color when colors are
+/// preserved, the configured ASCII color otherwise
+pub(crate) fn tile_colors(
+    source: Option<&RgbaImage>,
+    config: &AsciiConfig,
+    tile_count: u32,
+) -> Vec<[u8; 3]> {
+    match source {
+        Some(src) => tile_average_colors(src, config.tile_dimensions()),
+        None => vec![config.ascii_color; tile_count as usize],
+    }
+}
+
+/// Step 7 composition: mixes `base` into the rendered image by
+/// `blend_with_base`, as the shader's blend with the original frame
+pub(crate) fn blend_with_base(image: &mut RgbaImage, base: &RgbaImage, config: &AsciiConfig) {
+    let amount = config.blend_with_base;
+    if amount <= 0.0 {
+        return;
+    }
+    for (pixel, base) in image.pixels_mut().zip(base.pixels()) {
+        for channel in 0..3 {
+            let mixed = pixel[channel] as f32 * (1.0 - amount) + base[channel] as f32 * amount;
+            pixel[channel] = mixed.round() as u8;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{BlurMode, Charset, EdgeMode, Preset, ThresholdMode};
+    use crate::edges::DogSobel;
+    use crate::lut::{BLOCK_CHARS, FILL_CHARS};
 
     #[test]
     fn test_resize_to_valid_dimensions_no_resize() {
         let img = RgbaImage::new(160, 160); // Already valid (20*8 x 20*8)
-        let (resized, was_resized) = resize_to_valid_dimensions(&img);
+        let resized = resize_to_valid_dimensions(&img, (8, 8));
         assert_eq!(resized.dimensions(), (160, 160));
-        assert!(!was_resized);
+        assert!(matches!(resized, Cow::Borrowed(_)));
     }
 
     #[test]
     fn test_resize_to_valid_dimensions_resize_needed() {
         let img = RgbaImage::new(100, 100); // Not multiple of 8
-        let (resized, was_resized) = resize_to_valid_dimensions(&img);
+        let resized = resize_to_valid_dimensions(&img, (8, 8));
         assert_eq!(resized.dimensions(), (96, 96)); // 100 -> 96 (12*8)
-        assert!(was_resized);
+        assert!(matches!(resized, Cow::Owned(_)));
     }
 
     #[test]
     fn test_resize_to_valid_dimensions_asymmetric() {
         let img = RgbaImage::new(127, 85); // Both not multiples of 8
-        let (resized, was_resized) = resize_to_valid_dimensions(&img);
+        let resized = resize_to_valid_dimensions(&img, (8, 8));
         assert_eq!(resized.dimensions(), (120, 80)); // 127 -> 120, 85 -> 80
-        assert!(was_resized);
+        assert!(matches!(resized, Cow::Owned(_)));
     }
 
     #[test]
     fn test_process_invalid_dimensions_auto_resize() {
         let img = RgbaImage::new(100, 100); // Not multiple of 8, will be auto-resized
         let config = AsciiConfig::default();
-        let result = process_image(&img, &config);
+        let result = process_image(&img, &config).render_image();
         assert_eq!(result.dimensions(), (96, 96)); // Resized to 96x96
     }
 
@@ -200,7 +1102,752 @@ mod tests {
     fn test_process_valid_dimensions() {
         let img = RgbaImage::new(160, 160); // 20*8 x 20*8
         let config = AsciiConfig::default();
-        let result = process_image(&img, &config);
+        let result = process_image(&img, &config).render_image();
         assert_eq!(result.dimensions(), (160, 160));
     }
+
+    #[test]
+    fn test_process_with_local_contrast() {
+        let img = RgbaImage::from_fn(64, 64, |x, _| {
+            let v = (x * 4) as u8;
+            image::Rgba([v, v, v, 255])
+        });
+        let config = AsciiConfig {
+            local_contrast_radius: Some(2),
+            ..Default::default()
+        };
+        let result = process_image(&img, &config).render_image();
+        assert_eq!(result.dimensions(), (64, 64));
+    }
+
+    #[test]
+    fn test_process_gray_matches_rgba() {
+        let gray = GrayImage::from_fn(96, 64, |x, y| image::Luma([((x * 3 + y) % 256) as u8]));
+        let rgba = RgbaImage::from_fn(96, 64, |x, y| {
+            let v = gray.get_pixel(x, y)[0];
+            image::Rgba([v, v, v, 255])
+        });
+        let config = AsciiConfig::default();
+        assert_eq!(
+            process_gray(&gray, &config).render_image(),
+            process_image(&rgba, &config).render_image()
+        );
+
+        // Odd sizes are resized like RGBA input
+        let odd = GrayImage::new(100, 85);
+        assert_eq!(process_gray(&odd, &config).dimensions(), (96, 80));
+    }
+
+    #[test]
+    fn test_process_yuv() {
+        // Full-range gray frame: the Y plane is the luminance image
+        let luma = GrayImage::from_fn(64, 32, |x, _| image::Luma([(x * 4) as u8]));
+        let data = [luma.as_raw().as_slice(), &[128; 2 * 32 * 16]].concat();
+        let frame = YuvFrame::from_i420(&data, 64, 32)
+            .unwrap()
+            .with_full_range(true);
+        let config = AsciiConfig::default();
+
+        assert_eq!(
+            process_yuv(&frame, &config, false).render_image(),
+            process_gray(&luma, &config).render_image()
+        );
+        assert_eq!(process_yuv(&frame, &config, true).dimensions(), (64, 32));
+    }
+
+    #[test]
+    fn test_process_auto_invert() {
+        // Dark strokes on a white page
+        let page = RgbaImage::from_fn(64, 64, |x, y| {
+            let ink = (x / 4) % 4 == 0 || y == 30;
+            let v = if ink { 10 } else { 245 };
+            image::Rgba([v, v, v, 255])
+        });
+        let auto = AsciiConfig {
+            auto_invert: true,
+            ..Default::default()
+        };
+        let inverted = AsciiConfig {
+            invert_luminance: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            process_image(&page, &auto).render_image(),
+            process_image(&page, &inverted).render_image()
+        );
+
+        // Dark images are left alone
+        let night = RgbaImage::from_pixel(64, 64, image::Rgba([30, 30, 30, 255]));
+        assert_eq!(
+            process_image(&night, &auto).render_image(),
+            process_image(&night, &AsciiConfig::default()).render_image()
+        );
+    }
+
+    #[test]
+    fn test_process_rgb_split() {
+        // Red on the left, blue on the right: each channel gets its own fill
+        let input = RgbaImage::from_fn(64, 32, |x, _| {
+            if x < 32 {
+                image::Rgba([255, 0, 0, 255])
+            } else {
+                image::Rgba([0, 0, 255, 255])
+            }
+        });
+        let config = AsciiConfig {
+            rgb_split: Some(0),
+            draw_edges: false,
+            ..Default::default()
+        };
+        let output = process_image(&input, &config).render_image();
+        assert_eq!(output.dimensions(), (64, 32));
+        assert!(output.pixels().any(|p| p[0] == 255 && p[2] == 0));
+        assert!(output.pixels().any(|p| p[0] == 0 && p[2] == 255));
+        assert!(output.pixels().all(|p| p[1] == 0 || p[1] == 255));
+    }
+
+    #[test]
+    fn test_process_edge_debug() {
+        // Vertical step: the boundary tiles vote vertical and render red
+        let img = RgbaImage::from_fn(64, 64, |x, _| {
+            let v = if x < 32 { 0 } else { 255 };
+            image::Rgba([v, v, v, 255])
+        });
+        let config = AsciiConfig {
+            edge_debug: true,
+            ..Default::default()
+        };
+        let result = process_image(&img, &config).render_image();
+        assert_eq!(result.dimensions(), (64, 64));
+        assert!(result.pixels().any(|p| p.0 == [255, 64, 64, 255]));
+    }
+
+    #[test]
+    fn test_try_process_image() {
+        let img = RgbaImage::from_pixel(40, 24, image::Rgba([90, 90, 90, 255]));
+        let config = AsciiConfig::default();
+        assert_eq!(
+            try_process_image(&img, &config).unwrap().render_image(),
+            process_image(&img, &config).render_image()
+        );
+
+        let invalid = AsciiConfig {
+            kernel_size: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            try_process_image(&img, &invalid),
+            Err(AsciiError::InvalidConfig {
+                field: "kernel_size",
+                ..
+            })
+        ));
+        assert!(matches!(
+            try_process_image_preserve_colors(&RgbaImage::new(100, 5), &config),
+            Err(AsciiError::ImageTooSmall {
+                width: 100,
+                height: 5,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_processor_matches_process_image() {
+        let frame = |width, height, shift| {
+            RgbaImage::from_fn(width, height, |x, y| {
+                let v = if (x + shift) % 40 < 20 { 30 } else { 220 };
+                image::Rgba([v, (y * 5) as u8, 90, 255])
+            })
+        };
+        let config = AsciiConfig::default();
+        let mut processor = Processor::new(&config, false);
+
+        // Buffers are reused across frames and resized when the size changes
+        for input in [frame(64, 48, 0), frame(64, 48, 7), frame(100, 60, 3)] {
+            assert_eq!(
+                processor.process(&input).render_image(),
+                process_image(&input, &config).render_image()
+            );
+        }
+
+        let braille = AsciiConfig {
+            output_mode: OutputMode::Braille,
+            ..config
+        };
+        processor.set_config(&braille);
+        processor.set_preserve_colors(true);
+        let input = frame(64, 48, 5);
+        assert_eq!(
+            processor.process(&input).render_image(),
+            process_image_preserve_colors(&input, &braille).render_image()
+        );
+    }
+
+    #[test]
+    fn test_custom_edge_detector() {
+        /// Marks every pixel as a horizontal edge
+        struct AllHorizontal;
+
+        impl EdgeDetector for AllHorizontal {
+            fn detect(
+                &mut self,
+                lum: &GrayImage,
+                _config: &AsciiConfig,
+                angles: &mut Vec<f32>,
+                valid_mask: &mut Vec<bool>,
+            ) {
+                let size = (lum.width() * lum.height()) as usize;
+                *angles = vec![std::f32::consts::FRAC_PI_2; size];
+                *valid_mask = vec![true; size];
+            }
+        }
+
+        let input = RgbaImage::new(32, 16);
+        let art = Processor::new(&AsciiConfig::default(), false)
+            .with_edge_detector(AllHorizontal)
+            .process(&input);
+        assert_eq!(
+            art.edge_directions().unwrap(),
+            [EdgeDirection::Horizontal; 8]
+        );
+        // A flat image has no edges under the default detector
+        let art = process_image(&input, &AsciiConfig::default());
+        assert!(
+            art.edge_directions()
+                .unwrap()
+                .iter()
+                .all(|d| *d == EdgeDirection::None)
+        );
+    }
+
+    #[test]
+    fn test_processor_thread_options() {
+        let img = RgbaImage::from_fn(96, 64, |x, y| {
+            let v = if (x / 16 + y / 16) % 2 == 0 { 20 } else { 230 };
+            image::Rgba([v, (x * 2) as u8, v, 255])
+        });
+        let config = AsciiConfig::default();
+        let expected = process_image_preserve_colors(&img, &config).render_image();
+
+        for threads in [1, 2] {
+            let options = ProcessorOptions::new().threads(threads);
+            let mut processor = Processor::new(&config, true).with_options(options.clone());
+            assert_eq!(processor.options(), &options);
+            assert_eq!(processor.process(&img).render_image(), expected);
+        }
+    }
+
+    #[test]
+    fn test_processor_frame_budget() {
+        use crate::adaptive::MAX_LEVEL;
+
+        let img = RgbaImage::from_fn(96, 64, |x, y| {
+            let v = if (x / 16 + y / 16) % 2 == 0 { 20 } else { 230 };
+            image::Rgba([v, (x * 2) as u8, v, 255])
+        });
+        let config = AsciiConfig::default();
+        assert_eq!(Processor::new(&config, true).quality_level(), 0);
+
+        // No conversion fits this budget, so quality drops to the lowest level
+        let mut processor =
+            Processor::new(&config, true).with_options(ProcessorOptions::new().target_ms(1e-6));
+        for _ in 0..20 {
+            let level = processor.quality_level();
+            let art = processor.process(&img);
+            assert_eq!(
+                art.config().first_changed_stage(&degrade(&config, level)),
+                None
+            );
+            assert_eq!(
+                art.render_image(),
+                process_image_preserve_colors(&img, &degrade(&config, level)).render_image(),
+                "level {}",
+                level
+            );
+        }
+        assert_eq!(processor.quality_level(), MAX_LEVEL);
+    }
+
+    #[test]
+    fn test_processor_reuses_unchanged_stages() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// The default detector, counting its runs
+        struct Counting(DogSobel, Arc<AtomicUsize>);
+
+        impl EdgeDetector for Counting {
+            fn detect(
+                &mut self,
+                lum: &GrayImage,
+                config: &AsciiConfig,
+                angles: &mut Vec<f32>,
+                valid_mask: &mut Vec<bool>,
+            ) {
+                self.1.fetch_add(1, Ordering::Relaxed);
+                self.0.detect(lum, config, angles, valid_mask);
+            }
+        }
+
+        let input = RgbaImage::from_fn(64, 48, |x, y| {
+            let inside = (x as i32 - 30).pow(2) + (y as i32 - 24).pow(2) < 300;
+            let v = if inside { 220 } else { (x * 2) as u8 };
+            image::Rgba([v, v / 2, 255 - v, 255])
+        });
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut config = AsciiConfig::default();
+        let mut processor = Processor::new(&config, false)
+            .with_edge_detector(Counting(DogSobel::default(), runs.clone()));
+        let check = |processor: &mut Processor, config: &AsciiConfig, runs_after| {
+            processor.set_config(config);
+            assert_eq!(
+                processor.process(&input).render_image(),
+                process_image(&input, config).render_image()
+            );
+            assert_eq!(runs.load(Ordering::Relaxed), runs_after);
+        };
+
+        check(&mut processor, &config, 1);
+        // Fill and rendering settings reuse the edges
+        config.ascii_color = [255, 0, 0];
+        config.invert_luminance = true;
+        config.charset = Charset::Blocks;
+        check(&mut processor, &config, 1);
+        config.edge_threshold = 4;
+        check(&mut processor, &config, 1);
+        // Blur settings rerun the detector
+        config.sigma = 3.0;
+        check(&mut processor, &config, 2);
+        config.luminance_mode = crate::config::LuminanceMode::Max;
+        check(&mut processor, &config, 3);
+        check(&mut processor, &config, 3);
+    }
+
+    #[test]
+    fn test_process_image_output_grid() {
+        let img = RgbaImage::from_fn(100, 64, |_, _| image::Rgba([200, 40, 40, 255]));
+        let config = AsciiConfig::default();
+
+        let output = process_image_output(&img, &config, false);
+        assert_eq!((output.columns, output.rows), (12, 8));
+        assert_eq!(output.chars.len(), 96);
+        assert_eq!(output.image.dimensions(), (96, 64));
+        assert!(output.colors.iter().all(|&c| c == config.ascii_color));
+
+        // Flat input: no edges, so the fill layer is the composite
+        assert!(output.edge_chars.iter().all(|&c| c == ' '));
+        assert_eq!(output.fill_chars, output.chars);
+
+        let colored = process_image_output(&img, &config, true);
+        assert!(colored.colors.iter().all(|&c| c == [200, 40, 40]));
+    }
+
+    #[test]
+    fn test_process_charset_blocks() {
+        let img = RgbaImage::from_fn(64, 16, |x, _| {
+            let v = (x * 4) as u8;
+            image::Rgba([v, v, v, 255])
+        });
+        let config = AsciiConfig {
+            charset: Charset::Blocks,
+            draw_edges: false,
+            ..Default::default()
+        };
+        let chars = process_image(&img, &config).chars();
+        assert!(chars.iter().all(|c| BLOCK_CHARS.contains(c)));
+        assert_eq!(chars[0], ' ');
+        assert_eq!(chars[5], '▓');
+        assert_eq!(chars[7], '█');
+    }
+
+    #[test]
+    fn test_process_bilateral_pre_filter() {
+        // Gray halves with blotchy noise, split by one strong vertical edge
+        let img = RgbaImage::from_fn(64, 64, |x, y| {
+            let (bx, by) = (x / 2, y / 2);
+            let hash =
+                (bx.wrapping_mul(73856093) ^ by.wrapping_mul(19349663)).wrapping_mul(2654435761);
+            let v = if x < 32 { 60 } else { 180 } + ((hash >> 24) % 24) as u8;
+            image::Rgba([v, v, v, 255])
+        });
+        // Edge columns per tile row, with fine edge settings that pick up the noise
+        let edge_columns = |config: &AsciiConfig| {
+            let art = process_image(&img, config);
+            let directions = art.edge_directions().unwrap().to_vec();
+            (0..64)
+                .filter(|&i| directions[i] != EdgeDirection::None)
+                .map(|i| i % 8)
+                .collect::<Vec<_>>()
+        };
+        let detailed = AsciiConfig::preset(Preset::HighDetail);
+
+        assert_eq!(edge_columns(&detailed).len(), 64);
+        let filtered = edge_columns(&AsciiConfig {
+            pre_filter: PreFilter::BILATERAL,
+            ..detailed
+        });
+        assert_eq!(filtered.len(), 16);
+        assert!(filtered.iter().all(|&column| column == 3 || column == 4));
+    }
+
+    #[test]
+    fn test_process_median_radius() {
+        // Flat gray with scattered white specks, like a noisy sensor
+        let img = RgbaImage::from_fn(64, 64, |x, y| {
+            let v = if (x * 7 + y * 11) % 23 == 0 { 255 } else { 100 };
+            image::Rgba([v, v, v, 255])
+        });
+        let edge_tiles = |config: &AsciiConfig| {
+            let art = process_image(&img, config);
+            let directions = art.edge_directions().unwrap().to_vec();
+            directions
+                .iter()
+                .filter(|&&d| d != EdgeDirection::None)
+                .count()
+        };
+
+        assert!(edge_tiles(&AsciiConfig::default()) > 0);
+        let despeckled = AsciiConfig {
+            median_radius: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(edge_tiles(&despeckled), 0);
+    }
+
+    #[test]
+    fn test_process_xdog_edge_mode() {
+        let img = RgbaImage::from_fn(64, 32, |x, _| {
+            let v = if x < 28 { 40 } else { 200 };
+            image::Rgba([v, v, v, 255])
+        });
+        let directions = |config: &AsciiConfig| {
+            process_image(&img, config)
+                .edge_directions()
+                .unwrap()
+                .to_vec()
+        };
+
+        let xdog = directions(&AsciiConfig {
+            edge_mode: EdgeMode::XDOG,
+            ..Default::default()
+        });
+        assert_eq!(xdog, directions(&AsciiConfig::default()));
+        assert!(xdog.contains(&EdgeDirection::Vertical));
+    }
+
+    #[test]
+    fn test_process_sobel_edge_mode() {
+        // Soft shading on the left, a hard step on the right
+        let img = RgbaImage::from_fn(64, 32, |x, _| {
+            let v = if x < 40 { 60 + x as u8 * 2 } else { 230 };
+            image::Rgba([v, v, v, 255])
+        });
+        let config = AsciiConfig {
+            edge_mode: EdgeMode::SOBEL,
+            ..Default::default()
+        };
+        let art = process_image(&img, &config);
+        let directions = art.edge_directions().unwrap();
+
+        for row in directions.chunks(8) {
+            assert!(row[..4].iter().all(|&d| d == EdgeDirection::None));
+            assert!(row[4..6].contains(&EdgeDirection::Vertical));
+        }
+    }
+
+    #[test]
+    fn test_process_non_max_suppression() {
+        // Dark to bright step just left of the border between tile columns 1 and 2
+        let img = RgbaImage::from_fn(32, 32, |x, _| {
+            let v = if x < 15 { 0 } else { 255 };
+            image::Rgba([v, v, v, 255])
+        });
+        let edge_columns = |config: &AsciiConfig| {
+            let art = process_image(&img, config);
+            let directions = art.edge_directions().unwrap().to_vec();
+            (0..4)
+                .filter(|&column| directions[4 + column] != EdgeDirection::None)
+                .collect::<Vec<_>>()
+        };
+
+        // The Sobel response straddles the border and both tiles vote
+        assert_eq!(edge_columns(&AsciiConfig::default()), [1, 2]);
+        let thinned = AsciiConfig {
+            non_max_suppression: true,
+            ..Default::default()
+        };
+        assert_eq!(edge_columns(&thinned), [2]);
+    }
+
+    #[test]
+    fn test_process_threshold_mode() {
+        // Faint blocks whose DoG never reaches the default threshold
+        let img = RgbaImage::from_fn(64, 64, |x, y| {
+            let v = if (x / 16 + y / 16) % 2 == 0 { 120 } else { 132 };
+            image::Rgba([v, v, v, 255])
+        });
+        let edge_tiles = |config: &AsciiConfig| {
+            let art = process_image(&img, config);
+            let directions = art.edge_directions().unwrap().to_vec();
+            directions
+                .iter()
+                .filter(|&&d| d != EdgeDirection::None)
+                .count()
+        };
+
+        assert_eq!(edge_tiles(&AsciiConfig::default()), 0);
+        for threshold_mode in [ThresholdMode::Otsu, ThresholdMode::PERCENTILE] {
+            let config = AsciiConfig {
+                threshold_mode,
+                ..Default::default()
+            };
+            assert!(edge_tiles(&config) > 0);
+        }
+    }
+
+    #[test]
+    fn test_process_sobel_threshold() {
+        // Vertical bars: Sobel on the binary DoG peaks at a magnitude of 4.0
+        let img = RgbaImage::from_fn(64, 32, |x, _| {
+            let v = if (x / 16) % 2 == 0 { 30 } else { 220 };
+            image::Rgba([v, v, v, 255])
+        });
+        let edge_tiles = |sobel_threshold| {
+            let config = AsciiConfig {
+                sobel_threshold,
+                ..Default::default()
+            };
+            let art = process_image(&img, &config);
+            let directions = art.edge_directions().unwrap().to_vec();
+            directions
+                .iter()
+                .filter(|&&d| d != EdgeDirection::None)
+                .count()
+        };
+
+        assert!(edge_tiles(0.01) > 0);
+        assert_eq!(edge_tiles(4.0), 0);
+    }
+
+    #[test]
+    fn test_process_edge_dilation() {
+        // Faint vertical line of the given width
+        let edge_tiles = |line_width: u32, edge_dilation| {
+            let img = RgbaImage::from_fn(32, 32, |x, y| {
+                let on_line = (12..12 + line_width).contains(&x) && (4..28).contains(&y);
+                let v = if on_line { 140 } else { 100 };
+                image::Rgba([v, v, v, 255])
+            });
+            let config = AsciiConfig {
+                edge_dilation,
+                ..Default::default()
+            };
+            let art = process_image(&img, &config);
+            let directions = art.edge_directions().unwrap().to_vec();
+            directions
+                .iter()
+                .filter(|&&d| d != EdgeDirection::None)
+                .count()
+        };
+
+        // A one-pixel line is too thin to win a tile vote until thickened
+        assert_eq!(edge_tiles(1, 0), 0);
+        assert!(edge_tiles(1, 1) > 0);
+        // Erosion drops the thin bands of a two-pixel line
+        assert!(edge_tiles(2, 0) > 0);
+        assert_eq!(edge_tiles(2, -1), 0);
+    }
+
+    #[test]
+    fn test_process_box_blur() {
+        // Bright square on a dark background
+        let img = RgbaImage::from_fn(64, 64, |x, y| {
+            let v = if (16..48).contains(&x) && (16..48).contains(&y) {
+                220
+            } else {
+                30
+            };
+            image::Rgba([v, v, v, 255])
+        });
+        let directions = |blur_mode| {
+            let config = AsciiConfig {
+                blur_mode,
+                ..Default::default()
+            };
+            process_image(&img, &config)
+                .edge_directions()
+                .unwrap()
+                .to_vec()
+        };
+
+        let gaussian = directions(BlurMode::Gaussian);
+        let boxed = directions(BlurMode::Box);
+        let edge_tiles =
+            |d: &[EdgeDirection]| d.iter().filter(|&&d| d != EdgeDirection::None).count();
+        assert!(edge_tiles(&boxed) > 0);
+        // The approximation finds nearly the same edge tiles
+        let differing = gaussian.iter().zip(&boxed).filter(|(a, b)| a != b).count();
+        assert!(differing <= edge_tiles(&gaussian) / 4, "{}", differing);
+    }
+
+    #[test]
+    fn test_process_luminance_adjust() {
+        // Murky gradient that only spans a few ramp steps
+        let img = RgbaImage::from_fn(128, 16, |x, _| {
+            let v = 90 + (x / 4) as u8;
+            image::Rgba([v, v, v, 255])
+        });
+        let distinct = |config: &AsciiConfig| {
+            let mut chars = process_image(&img, config).fill_chars();
+            chars.sort_unstable();
+            chars.dedup();
+            chars.len()
+        };
+        let fill_only = AsciiConfig {
+            draw_edges: false,
+            ..Default::default()
+        };
+
+        let murky = distinct(&fill_only);
+        for luminance_adjust in [
+            LuminanceAdjust::AutoContrast,
+            LuminanceAdjust::Equalize,
+            LuminanceAdjust::CLAHE,
+        ] {
+            let adjusted = distinct(&AsciiConfig {
+                luminance_adjust,
+                ..fill_only.clone()
+            });
+            assert!(
+                adjusted > murky,
+                "{:?}: {} <= {}",
+                luminance_adjust,
+                adjusted,
+                murky
+            );
+        }
+    }
+
+    #[test]
+    fn test_process_linear_luminance() {
+        // Saturated red reads as a dark mid-tone on the encoded bytes
+        let img = RgbaImage::from_pixel(64, 16, image::Rgba([255, 0, 0, 255]));
+        let encoded = process_image(&img, &AsciiConfig::default());
+        let linear = process_image(
+            &img,
+            &AsciiConfig {
+                linear_luminance: true,
+                ..Default::default()
+            },
+        );
+
+        let ramp = &FILL_CHARS;
+        let level = |art: &AsciiArt| ramp.iter().position(|&c| c == art.chars()[0]).unwrap();
+        assert!(level(&linear) > level(&encoded));
+        assert!(linear.tile_luminance().unwrap()[0] > encoded.tile_luminance().unwrap()[0]);
+    }
+
+    #[test]
+    fn test_process_exposure_and_attenuation() {
+        let img = RgbaImage::from_pixel(64, 16, image::Rgba([100, 100, 100, 255]));
+        let tile_lum = |exposure, attenuation| {
+            let config = AsciiConfig {
+                exposure,
+                attenuation,
+                ..Default::default()
+            };
+            process_image(&img, &config).tile_luminance().unwrap()[0]
+        };
+
+        let plain = tile_lum(1.0, 1.0);
+        assert!(tile_lum(2.0, 1.0) > plain);
+        assert!(tile_lum(1.0, 2.0) < plain);
+        assert_eq!(tile_lum(0.0, 1.0), 0.0);
+        assert_eq!(tile_lum(5.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn test_process_blend_with_base() {
+        let img = RgbaImage::from_fn(64, 32, |x, y| {
+            image::Rgba([x as u8 * 4, y as u8 * 8, 90, 255])
+        });
+        let render = |blend_with_base| {
+            let config = AsciiConfig {
+                blend_with_base,
+                ..Default::default()
+            };
+            process_image(&img, &config).render_image()
+        };
+
+        let ascii = render(0.0);
+        let half = render(0.5);
+        let base = render(1.0);
+        for ((a, h), (b, src)) in ascii
+            .pixels()
+            .zip(half.pixels())
+            .zip(base.pixels().zip(img.pixels()))
+        {
+            assert_eq!(b.0[..3], src.0[..3]);
+            for c in 0..3 {
+                let mid = (a[c] as f32 + src[c] as f32) / 2.0;
+                assert!((h[c] as f32 - mid).abs() <= 0.5);
+            }
+        }
+
+        // The source is kept for blending but does not color the characters
+        let art = process_image(
+            &img,
+            &AsciiConfig {
+                blend_with_base: 0.5,
+                ..Default::default()
+            },
+        );
+        assert!(art.source().is_none());
+        assert_eq!(art.colors()[0], [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_process_tile_sizes() {
+        let img = RgbaImage::from_fn(100, 64, |x, _| {
+            let v = if x < 50 { 30 } else { 220 };
+            image::Rgba([v, v, v, 255])
+        });
+
+        for (tile_size, grid, size) in [
+            (4, (25, 16), (100, 64)),
+            (16, (6, 4), (96, 64)),
+            (32, (3, 2), (96, 64)),
+        ] {
+            let config = AsciiConfig {
+                tile_size,
+                ..Default::default()
+            };
+            let output = process_image_output(&img, &config, false);
+            assert_eq!((output.columns, output.rows), grid, "{}", tile_size);
+            assert_eq!(output.image.dimensions(), size, "{}", tile_size);
+        }
+
+        // 8×16 tiles: half as many rows, same image size
+        let config = AsciiConfig {
+            tile_height: Some(16),
+            ..Default::default()
+        };
+        let output = process_image_output(&img, &config, false);
+        assert_eq!((output.columns, output.rows), (12, 4));
+        assert_eq!(output.image.dimensions(), (96, 64));
+        assert_eq!(output.to_text().lines().count(), 4);
+
+        let config = AsciiConfig {
+            tile_size: 32,
+            ..Default::default()
+        };
+        assert!(matches!(
+            try_process_image(&RgbaImage::new(16, 64), &config),
+            Err(AsciiError::ImageTooSmall {
+                tile_size: (32, 32),
+                ..
+            })
+        ));
+    }
 }
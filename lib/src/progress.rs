@@ -0,0 +1,297 @@
+//! Progress reporting and cancellation for long conversions
+//!
+//! A conversion of a large image takes long enough that an interactive caller
+//! wants to show how far it got and to abort it when the settings change
+//! again. [`process_image_with_progress`] reports each finished pipeline stage
+//! to a callback and checks a [`CancelToken`] between stages, so the caller
+//! can run it on a worker thread and cancel it from the UI thread. The blur,
+//! DoG and Sobel passes of edge detection, which take most of the time, also
+//! report and check after every row.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::progress::{CancelToken, process_image_with_progress};
+//!
+//! let input = image::open("scan.png").unwrap().to_rgba8();
+//! let cancel = CancelToken::new();
+//! let art = process_image_with_progress(
+//!     &input,
+//!     &AsciiConfig::default(),
+//!     false,
+//!     &cancel,
+//!     |stage, fraction| println!("{:?}: {:.0}%", stage, fraction * 100.0),
+//! )
+//! .unwrap();
+//! println!("{}", art.render_text());
+//! ```
+
+use crate::art::AsciiArt;
+use crate::config::AsciiConfig;
+use crate::error::{AsciiError, check_image_size};
+use crate::params::PipelineStage;
+use crate::processor::{Processor, working_multiple};
+use image::RgbaImage;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shared flag that asks a running conversion to stop
+///
+/// Clones share the flag, so one clone can be handed to the worker and
+/// another kept to cancel from elsewhere.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Creates a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every conversion holding a clone of this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) was called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Share of the conversion done once `stage` finishes, by its rough cost on a
+/// large image with the default settings
+pub(crate) fn stage_fraction(stage: PipelineStage) -> f32 {
+    match stage {
+        PipelineStage::Luminance => 0.05,
+        PipelineStage::Blur => 0.15,
+        PipelineStage::EdgeDetection => 0.75,
+        PipelineStage::EdgeVoting => 0.8,
+        PipelineStage::Fill | PipelineStage::Rendering => 1.0,
+    }
+}
+
+/// Share of the conversion done when `stage` starts
+fn stage_start(stage: PipelineStage) -> f32 {
+    match stage {
+        PipelineStage::Luminance => 0.0,
+        PipelineStage::Blur => stage_fraction(PipelineStage::Luminance),
+        PipelineStage::EdgeDetection => stage_fraction(PipelineStage::Blur),
+        PipelineStage::EdgeVoting => stage_fraction(PipelineStage::EdgeDetection),
+        PipelineStage::Fill => stage_fraction(PipelineStage::EdgeVoting),
+        PipelineStage::Rendering => stage_fraction(PipelineStage::Fill),
+    }
+}
+
+/// Cancellation checks and progress reports inside a stage, row by row
+///
+/// A stage made of several full-image passes calls [`begin_pass`](Self::begin_pass)
+/// before each and [`row`](Self::row) after each row, so that a cancelled
+/// conversion stops within a row and the reported fraction moves through the
+/// stage. The public filter functions run with [`unchecked`](Self::unchecked).
+pub(crate) struct StageRows<'a> {
+    /// None when nothing is checked
+    cancel: Option<&'a CancelToken>,
+    /// None when nothing is reported
+    progress: Option<&'a mut dyn FnMut(PipelineStage, f32)>,
+    stage: PipelineStage,
+    /// Passes the stage is split into, for spreading its fraction evenly
+    passes: usize,
+    /// Passes begun so far
+    begun: usize,
+}
+
+impl<'a> StageRows<'a> {
+    /// Rows of the `passes` passes of `stage`, checked against `cancel` and
+    /// reported to `progress`
+    pub(crate) fn new(
+        stage: PipelineStage,
+        passes: usize,
+        cancel: &'a CancelToken,
+        progress: &'a mut dyn FnMut(PipelineStage, f32),
+    ) -> Self {
+        Self {
+            cancel: Some(cancel),
+            progress: Some(progress),
+            stage,
+            passes: passes.max(1),
+            begun: 0,
+        }
+    }
+
+    /// Rows that are never cancelled or reported
+    pub(crate) fn unchecked() -> StageRows<'static> {
+        StageRows {
+            cancel: None,
+            progress: None,
+            stage: PipelineStage::Luminance,
+            passes: 1,
+            begun: 0,
+        }
+    }
+
+    /// Starts the next pass
+    pub(crate) fn begin_pass(&mut self) {
+        self.begun += 1;
+    }
+
+    /// Reports that `done` of the `total` rows of the current pass are
+    /// finished
+    ///
+    /// Returns false when the conversion was cancelled and the pass should
+    /// stop; the caller's next [`finish_stage`] then fails.
+    pub(crate) fn row(&mut self, done: usize, total: usize) -> bool {
+        if self.cancel.is_some_and(CancelToken::is_cancelled) {
+            return false;
+        }
+        let Some(progress) = self.progress.as_mut() else {
+            return true;
+        };
+
+        let pass = self.begun.saturating_sub(1) as f32 + done as f32 / total.max(1) as f32;
+        let share = (pass / self.passes as f32).min(1.0);
+        let (start, end) = (stage_start(self.stage), stage_fraction(self.stage));
+        progress(self.stage, start + (end - start) * share);
+        true
+    }
+}
+
+/// Reports `stage` as finished, then fails with [`AsciiError::Cancelled`] if
+/// the conversion should stop before the next one
+pub(crate) fn finish_stage(
+    stage: PipelineStage,
+    cancel: &CancelToken,
+    progress: &mut dyn FnMut(PipelineStage, f32),
+) -> Result<(), AsciiError> {
+    progress(stage, stage_fraction(stage));
+    if cancel.is_cancelled() {
+        return Err(AsciiError::Cancelled);
+    }
+    Ok(())
+}
+
+/// Converts an image, reporting progress and stopping early when cancelled
+///
+/// `progress` is called after each pipeline stage, and after each row of the
+/// edge detection passes, with the stage and the share of the conversion done
+/// so far, from 0 to 1; the last call is `(PipelineStage::Fill, 1.0)`.
+/// `cancel` is checked before the first stage, between stages and between
+/// those rows. The result equals [`process_image`](crate::process_image)
+/// / [`process_image_preserve_colors`](crate::process_image_preserve_colors).
+/// Rendering is left to the caller, see [`AsciiArt`].
+///
+/// # Arguments
+/// * `input` - The input RGBA image to convert
+/// * `config` - Configuration parameters for the ASCII conversion
+/// * `preserve_colors` - Sample colors from the source image instead of `ascii_color`
+/// * `cancel` - Token checked between stages and rows
+/// * `progress` - Called with each finished stage or row and the fraction done
+///
+/// # Errors
+/// [`AsciiError::Cancelled`] when `cancel` was cancelled before the last
+/// stage finished, otherwise the errors of
+/// [`try_process_image`](crate::try_process_image).
+pub fn process_image_with_progress(
+    input: &RgbaImage,
+    config: &AsciiConfig,
+    preserve_colors: bool,
+    cancel: &CancelToken,
+    mut progress: impl FnMut(PipelineStage, f32) + Send,
+) -> Result<AsciiArt, AsciiError> {
+    config.validate()?;
+    check_image_size(input.width(), input.height(), working_multiple(config))?;
+
+    Processor::new(config, preserve_colors).process_with_progress(input, cancel, &mut progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OutputMode;
+    use crate::processor::process_image_preserve_colors;
+
+    fn test_image() -> RgbaImage {
+        RgbaImage::from_fn(64, 48, |x, y| {
+            let v = if (x / 12 + y / 8) % 2 == 0 { 30 } else { 220 };
+            image::Rgba([v, (x * 4) as u8, (y * 5) as u8, 255])
+        })
+    }
+
+    #[test]
+    fn test_progress_matches_process_image() {
+        let img = test_image();
+        for config in [
+            AsciiConfig::default(),
+            AsciiConfig {
+                output_mode: OutputMode::Braille,
+                ..AsciiConfig::default()
+            },
+        ] {
+            let mut reports = Vec::new();
+            let art =
+                process_image_with_progress(&img, &config, true, &CancelToken::new(), |s, f| {
+                    reports.push((s, f))
+                })
+                .unwrap();
+            assert_eq!(
+                art.render_image(),
+                process_image_preserve_colors(&img, &config).render_image()
+            );
+            assert!(reports.windows(2).all(|w| w[0].1 <= w[1].1));
+            assert_eq!(reports.last(), Some(&(PipelineStage::Fill, 1.0)));
+        }
+    }
+
+    #[test]
+    fn test_cancelled_conversion() {
+        let img = test_image();
+        let config = AsciiConfig::default();
+
+        let cancel = CancelToken::new();
+        cancel.clone().cancel();
+        let result = process_image_with_progress(&img, &config, false, &cancel, |_, _| {});
+        assert_eq!(result.err(), Some(AsciiError::Cancelled));
+
+        // Cancelling from the callback stops before the next stage
+        let cancel = CancelToken::new();
+        let mut stages = Vec::new();
+        let result = process_image_with_progress(&img, &config, false, &cancel, |stage, _| {
+            stages.push(stage);
+            if stage == PipelineStage::Blur {
+                cancel.cancel();
+            }
+        });
+        assert_eq!(result.err(), Some(AsciiError::Cancelled));
+        assert_eq!(stages.last(), Some(&PipelineStage::Blur));
+    }
+
+    #[test]
+    fn test_edge_detection_reports_and_cancels_by_row() {
+        let img = test_image();
+        let config = AsciiConfig::default();
+        let done = stage_fraction(PipelineStage::EdgeDetection);
+
+        // Fractions move through the stage instead of jumping to its end
+        let mut within = Vec::new();
+        process_image_with_progress(&img, &config, false, &CancelToken::new(), |stage, f| {
+            if stage == PipelineStage::EdgeDetection && f < done {
+                within.push(f);
+            }
+        })
+        .unwrap();
+        assert!(within.len() > img.height() as usize);
+        assert!(within.windows(2).all(|w| w[0] <= w[1]));
+        assert!(within[0] >= stage_fraction(PipelineStage::Blur));
+
+        // Cancelling after the first row stops the remaining rows
+        let cancel = CancelToken::new();
+        let mut rows = 0;
+        let result = process_image_with_progress(&img, &config, false, &cancel, |stage, f| {
+            if stage == PipelineStage::EdgeDetection && f < done {
+                rows += 1;
+                cancel.cancel();
+            }
+        });
+        assert_eq!(result.err(), Some(AsciiError::Cancelled));
+        assert_eq!(rows, 1);
+    }
+}
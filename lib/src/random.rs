@@ -0,0 +1,205 @@
+//! Random and mutated configurations for parameter-space exploration
+//!
+//! Everything here is driven by an explicit seed, so a configuration found by
+//! exploring can be reproduced later from the same seed.
+
+use crate::config::{AsciiConfig, FillDither, TileStatistic};
+
+/// Small deterministic PRNG (SplitMix64)
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in [0.0, 1.0)
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform float in [min, max]
+    fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Uniform integer in [min, max]
+    fn range_u32(&mut self, min: u32, max: u32) -> u32 {
+        min + (self.next_u64() % (max - min + 1) as u64) as u32
+    }
+
+    /// True with the given probability
+    fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+}
+
+impl AsciiConfig {
+    /// Generates a random, valid configuration from a seed
+    ///
+    /// Parameters are drawn from sub-ranges of the valid ranges that tend to
+    /// produce usable images (e.g. tau near 1.0), and at least one of
+    /// `draw_edges` / `draw_fill` is always enabled.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed for the generator; the same seed always gives the same config
+    pub fn random(seed: u64) -> Self {
+        let mut rng = SplitMix64(seed);
+
+        let draw_edges = rng.chance(0.8);
+        let draw_fill = !draw_edges || rng.chance(0.8);
+
+        // Bright foreground on a dark background (or the reverse)
+        let bright = [
+            rng.range_u32(128, 255) as u8,
+            rng.range_u32(128, 255) as u8,
+            rng.range_u32(128, 255) as u8,
+        ];
+        let dark = [
+            rng.range_u32(0, 48) as u8,
+            rng.range_u32(0, 48) as u8,
+            rng.range_u32(0, 48) as u8,
+        ];
+        let (ascii_color, bg_color) = if rng.chance(0.8) {
+            (bright, dark)
+        } else {
+            (dark, bright)
+        };
+
+        Self {
+            kernel_size: rng.range_u32(1, 6),
+            sigma: rng.range_f32(0.5, 4.0),
+            sigma_scale: rng.range_f32(1.2, 3.0),
+            tau: rng.range_f32(0.9, 1.1),
+            // Log-uniform over the valid threshold range
+            threshold: 0.001 * 100f32.powf(rng.next_f32()),
+            edge_threshold: rng.range_u32(2, 24),
+            edge_junction_margin: rng.chance(0.3).then(|| rng.range_u32(0, 4)),
+            ascii_color,
+            bg_color,
+            tile_statistic: random_tile_statistic(&mut rng),
+            local_contrast_radius: rng.chance(0.3).then(|| rng.range_u32(1, 8)),
+            fill_gamma: rng.range_f32(0.6, 2.0),
+            fill_dither: random_fill_dither(&mut rng),
+            draw_edges,
+            draw_fill,
+            invert_luminance: rng.chance(0.15),
+            // Modes, charsets and effects change the look wholesale; they
+            // keep their defaults so the seed explores one style
+            ..Self::default()
+        }
+    }
+
+    /// Returns a slightly perturbed copy of this configuration
+    ///
+    /// Continuous parameters move by up to `amount` times their valid range and
+    /// are clamped back into it; discrete choices flip with a probability that
+    /// grows with `amount`. Colors are left unchanged.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed for the generator
+    /// * `amount` - Mutation strength in [0.0, 1.0]
+    pub fn mutate(&self, seed: u64, amount: f32) -> Self {
+        let mut rng = SplitMix64(seed);
+        let amount = amount.clamp(0.0, 1.0);
+        let flip = amount * 0.25;
+
+        let mut jitter = |value: f32, min: f32, max: f32| {
+            (value + rng.range_f32(-1.0, 1.0) * amount * (max - min)).clamp(min, max)
+        };
+
+        let mut config = Self {
+            kernel_size: jitter(self.kernel_size as f32, 1.0, 10.0).round() as u32,
+            sigma: jitter(self.sigma, 0.0, 5.0),
+            sigma_scale: jitter(self.sigma_scale, 0.0, 5.0),
+            tau: jitter(self.tau, 0.0, 1.1),
+            threshold: jitter(self.threshold, 0.001, 0.1),
+            edge_threshold: jitter(self.edge_threshold as f32, 0.0, 64.0).round() as u32,
+            fill_gamma: jitter(self.fill_gamma, 0.1, 5.0),
+            ..self.clone()
+        };
+
+        if rng.chance(flip) {
+            config.tile_statistic = random_tile_statistic(&mut rng);
+        }
+        if rng.chance(flip) {
+            config.fill_dither = random_fill_dither(&mut rng);
+        }
+        if rng.chance(flip) {
+            config.edge_junction_margin = match config.edge_junction_margin {
+                Some(_) => None,
+                None => Some(rng.range_u32(0, 4)),
+            };
+        }
+        if rng.chance(flip) {
+            config.local_contrast_radius = match config.local_contrast_radius {
+                Some(_) => None,
+                None => Some(rng.range_u32(1, 8)),
+            };
+        }
+
+        config
+    }
+}
+
+fn random_tile_statistic(rng: &mut SplitMix64) -> TileStatistic {
+    match rng.range_u32(0, 3) {
+        0 => TileStatistic::Mean,
+        1 => TileStatistic::Median,
+        2 => TileStatistic::Max,
+        _ => TileStatistic::Percentile(rng.range_u32(50, 100) as u8),
+    }
+}
+
+fn random_fill_dither(rng: &mut SplitMix64) -> FillDither {
+    match rng.range_u32(0, 2) {
+        0 => FillDither::Off,
+        1 => FillDither::Ordered,
+        _ => FillDither::Noise,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_is_valid() {
+        for seed in 0..200 {
+            let config = AsciiConfig::random(seed);
+            assert!(config.validate().is_ok(), "seed {} invalid", seed);
+            assert!(config.draw_edges || config.draw_fill);
+        }
+    }
+
+    #[test]
+    fn test_random_is_deterministic() {
+        let a = AsciiConfig::random(42);
+        let b = AsciiConfig::random(42);
+        assert_eq!(a.sigma, b.sigma);
+        assert_eq!(a.edge_threshold, b.edge_threshold);
+        assert_eq!(a.ascii_color, b.ascii_color);
+    }
+
+    #[test]
+    fn test_mutate_is_valid() {
+        let base = AsciiConfig::default();
+        for seed in 0..200 {
+            let config = base.mutate(seed, 1.0);
+            assert!(config.validate().is_ok(), "seed {} invalid", seed);
+        }
+    }
+
+    #[test]
+    fn test_mutate_zero_amount_keeps_values() {
+        let base = AsciiConfig::random(7);
+        let config = base.mutate(3, 0.0);
+        assert_eq!(config.sigma, base.sigma);
+        assert_eq!(config.kernel_size, base.kernel_size);
+        assert_eq!(config.tile_statistic, base.tile_statistic);
+    }
+}
@@ -0,0 +1,439 @@
+//! Per-region config overrides
+//!
+//! [`process_image_regions`] converts an image with different settings in
+//! different areas: each [`RegionOverride`] pairs a rectangle or mask with the
+//! parameters to change there (by name, as in [`crate::params`]) and optionally
+//! a color mode. A tile belongs to a region when its center lies inside it;
+//! where regions overlap, later ones win parameter by parameter.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::incremental::Rect;
+//! use ascii_rendr::params::ParamValue;
+//! use ascii_rendr::regions::{RegionOverride, process_image_regions};
+//!
+//! let input = image::open("portrait.jpg").unwrap().to_rgba8();
+//! let regions = [
+//!     // More edges on the face, in its own colors
+//!     RegionOverride::rect(Rect::new(120, 60, 160, 200))
+//!         .set("edge_threshold", ParamValue::Int(4))
+//!         .preserve_colors(true),
+//! ];
+//! let output = process_image_regions(&input, &AsciiConfig::default(), false, &regions).unwrap();
+//! output.save("portrait_ascii.png").unwrap();
+//! ```
+
+use crate::braille::process_braille;
+use crate::config::{AsciiConfig, OutputMode};
+use crate::error::AsciiError;
+use crate::incremental::{Rect, compute_region_layers};
+use crate::params::{ParamValue, param_info};
+use crate::processor::{
+    blend_with_base, compute_char_layers, luminance, resize_to_valid_dimensions, working_multiple,
+};
+use image::{GrayImage, RgbaImage, imageops};
+use std::collections::BTreeMap;
+
+/// Area of the input image a region covers
+#[derive(Debug, Clone)]
+pub enum RegionShape {
+    /// Rectangle in input pixel coordinates
+    Rect(Rect),
+    /// Mask sized like the input; values above 127 are inside
+    Mask(GrayImage),
+}
+
+/// Parameters (and color mode) that replace the base settings inside a region
+#[derive(Debug, Clone)]
+pub struct RegionOverride {
+    /// Where the override applies
+    pub shape: RegionShape,
+    /// Parameters to set, by field name, applied in order
+    pub params: Vec<(String, ParamValue)>,
+    /// Color mode inside the region, or None to keep the base one
+    pub preserve_colors: Option<bool>,
+}
+
+impl RegionOverride {
+    /// Override without changes yet for the given shape
+    pub fn new(shape: RegionShape) -> Self {
+        Self {
+            shape,
+            params: Vec::new(),
+            preserve_colors: None,
+        }
+    }
+
+    /// Override for a rectangle
+    pub fn rect(rect: Rect) -> Self {
+        Self::new(RegionShape::Rect(rect))
+    }
+
+    /// Override for a mask sized like the input
+    pub fn mask(mask: GrayImage) -> Self {
+        Self::new(RegionShape::Mask(mask))
+    }
+
+    /// Sets a parameter inside the region
+    pub fn set(mut self, name: &str, value: ParamValue) -> Self {
+        self.params.push((name.to_string(), value));
+        self
+    }
+
+    /// Uses source colors (or the configured colors) inside the region
+    pub fn preserve_colors(mut self, preserve_colors: bool) -> Self {
+        self.preserve_colors = Some(preserve_colors);
+        self
+    }
+
+    /// Whether input pixel (`x`, `y`) lies inside the region
+    fn contains(&self, x: u32, y: u32) -> bool {
+        match &self.shape {
+            RegionShape::Rect(rect) => {
+                (rect.x..rect.x.saturating_add(rect.width)).contains(&x)
+                    && (rect.y..rect.y.saturating_add(rect.height)).contains(&y)
+            }
+            RegionShape::Mask(mask) => {
+                x < mask.width() && y < mask.height() && mask.get_pixel(x, y)[0] > 127
+            }
+        }
+    }
+}
+
+/// Converts an image with per-region overrides of the settings
+///
+/// Tiles outside every region use `config` and `preserve_colors`. For each
+/// distinct combination of overlapping regions, edges are computed only over
+/// the tiles it covers (plus the blur margin), so small regions cost little.
+///
+/// # Arguments
+/// * `input` - The input RGBA image to convert
+/// * `config` - Settings outside the regions
+/// * `preserve_colors` - Color mode outside the regions
+/// * `regions` - Overrides, later ones taking precedence where they overlap
+///
+/// # Returns
+/// The rendered image: tiles drawn with different settings cannot share one
+/// [`AsciiArt`](crate::AsciiArt), which carries a single config.
+///
+/// # Errors
+/// Returns [`AsciiError::InvalidConfig`] if `config` is invalid, or an
+/// override gives an invalid value or changes `tile_size` or `tile_height`,
+/// and [`AsciiError::ParseError`] if an override names an unknown parameter.
+/// Override errors are prefixed with the region index.
+///
+/// # Note
+/// If the input image dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering; regions stay in input coordinates.
+pub fn process_image_regions(
+    input: &RgbaImage,
+    config: &AsciiConfig,
+    preserve_colors: bool,
+    regions: &[RegionOverride],
+) -> Result<RgbaImage, AsciiError> {
+    config.validate()?;
+
+    // Automatically resize if dimensions are not multiples of the tile size;
+    // regions are grouped by ASCII tiles even when the base is braille, so the
+    // braille multiple only widens it when the base or a region uses braille
+    let (tile_w, tile_h) = config.tile_dimensions();
+    let multiple = regions
+        .iter()
+        .map(|region| {
+            let mut region_config = config.clone();
+            for (name, value) in &region.params {
+                // Bad overrides are reported once the regions are grouped
+                let _ = region_config.set_param(name, value.clone());
+            }
+            working_multiple(&region_config)
+        })
+        .chain([working_multiple(config)])
+        .fold((tile_w, tile_h), |(w, h), (mw, mh)| {
+            (lcm(w, mw), lcm(h, mh))
+        });
+    let working_image = resize_to_valid_dimensions(input, multiple);
+    let (width, height) = working_image.dimensions();
+
+    // Steps 1-7 with the base settings
+    let lum = luminance(&working_image, config);
+    let mut output = if config.output_mode == OutputMode::Braille {
+        process_braille(&lum, config, preserve_colors.then_some(&working_image))
+    } else {
+        let layers = compute_char_layers(&working_image, config);
+        let chars = layers.composite(config);
+        layers.render(&chars, config, preserve_colors.then_some(&working_image))
+    };
+    blend_with_base(&mut output, &working_image, config);
+
+    // Group tiles by the regions containing their centers
+    let (scale_x, scale_y) = (
+        input.width() as f32 / width.max(1) as f32,
+        input.height() as f32 / height.max(1) as f32,
+    );
+    let mut groups: BTreeMap<Vec<usize>, Vec<(u32, u32)>> = BTreeMap::new();
+    for tile_y in 0..height / tile_h {
+        for tile_x in 0..width / tile_w {
+            let x = ((tile_x * tile_w + tile_w / 2) as f32 * scale_x) as u32;
+            let y = ((tile_y * tile_h + tile_h / 2) as f32 * scale_y) as u32;
+            let covering: Vec<usize> = (0..regions.len())
+                .filter(|&i| regions[i].contains(x, y))
+                .collect();
+            if !covering.is_empty() {
+                groups.entry(covering).or_default().push((tile_x, tile_y));
+            }
+        }
+    }
+    if groups.is_empty() {
+        return Ok(output);
+    }
+
+    for (covering, tiles) in groups {
+        let mut region_config = config.clone();
+        let mut region_preserve = preserve_colors;
+        for &i in &covering {
+            for (name, value) in &regions[i].params {
+                let info = param_info(name).ok_or_else(|| {
+                    AsciiError::ParseError(format!("region {}: unknown parameter `{}`", i, name))
+                })?;
+                region_config
+                    .set_param(info.name, value.clone())
+                    .map_err(|e| {
+                        AsciiError::invalid_config(info.name, format!("region {}: {}", i, e))
+                    })?;
+            }
+            region_preserve = regions[i].preserve_colors.unwrap_or(region_preserve);
+        }
+        let last = covering[covering.len() - 1];
+        region_config
+            .validate()
+            .map_err(|e| e.context(format_args!("region {}", last)))?;
+        if region_config.tile_dimensions() != (tile_w, tile_h) {
+            return Err(AsciiError::invalid_config(
+                "tile_size",
+                format!(
+                    "region {}: tile_size and tile_height cannot differ from the base config",
+                    last
+                ),
+            ));
+        }
+
+        // Steps 2-7 over the bounding box of the group's tiles; `rgb_split`
+        // shifts glyphs across tiles and braille mode does not use them, so
+        // both need the whole image
+        let braille = region_config.output_mode == OutputMode::Braille;
+        let full_image = region_config.rgb_split.is_some() || braille;
+        let bounds = if full_image {
+            Rect::new(0, 0, width, height)
+        } else {
+            tile_bounds(&tiles, (tile_w, tile_h))
+        };
+        let base = imageops::crop_imm(
+            &*working_image,
+            bounds.x,
+            bounds.y,
+            bounds.width,
+            bounds.height,
+        )
+        .to_image();
+        let source = region_preserve.then_some(&base);
+        let mut patch = if braille {
+            process_braille(&lum, &region_config, source)
+        } else {
+            let region_layers = if full_image {
+                compute_char_layers(&working_image, &region_config)
+            } else {
+                compute_region_layers(&lum, &region_config, bounds)
+            };
+            let chars = region_layers.composite(&region_config);
+            region_layers.render(&chars, &region_config, source)
+        };
+        blend_with_base(&mut patch, &base, &region_config);
+
+        for (tile_x, tile_y) in tiles {
+            let (x, y) = (tile_x * tile_w, tile_y * tile_h);
+            let tile = imageops::crop_imm(&patch, x - bounds.x, y - bounds.y, tile_w, tile_h);
+            imageops::replace(&mut output, &tile.to_image(), x as i64, y as i64);
+        }
+    }
+
+    Ok(output)
+}
+
+/// Least common multiple of two positive sizes
+fn lcm(a: u32, b: u32) -> u32 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x.max(1) * b
+}
+
+/// Pixel rectangle spanning the given tiles
+fn tile_bounds(tiles: &[(u32, u32)], tile_size: (u32, u32)) -> Rect {
+    let min_x = tiles.iter().map(|t| t.0).min().unwrap_or(0);
+    let min_y = tiles.iter().map(|t| t.1).min().unwrap_or(0);
+    let max_x = tiles.iter().map(|t| t.0).max().unwrap_or(0);
+    let max_y = tiles.iter().map(|t| t.1).max().unwrap_or(0);
+    Rect::new(
+        min_x * tile_size.0,
+        min_y * tile_size.1,
+        (max_x - min_x + 1) * tile_size.0,
+        (max_y - min_y + 1) * tile_size.1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{process_image, process_image_preserve_colors};
+    use image::Rgba;
+
+    fn scene() -> RgbaImage {
+        RgbaImage::from_fn(80, 64, |x, y| {
+            let v = if (x / 12 + y / 20) % 2 == 0 { 210 } else { 40 };
+            Rgba([v, (x * 3) as u8, (y * 4) as u8, 255])
+        })
+    }
+
+    /// Whether the 8×8 tile at (`tile_x`, `tile_y`) is identical in both images
+    fn same_tile(a: &RgbaImage, b: &RgbaImage, tile_x: u32, tile_y: u32) -> bool {
+        (0..8).all(|y| {
+            (0..8).all(|x| {
+                a.get_pixel(tile_x * 8 + x, tile_y * 8 + y)
+                    == b.get_pixel(tile_x * 8 + x, tile_y * 8 + y)
+            })
+        })
+    }
+
+    #[test]
+    fn test_regions_select_settings_per_tile() {
+        let config = AsciiConfig::default();
+        let region = RegionOverride::rect(Rect::new(0, 0, 40, 64))
+            .set("draw_fill", ParamValue::Bool(false))
+            .set("sigma", ParamValue::Float(1.0))
+            .preserve_colors(true);
+        let output = process_image_regions(&scene(), &config, false, &[region]).unwrap();
+
+        let overridden = process_image_preserve_colors(
+            &scene(),
+            &AsciiConfig {
+                draw_fill: false,
+                sigma: 1.0,
+                ..config.clone()
+            },
+        )
+        .render_image();
+        let base = process_image(&scene(), &config).render_image();
+        for tile_y in 0..8 {
+            for tile_x in 0..10 {
+                let expected = if tile_x < 5 { &overridden } else { &base };
+                assert!(same_tile(&output, expected, tile_x, tile_y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlapping_regions_and_masks() {
+        let config = AsciiConfig::default();
+        let mask = GrayImage::from_pixel(80, 64, image::Luma([255]));
+        let regions = [
+            RegionOverride::mask(mask).set("edge_threshold", ParamValue::Int(2)),
+            RegionOverride::rect(Rect::new(0, 0, 80, 64))
+                .set("edge_threshold", ParamValue::Int(30)),
+        ];
+        let output = process_image_regions(&scene(), &config, false, &regions).unwrap();
+        let expected = process_image(
+            &scene(),
+            &AsciiConfig {
+                edge_threshold: 30,
+                ..config.clone()
+            },
+        )
+        .render_image();
+        assert_eq!(output, expected);
+
+        // No regions is a plain conversion
+        assert_eq!(
+            process_image_regions(&scene(), &config, false, &[]).unwrap(),
+            process_image(&scene(), &config).render_image()
+        );
+    }
+
+    #[test]
+    fn test_regions_with_saliency() {
+        // Saliency is estimated on the whole image, not the region
+        let config = AsciiConfig::default();
+        let region = RegionOverride::rect(Rect::new(0, 0, 80, 32))
+            .set("saliency_strength", ParamValue::Float(0.8));
+        let output = process_image_regions(&scene(), &config, false, &[region]).unwrap();
+        let expected = process_image(
+            &scene(),
+            &AsciiConfig {
+                saliency_strength: 0.8,
+                ..config.clone()
+            },
+        )
+        .render_image();
+        for tile_x in 0..10 {
+            for tile_y in 0..4 {
+                assert!(same_tile(&output, &expected, tile_x, tile_y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_working_size_widens_only_for_braille() {
+        let config = AsciiConfig {
+            tile_size: 4,
+            ..AsciiConfig::default()
+        };
+        let input = RgbaImage::from_fn(84, 60, |x, y| scene().get_pixel(x % 80, y % 64).to_owned());
+        let region = RegionOverride::rect(Rect::new(0, 0, 40, 60));
+
+        // 4×4 tiles fit the input as is
+        let ascii = region.clone().set("sigma", ParamValue::Float(1.0));
+        let output = process_image_regions(&input, &config, false, &[ascii]).unwrap();
+        assert_eq!(output.dimensions(), (84, 60));
+
+        // Braille cells need multiples of 8
+        let braille = region.set("output_mode", ParamValue::Choice("braille".into()));
+        let output = process_image_regions(&input, &config, false, &[braille]).unwrap();
+        assert_eq!(output.dimensions(), (80, 56));
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        let config = AsciiConfig::default();
+        let unknown = RegionOverride::rect(Rect::new(0, 0, 8, 8)).set("nope", ParamValue::Int(1));
+        let err = process_image_regions(&scene(), &config, false, &[unknown]).unwrap_err();
+        assert!(matches!(err, AsciiError::ParseError(_)));
+
+        let out_of_range =
+            RegionOverride::rect(Rect::new(0, 0, 8, 8)).set("kernel_size", ParamValue::Int(99));
+        let err = process_image_regions(&scene(), &config, false, &[out_of_range]).unwrap_err();
+        assert!(matches!(
+            err,
+            AsciiError::InvalidConfig {
+                field: "kernel_size",
+                ..
+            }
+        ));
+        assert!(err.to_string().starts_with("region 0"));
+
+        let taller = RegionOverride::rect(Rect::new(0, 0, 8, 8))
+            .set("tile_size", ParamValue::Choice("16".into()));
+        let err = process_image_regions(&scene(), &config, false, &[taller]).unwrap_err();
+        assert!(matches!(
+            err,
+            AsciiError::InvalidConfig {
+                field: "tile_size",
+                ..
+            }
+        ));
+
+        // Regions that cover no tile center are never applied
+        let missed = RegionOverride::rect(Rect::new(0, 0, 2, 2)).set("nope", ParamValue::Int(1));
+        assert!(process_image_regions(&scene(), &config, false, &[missed]).is_ok());
+    }
+}
@@ -0,0 +1,316 @@
+//! Classic ANSI art files (`.ans`)
+//!
+//! [`render_ans`] writes the character grid of a [`ProcessOutput`] the way
+//! BBS-era art was stored: CP437 bytes, SGR escape sequences restricted to the
+//! 16 CGA colors, and a SAUCE record with the canvas width so editors such as
+//! PabloDraw and viewers like ansilove lay it out correctly. Characters without
+//! a CP437 equivalent are replaced: braille patterns by the shade block of
+//! matching density, anything else by `?`.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::processor::process_image_output;
+//! use ascii_rendr::render::ans::{AnsOptions, save_ans};
+//!
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! let output = process_image_output(&input, &AsciiConfig::default(), true);
+//! let options = AnsOptions {
+//!     title: "Photo".to_string(),
+//!     ..AnsOptions::default()
+//! };
+//! save_ans(&output, &options, "photo.ans").unwrap();
+//! ```
+
+use crate::output::ProcessOutput;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The 16 CGA colors in ANSI order (black, red, green, brown, blue, magenta,
+/// cyan, light gray, then their bright variants)
+pub const CGA_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [170, 0, 0],
+    [0, 170, 0],
+    [170, 85, 0],
+    [0, 0, 170],
+    [170, 0, 170],
+    [0, 170, 170],
+    [170, 170, 170],
+    [85, 85, 85],
+    [255, 85, 85],
+    [85, 255, 85],
+    [255, 255, 85],
+    [85, 85, 255],
+    [255, 85, 255],
+    [85, 255, 255],
+    [255, 255, 255],
+];
+
+/// Metadata and color options for `.ans` output
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AnsOptions {
+    /// SAUCE title, up to 35 characters
+    pub title: String,
+    /// SAUCE author, up to 20 characters
+    pub author: String,
+    /// SAUCE group, up to 20 characters
+    pub group: String,
+    /// Allow bright background colors ("iCE colors") instead of blinking text;
+    /// without it backgrounds are limited to the first 8 colors
+    pub ice_colors: bool,
+}
+
+/// Writes the character grid of `art` as an ANSI art file with a SAUCE record
+///
+/// Foreground colors are quantized to the 16 CGA colors, the background color
+/// to the first 8 (or all 16 with `ice_colors`). Rows are separated by CR LF.
+pub fn render_ans(art: &ProcessOutput, options: &AnsOptions) -> Vec<u8> {
+    grid_to_ans(
+        art.columns,
+        art.rows,
+        &art.chars,
+        &art.colors,
+        art.bg_color,
+        options,
+    )
+}
+
+/// Writes a character grid with one foreground color per character as an
+/// ANSI art file; see [`render_ans`]
+///
+/// # Arguments
+/// * `columns` - Characters per row
+/// * `rows` - Number of rows, recorded in the SAUCE record
+/// * `chars` - Characters, row-major
+/// * `colors` - Foreground color per character, row-major
+/// * `background` - Background color of every cell
+/// * `options` - SAUCE metadata and iCE colors
+pub fn grid_to_ans(
+    columns: u32,
+    rows: u32,
+    chars: &[char],
+    colors: &[[u8; 3]],
+    background: [u8; 3],
+    options: &AnsOptions,
+) -> Vec<u8> {
+    assert_eq!(chars.len(), colors.len());
+    let width = columns.max(1) as usize;
+    let background = nearest_cga(background, if options.ice_colors { 16 } else { 8 });
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"\x1b[0m");
+    let mut current = None;
+    for (row, (line, colors)) in chars.chunks(width).zip(colors.chunks(width)).enumerate() {
+        if row > 0 {
+            out.extend_from_slice(b"\r\n");
+        }
+        for (&ch, &color) in line.iter().zip(colors) {
+            let foreground = nearest_cga(color, 16);
+            // Only emit attributes when the color changes
+            if current != Some(foreground) {
+                out.extend_from_slice(sgr(foreground, background).as_bytes());
+                current = Some(foreground);
+            }
+            out.push(to_cp437(ch));
+        }
+    }
+    out.extend_from_slice(b"\x1b[0m");
+
+    let file_size = out.len() as u32;
+    // End-of-file marker, then the SAUCE record
+    out.push(0x1a);
+    out.extend_from_slice(&sauce(
+        options,
+        columns,
+        rows,
+        file_size,
+        &sauce_date(SystemTime::now()),
+    ));
+    out
+}
+
+/// Writes [`render_ans`] output to `path`
+pub fn save_ans(
+    art: &ProcessOutput,
+    options: &AnsOptions,
+    path: impl AsRef<Path>,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    std::fs::write(path, render_ans(art, options)).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Index of the closest of the first `count` CGA colors
+pub fn nearest_cga(color: [u8; 3], count: usize) -> u8 {
+    let distance = |c: &[u8; 3]| -> u32 {
+        (0..3)
+            .map(|i| (color[i] as i32 - c[i] as i32).pow(2) as u32)
+            .sum()
+    };
+    CGA_PALETTE[..count.min(16)]
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, c)| distance(c))
+        .map_or(0, |(i, _)| i as u8)
+}
+
+/// SGR sequence setting a CGA foreground and background color
+///
+/// Bright foregrounds use bold, bright backgrounds blink (shown as bright
+/// backgrounds by viewers honoring iCE colors).
+fn sgr(foreground: u8, background: u8) -> String {
+    let mut codes = vec!["0".to_string()];
+    if foreground >= 8 {
+        codes.push("1".to_string());
+    }
+    if background >= 8 {
+        codes.push("5".to_string());
+    }
+    codes.push((30 + foreground % 8).to_string());
+    codes.push((40 + background % 8).to_string());
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// CP437 byte for a character
+fn to_cp437(ch: char) -> u8 {
+    match ch {
+        ' '..='~' => ch as u8,
+        '░' => 0xb0,
+        '▒' => 0xb1,
+        '▓' => 0xb2,
+        '█' => 0xdb,
+        '▄' => 0xdc,
+        '▌' => 0xdd,
+        '▐' => 0xde,
+        '▀' => 0xdf,
+        '■' => 0xfe,
+        // Box-drawing edge characters; CP437 has no heavy lines or diagonals
+        '│' | '┃' => 0xb3,
+        '─' | '━' => 0xc4,
+        '╱' => b'/',
+        '╲' => b'\\',
+        '·' => 0xfa,
+        '\u{2800}'..='\u{28ff}' => {
+            // Braille: shade by the number of dots
+            match (ch as u32 - 0x2800).count_ones() {
+                0 => b' ',
+                1..=2 => 0xb0,
+                3..=5 => 0xb1,
+                6..=7 => 0xb2,
+                _ => 0xdb,
+            }
+        }
+        _ => b'?',
+    }
+}
+
+/// 128-byte SAUCE 00 record for an ANSi character file
+fn sauce(options: &AnsOptions, columns: u32, rows: u32, file_size: u32, date: &[u8; 8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(128);
+    record.extend_from_slice(b"SAUCE00");
+    push_field(&mut record, &options.title, 35);
+    push_field(&mut record, &options.author, 20);
+    push_field(&mut record, &options.group, 20);
+    record.extend_from_slice(date);
+    record.extend_from_slice(&file_size.to_le_bytes());
+    // Data type: character, file type: ANSi
+    record.push(1);
+    record.push(1);
+    record.extend_from_slice(&(columns.min(u16::MAX as u32) as u16).to_le_bytes());
+    record.extend_from_slice(&(rows.min(u16::MAX as u32) as u16).to_le_bytes());
+    record.extend_from_slice(&[0; 4]);
+    // No comments
+    record.push(0);
+    // Flags: iCE colors (bit 0), 8-pixel letter spacing (bits 1-2 = 01)
+    record.push(options.ice_colors as u8 | 0b010);
+    let mut font = b"IBM VGA".to_vec();
+    font.resize(22, 0);
+    record.extend_from_slice(&font);
+    debug_assert_eq!(record.len(), 128);
+    record
+}
+
+/// Appends `text` as a space-padded CP437 field of `len` bytes
+fn push_field(record: &mut Vec<u8>, text: &str, len: usize) {
+    let mut field: Vec<u8> = text.chars().map(to_cp437).take(len).collect();
+    field.resize(len, b' ');
+    record.extend_from_slice(&field);
+}
+
+/// `CCYYMMDD` of a point in time (UTC)
+fn sauce_date(time: SystemTime) -> [u8; 8] {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400) as i64;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    let text = format!("{:04}{:02}{:02}", year, month, day);
+    let mut date = [b'0'; 8];
+    date.copy_from_slice(&text.as_bytes()[..8]);
+    date
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+    use std::time::Duration;
+
+    fn sample() -> ProcessOutput {
+        ProcessOutput {
+            image: RgbaImage::new(24, 16),
+            columns: 3,
+            rows: 2,
+            chars: vec!['|', '-', '\u{28ff}', '.', '.', 'é'],
+            edge_chars: vec![' '; 6],
+            fill_chars: vec![' '; 6],
+            colors: vec![
+                [250, 80, 80],
+                [250, 80, 80],
+                [0, 160, 0],
+                [1, 2, 3],
+                [1, 2, 3],
+                [1, 2, 3],
+            ],
+            bg_color: [0, 0, 160],
+        }
+    }
+
+    #[test]
+    fn test_render_ans() {
+        let data = render_ans(&sample(), &AnsOptions::default());
+        let (body, record) = data.split_at(data.len() - 128);
+
+        // Bright red on blue, green, then black
+        let expected: &[u8] =
+            b"\x1b[0m\x1b[0;1;31;44m|-\x1b[0;32;44m\xdb\r\n\x1b[0;30;44m..?\x1b[0m\x1a";
+        assert_eq!(body, expected);
+
+        assert!(record.starts_with(b"SAUCE00"));
+        let file_size = u32::from_le_bytes(record[90..94].try_into().unwrap());
+        assert_eq!(file_size as usize, body.len() - 1);
+        // Character / ANSi, 3 columns by 2 rows
+        assert_eq!(&record[94..100], &[1, 1, 3, 0, 2, 0]);
+        assert_eq!(&record[106..113], b"IBM VGA");
+    }
+
+    #[test]
+    fn test_colors_and_date() {
+        assert_eq!(nearest_cga([255, 255, 255], 16), 15);
+        assert_eq!(nearest_cga([255, 255, 255], 8), 7);
+        assert_eq!(nearest_cga([160, 90, 10], 16), 3);
+        assert_eq!(sgr(9, 12), "\x1b[0;1;5;31;44m");
+
+        assert_eq!(&sauce_date(UNIX_EPOCH), b"19700101");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(951_782_400);
+        assert_eq!(&sauce_date(leap_day), b"20000229");
+    }
+}
@@ -0,0 +1,230 @@
+//! 24-bit ANSI terminal output
+//!
+//! [`render_ansi`] converts an image to text colored with truecolor escape
+//! sequences (`ESC[38;2;r;g;bm`), one character per tile, ready to print to a
+//! terminal. Glyphs are not rasterized, so this is cheaper than building a
+//! [`ProcessOutput`](crate::output::ProcessOutput) when only text is needed.
+//!
+//! [`render_half_blocks`] is the non-ASCII counterpart for photo-like
+//! previews: every character cell is an upper half block (`▀`) whose
+//! foreground and background colors are two vertically stacked pixels.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::render::ansi::{AnsiOptions, render_ansi};
+//!
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! let options = AnsiOptions { background: false };
+//! println!("{}", render_ansi(&input, &AsciiConfig::default(), true, &options));
+//! ```
+
+use crate::config::AsciiConfig;
+use crate::processor::{process_image, process_image_preserve_colors};
+use image::RgbaImage;
+use std::fmt::Write;
+
+/// Options for ANSI output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnsiOptions {
+    /// Paint every cell with the configured background color; otherwise the
+    /// terminal's own background shows through
+    pub background: bool,
+}
+
+impl Default for AnsiOptions {
+    fn default() -> Self {
+        Self { background: true }
+    }
+}
+
+/// Converts an image to ANSI-colored text
+///
+/// # Arguments
+/// * `input` - The input RGBA image to convert
+/// * `config` - Configuration parameters for the ASCII conversion
+/// * `preserve_colors` - Color each character with its tile's average source
+///   color instead of `ascii_color`
+/// * `options` - Output options
+///
+/// # Returns
+/// One line per tile row, each ending with a color reset
+///
+/// # Note
+/// If the input image dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering.
+pub fn render_ansi(
+    input: &RgbaImage,
+    config: &AsciiConfig,
+    preserve_colors: bool,
+    options: &AnsiOptions,
+) -> String {
+    // Steps 1-6 only; glyphs are never rasterized
+    let art = if preserve_colors {
+        process_image_preserve_colors(input, config)
+    } else {
+        process_image(input, config)
+    };
+    art.render_ansi(options)
+}
+
+/// Writes a character grid with one foreground color per character
+///
+/// Color codes are only emitted where the color changes within a line.
+///
+/// # Arguments
+/// * `columns` - Characters per row
+/// * `chars` - Characters, row-major
+/// * `colors` - Foreground color per character, row-major
+/// * `background` - Background color for every cell, or None for the terminal's
+pub fn grid_to_ansi(
+    columns: u32,
+    chars: &[char],
+    colors: &[[u8; 3]],
+    background: Option<[u8; 3]>,
+) -> String {
+    assert_eq!(chars.len(), colors.len());
+    let mut out = String::new();
+
+    for (row, (line, line_colors)) in chars
+        .chunks(columns.max(1) as usize)
+        .zip(colors.chunks(columns.max(1) as usize))
+        .enumerate()
+    {
+        if row > 0 {
+            out.push('\n');
+        }
+        if let Some([r, g, b]) = background {
+            let _ = write!(out, "\x1b[48;2;{};{};{}m", r, g, b);
+        }
+        let mut current = None;
+        for (&ch, &color) in line.iter().zip(line_colors) {
+            // Only emit a color change when the color differs from the previous cell
+            if current != Some(color) {
+                let [r, g, b] = color;
+                let _ = write!(out, "\x1b[38;2;{};{};{}m", r, g, b);
+                current = Some(color);
+            }
+            out.push(ch);
+        }
+        out.push_str("\x1b[0m");
+    }
+
+    out
+}
+
+/// Draws an image with half-block characters, two pixels per cell
+///
+/// Each cell is `▀` with the upper pixel as foreground and the lower one as
+/// background color, so the text has `width` columns and `height / 2` rows
+/// (rounded up; a last odd row leaves the terminal background below). Since
+/// terminal cells are about twice as tall as wide, the pixels come out roughly
+/// square: scale the image to the terminal's columns × 2·rows first. Alpha is
+/// ignored.
+pub fn render_half_blocks(input: &RgbaImage) -> String {
+    let (width, height) = input.dimensions();
+    let mut out = String::new();
+
+    for row in 0..height.div_ceil(2) {
+        if row > 0 {
+            out.push('\n');
+        }
+        let (mut fg, mut bg) = (None, None);
+        for x in 0..width {
+            let top = input.get_pixel(x, row * 2);
+            let top = [top[0], top[1], top[2]];
+            if fg != Some(top) {
+                let [r, g, b] = top;
+                let _ = write!(out, "\x1b[38;2;{};{};{}m", r, g, b);
+                fg = Some(top);
+            }
+            if row * 2 + 1 < height {
+                let bottom = input.get_pixel(x, row * 2 + 1);
+                let bottom = [bottom[0], bottom[1], bottom[2]];
+                if bg != Some(bottom) {
+                    let [r, g, b] = bottom;
+                    let _ = write!(out, "\x1b[48;2;{};{};{}m", r, g, b);
+                    bg = Some(bottom);
+                }
+            }
+            out.push('▀');
+        }
+        out.push_str("\x1b[0m");
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OutputMode;
+    use crate::process_image_output;
+    use image::Rgba;
+
+    #[test]
+    fn test_grid_to_ansi() {
+        let chars = ['|', '-', ' ', '.', '.', '@'];
+        let colors = [
+            [9, 0, 0],
+            [9, 0, 0],
+            [0, 9, 0],
+            [1, 2, 3],
+            [1, 2, 3],
+            [1, 2, 3],
+        ];
+
+        let ansi = grid_to_ansi(3, &chars, &colors, Some([0, 0, 0]));
+        assert_eq!(ansi.lines().count(), 2);
+        assert_eq!(ansi.matches("\x1b[48;2;0;0;0m").count(), 2);
+        assert_eq!(ansi.matches("\x1b[38;2;9;0;0m").count(), 1);
+        assert!(ansi.ends_with("..@\x1b[0m"));
+
+        let plain = grid_to_ansi(3, &chars, &colors, None);
+        assert!(!plain.contains("\x1b[48;"));
+        assert!(plain.starts_with("\x1b[38;2;9;0;0m|-"));
+    }
+
+    #[test]
+    fn test_render_ansi_matches_output() {
+        let input =
+            RgbaImage::from_fn(48, 32, |x, y| Rgba([(x * 5) as u8, (y * 7) as u8, 50, 255]));
+        let config = AsciiConfig::default();
+        for preserve_colors in [false, true] {
+            let output = process_image_output(&input, &config, preserve_colors);
+            assert_eq!(
+                render_ansi(&input, &config, preserve_colors, &AnsiOptions::default()),
+                output.to_ansi()
+            );
+        }
+
+        let braille = AsciiConfig {
+            output_mode: OutputMode::Braille,
+            ..config
+        };
+        assert_eq!(
+            render_ansi(&input, &braille, true, &AnsiOptions::default()),
+            process_image_output(&input, &braille, true).to_ansi()
+        );
+    }
+
+    #[test]
+    fn test_render_half_blocks() {
+        let image = RgbaImage::from_fn(2, 3, |x, y| match (x, y) {
+            (_, 0) => Rgba([255, 0, 0, 255]),
+            (0, 1) => Rgba([0, 0, 255, 255]),
+            (1, 1) => Rgba([0, 255, 0, 255]),
+            _ => Rgba([9, 9, 9, 255]),
+        });
+        let text = render_half_blocks(&image);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        // Red over blue, then red (unchanged) over green
+        assert_eq!(
+            lines[0],
+            "\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m▀\x1b[48;2;0;255;0m▀\x1b[0m"
+        );
+        // Odd last row: foreground only
+        assert_eq!(lines[1], "\x1b[38;2;9;9;9m▀▀\x1b[0m");
+    }
+}
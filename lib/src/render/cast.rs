@@ -0,0 +1,229 @@
+//! Asciinema recordings (`.cast`)
+//!
+//! [`CastWriter`] turns a sequence of converted frames into an asciinema v2
+//! recording: a JSON header line with the terminal size, then one timed
+//! output event per frame that redraws the screen with 24-bit ANSI text. The
+//! result plays back with `asciinema play` or embeds on a web page with the
+//! asciinema player, at a fraction of the size of a video.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::frames::process_frames;
+//! use ascii_rendr::render::cast::{CastOptions, save_cast};
+//!
+//! let frames = (1..=24).map(|i| image::open(format!("frame{}.png", i)).unwrap().to_rgba8());
+//! let art = process_frames(frames, &AsciiConfig::default(), true);
+//! save_cast("clip.cast", art, 12.0, &CastOptions::default()).unwrap();
+//! ```
+
+use crate::art::AsciiArt;
+use crate::render::ansi::AnsiOptions;
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Options for `.cast` output
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CastOptions {
+    /// Recording title shown by players; left out when empty
+    pub title: String,
+    /// Colors of the frames
+    pub ansi: AnsiOptions,
+}
+
+/// Writes frames as an asciinema v2 recording
+///
+/// The header is written with the first frame, whose grid size becomes the
+/// terminal size of the recording; later frames should keep that size.
+pub struct CastWriter<W: Write> {
+    writer: W,
+    options: CastOptions,
+    /// Time of the last event, in seconds
+    last_time: Option<f64>,
+}
+
+impl<W: Write> CastWriter<W> {
+    /// Creates a writer; nothing is written before the first frame
+    pub fn new(writer: W, options: CastOptions) -> Self {
+        Self {
+            writer,
+            options,
+            last_time: None,
+        }
+    }
+
+    /// Writes a frame shown `time` seconds after the start of the recording
+    ///
+    /// # Errors
+    /// If `time` is negative, not finite or earlier than the previous frame,
+    /// or writing fails.
+    pub fn write_frame(&mut self, art: &AsciiArt, time: f64) -> Result<(), String> {
+        if !time.is_finite() || time < self.last_time.unwrap_or(0.0) {
+            return Err(format!(
+                "frame time {} must not go back in time or be negative",
+                time
+            ));
+        }
+
+        // Clear the screen and hide the cursor once, then redraw in place
+        let mut output = if self.last_time.is_none() {
+            self.write_header(art.columns(), art.rows())?;
+            "\x1b[?25l\x1b[2J\x1b[H".to_string()
+        } else {
+            "\x1b[H".to_string()
+        };
+        // The recording holds raw terminal output, where a line break is CR LF
+        output.push_str(&art.render_ansi(&self.options.ansi).replace('\n', "\r\n"));
+
+        self.last_time = Some(time);
+        self.write_line(&json!([round_time(time), "o", output]))
+    }
+
+    /// Restores the cursor at `time` and flushes, returning the inner writer
+    ///
+    /// Without a call the recording still plays, but ends with the cursor
+    /// hidden. `time` of `None` uses the time of the last frame.
+    pub fn finish(mut self, time: Option<f64>) -> Result<W, String> {
+        if let Some(last_time) = self.last_time {
+            let time = time.unwrap_or(last_time).max(last_time);
+            self.write_line(&json!([round_time(time), "o", "\x1b[?25h\r\n"]))?;
+        }
+        self.writer.flush().map_err(|e| e.to_string())?;
+        Ok(self.writer)
+    }
+
+    fn write_header(&mut self, columns: u32, rows: u32) -> Result<(), String> {
+        let mut header = json!({
+            "version": 2,
+            "width": columns,
+            "height": rows,
+            "env": { "TERM": "xterm-256color" },
+        });
+        if !self.options.title.is_empty() {
+            header["title"] = json!(self.options.title);
+        }
+        self.write_line(&header)
+    }
+
+    fn write_line(&mut self, value: &serde_json::Value) -> Result<(), String> {
+        writeln!(self.writer, "{}", value).map_err(|e| e.to_string())
+    }
+}
+
+/// Event times in microseconds are plenty for terminal playback
+fn round_time(time: f64) -> f64 {
+    (time * 1e6).round() / 1e6
+}
+
+/// Writes frames played at `fps` as an asciinema recording to `path`
+///
+/// The recording lasts until the last frame has been shown for one frame
+/// time.
+///
+/// # Errors
+/// If `fps` is not positive, there are no frames, or writing fails.
+pub fn save_cast(
+    path: impl AsRef<Path>,
+    frames: impl IntoIterator<Item = AsciiArt>,
+    fps: f32,
+    options: &CastOptions,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    let error = |e: String| format!("{}: {}", path.display(), e);
+    if !(fps.is_finite() && fps > 0.0) {
+        return Err(error(format!("frame rate must be positive, got {}", fps)));
+    }
+    let mut frames = frames.into_iter().peekable();
+    if frames.peek().is_none() {
+        return Err(error("no frames to write".to_string()));
+    }
+    let file = File::create(path).map_err(|e| error(e.to_string()))?;
+
+    let mut writer = CastWriter::new(BufWriter::new(file), options.clone());
+    let mut count = 0;
+    for art in frames {
+        writer
+            .write_frame(&art, count as f64 / fps as f64)
+            .map_err(error)?;
+        count += 1;
+    }
+    writer
+        .finish(Some(count as f64 / fps as f64))
+        .map(drop)
+        .map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AsciiConfig;
+    use crate::processor::process_image_preserve_colors;
+    use image::{Rgba, RgbaImage};
+
+    fn frame(shade: u8) -> AsciiArt {
+        let input = RgbaImage::from_fn(32, 16, |x, _| {
+            Rgba([if x < 16 { shade } else { 255 - shade }, 80, 40, 255])
+        });
+        process_image_preserve_colors(&input, &AsciiConfig::default())
+    }
+
+    #[test]
+    fn test_cast_events() {
+        let options = CastOptions {
+            title: "Test".to_string(),
+            ..CastOptions::default()
+        };
+        let mut writer = CastWriter::new(Vec::new(), options);
+        writer.write_frame(&frame(0), 0.0).unwrap();
+        writer.write_frame(&frame(200), 0.5).unwrap();
+        assert!(writer.write_frame(&frame(0), 0.25).is_err());
+        let data = String::from_utf8(writer.finish(None).unwrap()).unwrap();
+
+        let lines: Vec<serde_json::Value> = data
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(
+            (lines[0]["width"].as_u64(), lines[0]["height"].as_u64()),
+            (Some(4), Some(2))
+        );
+        assert_eq!(lines[0]["title"], "Test");
+
+        assert_eq!(lines[2][0], 0.5);
+        assert_eq!(lines[2][1], "o");
+        let expected = frame(200).render_ansi(&AnsiOptions::default());
+        let text = lines[2][2].as_str().unwrap();
+        assert_eq!(text, format!("\x1b[H{}", expected.replace('\n', "\r\n")));
+        assert!(
+            lines[1][2]
+                .as_str()
+                .unwrap()
+                .starts_with("\x1b[?25l\x1b[2J")
+        );
+        assert_eq!(lines[3][0], 0.5);
+    }
+
+    #[test]
+    fn test_save_cast_checks_input() {
+        let path = std::env::temp_dir().join(format!("ascii-rendr-{}.cast", std::process::id()));
+        assert!(save_cast(&path, Vec::new(), 10.0, &CastOptions::default()).is_err());
+        let err = save_cast(&path, vec![frame(0)], 0.0, &CastOptions::default()).unwrap_err();
+        assert!(err.contains("frame rate"));
+
+        save_cast(
+            &path,
+            vec![frame(0), frame(90)],
+            4.0,
+            &CastOptions::default(),
+        )
+        .unwrap();
+        let data = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let last: serde_json::Value = serde_json::from_str(data.lines().last().unwrap()).unwrap();
+        assert_eq!(last[0], 0.5);
+        assert!(!data.lines().next().unwrap().contains("title"));
+    }
+}
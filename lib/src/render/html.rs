@@ -0,0 +1,218 @@
+//! HTML/CSS output
+//!
+//! [`render_html`] writes the character grid of a [`ProcessOutput`] into a
+//! `<pre>` block that can be embedded in a web page. Colors are written either
+//! inline on each `<span>` or as a small CSS class palette in a `<style>` block,
+//! which keeps pages with many repeated colors smaller. When every tile has the
+//! same color (colors not preserved), the color is set once on the `<pre>` and
+//! the text is written without spans.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::processor::{HtmlOptions, HtmlPalette, process_image_output, render_html};
+//!
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! let output = process_image_output(&input, &AsciiConfig::default(), true);
+//! let options = HtmlOptions {
+//!     palette: HtmlPalette::Classes,
+//!     ..HtmlOptions::default()
+//! };
+//! std::fs::write("photo.html", render_html(&output, &options)).unwrap();
+//! ```
+
+use crate::output::ProcessOutput;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// How colors are attached to the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HtmlPalette {
+    /// `style="color:rgb(...)"` on every span
+    #[default]
+    Inline,
+    /// One CSS class per distinct color, defined in a `<style>` block
+    Classes,
+}
+
+/// Options for HTML output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlOptions {
+    /// Inline colors or a class palette
+    pub palette: HtmlPalette,
+    /// Paint the block with the configured background color; otherwise the
+    /// page background shows through
+    pub background: bool,
+    /// Prefix for generated class names (`<prefix>` on the `<pre>`,
+    /// `<prefix>-0`, `<prefix>-1`, … for colors); use distinct prefixes when
+    /// embedding several outputs in one page
+    pub class_prefix: String,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            palette: HtmlPalette::Inline,
+            background: true,
+            class_prefix: "ascii".to_string(),
+        }
+    }
+}
+
+/// Writes the character grid of `art` as an HTML `<pre>` block
+///
+/// Colors are taken from the output as-is, so an output produced with preserved
+/// colors is written with per-tile source colors, and one produced without with
+/// the single configured ASCII color.
+pub fn render_html(art: &ProcessOutput, options: &HtmlOptions) -> String {
+    grid_to_html(
+        art.columns,
+        &art.chars,
+        &art.colors,
+        options.background.then_some(art.bg_color),
+        options,
+    )
+}
+
+/// Writes a character grid with one foreground color per character as HTML
+///
+/// One `<span>` is emitted per run of equal colors within a line. `&`, `<` and
+/// `>` are escaped.
+///
+/// # Arguments
+/// * `columns` - Characters per row
+/// * `chars` - Characters, row-major
+/// * `colors` - Foreground color per character, row-major
+/// * `background` - Background color of the block, or None for the page's
+/// * `options` - Palette style and class prefix (`background` is ignored)
+pub fn grid_to_html(
+    columns: u32,
+    chars: &[char],
+    colors: &[[u8; 3]],
+    background: Option<[u8; 3]>,
+    options: &HtmlOptions,
+) -> String {
+    assert_eq!(chars.len(), colors.len());
+    let solid = colors
+        .first()
+        .filter(|&&first| colors.iter().all(|&c| c == first));
+
+    let mut style = String::new();
+    if let Some([r, g, b]) = background {
+        let _ = write!(style, "background:rgb({},{},{});", r, g, b);
+    }
+    if let Some([r, g, b]) = solid {
+        let _ = write!(style, "color:rgb({},{},{});", r, g, b);
+    }
+    style.push_str("font-family:monospace;line-height:1");
+
+    // Class palette, numbered in order of first appearance
+    let mut classes: HashMap<[u8; 3], usize> = HashMap::new();
+    let mut out = String::new();
+    let use_classes = options.palette == HtmlPalette::Classes && solid.is_none();
+    if use_classes {
+        let prefix = &options.class_prefix;
+        out.push_str("<style>");
+        for &color in colors {
+            if !classes.contains_key(&color) {
+                let index = classes.len();
+                classes.insert(color, index);
+                let [r, g, b] = color;
+                let _ = write!(
+                    out,
+                    ".{p} .{p}-{i}{{color:rgb({r},{g},{b})}}",
+                    p = prefix,
+                    i = index
+                );
+            }
+        }
+        out.push_str("</style>");
+        let _ = write!(out, "<pre class=\"{}\" style=\"{}\">", prefix, style);
+    } else {
+        let _ = write!(out, "<pre style=\"{}\">", style);
+    }
+
+    for (line, line_colors) in chars
+        .chunks(columns.max(1) as usize)
+        .zip(colors.chunks(columns.max(1) as usize))
+    {
+        let mut current = None;
+        for (&ch, &color) in line.iter().zip(line_colors) {
+            if solid.is_none() && current != Some(color) {
+                if current.is_some() {
+                    out.push_str("</span>");
+                }
+                if use_classes {
+                    let _ = write!(
+                        out,
+                        "<span class=\"{}-{}\">",
+                        options.class_prefix, classes[&color]
+                    );
+                } else {
+                    let [r, g, b] = color;
+                    let _ = write!(out, "<span style=\"color:rgb({},{},{})\">", r, g, b);
+                }
+                current = Some(color);
+            }
+            match ch {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                ch => out.push(ch),
+            }
+        }
+        if current.is_some() {
+            out.push_str("</span>");
+        }
+        out.push('\n');
+    }
+
+    out.push_str("</pre>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHARS: [char; 6] = ['|', '-', '<', '.', '.', '@'];
+    const COLORS: [[u8; 3]; 6] = [
+        [9, 0, 0],
+        [9, 0, 0],
+        [0, 9, 0],
+        [9, 0, 0],
+        [9, 0, 0],
+        [1, 2, 3],
+    ];
+
+    #[test]
+    fn test_grid_to_html_palettes() {
+        let inline = grid_to_html(3, &CHARS, &COLORS, Some([0, 0, 0]), &HtmlOptions::default());
+        assert!(inline.starts_with("<pre style=\"background:rgb(0,0,0);font-family"));
+        assert!(inline.contains("|-</span><span style=\"color:rgb(0,9,0)\">&lt;</span>"));
+        assert_eq!(inline.matches("<span").count(), 4);
+
+        let options = HtmlOptions {
+            palette: HtmlPalette::Classes,
+            class_prefix: "art".to_string(),
+            ..HtmlOptions::default()
+        };
+        let classes = grid_to_html(3, &CHARS, &COLORS, None, &options);
+        // Three distinct colors, each defined once and reused across lines
+        assert!(classes.starts_with(
+            "<style>.art .art-0{color:rgb(9,0,0)}.art .art-1{color:rgb(0,9,0)}\
+             .art .art-2{color:rgb(1,2,3)}</style><pre class=\"art\""
+        ));
+        assert_eq!(classes.matches("<span class=\"art-0\">").count(), 2);
+        assert!(!classes.contains("background"));
+    }
+
+    #[test]
+    fn test_grid_to_html_solid_color() {
+        let colors = [[7, 7, 7]; 6];
+        let html = grid_to_html(3, &CHARS, &colors, None, &HtmlOptions::default());
+        assert_eq!(
+            html,
+            "<pre style=\"color:rgb(7,7,7);font-family:monospace;line-height:1\">|-&lt;\n..@\n</pre>"
+        );
+    }
+}
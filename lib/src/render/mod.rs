@@ -0,0 +1,13 @@
+//! Renderers that emit the character grid as text instead of pixels
+//!
+//! The image renderer in [`crate::ascii`] rasterizes 8×8 glyphs; the modules
+//! here write one character per tile for destinations that draw text
+//! themselves. [`renderer::TileRenderer`] puts all of them, and any custom
+//! output, behind one trait.
+
+pub mod ans;
+pub mod ansi;
+pub mod cast;
+pub mod html;
+pub mod renderer;
+pub mod svg;
@@ -0,0 +1,137 @@
+//! Pluggable output for a finished conversion
+//!
+//! A [`TileRenderer`] turns an [`AsciiArt`] into some output. The built-in
+//! outputs all implement it: [`BitmapRenderer`] draws the glyph image,
+//! [`TextRenderer`] writes plain text, and the option structs of the text
+//! renderers ([`AnsiOptions`], [`HtmlOptions`], [`SvgOptions`], [`AnsOptions`])
+//! render with their settings. Code that is generic over the output, or that
+//! brings its own format, takes a `TileRenderer` instead of a fixed method.
+//!
+//! ```
+//! use ascii_rendr::render::renderer::TileRenderer;
+//! use ascii_rendr::{AsciiArt, AsciiConfig, process_image};
+//!
+//! /// Counts the tiles that got an edge character
+//! struct EdgeCount;
+//!
+//! impl TileRenderer for EdgeCount {
+//!     type Output = usize;
+//!
+//!     fn render(&self, art: &AsciiArt) -> usize {
+//!         art.edge_chars().iter().filter(|c| **c != ' ').count()
+//!     }
+//! }
+//!
+//! let input = image::RgbaImage::new(64, 32);
+//! let art = process_image(&input, &AsciiConfig::default());
+//! assert_eq!(art.render(&EdgeCount), 0);
+//! ```
+
+use crate::art::AsciiArt;
+use crate::render::ans::{AnsOptions, grid_to_ans};
+use crate::render::ansi::AnsiOptions;
+use crate::render::html::HtmlOptions;
+use crate::render::svg::SvgOptions;
+use image::RgbaImage;
+
+/// Turns a converted character grid into an output
+pub trait TileRenderer {
+    /// What the renderer produces
+    type Output;
+
+    /// Renders every tile of `art`
+    fn render(&self, art: &AsciiArt) -> Self::Output;
+}
+
+/// The rasterized image with one glyph per tile; see [`AsciiArt::render_image`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BitmapRenderer;
+
+impl TileRenderer for BitmapRenderer {
+    type Output = RgbaImage;
+
+    fn render(&self, art: &AsciiArt) -> RgbaImage {
+        art.render_image()
+    }
+}
+
+/// Plain text, one line per row; see [`AsciiArt::render_text`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextRenderer;
+
+impl TileRenderer for TextRenderer {
+    type Output = String;
+
+    fn render(&self, art: &AsciiArt) -> String {
+        art.render_text()
+    }
+}
+
+impl TileRenderer for AnsiOptions {
+    type Output = String;
+
+    fn render(&self, art: &AsciiArt) -> String {
+        art.render_ansi(self)
+    }
+}
+
+impl TileRenderer for HtmlOptions {
+    type Output = String;
+
+    fn render(&self, art: &AsciiArt) -> String {
+        art.render_html(self)
+    }
+}
+
+impl TileRenderer for SvgOptions {
+    type Output = String;
+
+    fn render(&self, art: &AsciiArt) -> String {
+        art.render_svg(self)
+    }
+}
+
+impl TileRenderer for AnsOptions {
+    type Output = Vec<u8>;
+
+    fn render(&self, art: &AsciiArt) -> Vec<u8> {
+        grid_to_ans(
+            art.columns(),
+            art.rows(),
+            &art.chars(),
+            &art.colors(),
+            art.config().bg_color,
+            self,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AsciiConfig;
+    use crate::process_image_output;
+    use crate::render::ans::render_ans;
+    use image::Rgba;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_renderers_match_direct_output() {
+        let input = RgbaImage::from_fn(48, 32, |x, y| {
+            Rgba([if x < 24 { 20 } else { 230 }, (y * 7) as u8, 90, 255])
+        });
+        let config = AsciiConfig::default();
+        let art = AsciiArt::from_image(Cow::Borrowed(&input), &config, true);
+        let output = process_image_output(&input, &config, true);
+
+        assert_eq!(art.render(&BitmapRenderer), output.image);
+        assert_eq!(art.render(&TextRenderer), output.to_text());
+        let ansi = AnsiOptions { background: false };
+        assert_eq!(art.render(&ansi), art.render_ansi(&ansi));
+        let ans = AnsOptions {
+            title: "test".to_string(),
+            ..AnsOptions::default()
+        };
+        assert_eq!(art.render(&ans), render_ans(&output, &ans));
+    }
+}
@@ -0,0 +1,173 @@
+//! SVG vector output
+//!
+//! [`render_svg`] writes the character grid of a [`ProcessOutput`] as SVG
+//! `<text>` elements centered in cells of the tile size (8×8 by default), so
+//! the document has the pixel dimensions of the rendered image but scales
+//! without loss, for print or laser engraving. Every character carries its own
+//! fill color; blank cells are left out.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::processor::{SvgOptions, process_image_output, render_svg};
+//!
+//! let input = image::open("photo.jpg").unwrap().to_rgba8();
+//! let output = process_image_output(&input, &AsciiConfig::default(), true);
+//! std::fs::write("photo.svg", render_svg(&output, &SvgOptions::default())).unwrap();
+//! ```
+
+use crate::output::ProcessOutput;
+use std::fmt::Write;
+
+/// Options for SVG output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Fill the document with the configured background color; otherwise it is
+    /// transparent (engravers usually want only the glyphs)
+    pub background: bool,
+    /// CSS font family for the glyphs
+    pub font_family: String,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            background: true,
+            font_family: "monospace".to_string(),
+        }
+    }
+}
+
+/// Writes the character grid of `art` as an SVG document
+pub fn render_svg(art: &ProcessOutput, options: &SvgOptions) -> String {
+    let cell_size = (
+        art.image.width() / art.columns.max(1),
+        art.image.height() / art.rows.max(1),
+    );
+    grid_to_svg(
+        art.columns,
+        cell_size,
+        &art.chars,
+        &art.colors,
+        options.background.then_some(art.bg_color),
+        options,
+    )
+}
+
+/// Writes a character grid with one fill color per character as SVG
+///
+/// # Arguments
+/// * `columns` - Characters per row
+/// * `cell_size` - Width and height of a character cell in SVG user units
+/// * `chars` - Characters, row-major
+/// * `colors` - Fill color per character, row-major
+/// * `background` - Background color, or None for a transparent document
+/// * `options` - Font family (`background` is ignored)
+pub fn grid_to_svg(
+    columns: u32,
+    cell_size: (u32, u32),
+    chars: &[char],
+    colors: &[[u8; 3]],
+    background: Option<[u8; 3]>,
+    options: &SvgOptions,
+) -> String {
+    assert_eq!(chars.len(), colors.len());
+    let columns = columns.max(1);
+    let rows = chars.len().div_ceil(columns as usize) as u32;
+    let (cell_w, cell_h) = cell_size;
+    let (width, height) = (columns * cell_w, rows * cell_h);
+    // Monospace glyphs are about half as wide as they are tall
+    let font_size = cell_h.min(cell_w * 2);
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = width,
+        h = height
+    );
+    if let Some([r, g, b]) = background {
+        let _ = writeln!(
+            out,
+            "<rect width=\"100%\" height=\"100%\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+            r, g, b
+        );
+    }
+    let _ = writeln!(
+        out,
+        "<g font-family=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" xml:space=\"preserve\">",
+        escape(&options.font_family),
+        font_size
+    );
+
+    for (i, (&ch, &[r, g, b])) in chars.iter().zip(colors).enumerate() {
+        if ch.is_whitespace() {
+            continue;
+        }
+        let (col, row) = (i as u32 % columns, i as u32 / columns);
+        let _ = writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\" fill=\"#{:02x}{:02x}{:02x}\">{}</text>",
+            col * cell_w + cell_w / 2,
+            row * cell_h + cell_h / 2,
+            r,
+            g,
+            b,
+            escape(ch.encode_utf8(&mut [0; 4]))
+        );
+    }
+
+    out.push_str("</g>\n</svg>\n");
+    out
+}
+
+/// Escapes text for XML content and attribute values
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_to_svg() {
+        let chars = ['|', ' ', '<', '.', '&', '@'];
+        let colors = [[255, 0, 16]; 6];
+        let svg = grid_to_svg(
+            3,
+            (8, 8),
+            &chars,
+            &colors,
+            Some([1, 2, 3]),
+            &SvgOptions::default(),
+        );
+
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"24\" height=\"16\" viewBox=\"0 0 24 16\">"
+        ));
+        assert!(svg.contains("<rect width=\"100%\" height=\"100%\" fill=\"#010203\"/>"));
+        // Blank cells are skipped
+        assert_eq!(svg.matches("<text ").count(), 5);
+        assert!(svg.contains("<text x=\"4\" y=\"4\" fill=\"#ff0010\">|</text>"));
+        assert!(svg.contains("<text x=\"20\" y=\"4\" fill=\"#ff0010\">&lt;</text>"));
+        assert!(svg.contains("<text x=\"12\" y=\"12\" fill=\"#ff0010\">&amp;</text>"));
+        assert!(svg.ends_with("</g>\n</svg>\n"));
+
+        let transparent = grid_to_svg(3, (8, 8), &chars, &colors, None, &SvgOptions::default());
+        assert!(!transparent.contains("<rect"));
+
+        // 8×16 terminal-shaped cells
+        let tall = grid_to_svg(3, (8, 16), &chars, &colors, None, &SvgOptions::default());
+        assert!(tall.contains("width=\"24\" height=\"32\""));
+        assert!(tall.contains("font-size=\"16\""));
+        assert!(tall.contains("<text x=\"12\" y=\"24\" fill=\"#ff0010\">&amp;</text>"));
+    }
+}
@@ -0,0 +1,279 @@
+//! Visual saliency estimation
+//!
+//! Implements the spectral residual method of Hou and Zhang (2007): the log
+//! amplitude spectrum of a small thumbnail minus its local average leaves the
+//! "unexpected" part of the image, which transformed back highlights focal
+//! subjects against flat or repetitive backgrounds. With
+//! `AsciiConfig::saliency_strength` the pipeline uses the map to lower the edge
+//! threshold on salient tiles and raise it on the rest.
+//!
+//! Tile sizes are not biased: every stage works on a single, uniform tile
+//! grid, so saliency only moves the edge threshold. [`saliency_map`] can still
+//! drive other per-area choices, e.g. as a mask for
+//! [`process_image_regions`](crate::regions::process_image_regions).
+
+use image::{GrayImage, Luma};
+
+/// Side of the square thumbnail the spectrum is computed on (a power of two)
+const SALIENCY_SIZE: usize = 64;
+
+/// Blur applied to the raw saliency, in thumbnail pixels
+const SALIENCY_SIGMA: f32 = 3.0;
+
+/// Saliency of every pixel, scaled to 0-255, with the size of `lum`
+pub fn saliency_map(lum: &GrayImage) -> GrayImage {
+    let map = spectral_residual(lum);
+    let (width, height) = lum.dimensions();
+    GrayImage::from_fn(width, height, |x, y| {
+        let u = (x as f32 + 0.5) / width as f32;
+        let v = (y as f32 + 0.5) / height as f32;
+        Luma([(sample(&map, u, v) * 255.0).round() as u8])
+    })
+}
+
+/// Saliency (0.0-1.0) at the center of each tile, row by row
+pub(crate) fn tile_saliency(lum: &GrayImage, tile_width: u32, tile_height: u32) -> Vec<f32> {
+    let map = spectral_residual(lum);
+    (0..tile_height)
+        .flat_map(|ty| (0..tile_width).map(move |tx| (tx, ty)))
+        .map(|(tx, ty)| {
+            let u = (tx as f32 + 0.5) / tile_width as f32;
+            let v = (ty as f32 + 0.5) / tile_height as f32;
+            sample(&map, u, v)
+        })
+        .collect()
+}
+
+/// Saliency of a `SALIENCY_SIZE`² thumbnail of `lum`, normalized to 0.0-1.0
+///
+/// A featureless image has no residual; it gets a flat map of 0.5.
+fn spectral_residual(lum: &GrayImage) -> Vec<f32> {
+    const N: usize = SALIENCY_SIZE;
+    let thumbnail = downsample(lum, N);
+    if thumbnail.iter().all(|&v| v == thumbnail[0]) {
+        return vec![0.5; N * N];
+    }
+
+    let mut spectrum: Vec<(f32, f32)> = thumbnail.iter().map(|&v| (v, 0.0)).collect();
+    fft_2d(&mut spectrum, N, false);
+
+    // Log amplitude minus its 3×3 average, keeping the phase
+    let log_amplitude: Vec<f32> = spectrum
+        .iter()
+        .map(|&(re, im)| (re.hypot(im) + 1e-6).ln())
+        .collect();
+    let average = box_filter_3x3(&log_amplitude, N);
+    for (i, value) in spectrum.iter_mut().enumerate() {
+        let phase = value.1.atan2(value.0);
+        let magnitude = (log_amplitude[i] - average[i]).exp();
+        *value = (magnitude * phase.cos(), magnitude * phase.sin());
+    }
+
+    fft_2d(&mut spectrum, N, true);
+    let energy: Vec<f32> = spectrum.iter().map(|&(re, im)| re * re + im * im).collect();
+    let smoothed = gaussian_blur(&energy, N, SALIENCY_SIGMA);
+
+    let min = smoothed.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = smoothed.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    if max - min <= f32::EPSILON {
+        return vec![0.5; N * N];
+    }
+    smoothed.iter().map(|v| (v - min) / (max - min)).collect()
+}
+
+/// Area-averaged `size`×`size` thumbnail, values 0.0-1.0
+fn downsample(lum: &GrayImage, size: usize) -> Vec<f32> {
+    let (width, height) = (lum.width() as usize, lum.height() as usize);
+    let span = |i: usize, extent: usize| {
+        let start = (i * extent / size).min(extent.saturating_sub(1));
+        let end = ((i + 1) * extent / size).max(start + 1).min(extent.max(1));
+        start..end
+    };
+
+    let mut thumbnail = vec![0.0; size * size];
+    if width == 0 || height == 0 {
+        return thumbnail;
+    }
+    for ty in 0..size {
+        for tx in 0..size {
+            let (xs, ys) = (span(tx, width), span(ty, height));
+            let count = (xs.len() * ys.len()) as f32;
+            let sum: u32 = ys
+                .flat_map(|y| xs.clone().map(move |x| (x, y)))
+                .map(|(x, y)| lum.get_pixel(x as u32, y as u32)[0] as u32)
+                .sum();
+            thumbnail[ty * size + tx] = sum as f32 / count / 255.0;
+        }
+    }
+    thumbnail
+}
+
+/// Bilinear lookup at relative position (`u`, `v`) in 0.0-1.0
+fn sample(map: &[f32], u: f32, v: f32) -> f32 {
+    let n = SALIENCY_SIZE;
+    let x = (u * n as f32 - 0.5).clamp(0.0, (n - 1) as f32);
+    let y = (v * n as f32 - 0.5).clamp(0.0, (n - 1) as f32);
+    let (x0, y0) = (x as usize, y as usize);
+    let (x1, y1) = ((x0 + 1).min(n - 1), (y0 + 1).min(n - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let top = map[y0 * n + x0] * (1.0 - fx) + map[y0 * n + x1] * fx;
+    let bottom = map[y1 * n + x0] * (1.0 - fx) + map[y1 * n + x1] * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+/// In-place 2D FFT of an `n`×`n` grid (`n` a power of two); the inverse is scaled
+fn fft_2d(data: &mut [(f32, f32)], n: usize, inverse: bool) {
+    for row in data.chunks_mut(n) {
+        fft(row, inverse);
+    }
+    let mut column = vec![(0.0, 0.0); n];
+    for x in 0..n {
+        for (y, value) in column.iter_mut().enumerate() {
+            *value = data[y * n + x];
+        }
+        fft(&mut column, inverse);
+        for (y, value) in column.iter().enumerate() {
+            data[y * n + x] = *value;
+        }
+    }
+}
+
+/// In-place iterative radix-2 FFT; the inverse divides by the length
+fn fft(data: &mut [(f32, f32)], inverse: bool) {
+    let n = data.len();
+    debug_assert!(n.is_power_of_two());
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (wr, wi) = ((angle * k as f32).cos(), (angle * k as f32).sin());
+                let (ar, ai) = data[start + k];
+                let (br, bi) = data[start + k + len / 2];
+                let (tr, ti) = (br * wr - bi * wi, br * wi + bi * wr);
+                data[start + k] = (ar + tr, ai + ti);
+                data[start + k + len / 2] = (ar - tr, ai - ti);
+            }
+        }
+        len <<= 1;
+    }
+
+    if inverse {
+        let scale = 1.0 / n as f32;
+        for value in data.iter_mut() {
+            *value = (value.0 * scale, value.1 * scale);
+        }
+    }
+}
+
+/// 3×3 mean of an `n`×`n` grid, borders clamped
+fn box_filter_3x3(values: &[f32], n: usize) -> Vec<f32> {
+    let at = |x: isize, y: isize| {
+        let x = x.clamp(0, n as isize - 1) as usize;
+        let y = y.clamp(0, n as isize - 1) as usize;
+        values[y * n + x]
+    };
+    (0..n * n)
+        .map(|i| {
+            let (x, y) = ((i % n) as isize, (i / n) as isize);
+            let mut sum = 0.0;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    sum += at(x + dx, y + dy);
+                }
+            }
+            sum / 9.0
+        })
+        .collect()
+}
+
+/// Separable Gaussian blur of an `n`×`n` grid, borders clamped
+fn gaussian_blur(values: &[f32], n: usize, sigma: f32) -> Vec<f32> {
+    let radius = (sigma * 3.0).ceil() as isize;
+    let weights: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+
+    let pass = |input: &[f32], horizontal: bool| -> Vec<f32> {
+        (0..n * n)
+            .map(|i| {
+                let (x, y) = ((i % n) as isize, (i / n) as isize);
+                let mut sum = 0.0;
+                for (k, w) in weights.iter().enumerate() {
+                    let offset = k as isize - radius;
+                    let (sx, sy) = if horizontal {
+                        ((x + offset).clamp(0, n as isize - 1), y)
+                    } else {
+                        (x, (y + offset).clamp(0, n as isize - 1))
+                    };
+                    sum += w * input[sy as usize * n + sx as usize];
+                }
+                sum / total
+            })
+            .collect()
+    };
+    pass(&pass(values, true), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_round_trip() {
+        let original: Vec<(f32, f32)> = (0..16).map(|i| ((i * 7 % 5) as f32, 0.0)).collect();
+        let mut data = original.clone();
+        fft(&mut data, false);
+        // DC term is the sum
+        let sum: f32 = original.iter().map(|v| v.0).sum();
+        assert!((data[0].0 - sum).abs() < 1e-4);
+        fft(&mut data, true);
+        for (a, b) in data.iter().zip(&original) {
+            assert!((a.0 - b.0).abs() < 1e-4 && a.1.abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_saliency_finds_odd_object() {
+        // Regular stripes with one bright block: the block stands out
+        let lum = GrayImage::from_fn(128, 128, |x, y| {
+            let block = (80..104).contains(&x) && (24..48).contains(&y);
+            Luma([if block {
+                255
+            } else if (x / 4) % 2 == 0 {
+                60
+            } else {
+                100
+            }])
+        });
+        let map = saliency_map(&lum);
+        assert_eq!(map.dimensions(), (128, 128));
+        assert!(map.get_pixel(92, 36)[0] > map.get_pixel(24, 100)[0]);
+
+        let tiles = tile_saliency(&lum, 16, 16);
+        assert_eq!(tiles.len(), 256);
+        assert!(tiles[4 * 16 + 11] > tiles[12 * 16 + 3]);
+
+        // Nothing stands out in a flat image
+        let flat = tile_saliency(&GrayImage::from_pixel(64, 64, Luma([128])), 8, 8);
+        assert!(flat.iter().all(|&s| s == 0.5));
+    }
+}
@@ -0,0 +1,232 @@
+//! Subject isolation with an external segmentation mask
+//!
+//! The library does not segment images itself; any model or tool that produces
+//! a foreground mask (white subject on black, soft edges allowed) can be plugged
+//! in. [`process_image_masked`] converts the image and composes the ASCII
+//! rendering with the original according to a [`MaskComposite`] mode, for
+//! example an ASCII portrait over the untouched photo background.
+//!
+//! ```no_run
+//! use ascii_rendr::AsciiConfig;
+//! use ascii_rendr::segmentation::{MaskComposite, process_image_masked};
+//!
+//! let input = image::open("portrait.jpg").unwrap().to_rgba8();
+//! let mask = image::open("portrait_mask.png").unwrap().to_luma8();
+//! let output = process_image_masked(
+//!     &input,
+//!     &mask,
+//!     &AsciiConfig::default(),
+//!     true,
+//!     MaskComposite::AsciiSubject,
+//! );
+//! output.save("portrait_ascii.png").unwrap();
+//! ```
+
+use crate::config::AsciiConfig;
+use crate::processor::{
+    process_image, process_image_preserve_colors, resize_to_valid_dimensions, working_multiple,
+};
+use image::{GrayImage, Rgba, RgbaImage, imageops};
+
+/// How the ASCII rendering and the original are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaskComposite {
+    /// ASCII subject over the original background
+    #[default]
+    AsciiSubject,
+    /// Original subject over an ASCII background
+    AsciiBackground,
+    /// ASCII subject on the configured background color
+    SubjectOnly,
+}
+
+impl MaskComposite {
+    /// All modes, in menu order
+    pub const ALL: [MaskComposite; 3] = [
+        MaskComposite::AsciiSubject,
+        MaskComposite::AsciiBackground,
+        MaskComposite::SubjectOnly,
+    ];
+
+    /// Human-readable name
+    pub fn name(self) -> &'static str {
+        match self {
+            MaskComposite::AsciiSubject => "ASCII Subject",
+            MaskComposite::AsciiBackground => "ASCII Background",
+            MaskComposite::SubjectOnly => "Subject Only",
+        }
+    }
+}
+
+/// Converts an image to ASCII art and composes it with the original by a
+/// foreground mask
+///
+/// # Arguments
+/// * `input` - The input RGBA image to convert
+/// * `mask` - Foreground mask (255 = subject); any size, stretched to the image
+/// * `config` - Configuration parameters for the ASCII conversion
+/// * `preserve_colors` - Whether the ASCII rendering keeps source colors
+/// * `mode` - Which part of the image is rendered as ASCII
+///
+/// # Returns
+/// The composed image, with the dimensions of the ASCII output
+///
+/// # Note
+/// If the input image dimensions are not multiples of the tile size, it will be
+/// automatically resized (rounded down) to the nearest valid dimensions using
+/// Lanczos3 filtering; the original side of the composition is resized the same way.
+pub fn process_image_masked(
+    input: &RgbaImage,
+    mask: &GrayImage,
+    config: &AsciiConfig,
+    preserve_colors: bool,
+    mode: MaskComposite,
+) -> RgbaImage {
+    let ascii = if preserve_colors {
+        process_image_preserve_colors(input, config).render_image()
+    } else {
+        process_image(input, config).render_image()
+    };
+    let original = resize_to_valid_dimensions(input, working_multiple(config));
+    composite_with_mask(&original, &ascii, mask, mode, config.bg_color)
+}
+
+/// Composes an ASCII rendering with the original image by a foreground mask
+///
+/// `original` and `ascii` must have the same dimensions; the mask is stretched
+/// to them when its size differs. Mask values in between 0 and 255 blend
+/// linearly, so soft matting edges are kept. `bg_color` fills the background in
+/// [`MaskComposite::SubjectOnly`] mode. An empty mask selects no subject.
+pub fn composite_with_mask(
+    original: &RgbaImage,
+    ascii: &RgbaImage,
+    mask: &GrayImage,
+    mode: MaskComposite,
+    bg_color: [u8; 3],
+) -> RgbaImage {
+    assert_eq!(
+        original.dimensions(),
+        ascii.dimensions(),
+        "original and ASCII images must have the same dimensions"
+    );
+    let (width, height) = ascii.dimensions();
+    let mask = if mask.width() == 0 || mask.height() == 0 {
+        GrayImage::new(width, height)
+    } else if mask.dimensions() != (width, height) {
+        imageops::resize(mask, width, height, imageops::FilterType::Triangle)
+    } else {
+        mask.clone()
+    };
+
+    let background = Rgba([bg_color[0], bg_color[1], bg_color[2], 255]);
+    RgbaImage::from_fn(width, height, |x, y| {
+        let (subject, rest) = match mode {
+            MaskComposite::AsciiSubject => (*ascii.get_pixel(x, y), *original.get_pixel(x, y)),
+            MaskComposite::AsciiBackground => (*original.get_pixel(x, y), *ascii.get_pixel(x, y)),
+            MaskComposite::SubjectOnly => (*ascii.get_pixel(x, y), background),
+        };
+        blend(subject, rest, mask.get_pixel(x, y)[0])
+    })
+}
+
+/// `a` weighted by `alpha`/255 plus `b` weighted by the rest
+fn blend(a: Rgba<u8>, b: Rgba<u8>, alpha: u8) -> Rgba<u8> {
+    match alpha {
+        255 => a,
+        0 => b,
+        _ => {
+            let (wa, wb) = (alpha as u32, 255 - alpha as u32);
+            Rgba(std::array::from_fn(|c| {
+                ((a[c] as u32 * wa + b[c] as u32 * wb + 127) / 255) as u8
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    fn photo() -> RgbaImage {
+        RgbaImage::from_fn(32, 16, |x, y| {
+            Rgba([(x * 8) as u8, (y * 16) as u8, 90, 255])
+        })
+    }
+
+    /// Left half subject, right half background
+    fn left_mask(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, _| {
+            Luma([if x < width / 2 { 255 } else { 0 }])
+        })
+    }
+
+    #[test]
+    fn test_composite_modes() {
+        let config = AsciiConfig::default();
+        let original = photo();
+        let ascii = process_image(&original, &config).render_image();
+        let mask = left_mask(32, 16);
+
+        let subject = composite_with_mask(
+            &original,
+            &ascii,
+            &mask,
+            MaskComposite::AsciiSubject,
+            [0, 0, 0],
+        );
+        assert_eq!(subject.get_pixel(3, 5), ascii.get_pixel(3, 5));
+        assert_eq!(subject.get_pixel(28, 5), original.get_pixel(28, 5));
+
+        let background = composite_with_mask(
+            &original,
+            &ascii,
+            &mask,
+            MaskComposite::AsciiBackground,
+            [0, 0, 0],
+        );
+        assert_eq!(background.get_pixel(3, 5), original.get_pixel(3, 5));
+        assert_eq!(background.get_pixel(28, 5), ascii.get_pixel(28, 5));
+
+        let only = composite_with_mask(
+            &original,
+            &ascii,
+            &mask,
+            MaskComposite::SubjectOnly,
+            [1, 2, 3],
+        );
+        assert_eq!(only.get_pixel(3, 5), ascii.get_pixel(3, 5));
+        assert_eq!(*only.get_pixel(28, 5), Rgba([1, 2, 3, 255]));
+    }
+
+    #[test]
+    fn test_mask_scaling_and_soft_edges() {
+        let config = AsciiConfig::default();
+        // Mask at a quarter of the resolution, as segmentation models often return
+        let output = process_image_masked(
+            &photo(),
+            &left_mask(8, 4),
+            &config,
+            false,
+            MaskComposite::AsciiSubject,
+        );
+        let ascii = process_image(&photo(), &config).render_image();
+        assert_eq!(output.dimensions(), (32, 16));
+        assert_eq!(output.get_pixel(1, 1), ascii.get_pixel(1, 1));
+        assert_eq!(output.get_pixel(30, 1), photo().get_pixel(30, 1));
+
+        assert_eq!(
+            blend(Rgba([255, 0, 100, 255]), Rgba([0, 255, 100, 255]), 128),
+            Rgba([128, 127, 100, 255])
+        );
+
+        let empty = composite_with_mask(
+            &photo(),
+            &ascii,
+            &GrayImage::new(0, 0),
+            MaskComposite::AsciiSubject,
+            [0, 0, 0],
+        );
+        assert_eq!(empty, photo());
+    }
+}
@@ -0,0 +1,214 @@
+//! Shimmering animation from a still image
+//!
+//! [`Shimmer`] runs the pipeline once and then renders frames in which every
+//! fill character cycles through the characters of its brightness class
+//! ([`SHIMMER_CLASSES`](crate::lut::SHIMMER_CLASSES)). Each tile starts at a
+//! phase taken from a seeded hash, so neighbouring tiles change at different
+//! moments and the picture "shimmers" while its tones stay put. Edge characters
+//! are not animated.
+//!
+//! The animation loops seamlessly over any frame count, so it can be saved as a
+//! GIF or animated WebP with [`Shimmer::save_animation`] or written to a video
+//! frame by frame.
+
+use crate::animation;
+use crate::ascii::{composite_chars, render_ascii_to_image_with_source};
+use crate::config::AsciiConfig;
+use crate::lut::shimmer_class;
+use crate::par::*;
+use crate::processor::{compute_char_layers, resize_to_valid_dimensions, working_multiple};
+use image::{Frame, RgbaImage};
+use std::path::Path;
+
+/// A still image prepared for shimmer animation
+pub struct Shimmer {
+    config: AsciiConfig,
+    edges: Vec<Vec<char>>,
+    fill: Vec<Vec<char>>,
+    tile_width: u32,
+    tile_height: u32,
+    source: Option<RgbaImage>,
+    seed: u64,
+    cycles: u32,
+}
+
+impl Shimmer {
+    /// Runs the pipeline on `input`, keeping the layers needed to render frames
+    ///
+    /// # Arguments
+    /// * `input` - The still image to animate
+    /// * `config` - Configuration parameters for the ASCII conversion
+    /// * `preserve_colors` - Sample colors from the source image instead of `ascii_color`
+    pub fn new(input: &RgbaImage, config: &AsciiConfig, preserve_colors: bool) -> Self {
+        // Validate config
+        config.validate().expect("Invalid configuration");
+
+        // Automatically resize if dimensions are not multiples of the tile size
+        let working_image = resize_to_valid_dimensions(input, working_multiple(config));
+        let layers = compute_char_layers(&working_image, config);
+
+        Self {
+            config: config.clone(),
+            edges: layers.edges,
+            fill: layers.fill,
+            tile_width: layers.tile_width,
+            tile_height: layers.tile_height,
+            source: preserve_colors.then(|| working_image.into_owned()),
+            seed: 0,
+            cycles: 1,
+        }
+    }
+
+    /// Sets the seed for the per-tile phases (default 0)
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets how many times each tile runs through its class per loop (default 1)
+    ///
+    /// Higher values shimmer faster.
+    pub fn with_cycles(mut self, cycles: u32) -> Self {
+        self.cycles = cycles.max(1);
+        self
+    }
+
+    /// Renders the frame at position `t` in the loop
+    ///
+    /// # Arguments
+    /// * `t` - Loop position in [0.0, 1.0); values outside wrap around
+    pub fn frame(&self, t: f32) -> RgbaImage {
+        let t = t.rem_euclid(1.0);
+        let cycles = self.cycles as f32;
+
+        let fill: Vec<Vec<char>> = self
+            .fill
+            .par_iter()
+            .enumerate()
+            .map(|(tile_idx, tile_chars)| {
+                let phase = tile_phase(self.seed, tile_idx);
+                tile_chars
+                    .iter()
+                    .map(|&ch| match shimmer_class(ch) {
+                        Some(class) if class.len() > 1 => {
+                            // Each tile steps through its class `cycles` times per
+                            // loop, starting at its own phase
+                            let len = class.len();
+                            let step = ((t + phase) * cycles * len as f32) as usize;
+                            let start = class.iter().position(|&c| c == ch).unwrap_or(0);
+                            class[(start + step) % len]
+                        }
+                        _ => ch,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let chars = composite_chars(&self.edges, &fill, &self.config);
+        render_ascii_to_image_with_source(
+            &chars,
+            self.tile_width,
+            self.tile_height,
+            &self.config,
+            self.source.as_ref(),
+        )
+    }
+
+    /// Renders one full loop of `count` frames
+    pub fn frames(&self, count: u32) -> impl Iterator<Item = RgbaImage> + '_ {
+        let count = count.max(1);
+        (0..count).map(move |i| self.frame(i as f32 / count as f32))
+    }
+
+    /// Saves one loop of `count` frames as an endlessly repeating GIF
+    ///
+    /// # Arguments
+    /// * `path` - Output file
+    /// * `count` - Number of frames in the loop
+    /// * `fps` - Playback frame rate
+    pub fn save_gif(&self, path: impl AsRef<Path>, count: u32, fps: f32) -> Result<(), String> {
+        animation::save_gif(path, self.timed_frames(count, fps)?)
+    }
+
+    /// Saves one loop of `count` frames as a GIF, animated WebP or APNG, by
+    /// extension
+    ///
+    /// # Arguments
+    /// * `path` - Output file (`.gif`, `.webp` or `.png`)
+    /// * `count` - Number of frames in the loop
+    /// * `fps` - Playback frame rate
+    pub fn save_animation(
+        &self,
+        path: impl AsRef<Path>,
+        count: u32,
+        fps: f32,
+    ) -> Result<(), String> {
+        animation::save_frames(path, self.timed_frames(count, fps)?)
+    }
+
+    /// One loop of frames with the delay for `fps`
+    fn timed_frames(
+        &self,
+        count: u32,
+        fps: f32,
+    ) -> Result<impl Iterator<Item = Frame> + '_, String> {
+        let delay = animation::frame_delay(fps)?;
+        Ok(self
+            .frames(count)
+            .map(move |image| Frame::from_parts(image, 0, 0, delay)))
+    }
+}
+
+/// Stable pseudo-random phase in [0.0, 1.0) for a tile
+fn tile_phase(seed: u64, tile_idx: usize) -> f32 {
+    // SplitMix64 finalizer over the seed and tile index
+    let mut h = seed ^ (tile_idx as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^= h >> 31;
+    (h >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient() -> RgbaImage {
+        RgbaImage::from_fn(64, 64, |x, _| {
+            let v = (x * 4) as u8;
+            image::Rgba([v, v, v, 255])
+        })
+    }
+
+    #[test]
+    fn test_shimmer_loops_and_moves() {
+        let shimmer = Shimmer::new(&gradient(), &AsciiConfig::default(), false).with_seed(7);
+        let first = shimmer.frame(0.0);
+        assert_eq!(first.dimensions(), (64, 64));
+        assert_eq!(shimmer.frame(1.0), first);
+        assert!((1..8).any(|i| shimmer.frame(i as f32 / 8.0) != first));
+    }
+
+    #[test]
+    fn test_shimmer_keeps_brightness_classes() {
+        let config = AsciiConfig {
+            draw_edges: false,
+            ..Default::default()
+        };
+        let shimmer = Shimmer::new(&gradient(), &config, false);
+        let ink = |image: &RgbaImage| image.pixels().filter(|p| p[0] > 0).count();
+        let first = ink(&shimmer.frame(0.0));
+        for image in shimmer.frames(6) {
+            // '/' and '\' cover one pixel less than the rest of their class
+            assert!(ink(&image).abs_diff(first) <= 64);
+        }
+    }
+
+    #[test]
+    fn test_tile_phase_range() {
+        for i in 0..1000 {
+            assert!((0.0..1.0).contains(&tile_phase(42, i)));
+        }
+        assert_ne!(tile_phase(1, 5), tile_phase(2, 5));
+    }
+}
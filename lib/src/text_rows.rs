@@ -0,0 +1,246 @@
+//! Progressive text output, one row of characters at a time
+//!
+//! [`TextRows`] yields the text of each tile row as soon as it is converted,
+//! so a terminal preview can start printing the top of a large image while the
+//! rest is still being processed. Luminance, the pre-filters, saliency
+//! thresholds and the fill layer are cheap and computed up front; the costly
+//! edge passes run per row on a band of the luminance image extended by as far
+//! as they reach (as in [`crate::banded`]), so the rows match
+//! [`AsciiArt::render_text`](crate::AsciiArt::render_text) of the same
+//! conversion. Braille output is converted in one piece.
+
+use crate::art::AsciiArt;
+use crate::ascii::{composite_chars, select_edge_chars, tile_representative_chars};
+use crate::config::{AsciiConfig, OutputMode};
+use crate::incremental::{Rect, compute_region_edges};
+use crate::processor::{
+    compute_fill_layer, luminance, pre_filter_luminance, resize_to_valid_dimensions,
+    tile_edge_thresholds, working_multiple,
+};
+use image::{GrayImage, RgbaImage};
+
+/// Iterator over the text rows of an image, converted lazily
+pub struct TextRows {
+    config: AsciiConfig,
+    source: RowSource,
+    columns: u32,
+    rows: u32,
+    next_row: u32,
+}
+
+/// What rows are converted from
+enum RowSource {
+    /// Pre-filtered luminance, fill layer and per-tile edge thresholds
+    Tiles {
+        lum: GrayImage,
+        fill: Vec<Vec<char>>,
+        thresholds: Option<Vec<u32>>,
+    },
+    /// Braille rows, converted up front
+    Converted(Vec<String>),
+}
+
+impl TextRows {
+    /// Prepares `input` for row-by-row conversion
+    ///
+    /// # Arguments
+    /// * `input` - The input RGBA image to convert
+    /// * `config` - Configuration parameters for the ASCII conversion
+    pub fn new(input: &RgbaImage, config: &AsciiConfig) -> Self {
+        // Validate config
+        config.validate().expect("Invalid configuration");
+
+        // Automatically resize if dimensions are not multiples of the tile size
+        let working_image = resize_to_valid_dimensions(input, working_multiple(config));
+
+        if config.output_mode == OutputMode::Braille {
+            let art = AsciiArt::from_image(working_image, config, false);
+            let (columns, rows) = (art.columns(), art.rows());
+            let lines = art
+                .chars()
+                .chunks(columns.max(1) as usize)
+                .map(|line| line.iter().collect())
+                .collect();
+            return Self {
+                config: config.clone(),
+                source: RowSource::Converted(lines),
+                columns,
+                rows,
+                next_row: 0,
+            };
+        }
+
+        let lum = luminance(&working_image, config);
+        let lum = pre_filter_luminance(&lum, config).into_owned();
+        let thresholds = tile_edge_thresholds(&lum, config);
+        let (fill, _tile_lum) = compute_fill_layer(&lum, config);
+        let (tile_w, tile_h) = config.tile_dimensions();
+
+        Self {
+            config: config.clone(),
+            columns: lum.width() / tile_w,
+            rows: lum.height() / tile_h,
+            source: RowSource::Tiles {
+                lum,
+                fill,
+                thresholds,
+            },
+            next_row: 0,
+        }
+    }
+
+    /// Number of characters per row
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Total number of rows
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Converts one row
+    fn convert_row(&self, row: u32) -> String {
+        let (lum, fill, thresholds) = match &self.source {
+            RowSource::Tiles {
+                lum,
+                fill,
+                thresholds,
+            } => (lum, fill, thresholds),
+            RowSource::Converted(lines) => return lines[row as usize].clone(),
+        };
+
+        // Steps 2-4 on this tile row and the margin the edge passes read
+        let (_, tile_h) = self.config.tile_dimensions();
+        let tiles = Rect::new(0, row * tile_h, lum.width(), tile_h);
+        let (directions, _offsets) =
+            compute_region_edges(lum, &self.config, tiles, thresholds.as_deref());
+
+        let edges = select_edge_chars(&directions, self.columns, 1, &self.config);
+        let start = (row * self.columns) as usize;
+        let fill = &fill[start..start + self.columns as usize];
+        let chars = composite_chars(&edges, fill, &self.config);
+        tile_representative_chars(&chars).into_iter().collect()
+    }
+}
+
+impl Iterator for TextRows {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.next_row >= self.rows {
+            return None;
+        }
+        let row = self.convert_row(self.next_row);
+        self.next_row += 1;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.rows - self.next_row) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for TextRows {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BlurMode, PreFilter};
+    use crate::{process_image, process_image_output};
+
+    /// Circle with a hard outline over a textured background, so several rows
+    /// contain edges
+    fn scene() -> RgbaImage {
+        RgbaImage::from_fn(96, 80, |x, y| {
+            let (dx, dy) = (x as f32 - 48.0, y as f32 - 40.0);
+            let v = if (dx * dx + dy * dy).sqrt() < 28.0 {
+                230
+            } else {
+                40 + ((x / 5 + y / 7) % 3 * 30) as u8
+            };
+            image::Rgba([v, v, v, 255])
+        })
+    }
+
+    #[test]
+    fn test_text_rows_match_full_output() {
+        let input = scene();
+        for kernel_size in [1, 2, 10] {
+            let config = AsciiConfig {
+                kernel_size,
+                ..Default::default()
+            };
+            let expected = process_image_output(&input, &config, false).to_text();
+            assert!(expected.contains(['|', '-', '/', '\\']));
+            let rows = TextRows::new(&input, &config);
+            assert_eq!((rows.columns(), rows.rows()), (12, 10));
+            assert_eq!(rows.len(), 10);
+            assert_eq!(rows.collect::<Vec<_>>().join("\n"), expected);
+        }
+
+        // Settings that widen the edge passes' reach or change their input
+        let configs = [
+            AsciiConfig {
+                median_radius: Some(2),
+                ..Default::default()
+            },
+            AsciiConfig {
+                pre_filter: PreFilter::Kuwahara {
+                    radius: 4,
+                    sectors: 8,
+                },
+                ..Default::default()
+            },
+            AsciiConfig {
+                edge_dilation: 3,
+                ..Default::default()
+            },
+            AsciiConfig {
+                blur_mode: BlurMode::Box,
+                sigma: 4.0,
+                ..Default::default()
+            },
+            AsciiConfig {
+                structure_tensor_sigma: Some(2.0),
+                non_max_suppression: true,
+                ..Default::default()
+            },
+            AsciiConfig {
+                saliency_strength: 0.8,
+                ..Default::default()
+            },
+            AsciiConfig {
+                tile_height: Some(16),
+                positional_edges: true,
+                ..Default::default()
+            },
+        ];
+        for (i, config) in configs.iter().enumerate() {
+            let expected = process_image(&input, config).render_text();
+            let rows = TextRows::new(&input, config);
+            assert_eq!(
+                rows.collect::<Vec<_>>().join("\n"),
+                expected,
+                "config {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_text_rows_braille() {
+        let config = AsciiConfig {
+            output_mode: OutputMode::Braille,
+            ..Default::default()
+        };
+        let expected = process_image(&scene(), &config);
+        let rows = TextRows::new(&scene(), &config);
+        assert_eq!(
+            (rows.columns(), rows.rows()),
+            (expected.columns(), expected.rows())
+        );
+        assert_eq!(rows.collect::<Vec<_>>().join("\n"), expected.render_text());
+    }
+}
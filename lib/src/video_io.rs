@@ -0,0 +1,745 @@
+//! Video decoding and encoding through the `ffmpeg` command-line tools
+//!
+//! [`VideoReader`] yields RGBA frames from any file ffmpeg can decode and
+//! [`VideoWriter`] encodes RGBA frames to any format it can write (MP4, GIF,
+//! WebM, ...), chosen from the output extension. Frames travel as raw RGBA
+//! over pipes, so `ffmpeg` and `ffprobe` only need to be on `PATH`; nothing
+//! is linked at build time. [`convert_video`] ties the two together for the
+//! common case of converting a whole file, and [`LiveCapture`] reads a camera
+//! for live conversion.
+
+use crate::config::AsciiConfig;
+use crate::error::check_image_size;
+use crate::frames::process_frames;
+use crate::processor::working_multiple;
+use image::RgbaImage;
+use std::ffi::OsStr;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+/// Frame rate assumed when the source does not report one
+const FALLBACK_FRAME_RATE: f64 = 30.0;
+
+/// Stream properties of a video file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoInfo {
+    pub width: u32,
+    pub height: u32,
+    /// Frames per second
+    pub frame_rate: f64,
+}
+
+/// Parses `ffprobe -show_entries stream=width,height,r_frame_rate -of csv=p=0` output
+fn parse_stream_info(output: &str) -> Result<VideoInfo, String> {
+    let line = output
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .ok_or("no video stream found")?;
+    let fields: Vec<&str> = line.trim().split(',').collect();
+    let [width, height, rate] = fields[..] else {
+        return Err(format!("unexpected ffprobe output: {}", line));
+    };
+
+    let width: u32 = width
+        .parse()
+        .map_err(|_| format!("invalid width: {}", width))?;
+    let height: u32 = height
+        .parse()
+        .map_err(|_| format!("invalid height: {}", height))?;
+
+    // Frame rate is reported as a fraction, e.g. "30000/1001"
+    let frame_rate = match rate.split_once('/') {
+        Some((num, den)) => {
+            let num: f64 = num
+                .parse()
+                .map_err(|_| format!("invalid frame rate: {}", rate))?;
+            let den: f64 = den
+                .parse()
+                .map_err(|_| format!("invalid frame rate: {}", rate))?;
+            if den == 0.0 { 0.0 } else { num / den }
+        }
+        None => rate
+            .parse()
+            .map_err(|_| format!("invalid frame rate: {}", rate))?,
+    };
+
+    if width == 0 || height == 0 {
+        return Err(format!("invalid video dimensions {}x{}", width, height));
+    }
+
+    Ok(VideoInfo {
+        width,
+        height,
+        frame_rate,
+    })
+}
+
+/// Reads `buf.len()` bytes, returning `Ok(false)` on a clean end of stream
+fn read_frame_bytes(reader: &mut impl Read, buf: &mut [u8]) -> Result<bool, String> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err("video stream ended mid-frame".to_string()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(format!("failed to read frame: {}", e)),
+        }
+    }
+    Ok(true)
+}
+
+/// Decodes a video file into RGBA frames
+///
+/// Iterating yields `Result<RgbaImage, String>` until the stream ends.
+pub struct VideoReader {
+    info: VideoInfo,
+    /// Shared so a [`LiveCapture`] can kill a stalled camera from another thread
+    child: Arc<Mutex<Child>>,
+    stdout: ChildStdout,
+    finished: bool,
+}
+
+impl VideoReader {
+    /// Opens a video file for decoding
+    ///
+    /// # Errors
+    /// Returns an error if `ffprobe`/`ffmpeg` cannot be run or the file has no
+    /// video stream.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        Self::open_input(None, path.as_os_str()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Opens a camera for live capture
+    ///
+    /// `device` names the camera the way the platform's ffmpeg input does: a
+    /// V4L2 device on Linux (`/dev/video0`), an AVFoundation index on macOS
+    /// (`0`) or a DirectShow name on Windows (`video=Integrated Camera`).
+    /// Frames arrive at the camera's own pace and the stream never ends.
+    ///
+    /// # Errors
+    /// Returns an error if `ffprobe`/`ffmpeg` cannot be run or the camera
+    /// cannot be opened.
+    pub fn open_camera(device: &str) -> Result<Self, String> {
+        let format = if cfg!(target_os = "linux") {
+            "v4l2"
+        } else if cfg!(target_os = "macos") {
+            "avfoundation"
+        } else {
+            "dshow"
+        };
+        Self::open_input(Some(format), OsStr::new(device))
+            .map_err(|e| format!("camera {}: {}", device, e))
+    }
+
+    /// Probes and starts decoding an ffmpeg input, optionally with a forced format
+    fn open_input(format: Option<&str>, input: &OsStr) -> Result<Self, String> {
+        let format_args = format.map_or(Vec::new(), |f| vec!["-f", f]);
+
+        let probe = Command::new("ffprobe")
+            .args(["-v", "error", "-select_streams", "v:0"])
+            .args(["-show_entries", "stream=width,height,r_frame_rate"])
+            .args(["-of", "csv=p=0"])
+            .args(&format_args)
+            .arg(input)
+            .output()
+            .map_err(|e| format!("failed to run ffprobe: {}", e))?;
+        if !probe.status.success() {
+            return Err(String::from_utf8_lossy(&probe.stderr).trim().to_string());
+        }
+        let info = parse_stream_info(&String::from_utf8_lossy(&probe.stdout))?;
+
+        let mut child = Command::new("ffmpeg")
+            .args(["-v", "error"])
+            .args(&format_args)
+            .arg("-i")
+            .arg(input)
+            .args(["-map", "0:v:0", "-f", "rawvideo", "-pix_fmt", "rgba", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("failed to run ffmpeg: {}", e))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        Ok(Self {
+            info,
+            child: Arc::new(Mutex::new(child)),
+            stdout,
+            finished: false,
+        })
+    }
+
+    /// Stream properties of the opened video
+    pub fn info(&self) -> VideoInfo {
+        self.info
+    }
+
+    /// Decodes the next frame, or `Ok(None)` at the end of the stream
+    pub fn next_frame(&mut self) -> Result<Option<RgbaImage>, String> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut buf = vec![0u8; self.info.width as usize * self.info.height as usize * 4];
+        if !read_frame_bytes(&mut self.stdout, &mut buf)? {
+            self.finished = true;
+            let status = self
+                .child
+                .lock()
+                .unwrap()
+                .wait()
+                .map_err(|e| format!("failed to wait for ffmpeg: {}", e))?;
+            if !status.success() {
+                return Err(format!("ffmpeg exited with {}", status));
+            }
+            return Ok(None);
+        }
+
+        Ok(RgbaImage::from_raw(self.info.width, self.info.height, buf))
+    }
+}
+
+impl Iterator for VideoReader {
+    type Item = Result<RgbaImage, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame().transpose()
+    }
+}
+
+impl Drop for VideoReader {
+    fn drop(&mut self) {
+        if !self.finished {
+            let mut child = self.child.lock().unwrap();
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Camera feed that always hands out the newest frame
+///
+/// A background thread reads the camera continuously, so frames do not queue
+/// up behind a conversion slower than the camera: [`next_frame`](Self::next_frame)
+/// returns the newest frame and skips the ones in between, which suits live
+/// mirrors and streaming overlays where latency matters more than every frame.
+///
+/// ```no_run
+/// use ascii_rendr::video_io::LiveCapture;
+/// use ascii_rendr::{AsciiConfig, Processor};
+///
+/// let mut camera = LiveCapture::open("/dev/video0").unwrap();
+/// let mut processor = Processor::new(&AsciiConfig::default(), true);
+/// loop {
+///     let frame = camera.next_frame().unwrap();
+///     println!("{}", processor.process(&frame).render_text());
+/// }
+/// ```
+pub struct LiveCapture {
+    info: VideoInfo,
+    shared: Arc<LiveShared>,
+    /// ffmpeg process of a camera, killed on drop so a blocked read returns
+    camera: Option<Arc<Mutex<Child>>>,
+    /// Sequence number of the last frame handed out
+    last_sequence: u64,
+    dropped_frames: u64,
+    /// Set once iteration has yielded the error that stopped the camera
+    ended: bool,
+}
+
+/// State shared between a [`LiveCapture`] and its reader thread
+#[derive(Default)]
+struct LiveShared {
+    slot: Mutex<LiveSlot>,
+    /// Signalled when a frame or an error arrives
+    updated: Condvar,
+    stop: AtomicBool,
+}
+
+/// Newest frame of a live source and its sequence number, or why it stopped
+#[derive(Default)]
+struct LiveSlot {
+    sequence: u64,
+    frame: Option<RgbaImage>,
+    error: Option<String>,
+}
+
+impl LiveSlot {
+    /// Takes the frame if it is newer than `last_sequence`, counting the ones
+    /// it replaced as dropped
+    fn take_newer(&mut self, last_sequence: &mut u64, dropped: &mut u64) -> Option<RgbaImage> {
+        if self.sequence == *last_sequence {
+            return None;
+        }
+        let frame = self.frame.take()?;
+        *dropped += self.sequence - *last_sequence - 1;
+        *last_sequence = self.sequence;
+        Some(frame)
+    }
+}
+
+impl LiveCapture {
+    /// Opens a camera and starts reading it, see [`VideoReader::open_camera`]
+    /// for how `device` is named
+    ///
+    /// # Errors
+    /// Returns an error if `ffprobe`/`ffmpeg` cannot be run or the camera
+    /// cannot be opened.
+    pub fn open(device: &str) -> Result<Self, String> {
+        let mut reader = VideoReader::open_camera(device)?;
+        let camera = Arc::clone(&reader.child);
+        let mut capture = Self::from_fn(reader.info(), move || reader.next_frame());
+        capture.camera = Some(camera);
+        Ok(capture)
+    }
+
+    /// Starts a reader thread calling `read` for frames until it fails or
+    /// the stream ends, for live sources other than a camera (e.g. a file
+    /// paced to its frame rate)
+    pub fn from_fn(
+        info: VideoInfo,
+        mut read: impl FnMut() -> Result<Option<RgbaImage>, String> + Send + 'static,
+    ) -> Self {
+        let shared = Arc::new(LiveShared::default());
+        {
+            let shared = Arc::clone(&shared);
+            // Detached: a stalled camera or `read` must not block the drop
+            thread::spawn(move || {
+                while !shared.stop.load(Ordering::Relaxed) {
+                    let read = read();
+                    let mut slot = shared.slot.lock().unwrap();
+                    match read {
+                        Ok(Some(frame)) => {
+                            slot.sequence += 1;
+                            slot.frame = Some(frame);
+                        }
+                        Ok(None) => slot.error = Some("camera stream ended".to_string()),
+                        Err(e) => slot.error = Some(e),
+                    }
+                    shared.updated.notify_all();
+                    if slot.error.is_some() {
+                        return;
+                    }
+                }
+            });
+        }
+
+        Self {
+            info,
+            shared,
+            camera: None,
+            last_sequence: 0,
+            dropped_frames: 0,
+            ended: false,
+        }
+    }
+
+    /// Stream properties of the camera
+    pub fn info(&self) -> VideoInfo {
+        self.info
+    }
+
+    /// Waits for a frame newer than the last one returned and returns it
+    ///
+    /// # Errors
+    /// Returns the error that stopped the camera, or an error once its stream
+    /// ended.
+    pub fn next_frame(&mut self) -> Result<RgbaImage, String> {
+        let mut slot = self.shared.slot.lock().unwrap();
+        loop {
+            if let Some(frame) = slot.take_newer(&mut self.last_sequence, &mut self.dropped_frames)
+            {
+                return Ok(frame);
+            }
+            if let Some(e) = &slot.error {
+                return Err(e.clone());
+            }
+            slot = self.shared.updated.wait(slot).unwrap();
+        }
+    }
+
+    /// Returns a frame newer than the last one returned, or None if none has
+    /// arrived yet, without waiting
+    ///
+    /// # Errors
+    /// Same as [`next_frame`](Self::next_frame).
+    pub fn try_next_frame(&mut self) -> Result<Option<RgbaImage>, String> {
+        let mut slot = self.shared.slot.lock().unwrap();
+        if let Some(frame) = slot.take_newer(&mut self.last_sequence, &mut self.dropped_frames) {
+            return Ok(Some(frame));
+        }
+        match &slot.error {
+            Some(e) => Err(e.clone()),
+            None => Ok(None),
+        }
+    }
+
+    /// Frames skipped because they were replaced before being picked up
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+}
+
+impl Iterator for LiveCapture {
+    type Item = Result<RgbaImage, String>;
+
+    /// Yields frames until the camera fails or its stream ends
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended {
+            return None;
+        }
+        let frame = self.next_frame();
+        // Report the error once, then end
+        self.ended = frame.is_err();
+        Some(frame)
+    }
+}
+
+impl Drop for LiveCapture {
+    fn drop(&mut self) {
+        // The reader notices after its current frame; it is not waited for,
+        // since a stalled source may never deliver one. Killing a camera's
+        // ffmpeg ends its read right away and frees the device.
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(camera) = &self.camera {
+            let _ = camera.lock().unwrap().kill();
+        }
+    }
+}
+
+/// Encodes RGBA frames into a video file
+///
+/// The container and codec follow the output extension. Call
+/// [`VideoWriter::finish`] to flush the encoder and check for errors.
+pub struct VideoWriter {
+    path: PathBuf,
+    width: u32,
+    height: u32,
+    child: Child,
+    stdin: Option<ChildStdin>,
+}
+
+impl VideoWriter {
+    /// Starts encoding to `path`, overwriting any existing file
+    ///
+    /// # Arguments
+    /// * `path` - Output file; the extension selects the format
+    /// * `width`, `height` - Dimensions every frame must have
+    /// * `frame_rate` - Frames per second
+    pub fn create(
+        path: impl AsRef<Path>,
+        width: u32,
+        height: u32,
+        frame_rate: f64,
+    ) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        if width == 0 || height == 0 {
+            return Err(format!("invalid video dimensions {}x{}", width, height));
+        }
+        if !frame_rate.is_finite() || frame_rate <= 0.0 {
+            return Err(format!("invalid frame rate: {}", frame_rate));
+        }
+
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-v", "error", "-y", "-f", "rawvideo", "-pix_fmt", "rgba"])
+            .args(["-s", &format!("{}x{}", width, height)])
+            .args(["-r", &frame_rate.to_string()])
+            .args(["-i", "-"]);
+
+        // Most players only handle 4:2:0 H.264/VP9
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+        if matches!(extension.as_deref(), Some("mp4" | "mov" | "mkv" | "webm")) {
+            command.args(["-pix_fmt", "yuv420p"]);
+        }
+
+        let mut child = command
+            .arg(&path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("failed to run ffmpeg: {}", e))?;
+        let stdin = child.stdin.take();
+
+        Ok(Self {
+            path,
+            width,
+            height,
+            child,
+            stdin,
+        })
+    }
+
+    /// Appends a frame
+    ///
+    /// # Errors
+    /// Returns an error if the frame size differs from the writer's or the
+    /// encoder has exited.
+    pub fn write_frame(&mut self, frame: &RgbaImage) -> Result<(), String> {
+        if frame.dimensions() != (self.width, self.height) {
+            return Err(format!(
+                "frame is {}x{}, expected {}x{}",
+                frame.width(),
+                frame.height(),
+                self.width,
+                self.height
+            ));
+        }
+
+        let stdin = self.stdin.as_mut().ok_or("video writer already finished")?;
+        stdin
+            .write_all(frame.as_raw())
+            .map_err(|e| format!("{}: failed to write frame: {}", self.path.display(), e))
+    }
+
+    /// Flushes the encoder and waits for the file to be written
+    pub fn finish(mut self) -> Result<(), String> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Result<(), String> {
+        // Closing stdin signals end of stream to ffmpeg
+        if self.stdin.take().is_none() {
+            return Ok(());
+        }
+        let status = self
+            .child
+            .wait()
+            .map_err(|e| format!("failed to wait for ffmpeg: {}", e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "{}: ffmpeg exited with {}",
+                self.path.display(),
+                status
+            ))
+        }
+    }
+}
+
+impl Drop for VideoWriter {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// Converts every frame of a video file to ASCII art and encodes the result
+///
+/// Frames go through [`process_frames`], so several are converted at once
+/// with buffers reused across the whole file. The output keeps the source
+/// frame rate and has the dimensions of the converted frames (the source
+/// rounded down to multiples of the tile size); its format follows the
+/// `output` extension as in [`VideoWriter`]. Audio is not carried over.
+///
+/// # Arguments
+/// * `input` - Video file to convert
+/// * `output` - File to encode to, overwritten if it exists
+/// * `config` - Configuration parameters for the ASCII conversion
+/// * `preserve_colors` - Sample colors from the source frames instead of `ascii_color`
+///
+/// # Returns
+/// The number of frames written
+///
+/// # Errors
+/// Returns an error if the config is invalid, the video is smaller than one
+/// tile, or decoding or encoding fails.
+pub fn convert_video(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    config: &AsciiConfig,
+    preserve_colors: bool,
+) -> Result<usize, String> {
+    config.validate().map_err(|e| e.to_string())?;
+    let reader = VideoReader::open(input)?;
+    let info = reader.info();
+    check_image_size(info.width, info.height, working_multiple(config))
+        .map_err(|e| e.to_string())?;
+    let frame_rate = if info.frame_rate > 0.0 {
+        info.frame_rate
+    } else {
+        FALLBACK_FRAME_RATE
+    };
+
+    // Decoding stops at the first error, which is reported once the
+    // frames before it are written
+    let mut decode_error = None;
+    let decoded = reader.map_while(|frame| frame.map_err(|e| decode_error = Some(e)).ok());
+
+    let mut writer = None;
+    let mut frames = 0;
+    for art in process_frames(decoded, config, preserve_colors) {
+        let ascii = art.render_image();
+        // Created on the first frame, whose size every later frame shares
+        let writer = match &mut writer {
+            Some(writer) => writer,
+            None => writer.insert(VideoWriter::create(
+                output.as_ref(),
+                ascii.width(),
+                ascii.height(),
+                frame_rate,
+            )?),
+        };
+        writer.write_frame(&ascii)?;
+        frames += 1;
+    }
+    if let Some(e) = decode_error {
+        return Err(e);
+    }
+
+    match writer {
+        Some(writer) => writer.finish()?,
+        None => return Err("video has no frames".to_string()),
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_info() {
+        let info = parse_stream_info("1920,1080,30000/1001\n").unwrap();
+        assert_eq!((info.width, info.height), (1920, 1080));
+        assert!((info.frame_rate - 29.97).abs() < 0.01);
+
+        let info = parse_stream_info("\n640,480,25\n").unwrap();
+        assert_eq!(info.frame_rate, 25.0);
+    }
+
+    #[test]
+    fn test_parse_stream_info_errors() {
+        assert!(parse_stream_info("").is_err());
+        assert!(parse_stream_info("640,480").is_err());
+        assert!(parse_stream_info("0,480,25/1").is_err());
+        assert!(parse_stream_info("abc,480,25/1").is_err());
+    }
+
+    #[test]
+    fn test_read_frame_bytes() {
+        let data = [1u8, 2, 3, 4, 5, 6];
+        let mut reader = &data[..];
+        let mut buf = [0u8; 4];
+        assert!(read_frame_bytes(&mut reader, &mut buf).unwrap());
+        assert_eq!(buf, [1, 2, 3, 4]);
+        // Two bytes left: partial frame is an error
+        assert!(read_frame_bytes(&mut reader, &mut buf).is_err());
+
+        let mut empty: &[u8] = &[];
+        assert!(!read_frame_bytes(&mut empty, &mut buf).unwrap());
+    }
+
+    #[test]
+    fn test_live_capture() {
+        let info = VideoInfo {
+            width: 4,
+            height: 2,
+            frame_rate: 30.0,
+        };
+        let mut remaining = 3u8;
+        let mut capture = LiveCapture::from_fn(info, move || {
+            if remaining == 0 {
+                return Ok(None);
+            }
+            remaining -= 1;
+            Ok(Some(RgbaImage::from_pixel(
+                4,
+                2,
+                image::Rgba([remaining; 4]),
+            )))
+        });
+
+        // Every frame handed out is newer than the last; the rest are dropped
+        let mut last = u8::MAX;
+        let mut frames = 0;
+        for frame in capture.by_ref() {
+            let Ok(frame) = frame else { break };
+            assert!(frame[(0, 0)][0] < last);
+            last = frame[(0, 0)][0];
+            frames += 1;
+        }
+        assert_eq!(frames + capture.dropped_frames(), 3);
+        assert!(capture.next_frame().is_err());
+        assert!(capture.try_next_frame().is_err());
+        assert!(capture.next().is_none());
+    }
+
+    #[test]
+    fn test_live_capture_try_next_frame() {
+        let info = VideoInfo {
+            width: 1,
+            height: 1,
+            frame_rate: 30.0,
+        };
+        let (sender, receiver) = std::sync::mpsc::channel::<u8>();
+        let mut capture = LiveCapture::from_fn(info, move || {
+            Ok(receiver
+                .recv()
+                .ok()
+                .map(|v| RgbaImage::from_pixel(1, 1, image::Rgba([v; 4]))))
+        });
+
+        // Nothing has arrived yet
+        assert_eq!(capture.try_next_frame(), Ok(None));
+
+        sender.send(7).unwrap();
+        let frame = loop {
+            if let Some(frame) = capture.try_next_frame().unwrap() {
+                break frame;
+            }
+            thread::yield_now();
+        };
+        assert_eq!(frame[(0, 0)][0], 7);
+        // The same frame is not handed out twice
+        assert_eq!(capture.try_next_frame(), Ok(None));
+
+        // Closing the channel ends the stream
+        drop(sender);
+        while capture.try_next_frame() == Ok(None) {
+            thread::yield_now();
+        }
+        assert!(capture.try_next_frame().is_err());
+    }
+
+    #[test]
+    fn test_live_capture_drop_does_not_wait_for_read() {
+        let info = VideoInfo {
+            width: 1,
+            height: 1,
+            frame_rate: 30.0,
+        };
+        // A source that never delivers, like a stalled camera
+        let (sender, receiver) = std::sync::mpsc::channel::<()>();
+        let capture = LiveCapture::from_fn(info, move || {
+            let _ = receiver.recv();
+            Ok(None)
+        });
+        drop(capture);
+        drop(sender);
+    }
+
+    #[test]
+    fn test_convert_video_errors() {
+        // Settings are checked before ffmpeg is started
+        let config = AsciiConfig {
+            sigma: -1.0,
+            ..AsciiConfig::default()
+        };
+        let result = convert_video("in.mp4", "out.mp4", &config, false);
+        assert!(result.unwrap_err().contains("sigma"));
+
+        let result = convert_video("missing.mp4", "out.mp4", &AsciiConfig::default(), false);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Text or image watermark stamped onto rendered output
+//!
+//! A [`Watermark`] is applied after the ASCII pass, so it stays legible instead
+//! of being turned into characters itself. Text uses a built-in 3×5 pixel font
+//! (printable ASCII, lowercase drawn as uppercase) scaled by an integer factor.
+
+use image::{Rgba, RgbaImage};
+
+/// Corner of the output a watermark is anchored to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WatermarkCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// What a watermark draws
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatermarkContent {
+    /// Text in the built-in pixel font
+    Text(String),
+    /// An image, blended using its own alpha channel
+    Image(RgbaImage),
+}
+
+/// A text or image signature stamped in a corner of the output
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watermark {
+    pub content: WatermarkContent,
+    pub corner: WatermarkCorner,
+    /// 0.0 (invisible) to 1.0 (opaque)
+    pub opacity: f32,
+    /// Distance from the image edges in pixels
+    pub margin: u32,
+    /// Text color
+    pub color: [u8; 3],
+    /// Text pixel size (1 = 3×5 pixels per character)
+    pub scale: u32,
+}
+
+impl Watermark {
+    /// Text watermark with default placement (bottom right, 70% opacity, white)
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::with_content(WatermarkContent::Text(text.into()))
+    }
+
+    /// Image watermark with default placement (bottom right, 70% opacity)
+    pub fn image(image: RgbaImage) -> Self {
+        Self::with_content(WatermarkContent::Image(image))
+    }
+
+    fn with_content(content: WatermarkContent) -> Self {
+        Self {
+            content,
+            corner: WatermarkCorner::BottomRight,
+            opacity: 0.7,
+            margin: 8,
+            color: [255, 255, 255],
+            scale: 2,
+        }
+    }
+
+    /// Watermark rendered as an RGBA stamp, before opacity is applied
+    fn stamp(&self) -> RgbaImage {
+        match &self.content {
+            WatermarkContent::Image(image) => image.clone(),
+            WatermarkContent::Text(text) => {
+                let [r, g, b] = self.color;
+                render_text(text, self.scale.max(1), Rgba([r, g, b, 255]))
+            }
+        }
+    }
+
+    /// Blends the watermark into `image`
+    ///
+    /// A stamp larger than the image (minus margins) is clipped.
+    pub fn apply(&self, image: &mut RgbaImage) {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        if opacity == 0.0 {
+            return;
+        }
+
+        let stamp = self.stamp();
+        let (width, height) = image.dimensions();
+        let (stamp_width, stamp_height) = stamp.dimensions();
+
+        let left = match self.corner {
+            WatermarkCorner::TopLeft | WatermarkCorner::BottomLeft => self.margin as i64,
+            _ => width as i64 - self.margin as i64 - stamp_width as i64,
+        };
+        let top = match self.corner {
+            WatermarkCorner::TopLeft | WatermarkCorner::TopRight => self.margin as i64,
+            _ => height as i64 - self.margin as i64 - stamp_height as i64,
+        };
+
+        for (x, y, pixel) in stamp.enumerate_pixels() {
+            let px = left + x as i64;
+            let py = top + y as i64;
+            if px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+                continue;
+            }
+
+            let alpha = pixel[3] as f32 / 255.0 * opacity;
+            let dst = image.get_pixel_mut(px as u32, py as u32);
+            for c in 0..3 {
+                dst[c] = (dst[c] as f32 * (1.0 - alpha) + pixel[c] as f32 * alpha).round() as u8;
+            }
+        }
+    }
+}
+
+/// Renders text in the built-in font; unsupported characters render as `?`
+fn render_text(text: &str, scale: u32, color: Rgba<u8>) -> RgbaImage {
+    let chars: Vec<char> = text.chars().collect();
+    // 3 pixel glyphs with 1 pixel spacing
+    let width = (chars.len() as u32 * 4).saturating_sub(1).max(1) * scale;
+    let height = 5 * scale;
+    let mut stamp = RgbaImage::new(width, height);
+
+    for (i, &ch) in chars.iter().enumerate() {
+        let glyph = glyph(ch);
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                let x0 = (i as u32 * 4 + col) * scale;
+                let y0 = row as u32 * scale;
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        stamp.put_pixel(x0 + dx, y0 + dy, color);
+                    }
+                }
+            }
+        }
+    }
+
+    stamp
+}
+
+/// 3×5 bitmap of a character, one row per entry, MSB = left column
+fn glyph(ch: char) -> [u8; 5] {
+    let code = ch.to_ascii_uppercase() as u32;
+    match code {
+        32..=95 => FONT_3X5[(code - 32) as usize],
+        _ => FONT_3X5[('?' as u32 - 32) as usize],
+    }
+}
+
+/// Glyphs for ' ' through '_'
+const FONT_3X5: [[u8; 5]; 64] = [
+    [0b000, 0b000, 0b000, 0b000, 0b000], // space
+    [0b010, 0b010, 0b010, 0b000, 0b010], // !
+    [0b101, 0b101, 0b000, 0b000, 0b000], // "
+    [0b101, 0b111, 0b101, 0b111, 0b101], // #
+    [0b011, 0b110, 0b010, 0b011, 0b110], // $
+    [0b100, 0b001, 0b010, 0b100, 0b001], // %
+    [0b010, 0b101, 0b010, 0b101, 0b011], // &
+    [0b010, 0b010, 0b000, 0b000, 0b000], // '
+    [0b001, 0b010, 0b010, 0b010, 0b001], // (
+    [0b100, 0b010, 0b010, 0b010, 0b100], // )
+    [0b000, 0b101, 0b010, 0b101, 0b000], // *
+    [0b000, 0b010, 0b111, 0b010, 0b000], // +
+    [0b000, 0b000, 0b000, 0b010, 0b100], // ,
+    [0b000, 0b000, 0b111, 0b000, 0b000], // -
+    [0b000, 0b000, 0b000, 0b000, 0b010], // .
+    [0b001, 0b001, 0b010, 0b100, 0b100], // /
+    [0b111, 0b101, 0b101, 0b101, 0b111], // 0
+    [0b010, 0b110, 0b010, 0b010, 0b111], // 1
+    [0b110, 0b001, 0b010, 0b100, 0b111], // 2
+    [0b110, 0b001, 0b010, 0b001, 0b110], // 3
+    [0b101, 0b101, 0b111, 0b001, 0b001], // 4
+    [0b111, 0b100, 0b110, 0b001, 0b110], // 5
+    [0b011, 0b100, 0b111, 0b101, 0b111], // 6
+    [0b111, 0b001, 0b010, 0b010, 0b010], // 7
+    [0b111, 0b101, 0b111, 0b101, 0b111], // 8
+    [0b111, 0b101, 0b111, 0b001, 0b110], // 9
+    [0b000, 0b010, 0b000, 0b010, 0b000], // :
+    [0b000, 0b010, 0b000, 0b010, 0b100], // ;
+    [0b001, 0b010, 0b100, 0b010, 0b001], // <
+    [0b000, 0b111, 0b000, 0b111, 0b000], // =
+    [0b100, 0b010, 0b001, 0b010, 0b100], // >
+    [0b110, 0b001, 0b010, 0b000, 0b010], // ?
+    [0b010, 0b101, 0b111, 0b100, 0b011], // @
+    [0b010, 0b101, 0b111, 0b101, 0b101], // A
+    [0b110, 0b101, 0b110, 0b101, 0b110], // B
+    [0b011, 0b100, 0b100, 0b100, 0b011], // C
+    [0b110, 0b101, 0b101, 0b101, 0b110], // D
+    [0b111, 0b100, 0b110, 0b100, 0b111], // E
+    [0b111, 0b100, 0b110, 0b100, 0b100], // F
+    [0b011, 0b100, 0b101, 0b101, 0b011], // G
+    [0b101, 0b101, 0b111, 0b101, 0b101], // H
+    [0b111, 0b010, 0b010, 0b010, 0b111], // I
+    [0b001, 0b001, 0b001, 0b101, 0b010], // J
+    [0b101, 0b101, 0b110, 0b101, 0b101], // K
+    [0b100, 0b100, 0b100, 0b100, 0b111], // L
+    [0b101, 0b111, 0b111, 0b101, 0b101], // M
+    [0b101, 0b111, 0b111, 0b111, 0b101], // N
+    [0b010, 0b101, 0b101, 0b101, 0b010], // O
+    [0b110, 0b101, 0b110, 0b100, 0b100], // P
+    [0b010, 0b101, 0b101, 0b111, 0b011], // Q
+    [0b110, 0b101, 0b110, 0b101, 0b101], // R
+    [0b011, 0b100, 0b010, 0b001, 0b110], // S
+    [0b111, 0b010, 0b010, 0b010, 0b010], // T
+    [0b101, 0b101, 0b101, 0b101, 0b011], // U
+    [0b101, 0b101, 0b101, 0b010, 0b010], // V
+    [0b101, 0b101, 0b111, 0b111, 0b101], // W
+    [0b101, 0b101, 0b010, 0b101, 0b101], // X
+    [0b101, 0b101, 0b010, 0b010, 0b010], // Y
+    [0b111, 0b001, 0b010, 0b100, 0b111], // Z
+    [0b110, 0b100, 0b100, 0b100, 0b110], // [
+    [0b100, 0b100, 0b010, 0b001, 0b001], // \
+    [0b011, 0b001, 0b001, 0b001, 0b011], // ]
+    [0b010, 0b101, 0b000, 0b000, 0b000], // ^
+    [0b000, 0b000, 0b000, 0b000, 0b111], // _
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_text_size() {
+        let stamp = render_text("Ab", 2, Rgba([255, 255, 255, 255]));
+        assert_eq!(stamp.dimensions(), (14, 10));
+        // 'A' has its top row center lit and its corners dark
+        assert_eq!(stamp.get_pixel(2, 0)[3], 255);
+        assert_eq!(stamp.get_pixel(0, 0)[3], 0);
+    }
+
+    #[test]
+    fn test_apply_corner_and_opacity() {
+        let mut image = RgbaImage::from_pixel(32, 32, Rgba([0, 0, 0, 255]));
+        let watermark = Watermark {
+            corner: WatermarkCorner::TopLeft,
+            opacity: 0.5,
+            margin: 1,
+            ..Watermark::image(RgbaImage::from_pixel(4, 4, Rgba([200, 100, 0, 255])))
+        };
+        watermark.apply(&mut image);
+
+        assert_eq!(*image.get_pixel(1, 1), Rgba([100, 50, 0, 255]));
+        assert_eq!(*image.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(5, 5), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_apply_clips_large_stamp() {
+        let mut image = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 255]));
+        let watermark = Watermark {
+            opacity: 1.0,
+            margin: 0,
+            ..Watermark::text("too long for this image")
+        };
+        watermark.apply(&mut image);
+        assert_eq!(image.dimensions(), (8, 8));
+    }
+}
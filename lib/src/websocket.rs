@@ -0,0 +1,241 @@
+//! WebSocket streaming of processed frames
+//!
+//! [`FrameServer`] accepts WebSocket clients on a TCP address and pushes the
+//! most recently published frame to each of them, at most `fps` times per
+//! second. Frames are sent as text messages in the chosen [`StreamFormat`];
+//! slow clients skip frames rather than queueing them.
+//!
+//! ```no_run
+//! use ascii_rendr::websocket::{FrameServer, StreamFormat};
+//! use ascii_rendr::{AsciiConfig, process_image_output};
+//!
+//! let server = FrameServer::bind("0.0.0.0:9001", StreamFormat::Json, 15.0).unwrap();
+//! let frame = image::open("frame.png").unwrap().to_rgba8();
+//! server.publish(&process_image_output(&frame, &AsciiConfig::default(), true));
+//! ```
+
+use crate::output::ProcessOutput;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tungstenite::Message;
+
+/// How long a client may stall its handshake or a write before it is dropped
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Encoding of each WebSocket message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// [`ProcessOutput::to_json`]: lines plus per-character colors
+    Json,
+    /// [`ProcessOutput::to_ansi`]: 24-bit colored terminal text
+    Ansi,
+    /// [`ProcessOutput::to_text`]: plain text
+    Text,
+}
+
+impl StreamFormat {
+    fn encode(self, output: &ProcessOutput) -> String {
+        match self {
+            StreamFormat::Json => output.to_json(),
+            StreamFormat::Ansi => output.to_ansi(),
+            StreamFormat::Text => output.to_text(),
+        }
+    }
+}
+
+/// State shared between the server handle and its threads
+struct Shared {
+    /// Latest encoded frame and its sequence number (starting at 1)
+    frame: Mutex<Option<(u64, Arc<String>)>>,
+    format: StreamFormat,
+    frame_interval: Duration,
+    clients: AtomicUsize,
+    shutdown: AtomicBool,
+}
+
+/// Serves published frames to WebSocket clients
+///
+/// Dropping the server stops accepting connections and closes all clients.
+pub struct FrameServer {
+    shared: Arc<Shared>,
+    local_addr: SocketAddr,
+    accept_thread: Option<JoinHandle<()>>,
+}
+
+impl FrameServer {
+    /// Starts listening for WebSocket clients
+    ///
+    /// # Arguments
+    /// * `addr` - Address to bind, e.g. `"127.0.0.1:9001"` (port 0 picks a free port)
+    /// * `format` - Encoding of each frame message
+    /// * `fps` - Maximum frames per second sent to each client
+    pub fn bind(addr: impl ToSocketAddrs, format: StreamFormat, fps: f64) -> Result<Self, String> {
+        if !fps.is_finite() || fps <= 0.0 {
+            return Err(format!("invalid frame rate: {}", fps));
+        }
+
+        let listener = TcpListener::bind(addr).map_err(|e| format!("failed to bind: {}", e))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| format!("failed to bind: {}", e))?;
+
+        let shared = Arc::new(Shared {
+            frame: Mutex::new(None),
+            format,
+            frame_interval: Duration::from_secs_f64(1.0 / fps),
+            clients: AtomicUsize::new(0),
+            shutdown: AtomicBool::new(false),
+        });
+
+        let accept_shared = Arc::clone(&shared);
+        let accept_thread = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accept_shared.shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                let client_shared = Arc::clone(&accept_shared);
+                thread::spawn(move || serve_client(stream, client_shared));
+            }
+        });
+
+        Ok(Self {
+            shared,
+            local_addr,
+            accept_thread: Some(accept_thread),
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Number of currently connected clients
+    pub fn client_count(&self) -> usize {
+        self.shared.clients.load(Ordering::Relaxed)
+    }
+
+    /// Makes `output` the frame sent to clients on their next tick
+    ///
+    /// Encoding is skipped while no clients are connected.
+    pub fn publish(&self, output: &ProcessOutput) {
+        if self.client_count() == 0 {
+            return;
+        }
+        let payload = Arc::new(self.shared.format.encode(output));
+        let mut frame = self.shared.frame.lock().unwrap();
+        let sequence = frame.as_ref().map_or(1, |(seq, _)| seq + 1);
+        *frame = Some((sequence, payload));
+    }
+}
+
+impl Drop for FrameServer {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+        // Wake the accept loop so it can observe the shutdown flag; a wildcard
+        // address is not connectable everywhere, so go through loopback
+        let mut wake = self.local_addr;
+        if wake.ip().is_unspecified() {
+            wake.set_ip(match wake {
+                SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+                SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+            });
+        }
+        let _ = TcpStream::connect_timeout(&wake, CLIENT_TIMEOUT);
+        if let Some(handle) = self.accept_thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Sends new frames to one client until it disconnects or the server stops
+fn serve_client(stream: TcpStream, shared: Arc<Shared>) {
+    // A stalled client must not block its thread forever
+    let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(CLIENT_TIMEOUT));
+    let Ok(mut socket) = tungstenite::accept(stream) else {
+        return;
+    };
+
+    shared.clients.fetch_add(1, Ordering::Relaxed);
+    let mut sent = 0;
+
+    'frames: while !shared.shutdown.load(Ordering::Relaxed) {
+        let tick = Instant::now();
+
+        let latest = shared.frame.lock().unwrap().clone();
+        if let Some((sequence, payload)) = latest
+            && sequence != sent
+        {
+            if socket.send(Message::text(payload.as_str())).is_err() {
+                break;
+            }
+            sent = sequence;
+        }
+
+        // Wait out the tick reading from the client, so pings get answered
+        // and a close or a dropped connection is noticed right away
+        while let Some(remaining) = shared.frame_interval.checked_sub(tick.elapsed()) {
+            let timeout = remaining.max(Duration::from_millis(1));
+            let _ = socket.get_ref().set_read_timeout(Some(timeout));
+            match socket.read() {
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    break;
+                }
+                Err(_) => break 'frames,
+            }
+        }
+    }
+
+    let _ = socket.close(None);
+    shared.clients.fetch_sub(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_rejects_invalid_fps() {
+        assert!(FrameServer::bind("127.0.0.1:0", StreamFormat::Json, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_bind_and_shutdown() {
+        for addr in ["127.0.0.1:0", "0.0.0.0:0"] {
+            let server = FrameServer::bind(addr, StreamFormat::Text, 30.0).unwrap();
+            assert_ne!(server.local_addr().port(), 0);
+            assert_eq!(server.client_count(), 0);
+            // Dropping must not hang on the blocking accept loop
+            drop(server);
+        }
+    }
+
+    #[test]
+    fn test_closed_client_is_dropped() {
+        let server = FrameServer::bind("127.0.0.1:0", StreamFormat::Text, 30.0).unwrap();
+        let wait_for = |count: usize| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while server.client_count() != count && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+            server.client_count()
+        };
+
+        let (mut client, _) =
+            tungstenite::connect(format!("ws://{}", server.local_addr())).unwrap();
+        assert_eq!(wait_for(1), 1);
+
+        // No frame is ever published, so only reading notices the close
+        client.close(None).unwrap();
+        assert_eq!(wait_for(0), 0);
+    }
+}
@@ -0,0 +1,218 @@
+//! Planar YUV 4:2:0 frame input (I420 and NV12)
+//!
+//! Cameras and video decoders usually hand out YUV frames. Their Y plane already
+//! is luminance, so [`crate::process_yuv`] feeds it to the pipeline directly
+//! and only converts chroma to RGB when colors are preserved, instead of going
+//! YUV → RGBA → luminance on every frame.
+//!
+//! Conversion uses BT.709 coefficients, matching the luminance weights of
+//! [`calculate_luminance`](crate::filters::calculate_luminance).
+
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+/// Chroma planes of a 4:2:0 frame, at half resolution in both directions
+#[derive(Debug, Clone, Copy)]
+pub enum ChromaPlanes<'a> {
+    /// Separate U and V planes (I420)
+    Planar {
+        u: &'a [u8],
+        v: &'a [u8],
+        /// Bytes per row of each plane
+        stride: usize,
+    },
+    /// One plane of interleaved U/V pairs (NV12)
+    Interleaved {
+        uv: &'a [u8],
+        /// Bytes per row
+        stride: usize,
+    },
+}
+
+/// A borrowed YUV 4:2:0 frame
+#[derive(Debug, Clone, Copy)]
+pub struct YuvFrame<'a> {
+    pub width: u32,
+    pub height: u32,
+    /// Luma plane
+    pub y: &'a [u8],
+    /// Bytes per row of the luma plane
+    pub y_stride: usize,
+    pub chroma: ChromaPlanes<'a>,
+    /// Full-range (0-255) samples instead of video range (Y 16-235, UV 16-240)
+    pub full_range: bool,
+}
+
+impl<'a> YuvFrame<'a> {
+    /// Wraps a tightly packed video-range I420 buffer (Y plane, then U, then V)
+    pub fn from_i420(data: &'a [u8], width: u32, height: u32) -> Result<Self, String> {
+        let (y_len, chroma_width, chroma_len) = plane_sizes(width, height);
+        check_len("I420", data, y_len + 2 * chroma_len)?;
+
+        let (y, chroma) = data.split_at(y_len);
+        let (u, v) = chroma.split_at(chroma_len);
+        Ok(Self {
+            width,
+            height,
+            y,
+            y_stride: width as usize,
+            chroma: ChromaPlanes::Planar {
+                u,
+                v: &v[..chroma_len],
+                stride: chroma_width,
+            },
+            full_range: false,
+        })
+    }
+
+    /// Wraps a tightly packed video-range NV12 buffer (Y plane, then interleaved UV)
+    pub fn from_nv12(data: &'a [u8], width: u32, height: u32) -> Result<Self, String> {
+        let (y_len, chroma_width, chroma_len) = plane_sizes(width, height);
+        check_len("NV12", data, y_len + 2 * chroma_len)?;
+
+        let (y, uv) = data.split_at(y_len);
+        Ok(Self {
+            width,
+            height,
+            y,
+            y_stride: width as usize,
+            chroma: ChromaPlanes::Interleaved {
+                uv: &uv[..2 * chroma_len],
+                stride: 2 * chroma_width,
+            },
+            full_range: false,
+        })
+    }
+
+    /// Sets whether samples use the full 0-255 range
+    pub fn with_full_range(mut self, full_range: bool) -> Self {
+        self.full_range = full_range;
+        self
+    }
+
+    /// Luma plane as a full-range grayscale image
+    pub fn luma(&self) -> GrayImage {
+        GrayImage::from_fn(self.width, self.height, |x, y| {
+            let sample = self.y[y as usize * self.y_stride + x as usize];
+            Luma([(self.luma_value(sample) * 255.0).round() as u8])
+        })
+    }
+
+    /// Converts the frame to RGBA
+    pub fn to_rgba(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width, self.height, |x, y| {
+            let luma = self.luma_value(self.y[y as usize * self.y_stride + x as usize]);
+            let (u, v) = self.chroma_at(x / 2, y / 2);
+            let (cb, cr) = (self.chroma_value(u), self.chroma_value(v));
+
+            // BT.709
+            let r = luma + 1.5748 * cr;
+            let g = luma - 0.1873 * cb - 0.4681 * cr;
+            let b = luma + 1.8556 * cb;
+            let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+            Rgba([channel(r), channel(g), channel(b), 255])
+        })
+    }
+
+    /// U and V samples of a chroma position
+    fn chroma_at(&self, cx: u32, cy: u32) -> (u8, u8) {
+        let (cx, cy) = (cx as usize, cy as usize);
+        match self.chroma {
+            ChromaPlanes::Planar { u, v, stride } => (u[cy * stride + cx], v[cy * stride + cx]),
+            ChromaPlanes::Interleaved { uv, stride } => {
+                (uv[cy * stride + 2 * cx], uv[cy * stride + 2 * cx + 1])
+            }
+        }
+    }
+
+    /// Luma sample normalized to [0.0, 1.0]
+    fn luma_value(&self, sample: u8) -> f32 {
+        if self.full_range {
+            sample as f32 / 255.0
+        } else {
+            ((sample as f32 - 16.0) / 219.0).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Chroma sample normalized to [-0.5, 0.5]
+    fn chroma_value(&self, sample: u8) -> f32 {
+        let scale = if self.full_range { 255.0 } else { 224.0 };
+        ((sample as f32 - 128.0) / scale).clamp(-0.5, 0.5)
+    }
+}
+
+/// Luma plane length, chroma plane width and chroma plane length of a 4:2:0 frame
+fn plane_sizes(width: u32, height: u32) -> (usize, usize, usize) {
+    let chroma_width = width.div_ceil(2) as usize;
+    let chroma_height = height.div_ceil(2) as usize;
+    (
+        width as usize * height as usize,
+        chroma_width,
+        chroma_width * chroma_height,
+    )
+}
+
+fn check_len(format: &str, data: &[u8], expected: usize) -> Result<(), String> {
+    if data.len() < expected {
+        return Err(format!(
+            "{} buffer too small: expected {} bytes, got {}",
+            format,
+            expected,
+            data.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 4x2 frame: left half red-ish, right half gray, as (I420, NV12) buffers
+    fn frames() -> (Vec<u8>, Vec<u8>) {
+        let y = [81, 81, 126, 126, 81, 81, 126, 126];
+        let (u, v) = ([90, 128], [240, 128]);
+        let i420 = [&y[..], &u, &v].concat();
+        let nv12 = [&y[..], &[u[0], v[0], u[1], v[1]]].concat();
+        (i420, nv12)
+    }
+
+    #[test]
+    fn test_i420_and_nv12_agree() {
+        let (i420, nv12) = frames();
+        let a = YuvFrame::from_i420(&i420, 4, 2).unwrap();
+        let b = YuvFrame::from_nv12(&nv12, 4, 2).unwrap();
+        assert_eq!(a.to_rgba(), b.to_rgba());
+        assert_eq!(a.luma(), b.luma());
+    }
+
+    #[test]
+    fn test_video_range_conversion() {
+        let (i420, _) = frames();
+        let frame = YuvFrame::from_i420(&i420, 4, 2).unwrap();
+
+        // Gray stays gray; strong Cr gives red
+        let rgba = frame.to_rgba();
+        let gray = rgba.get_pixel(3, 0);
+        assert!(gray[0] == gray[1] && gray[1] == gray[2]);
+        let red = rgba.get_pixel(0, 0);
+        assert!(red[0] > 200 && red[1] < 60 && red[2] < 60);
+
+        // Video-range black and white expand to the full range
+        let y = [16, 235, 16, 235];
+        let data = [&y[..], &[128, 128]].concat();
+        let frame = YuvFrame::from_nv12(&data, 2, 2).unwrap();
+        assert_eq!(frame.luma().as_raw(), &vec![0, 255, 0, 255]);
+        let frame = frame.with_full_range(true);
+        assert_eq!(frame.luma().as_raw(), &vec![16, 235, 16, 235]);
+    }
+
+    #[test]
+    fn test_odd_size_and_short_buffer() {
+        // 3x3 luma with 2x2 chroma
+        let data = vec![128; 9 + 2 * 4];
+        let frame = YuvFrame::from_i420(&data, 3, 3).unwrap();
+        assert_eq!(frame.to_rgba().dimensions(), (3, 3));
+
+        assert!(YuvFrame::from_nv12(&data[..16], 3, 3).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! ascii-play - plays a video or camera feed as ASCII art in the terminal
+//!
+//! Frames are decoded through ffmpeg (see `ascii_rendr::video_io`), scaled to
+//! the terminal, converted and printed with 24-bit ANSI colors. Playback keeps
+//! the source frame rate by dropping frames when conversion falls behind.
+//!
+//! Keys: `q`/`Esc`/`Ctrl-C` quit, `Space` pauses, `b` switches between ASCII
+//! and half-block pictures.
+
+mod screen;
+
+use ascii_rendr::render::ansi::{AnsiOptions, render_half_blocks};
+use ascii_rendr::video_io::VideoReader;
+use ascii_rendr::{AsciiConfig, Processor};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use image::{RgbaImage, imageops};
+use screen::Screen;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+Usage: ascii-play [OPTIONS] <VIDEO>
+       ascii-play [OPTIONS] --camera [DEVICE]
+
+Options:
+  --camera [DEVICE]  Read a camera instead of a file (default device: /dev/video0
+                     on Linux, 0 on macOS; required on Windows)
+  --config <FILE>    Load settings from a TOML or JSON config file
+  --mono             Use the configured ASCII/background colors instead of source colors
+  --blocks           Start with half-block pixels instead of ASCII art
+  --loop             Restart the video when it ends
+  -h, --help         Show this help
+
+Keys: q / Esc / Ctrl-C quit, Space pause, b ASCII/half blocks";
+
+/// Frame rate assumed when the source does not report one
+const FALLBACK_FRAME_RATE: f64 = 30.0;
+
+/// Terminal cells are about twice as tall as they are wide
+const CELL_ASPECT: f64 = 2.0;
+
+/// Where frames come from
+enum Source {
+    File(PathBuf),
+    Camera(String),
+}
+
+/// Parsed command line
+struct Args {
+    source: Source,
+    config: AsciiConfig,
+    preserve_colors: bool,
+    half_blocks: bool,
+    looping: bool,
+}
+
+impl Args {
+    /// Parses arguments; `Ok(None)` means help was requested
+    fn parse(args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut args = args.peekable();
+        let mut source = None;
+        let mut config = AsciiConfig::default();
+        let mut preserve_colors = true;
+        let mut half_blocks = false;
+        let mut looping = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--camera" => {
+                    // An optional device name directly follows the flag
+                    let device = match args.next_if(|next| !next.starts_with('-')) {
+                        Some(device) => device,
+                        None => default_camera()?,
+                    };
+                    source = Some(Source::Camera(device));
+                }
+                "--config" => {
+                    let path = args.next().ok_or("--config needs a file")?;
+                    config = AsciiConfig::from_path(&path).map_err(|e| e.to_string())?;
+                }
+                "--mono" => preserve_colors = false,
+                "--blocks" => half_blocks = true,
+                "--loop" => looping = true,
+                flag if flag.starts_with('-') => {
+                    return Err(format!("unknown option `{}`", flag));
+                }
+                path => source = Some(Source::File(PathBuf::from(path))),
+            }
+        }
+
+        let source = source.ok_or("no video or camera given")?;
+        Ok(Some(Self {
+            source,
+            config,
+            preserve_colors,
+            half_blocks,
+            looping,
+        }))
+    }
+}
+
+fn default_camera() -> Result<String, String> {
+    if cfg!(target_os = "linux") {
+        Ok("/dev/video0".to_string())
+    } else if cfg!(target_os = "macos") {
+        Ok("0".to_string())
+    } else {
+        Err("--camera needs a device name on this platform".to_string())
+    }
+}
+
+fn open(source: &Source) -> Result<VideoReader, String> {
+    match source {
+        Source::File(path) => VideoReader::open(path),
+        Source::Camera(device) => VideoReader::open_camera(device),
+    }
+}
+
+/// Tile grid that fits a `width`×`height` frame into the terminal, keeping
+/// its aspect ratio on screen
+fn fit_tiles(width: u32, height: u32, columns: u16, rows: u16) -> (u32, u32) {
+    let (columns, rows) = (columns.max(1) as f64, rows.max(1) as f64);
+    let aspect = width as f64 / height.max(1) as f64;
+
+    let mut tiles_x = columns;
+    let mut tiles_y = (columns / aspect / CELL_ASPECT).round();
+    if tiles_y > rows {
+        tiles_y = rows;
+        tiles_x = (rows * aspect * CELL_ASPECT).round().min(columns);
+    }
+    ((tiles_x as u32).max(1), (tiles_y as u32).max(1))
+}
+
+/// Player state shared by the event and frame handling
+struct Player {
+    args: Args,
+    /// Keeps conversion buffers from one frame to the next
+    processor: Processor,
+    screen: Screen,
+    paused: bool,
+    quit: bool,
+    dropped_frames: u64,
+}
+
+impl Player {
+    /// Handles one terminal event
+    fn handle(&mut self, event: Event) -> Result<(), String> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.quit = true
+                }
+                KeyCode::Char(' ') => self.paused = !self.paused,
+                KeyCode::Char('b') => self.args.half_blocks = !self.args.half_blocks,
+                _ => {}
+            },
+            Event::Resize(columns, rows) => self
+                .screen
+                .resize(columns, rows)
+                .map_err(|e| format!("terminal error: {}", e))?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handles events until `deadline` (or one event when `deadline` has passed)
+    fn wait_until(&mut self, deadline: Instant) -> Result<(), String> {
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if event::poll(timeout).map_err(|e| format!("terminal error: {}", e))? {
+                let event = event::read().map_err(|e| format!("terminal error: {}", e))?;
+                self.handle(event)?;
+            }
+            if self.quit || Instant::now() >= deadline {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Converts and draws one frame with a status line below it
+    fn show(&mut self, frame: &RgbaImage, frame_rate: f64) -> Result<(), String> {
+        // Reserve the last terminal row for the status line
+        let rows = self.screen.rows.saturating_sub(1);
+        let (tiles_x, tiles_y) =
+            fit_tiles(frame.width(), frame.height(), self.screen.columns, rows);
+        let text = if self.args.half_blocks {
+            // One pixel per half cell
+            let scaled =
+                imageops::resize(frame, tiles_x, tiles_y * 2, imageops::FilterType::Triangle);
+            render_half_blocks(&scaled)
+        } else {
+            // With tall tiles (`tile_height = 16`) the frame keeps its shape
+            // instead of being squashed to square tiles
+            let (tile_w, tile_h) = self.args.config.tile_dimensions();
+            let scaled = imageops::resize(
+                frame,
+                tiles_x * tile_w,
+                tiles_y * tile_h,
+                imageops::FilterType::Triangle,
+            );
+            self.processor
+                .process(&scaled)
+                .render_ansi(&AnsiOptions::default())
+        };
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        lines.resize(rows as usize, String::new());
+        lines.push(format!(
+            "{:.1} fps  dropped {}  {}",
+            frame_rate,
+            self.dropped_frames,
+            if self.paused {
+                "[paused]"
+            } else {
+                "q quit, space pause, b blocks"
+            }
+        ));
+
+        self.screen
+            .draw(&lines)
+            .map_err(|e| format!("terminal error: {}", e))
+    }
+
+    fn run(&mut self) -> Result<(), String> {
+        let live = matches!(self.args.source, Source::Camera(_));
+        let mut reader = open(&self.args.source)?;
+        let reported_rate = reader.info().frame_rate;
+        let frame_rate = if reported_rate > 0.0 {
+            reported_rate
+        } else {
+            FALLBACK_FRAME_RATE
+        };
+        let frame_time = Duration::from_secs_f64(1.0 / frame_rate);
+        let mut deadline = Instant::now();
+
+        while !self.quit {
+            if self.paused {
+                self.wait_until(Instant::now() + Duration::from_millis(100))?;
+                // Do not try to catch up on the time spent paused
+                deadline = Instant::now();
+                continue;
+            }
+
+            let frame = match reader.next_frame()? {
+                Some(frame) => frame,
+                None if self.args.looping && !live => {
+                    reader = open(&self.args.source)?;
+                    continue;
+                }
+                None => break,
+            };
+
+            if live {
+                // Cameras deliver in real time; just keep up with input
+                self.show(&frame, frame_rate)?;
+                self.wait_until(Instant::now())?;
+                continue;
+            }
+
+            // Skip frames whose display time has already passed
+            if Instant::now() > deadline + frame_time {
+                self.dropped_frames += 1;
+                deadline += frame_time;
+                continue;
+            }
+            self.show(&frame, frame_rate)?;
+            deadline += frame_time;
+            self.wait_until(deadline)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("ascii-play: {}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let screen = match Screen::enter() {
+        Ok(screen) => screen,
+        Err(e) => {
+            eprintln!("ascii-play: cannot use terminal: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut player = Player {
+        processor: Processor::new(&args.config, args.preserve_colors),
+        args,
+        screen,
+        paused: false,
+        quit: false,
+        dropped_frames: 0,
+    };
+    let result = player.run();
+
+    // Restore the terminal before reporting errors
+    drop(player);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("ascii-play: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::style::{Print, ResetColor};
+use crossterm::terminal::{
+    self, BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+use std::io::{self, Stdout, Write};
+
+/// Full-screen terminal output with line-level double buffering
+///
+/// Lines of the last drawn frame are kept, so a new frame only rewrites the
+/// lines that changed, inside a synchronized update so the terminal shows it
+/// all at once. The terminal is restored when the screen is dropped.
+pub struct Screen {
+    out: Stdout,
+    /// Terminal width in cells
+    pub columns: u16,
+    /// Terminal height in cells
+    pub rows: u16,
+    /// Lines currently on screen
+    previous: Vec<String>,
+}
+
+impl Screen {
+    /// Switches to the alternate screen in raw mode with a hidden cursor
+    pub fn enter() -> io::Result<Self> {
+        let (columns, rows) = terminal::size()?;
+        terminal::enable_raw_mode()?;
+        let mut out = io::stdout();
+        execute!(out, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+        Ok(Self {
+            out,
+            columns,
+            rows,
+            previous: Vec::new(),
+        })
+    }
+
+    /// Adopts a new terminal size; the next frame is drawn in full
+    pub fn resize(&mut self, columns: u16, rows: u16) -> io::Result<()> {
+        self.columns = columns;
+        self.rows = rows;
+        self.previous.clear();
+        execute!(self.out, ResetColor, Clear(ClearType::All))
+    }
+
+    /// Draws `lines` from the top of the screen, rewriting only changed lines
+    ///
+    /// Lines past the previous frame's height are cleared when the new frame
+    /// is shorter.
+    pub fn draw(&mut self, lines: &[String]) -> io::Result<()> {
+        queue!(self.out, BeginSynchronizedUpdate)?;
+
+        for (row, line) in lines.iter().enumerate().take(self.rows as usize) {
+            if self.previous.get(row) != Some(line) {
+                queue!(
+                    self.out,
+                    MoveTo(0, row as u16),
+                    Print(line),
+                    ResetColor,
+                    Clear(ClearType::UntilNewLine)
+                )?;
+            }
+        }
+        for row in lines.len()..self.previous.len().min(self.rows as usize) {
+            queue!(
+                self.out,
+                MoveTo(0, row as u16),
+                Clear(ClearType::CurrentLine)
+            )?;
+        }
+
+        queue!(self.out, EndSynchronizedUpdate)?;
+        self.out.flush()?;
+        self.previous = lines.to_vec();
+        Ok(())
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, ResetColor, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}